@@ -5,7 +5,10 @@ use ::core::{
 };
 #[cfg(feature = "alloc")]
 use ::heresy::alloc::Global;
-use ::heresy::{alloc::Allocator, Box};
+use ::heresy::{
+    alloc::{AllocError, Allocator},
+    Box,
+};
 use ::ptr_meta::Pointee;
 
 use crate::{
@@ -140,10 +143,32 @@ where
     /// This doesn't actually allocate if the metadata provides a layout with
     /// zero size.
     ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    ///
     /// # Safety
     ///
     /// `metadata` must be valid for a pointer to `T`.
     pub unsafe fn new_unsized_in(metadata: T::Metadata, alloc: A) -> Self {
+        // SAFETY: The caller has ensured that `metadata` is valid for a pointer
+        // to `T`.
+        unsafe { Self::try_new_unsized_in(metadata, alloc).unwrap() }
+    }
+
+    /// Attempts to allocate memory for an unsized type with the given metadata
+    /// in the given allocator.
+    ///
+    /// This doesn't actually allocate if the metadata provides a layout with
+    /// zero size.
+    ///
+    /// # Safety
+    ///
+    /// `metadata` must be valid for a pointer to `T`.
+    pub unsafe fn try_new_unsized_in(
+        metadata: T::Metadata,
+        alloc: A,
+    ) -> Result<Self, AllocError> {
         // SAFETY: The caller has ensured that `metadata` is valid for a pointer
         // to `T`.
         let layout = unsafe { metadata.pointee_layout() };
@@ -153,15 +178,15 @@ where
             ptr_meta::from_raw_parts_mut(layout.align() as *mut (), metadata)
         } else {
             ptr_meta::from_raw_parts_mut(
-                alloc.allocate(layout).unwrap().as_ptr().cast(),
+                alloc.allocate(layout)?.as_ptr().cast(),
                 metadata,
             )
         };
-        Self {
+        Ok(Self {
             // SAFETY: `ptr` is non-null.
             ptr: unsafe { NonNull::new_unchecked(ptr) },
             alloc,
-        }
+        })
     }
 
     /// Returns a [`Slot`] of the internal contents.
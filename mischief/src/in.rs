@@ -5,7 +5,16 @@ use ::core::{
 use ::munge::{Destructure, Restructure};
 use ::ptr_meta::Pointee;
 
-use crate::{Frame, Metadata, Pointer, Region, RegionalAllocator, Slot};
+use crate::{
+    Frame,
+    Metadata,
+    Pointer,
+    Region,
+    RegionalAllocator,
+    RuntimeRegion,
+    Slot,
+    Unique,
+};
 
 /// A pointer which has its pointee in a specific memory region.
 pub struct In<P, R: Region> {
@@ -89,6 +98,24 @@ where
     }
 }
 
+impl<'a, P: Pointer, U: Unique> In<P, RuntimeRegion<'a, U>>
+where
+    P::Target: Pointee,
+    <P::Target as Pointee>::Metadata: Metadata<P::Target>,
+{
+    /// Creates a new `In` from a pointer if its pointee is contained in the
+    /// given runtime region.
+    pub fn try_new(ptr: P, region: &RuntimeRegion<'a, U>) -> Option<Self> {
+        if region.contains(ptr.target()) {
+            // SAFETY: We checked that the pointee of `ptr` is contained in the
+            // bounds of `region`.
+            Some(unsafe { Self::new_unchecked(ptr) })
+        } else {
+            None
+        }
+    }
+}
+
 impl<P, R: Region> In<P, R> {
     /// Unwraps an `In`, returning the underlying pointer.
     pub fn into_inner(this: Self) -> P {
@@ -211,3 +238,32 @@ where
 ///
 /// The `target` of the type must be located in `R`.
 pub unsafe trait Within<R: Region>: Pointer {}
+
+#[cfg(test)]
+mod tests {
+    use ::core::{mem::MaybeUninit, ptr};
+
+    use crate::{runtime_token, In, RuntimeRegion, Slot};
+
+    #[test]
+    fn try_new_runtime() {
+        runtime_token!(Token);
+
+        let mut token = Token::acquire();
+        let mut bytes = MaybeUninit::<[u8; 16]>::uninit();
+        let (region, bytes) =
+            RuntimeRegion::new(Slot::new(&mut bytes).unsize(), &mut token);
+        let start = bytes.ptr().as_ptr().cast::<u8>();
+
+        let inside = start.wrapping_add(4).cast::<u32>();
+        assert!(In::try_new(inside, &region).is_some());
+        let last = start.wrapping_add(12).cast::<u32>();
+        assert!(In::try_new(last, &region).is_some());
+
+        // Pointees must be completely contained in the region.
+        let straddling = start.wrapping_add(14).cast::<u32>();
+        assert!(In::try_new(straddling, &region).is_none());
+        let mut outside = 0u32;
+        assert!(In::try_new(ptr::addr_of_mut!(outside), &region).is_none());
+    }
+}
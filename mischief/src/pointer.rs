@@ -1,3 +1,5 @@
+use ::core::ptr::NonNull;
+
 /// A type that points to a single location in memory.
 ///
 /// # Safety
@@ -32,3 +34,12 @@ unsafe impl<T: ?Sized> Pointer for *mut T {
         *self
     }
 }
+
+// SAFETY: `NonNull<T>` does not implement `Deref` or `DerefMut`.
+unsafe impl<T: ?Sized> Pointer for NonNull<T> {
+    type Target = T;
+
+    fn target(&self) -> *mut Self::Target {
+        self.as_ptr()
+    }
+}
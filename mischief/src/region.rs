@@ -1,4 +1,8 @@
-use ::heresy::alloc::Allocator;
+use ::core::{alloc::Layout, marker::PhantomData, ptr::NonNull};
+use ::heresy::alloc::{AllocError, Allocator};
+use ::ptr_meta::Pointee;
+
+use crate::{layout_of_val_raw, In, Metadata, Slot, Unique};
 
 /// A contiguous memory region.
 ///
@@ -32,3 +36,185 @@ pub unsafe trait RegionalAllocator: Allocator {
     /// The region type for this allocator.
     type Region: Region;
 }
+
+/// Extension methods for [`RegionalAllocator`].
+pub trait RegionalAllocatorExt: RegionalAllocator {
+    /// Attempts to allocate a block of memory, returning it in the allocator's
+    /// region.
+    ///
+    /// See [`Allocator::allocate`] for more details.
+    fn allocate_in(
+        &self,
+        layout: Layout,
+    ) -> Result<In<NonNull<[u8]>, Self::Region>, AllocError> {
+        let ptr = self.allocate(layout)?;
+        // SAFETY: `ptr` was allocated by a `RegionalAllocator`, so it is
+        // contained in its region.
+        Ok(unsafe { In::new_unchecked(ptr) })
+    }
+
+    /// Behaves like `allocate_in`, but also ensures that the returned memory
+    /// is zero-initialized.
+    ///
+    /// See [`Allocator::allocate_zeroed`] for more details.
+    fn allocate_zeroed_in(
+        &self,
+        layout: Layout,
+    ) -> Result<In<NonNull<[u8]>, Self::Region>, AllocError> {
+        let ptr = self.allocate_zeroed(layout)?;
+        // SAFETY: `ptr` was allocated by a `RegionalAllocator`, so it is
+        // contained in its region.
+        Ok(unsafe { In::new_unchecked(ptr) })
+    }
+}
+
+impl<A: RegionalAllocator + ?Sized> RegionalAllocatorExt for A {}
+
+/// The base address of a region.
+///
+/// This is the address of the start of the allocated object that corresponds
+/// to `R`. Every memory segment contained in `R` is located at or after it.
+pub struct RegionBase<R: Region> {
+    ptr: NonNull<u8>,
+    _phantom: PhantomData<R>,
+}
+
+impl<R: Region> Clone for RegionBase<R> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R: Region> Copy for RegionBase<R> {}
+
+impl<R: Region> RegionBase<R> {
+    /// Creates a new `RegionBase` from a pointer to the start of a region.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to the start of the allocated object that corresponds
+    /// to `R`.
+    #[inline]
+    pub unsafe fn new_unchecked(ptr: NonNull<u8>) -> Self {
+        Self {
+            ptr,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the base address of the region.
+    #[inline]
+    pub fn as_ptr(&self) -> *mut u8 {
+        self.ptr.as_ptr()
+    }
+
+    /// Returns the offset of the given pointer from the base of the region.
+    #[inline]
+    pub fn offset_of<T: ?Sized>(&self, ptr: In<*mut T, R>) -> usize {
+        let ptr = In::into_inner(ptr).cast::<u8>();
+        // SAFETY:
+        // - `ptr` is contained in `R`, so it is in bounds of the allocated
+        //   object that corresponds to `R`. The base is the start of that same
+        //   allocated object.
+        // - The size of a `u8` is 1, so the distance between the base and `ptr`
+        //   is always a multiple of it.
+        let offset = unsafe { ptr.offset_from(self.as_ptr()) };
+        // `ptr` is located at or after the start of the allocated object, so
+        // the offset is never negative.
+        offset.unsigned_abs()
+    }
+}
+
+/// A `RegionalAllocator` that can provide the base address of its region.
+///
+/// # Safety
+///
+/// `region_base` must return a `RegionBase` whose address is the start of the
+/// allocated object that corresponds to `Self::Region`.
+pub unsafe trait BaseAllocator: RegionalAllocator {
+    /// Returns the base address of the allocator's region.
+    fn region_base(&self) -> RegionBase<Self::Region>;
+}
+
+/// A region whose bounds are checked at runtime.
+///
+/// A runtime region is created by associating a fresh [`Unique`] value with an
+/// allocated object. The unique value stays borrowed for as long as the region
+/// exists, so no other runtime region can have the same type at the same time.
+/// Memory segments can then be checked against the bounds of the region with
+/// [`In::try_new`] to produce an `In` when the region cannot be proven
+/// statically.
+pub struct RuntimeRegion<'a, U> {
+    ptr: NonNull<u8>,
+    len: usize,
+    _phantom: PhantomData<(&'a mut U, &'a mut [u8])>,
+}
+
+impl<U> Clone for RuntimeRegion<'_, U> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<U> Copy for RuntimeRegion<'_, U> {}
+
+// SAFETY: A `RuntimeRegion` borrows a unique value for its lifetime, so only
+// one allocated object can be associated with its type at a time.
+unsafe impl<U: Unique> Region for RuntimeRegion<'_, U> {}
+
+impl<'a, U: Unique> RuntimeRegion<'a, U> {
+    /// Creates a new runtime region from some bytes and a unique value.
+    ///
+    /// Returns the region along with the bytes, which are contained in it.
+    pub fn new(
+        bytes: Slot<'a, [u8]>,
+        _: &'a mut U,
+    ) -> (Self, In<Slot<'a, [u8]>, Self>) {
+        let ptr = bytes.as_ptr();
+        let region = Self {
+            // SAFETY: `bytes` is a slot, so its pointer is always non-null.
+            ptr: unsafe { NonNull::new_unchecked(ptr.cast()) },
+            len: bytes.len(),
+            _phantom: PhantomData,
+        };
+        // SAFETY: The region's bounds are exactly those of `bytes`.
+        let bytes = unsafe { In::new_unchecked(bytes) };
+        (region, bytes)
+    }
+
+    /// Returns the number of bytes in the region.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the region is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the base address of the region.
+    #[inline]
+    pub fn base(&self) -> RegionBase<Self> {
+        // SAFETY: `ptr` is the start of the bytes associated with the region.
+        unsafe { RegionBase::new_unchecked(self.ptr) }
+    }
+
+    /// Returns whether the pointee of the given pointer is completely contained
+    /// in the region.
+    pub fn contains<T: Pointee + ?Sized>(&self, ptr: *const T) -> bool
+    where
+        T::Metadata: Metadata<T>,
+    {
+        let size = layout_of_val_raw(ptr).size();
+        let start = self.ptr.as_ptr().addr();
+        let addr = ptr.cast::<u8>().addr();
+        addr >= start
+            && addr
+                .checked_add(size)
+                .is_some_and(|end| end <= start + self.len)
+    }
+}
@@ -1,5 +1,11 @@
 //! Unique types and tools for constructing and using them.
 
+use ::core::{
+    cell::UnsafeCell,
+    mem::MaybeUninit,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
 mod ghost;
 mod r#static;
 mod token;
@@ -19,80 +25,154 @@ pub unsafe trait Unique {}
 // type must also be unique.
 unsafe impl<T: Unique> Unique for &mut T {}
 
+/// The shared state of the unique values created by [`split_unique!`].
+///
+/// This holds on to the split value until all of the values that it was split
+/// into have been dropped, then drops it.
+#[doc(hidden)]
+pub struct SplitState<T> {
+    parent: UnsafeCell<MaybeUninit<T>>,
+    remaining: AtomicUsize,
+}
+
+// SAFETY: The parent value is only ever written by the holder of the unique
+// parent and dropped by the last of its split values, so it is never accessed
+// from multiple threads simultaneously. It may be dropped on a different thread
+// than it was written from, so it must be `Send`.
+unsafe impl<T: Send> Sync for SplitState<T> {}
+
+impl<T> Default for SplitState<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SplitState<T> {
+    /// Returns a new `SplitState` with no split value.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            parent: UnsafeCell::new(MaybeUninit::uninit()),
+            remaining: AtomicUsize::new(0),
+        }
+    }
+
+    /// Stores the value being split until `count` split values are released.
+    ///
+    /// # Safety
+    ///
+    /// - All of the values split from a previous call to `split` must have
+    ///   been released.
+    /// - `count` must be the number of split values created, and must be
+    ///   nonzero.
+    #[inline]
+    pub unsafe fn split(&self, parent: T, count: usize) {
+        // SAFETY: The caller has guaranteed that all of the previously split
+        // values have been released, so no other thread can be accessing the
+        // parent.
+        unsafe {
+            (*self.parent.get()).write(parent);
+        }
+        self.remaining.store(count, Ordering::Release);
+    }
+
+    /// Releases one split value, dropping the split value if it was the last.
+    ///
+    /// # Safety
+    ///
+    /// This must be called exactly once for each split value, when it is
+    /// dropped.
+    #[inline]
+    pub unsafe fn release(&self) {
+        if self.remaining.fetch_sub(1, Ordering::AcqRel) == 1 {
+            // SAFETY: This was the last split value, so the parent was written
+            // by `split` and no other split value can access it.
+            unsafe {
+                (*self.parent.get()).assume_init_drop();
+            }
+        }
+    }
+}
+
 /// Splits a unique value into several others.
+///
+/// `split_unique!(fn split(In) -> (A, B))` defines a function `split` and a new
+/// zero-sized struct for each of `A` and `B`. Each struct implements [`Unique`]
+/// and can only be created by calling `split`, which consumes a value of `In`.
+///
+/// The split value is held in a static until all of the values it was split
+/// into have been dropped, and then it is dropped as well. After that, `split`
+/// can be called again with another value of `In`. Because the split value is
+/// stored in a static and may be dropped on a different thread than it was
+/// split on, `In` must implement `Send`.
 #[macro_export]
 macro_rules! split_unique {
-    (fn $fn:ident($in:ty) -> $out:ident) => {
-        $crate::split_unique!(@define $in => ($out,));
-        $crate::split_unique!(@impl $fn($in) -> $out);
-    };
-    (fn $fn:ident($in:ty) -> ($($out:tt)*)) => {
-        $crate::split_unique!(@define $in => ($($out)*));
-        $crate::split_unique!(@impl $fn($in) -> ($($out)*));
-    };
-    (pub fn $fn:ident($in:ty) -> $out:ident) => {
-        $crate::split_unique!(@define $in => ($out,) $($vis)*);
-        $crate::split_unique!(@impl $fn($in) -> $out pub);
-    };
-    (pub fn $fn:ident($in:ty) -> ($($out:tt)*)) => {
-        $crate::split_unique!(@define $in => ($($out)*) $($vis)*);
-        $crate::split_unique!(@impl $fn($in) -> ($($out)*) pub);
-    };
-    (pub($($vis:tt)*) fn $fn:ident($in:ty) -> $out:ident) => {
-        $crate::split_unique!(@define $in => ($out,) $($vis)*);
-        $crate::split_unique!(@impl $fn($in) -> $out pub($($vis)*));
-    };
-    (pub($($vis:tt)*) fn $fn:ident($in:ty) -> ($($out:tt)*)) => {
-        $crate::split_unique!(@define $in => ($($out)*) $($vis)*);
-        $crate::split_unique!(@impl $fn($in) -> ($($out)*) pub($($vis)*));
+    (@define $vis:vis $in:ty => $first:ident; $($out:ident)*) => {
+        $(
+            $vis struct $out(::core::marker::PhantomData<&'static mut $in>);
+
+            impl ::core::ops::Drop for $out {
+                #[inline]
+                fn drop(&mut self) {
+                    // SAFETY: Each split value is released exactly once when
+                    // it is dropped.
+                    unsafe {
+                        $first::__split_state().release();
+                    }
+                }
+            }
+
+            // SAFETY: `$out` can only be acquired by exchanging another
+            // `Unique` for it. That unique value is retained until all of the
+            // values it was split into are dropped, so the exchange can only be
+            // performed once at a time.
+            unsafe impl $crate::Unique for $out {}
+        )*
+
+        impl $first {
+            #[doc(hidden)]
+            #[inline]
+            fn __split_state() -> &'static $crate::SplitState<$in> {
+                static STATE: $crate::SplitState<$in> =
+                    $crate::SplitState::new();
+                &STATE
+            }
+        }
     };
+    ($vis:vis fn $fn:ident($in:ty) -> $out:ident) => {
+        $crate::split_unique!(@define $vis $in => $out; $out);
 
-    (@impl $fn:ident($in:ty) -> $out:ident $($vis:tt)*) => {
         #[inline]
-        $($vis)* fn $fn(unique: $in) -> $out {
-            // Forgetting is semantically equivalent to moving into static
-            // variable permanently.
-            ::core::mem::forget(unique);
+        $vis fn $fn(unique: $in) -> $out {
+            // SAFETY: Holding `unique` guarantees that all of the values split
+            // from it previously have been dropped. We create one split value.
+            unsafe {
+                $out::__split_state().split(unique, 1);
+            }
             $out(::core::marker::PhantomData)
         }
     };
-    (@impl $fn:ident($in:ty) -> ($($out:ident),*) $($vis:tt)*) => {
-        #[inline]
-        $($vis)* fn $fn(unique: $in) -> ($($out),*) {
-            // Forgetting is semantically equivalent to moving into static
-            // variable permanently.
-            ::core::mem::forget(unique);
-            ($(
-                $out(::core::marker::PhantomData)
-            ),*)
-        }
-    };
-    (@impl $fn:ident($in:ty) -> ($($out:ident,)*) $($vis:tt)*) => {
-        #[inline]
-        $($vis)* fn $fn(unique: $in) -> ($($out,)*) {
-            // Forgetting is semantically equivalent to moving into static
-            // variable permanently.
-            ::core::mem::forget(unique);
-            ($(
-                $out(::core::marker::PhantomData),
-            )*)
-        }
-    };
-
-    (@define $in:ty => () $($vis:tt)*) => {};
-    (@define
-        $in:ty => ($out_first:ident $(, $out_rest:ident)* $(,)?) $($vis:tt)*
+    (
+        $vis:vis fn $fn:ident($in:ty) ->
+            ($first:ident $(, $rest:ident)* $(,)?)
     ) => {
-        $($vis)* struct $out_first(
-            ::core::marker::PhantomData<&'static mut $in>,
-        );
+        $crate::split_unique!(@define $vis $in => $first; $first $($rest)*);
 
-        // SAFETY: `$out` can only be acquired by exchanging another `Unique`
-        // for it. That unique value is retained indefinitely, so the exchange
-        // can only ever be performed once.
-        unsafe impl $crate::Unique for $out_first {}
-
-        $crate::split_unique!(@define $in => ($($out_rest,)*) $($vis:tt)*);
+        #[inline]
+        $vis fn $fn(unique: $in) -> ($first, $($rest,)*) {
+            let count = [stringify!($first) $(, stringify!($rest))*].len();
+            // SAFETY: Holding `unique` guarantees that all of the values split
+            // from it previously have been dropped. We create `count` split
+            // values.
+            unsafe {
+                $first::__split_state().split(unique, count);
+            }
+            (
+                $first(::core::marker::PhantomData),
+                $($rest(::core::marker::PhantomData),)*
+            )
+        }
     };
 }
 
@@ -116,8 +196,33 @@ mod tests {
         let (bar, baz, bat): (Bar, Baz, Bat) = barify(Foo::acquire());
         assert!(matches!(Foo::try_acquire(), Err(_)));
 
-        let _ = (bar, baz, bat);
-
+        drop(bar);
+        drop(baz);
         assert!(matches!(Foo::try_acquire(), Err(_)));
+
+        drop(bat);
+        let (bar, baz, bat) = barify(Foo::acquire());
+        drop((bar, baz, bat));
+        assert!(matches!(Foo::try_acquire(), Ok(_)));
+    }
+
+    #[test]
+    fn split_unique_lease_static() {
+        use crate::{lease_static, runtime_token, StaticVal};
+
+        runtime_token!(Coin);
+        split_unique!(fn halve(Coin) -> (Heads, Tails));
+        lease_static!(Heads => HeadsValue: i32);
+        lease_static!(Tails => TailsValue: i32);
+
+        for _ in 0..2 {
+            let (mut heads, mut tails) = halve(Coin::acquire());
+            let mut heads_value = StaticVal::<HeadsValue>::new(&mut heads, 1);
+            let tails_value = StaticVal::<TailsValue>::new(&mut tails, 2);
+            *heads_value += *tails_value;
+            assert_eq!(*heads_value, 3);
+        }
+
+        assert!(matches!(Coin::try_acquire(), Ok(_)));
     }
 }
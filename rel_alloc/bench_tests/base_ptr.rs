@@ -0,0 +1,45 @@
+use ::mischief::{BaseAllocator, In, StaticToken};
+use ::rel_alloc::EmplaceIn;
+use ::rel_allocators::{prefix::Prefix, slab::Slab};
+use ::rel_core::{BasePtr, I32};
+use ::rel_util::Align16;
+
+#[test]
+fn region_base_round_trip() {
+    let mut bytes = Align16::frame(10_000);
+    let start = bytes.slot().as_bytes().as_ptr().cast::<u8>();
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        // The region starts at the beginning of the allocator's bytes.
+        let base = alloc.region_base();
+        assert_eq!(base.as_ptr().cast_const(), start);
+
+        let values =
+            [1, 2, 3].map(|value| In::new(value.emplace_in::<I32>(alloc)));
+        for (value, expected) in values.iter().zip([1, 2, 3]) {
+            let target = value.as_raw();
+            let ptr = (base, target).emplace_in::<BasePtr<I32, _>>(alloc);
+
+            // SAFETY: `target` and `start` are both located in the
+            // allocator's bytes.
+            let offset = unsafe {
+                In::into_inner(target).cast::<u8>().offset_from(start)
+            };
+            assert_eq!(ptr.offset(), usize::try_from(offset).unwrap());
+
+            // The pointer resolves to its target with a fresh region base.
+            let resolved = ptr.resolve(alloc.region_base());
+            assert_eq!(In::into_inner(resolved), In::into_inner(target));
+            // SAFETY: The target is initialized and only read while the
+            // `Ref` exists.
+            let resolved =
+                unsafe { BasePtr::as_ref(&ptr, alloc.region_base()) };
+            assert_eq!(resolved.to_ne(), expected);
+        }
+    });
+}
@@ -0,0 +1,136 @@
+use ::core::ptr::NonNull;
+use ::mischief::StaticToken;
+use ::rel_alloc::{vec, EmplaceIn, RelBox, RelVec};
+use ::rel_allocators::{
+    prefix::{Prefix, RelPrefix},
+    slab::Slab,
+};
+use ::rel_core::{clone::Clone, EmplaceError, I32};
+use ::rel_util::Align16;
+use ::situ::{ops::DerefMutRaw, Ref};
+
+type Boxes<A> = RelVec<RelBox<I32, A>, A>;
+
+fn contents<A: ::situ::alloc::RawRegionalAllocator>(
+    boxes: Ref<'_, Boxes<A>>,
+) -> Vec<i32> {
+    RelVec::iter(boxes)
+        .map(|b| RelBox::deref_in(b).to_ne())
+        .collect()
+}
+
+#[test]
+fn nested() {
+    let mut bytes = Align16::frame(10_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let mut original = vec::WithCapacity(alloc, 2)
+            .emplace_in::<Boxes<RelPrefix<Slab, _>>>(alloc);
+        for i in 0..5 {
+            RelVec::push(original.as_mut(), i.emplace_in::<I32>(alloc));
+        }
+
+        let mut copy = Clone(original.as_ref())
+            .emplace_in::<Boxes<RelPrefix<Slab, _>>>(alloc);
+        assert_eq!(contents(copy.as_ref()), [0, 1, 2, 3, 4]);
+        assert_eq!(copy.capacity(), copy.len());
+
+        // Each boxed value is cloned into a new allocation.
+        for (a, b) in
+            RelVec::iter(original.as_ref()).zip(RelVec::iter(copy.as_ref()))
+        {
+            assert_ne!(
+                RelBox::deref_in(a).ptr().as_ptr(),
+                RelBox::deref_in(b).ptr().as_ptr(),
+            );
+        }
+
+        for b in RelVec::iter_mut(original.as_mut()) {
+            let mut value = RelBox::deref_mut_raw(b);
+            *value = I32::from_ne(-value.to_ne());
+        }
+        RelVec::push(copy.as_mut(), 5.emplace_in::<I32>(alloc));
+        assert_eq!(contents(original.as_ref()), [0, -1, -2, -3, -4]);
+        assert_eq!(contents(copy.as_ref()), [0, 1, 2, 3, 4, 5]);
+
+        // Clones of clones are independent too.
+        let nested =
+            Clone(copy.as_ref()).emplace_in::<Boxes<RelPrefix<Slab, _>>>(alloc);
+        drop(copy);
+        assert_eq!(contents(nested.as_ref()), [0, 1, 2, 3, 4, 5]);
+    });
+}
+
+#[test]
+#[should_panic = "AllocFailed"]
+fn alloc_failure_panics() {
+    let mut bytes = Align16::frame(256);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let mut original = vec::WithCapacity(alloc, 40)
+            .emplace_in::<RelVec<I32, RelPrefix<Slab, _>>>(alloc);
+        for i in 0..40 {
+            RelVec::push(original.as_mut(), i);
+        }
+        // There is no room left for a second buffer, so cloning panics.
+        let _ = Clone(original.as_ref())
+            .emplace_in::<RelVec<I32, RelPrefix<Slab, _>>>(alloc);
+    });
+}
+
+#[test]
+fn alloc_failure_errors() {
+    let mut bytes = Align16::frame(256);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let mut original = vec::WithCapacity(alloc, 40)
+            .emplace_in::<RelVec<I32, RelPrefix<Slab, _>>>(alloc);
+        for i in 0..40 {
+            RelVec::push(original.as_mut(), i);
+        }
+        let result = Clone(original.as_ref())
+            .try_emplace_in::<RelVec<I32, RelPrefix<Slab, _>>>(alloc);
+        assert!(matches!(result, Err(EmplaceError::AllocFailed)));
+    });
+}
+
+#[test]
+fn empty_does_not_allocate() {
+    let mut bytes = Align16::frame(1_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let original =
+            vec::New(alloc).emplace_in::<Boxes<RelPrefix<Slab, _>>>(alloc);
+        let mut copy = Clone(original.as_ref())
+            .emplace_in::<Boxes<RelPrefix<Slab, _>>>(alloc);
+        assert!(copy.is_empty());
+        assert_eq!(copy.capacity(), 0);
+        assert_eq!(RelVec::as_ptr(copy.as_ref()), NonNull::dangling().as_ptr());
+
+        // The clone gets a buffer when it grows.
+        RelVec::push(copy.as_mut(), 1.emplace_in::<I32>(alloc));
+        RelVec::push(copy.as_mut(), 2.emplace_in::<I32>(alloc));
+        assert_eq!(contents(copy.as_ref()), [1, 2]);
+        assert!(contents(original.as_ref()).is_empty());
+    });
+}
@@ -0,0 +1,129 @@
+use ::core::cmp::Ordering;
+use ::mischief::StaticToken;
+use ::rel_alloc::{string, vec, EmplaceIn, RelBox, RelString, RelVec};
+use ::rel_allocators::{
+    prefix::{Prefix, RelPrefix},
+    slab::Slab,
+};
+use ::rel_core::I32;
+use ::rel_util::Align16;
+use ::situ::{
+    cmp::{OrdRaw, PartialEqRaw, PartialOrdRaw},
+    Ref,
+};
+
+fn eq_raw<T: PartialEqRaw>(a: Ref<'_, T>, b: Ref<'_, T>) -> bool {
+    PartialEqRaw::eq_raw(a, b)
+}
+
+fn cmp_raw<T: OrdRaw>(a: Ref<'_, T>, b: Ref<'_, T>) -> Ordering {
+    assert_eq!(
+        PartialOrdRaw::partial_cmp_raw(a, b),
+        Some(OrdRaw::cmp_raw(a, b))
+    );
+    OrdRaw::cmp_raw(a, b)
+}
+
+#[test]
+fn vecs() {
+    let mut bytes = Align16::frame(10_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        type Ints<A> = RelVec<I32, A>;
+        let make = |values: &[i32]| {
+            let mut v = vec::WithCapacity(alloc, 8)
+                .emplace_in::<Ints<RelPrefix<Slab, _>>>(alloc);
+            for &value in values {
+                RelVec::push(v.as_mut(), value);
+            }
+            v
+        };
+        let a = make(&[1, 2, 3]);
+        let b = vec::FromIter(alloc, [1, 2, 3].into_iter())
+            .emplace_in::<Ints<RelPrefix<Slab, _>>>(alloc);
+        let shorter = make(&[1, 2]);
+        let larger = make(&[1, 3]);
+        let empty = make(&[]);
+
+        // Vecs with the same elements are equal regardless of capacity.
+        assert_ne!(a.capacity(), b.capacity());
+        assert!(eq_raw(a.as_ref(), b.as_ref()));
+        assert_eq!(cmp_raw(a.as_ref(), b.as_ref()), Ordering::Equal);
+
+        // Otherwise they are ordered lexicographically.
+        assert!(!eq_raw(a.as_ref(), shorter.as_ref()));
+        assert_eq!(cmp_raw(a.as_ref(), shorter.as_ref()), Ordering::Greater);
+        assert_eq!(cmp_raw(a.as_ref(), larger.as_ref()), Ordering::Less);
+        assert_eq!(cmp_raw(empty.as_ref(), shorter.as_ref()), Ordering::Less);
+        assert!(eq_raw(empty.as_ref(), make(&[]).as_ref()));
+    });
+}
+
+#[test]
+fn strings() {
+    let mut bytes = Align16::frame(10_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let make = |s: &str| {
+            string::Clone(alloc, s)
+                .emplace_in::<RelString<RelPrefix<Slab, _>>>(alloc)
+        };
+        let apple = make("apple");
+        let banana = make("banana");
+
+        assert!(eq_raw(apple.as_ref(), make("apple").as_ref()));
+        assert!(!eq_raw(apple.as_ref(), banana.as_ref()));
+        assert_eq!(cmp_raw(apple.as_ref(), banana.as_ref()), Ordering::Less);
+        assert_eq!(
+            cmp_raw(apple.as_ref(), make("app").as_ref()),
+            Ordering::Greater
+        );
+        assert_eq!(cmp_raw(make("").as_ref(), apple.as_ref()), Ordering::Less);
+    });
+}
+
+#[test]
+fn boxes() {
+    let mut bytes = Align16::frame(10_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        type Boxes<A> = RelVec<RelBox<I32, A>, A>;
+        let mut boxes = vec::WithCapacity(alloc, 4)
+            .emplace_in::<Boxes<RelPrefix<Slab, _>>>(alloc);
+        for i in [1, 2, 1] {
+            RelVec::push(boxes.as_mut(), i.emplace_in::<I32>(alloc));
+        }
+        let b = |i| RelVec::iter(boxes.as_ref()).nth(i).unwrap();
+
+        // Boxes compare their contents, not their addresses.
+        assert!(eq_raw(b(0), b(2)));
+        assert!(!eq_raw(b(0), b(1)));
+        assert_eq!(cmp_raw(b(0), b(1)), Ordering::Less);
+        assert_eq!(cmp_raw(b(1), b(2)), Ordering::Greater);
+
+        // So vecs of boxes compare their boxed values element-wise.
+        let mut other = vec::WithCapacity(alloc, 4)
+            .emplace_in::<Boxes<RelPrefix<Slab, _>>>(alloc);
+        for i in [1, 2, 1] {
+            RelVec::push(other.as_mut(), i.emplace_in::<I32>(alloc));
+        }
+        assert!(eq_raw(boxes.as_ref(), other.as_ref()));
+        RelVec::push(other.as_mut(), 0.emplace_in::<I32>(alloc));
+        assert_eq!(cmp_raw(boxes.as_ref(), other.as_ref()), Ordering::Less);
+    });
+}
@@ -0,0 +1,101 @@
+use ::core::mem::MaybeUninit;
+use ::mischief::{GhostMut, Slot, StaticToken};
+use ::rel_alloc::{EmplaceIn, RelBox};
+use ::rel_allocators::{
+    container::{ContainerError, Header},
+    legacy::{RelSlabAllocator, SlabAllocator},
+};
+use ::rel_core::{DefaultBasis, I32, U32};
+use ::rel_util::Align16;
+use ::situ::ops::DerefRaw;
+
+type Root<'a, U> = RelBox<I32, RelSlabAllocator<'a, U>>;
+
+fn load(saved: &[u8]) -> Result<i32, ContainerError> {
+    let mut backing = Align16([0u8; 256]);
+    let bytes = &mut backing.0[..saved.len()];
+    bytes.copy_from_slice(saved);
+
+    StaticToken::acquire(|token| {
+        let alloc = SlabAllocator::<_>::load_from::<Root<'_, StaticToken>>(
+            bytes, token,
+        )?;
+        // SAFETY: The container was checked to have a root of type `Root`.
+        let root =
+            unsafe { alloc.withdraw_unchecked::<Root<'_, StaticToken>>() };
+        Ok(RelBox::deref_raw(root.unwrap().as_ref()).to_ne())
+    })
+}
+
+#[test]
+fn save_and_load() {
+    let mut backing = Align16(MaybeUninit::<[u8; 256]>::zeroed());
+    let mut saved = Vec::new();
+    StaticToken::acquire(|mut token| {
+        let bytes = Slot::new(&mut backing.0).unsize();
+        let alloc =
+            SlabAllocator::<_>::try_new_in(bytes, GhostMut::new(&mut token))
+                .unwrap();
+        let int = 42.emplace_in::<I32>(alloc);
+        let root = int.emplace_in::<Root<'_, _>>(alloc);
+        assert!(alloc.deposit(root).is_none());
+        // SAFETY: The backing memory was zeroed, and the deposited root is a
+        // `Root`.
+        unsafe {
+            alloc
+                .save_to::<Root<'_, GhostMut<'_, StaticToken>>, _>(&mut saved)
+                .unwrap()
+        };
+    });
+
+    assert_eq!(load(&saved), Ok(42));
+
+    let mut corrupted = saved.clone();
+    *corrupted.last_mut().unwrap() ^= 1;
+    assert_eq!(load(&corrupted), Err(ContainerError::ChecksumMismatch));
+
+    let mut bad_magic = saved.clone();
+    bad_magic[0] = b'X';
+    assert_eq!(load(&bad_magic), Err(ContainerError::BadMagic));
+
+    assert_eq!(
+        load(&saved[..saved.len() - 1]),
+        Err(ContainerError::TooShort)
+    );
+
+    // A root offset which is not aligned for the root type is rejected, even
+    // though the root would still lie within the payload.
+    let mut misaligned = saved.clone();
+    let header = Header::from_bytes(&saved).unwrap();
+    let root_offset = header.root_offset + 1;
+    misaligned[16..24].copy_from_slice(&root_offset.to_le_bytes());
+    assert_eq!(
+        Header::from_bytes(&misaligned)
+            .unwrap()
+            .check::<Root<'_, StaticToken>, DefaultBasis>(&misaligned),
+        Err(ContainerError::Invalid),
+    );
+    assert_eq!(load(&misaligned), Err(ContainerError::Invalid));
+
+    // A container with a different root type is rejected before its root is
+    // withdrawn.
+    let result = StaticToken::acquire(|token| {
+        let mut backing = Align16([0u8; 256]);
+        let bytes = &mut backing.0[..saved.len()];
+        bytes.copy_from_slice(&saved);
+        SlabAllocator::<_>::load_from::<U32>(bytes, token).map(|_| ())
+    });
+    assert_eq!(result, Err(ContainerError::SchemaMismatch));
+
+    // So is a container whose root only differs in the type it points to.
+    let result = StaticToken::acquire(|token| {
+        let mut backing = Align16([0u8; 256]);
+        let bytes = &mut backing.0[..saved.len()];
+        bytes.copy_from_slice(&saved);
+        SlabAllocator::<_>::load_from::<
+            RelBox<U32, RelSlabAllocator<'_, StaticToken>>,
+        >(bytes, token)
+        .map(|_| ())
+    });
+    assert_eq!(result, Err(ContainerError::SchemaMismatch));
+}
@@ -0,0 +1,100 @@
+//! A slab control which can grow its last allocation in place, for testing the
+//! in-place growth paths of containers.
+
+use ::core::{alloc::Layout, ptr::NonNull};
+use ::heresy::alloc::{AllocError, Allocator};
+use ::mischief::StaticToken;
+use ::ptr_meta::PtrExt;
+use ::rel_allocators::{prefix::Prefix, slab::Slab, Control};
+use ::rel_core::Portable;
+use ::rel_util::Align16;
+
+#[derive(Portable)]
+#[repr(transparent)]
+pub struct GrowSlab(Slab);
+
+unsafe impl Control for GrowSlab {
+    unsafe fn new(memory: NonNull<[u8]>) -> Self {
+        // SAFETY: The caller has guaranteed that `memory` is valid.
+        Self(unsafe { Slab::new(memory) })
+    }
+
+    unsafe fn allocate(
+        &self,
+        memory: NonNull<[u8]>,
+        layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: `memory` is the memory segment of this control, which is
+        // also the memory segment of the wrapped slab.
+        unsafe { self.0.allocate(memory, layout) }
+    }
+
+    unsafe fn deallocate(
+        &self,
+        memory: NonNull<[u8]>,
+        ptr: NonNull<u8>,
+        layout: Layout,
+    ) {
+        // SAFETY: The caller's guarantees for `memory`, `ptr`, and `layout`
+        // hold for the wrapped slab because it made every allocation.
+        unsafe { self.0.deallocate(memory, ptr, layout) }
+    }
+
+    unsafe fn grow_in_place(
+        &self,
+        memory: NonNull<[u8]>,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        let base = PtrExt::to_raw_parts(memory.as_ptr()).0 as usize;
+        let end = ptr.as_ptr() as usize - base + old_layout.size();
+        // Only the last allocation is followed by free memory, so it's the only
+        // one that can grow. It grows by allocating the bytes right after it.
+        if end != self.0.len()
+            || ptr.as_ptr() as usize & (new_layout.align() - 1) != 0
+        {
+            return Err(AllocError);
+        }
+        let extra =
+            Layout::from_size_align(new_layout.size() - old_layout.size(), 1)
+                .unwrap();
+        // SAFETY: `memory` is the memory segment of the wrapped slab. With an
+        // alignment of 1, the extra bytes start right at the end of `ptr`.
+        unsafe { self.0.allocate(memory, extra)? };
+        Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()))
+    }
+}
+
+#[test]
+fn grows_last_allocation() {
+    let mut bytes = Align16::frame(1_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<GrowSlab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let old = Layout::array::<u32>(4).unwrap();
+        let new = Layout::array::<u32>(8).unwrap();
+        let first = alloc.allocate(old).unwrap().cast::<u8>();
+        // SAFETY: `first` was allocated with `old`, which is smaller than
+        // `new`.
+        let grown = unsafe { alloc.grow_in_place(first, old, new) }.unwrap();
+        assert_eq!(grown.cast::<u8>(), first);
+        assert_eq!(grown.len(), new.size());
+
+        // The next allocation starts after the grown one.
+        let second = alloc.allocate(old).unwrap().cast::<u8>();
+        assert_eq!(second.as_ptr() as usize, first.as_ptr() as usize + 32);
+
+        // Only the last allocation can grow.
+        let huge = Layout::array::<u32>(1_000).unwrap();
+        // SAFETY: `first` is currently allocated with `new` and `second` with
+        // `old`, and each is grown to a larger layout.
+        assert!(unsafe { alloc.grow_in_place(first, new, huge) }.is_err());
+        assert!(unsafe { alloc.grow_in_place(second, old, huge) }.is_err());
+        assert!(unsafe { alloc.grow_in_place(second, old, new) }.is_ok());
+    });
+}
@@ -21,11 +21,11 @@ use ::rel_allocators::{
 };
 use ::rel_core::{Emplace, EmplaceExt, Move, Portable, U16, U64};
 use ::rel_util::Align16;
-use ::situ::{alloc::RawRegionalAllocator, DropRaw};
+use ::situ::{alloc::RawRegionalAllocator, fmt::DebugRaw, DropRaw};
 
 use crate::{benchmarks::*, from_data::FromData, gen::generate_vec};
 
-#[derive(DropRaw, Move, Portable)]
+#[derive(DebugRaw, DropRaw, Move, Portable)]
 #[repr(C)]
 pub struct RelAddress {
     pub x0: u8,
@@ -57,7 +57,7 @@ unsafe impl<R: Region> Emplace<RelAddress, R> for &'_ data::Address {
     }
 }
 
-#[derive(DropRaw, Move, Portable)]
+#[derive(DebugRaw, DropRaw, Move, Portable)]
 #[repr(C)]
 pub struct RelEntry<A: RawRegionalAllocator> {
     pub address: RelAddress,
@@ -107,7 +107,7 @@ where
     }
 }
 
-#[derive(DropRaw, Move, Portable)]
+#[derive(DebugRaw, DropRaw, Move, Portable)]
 #[repr(C)]
 pub struct RelLog<A: RawRegionalAllocator> {
     pub entries: RelVec<RelEntry<A>, A>,
@@ -0,0 +1,147 @@
+use ::core::mem::MaybeUninit;
+use ::mischief::{GhostMut, Slot, StaticToken};
+use ::rel_alloc::{
+    btree_set,
+    hash_set,
+    vec,
+    EmplaceIn,
+    RelBTreeSet,
+    RelBox,
+    RelHashSet,
+    RelVec,
+};
+use ::rel_allocators::legacy::{RelSlabAllocator, SlabAllocator};
+use ::rel_core::{
+    option::RelOption,
+    padding::{ZeroPadded, ZeroPadding},
+    rel_tuple::RelTuple2,
+    I32,
+};
+use ::rel_util::Align16;
+
+type Item = RelOption<RelTuple2<u8, I32>>;
+type Items<'a, U> = RelVec<Item, RelSlabAllocator<'a, U>>;
+type Root<'a, U> = RelBox<Items<'a, U>, RelSlabAllocator<'a, U>>;
+
+fn build(garbage: u8) -> Vec<u8> {
+    let mut backing = Align16(MaybeUninit::new([garbage; 256]));
+    let mut saved = Vec::new();
+    StaticToken::acquire(|mut token| {
+        let bytes = Slot::new(&mut backing.0).unsize();
+        let alloc =
+            SlabAllocator::<_>::try_new_in(bytes, GhostMut::new(&mut token))
+                .unwrap();
+
+        let mut items =
+            vec::WithCapacity(alloc, 4).emplace_in::<Items<'_, _>>(alloc);
+        for i in 0..3 {
+            let item = (i % 2 == 0).then_some((i, -i32::from(i)));
+            RelVec::push(items.as_mut(), ZeroPadded(item));
+        }
+        let mut root = items.emplace_in::<Root<'_, _>>(alloc);
+        ZeroPadding::zero_padding(root.as_mut());
+
+        assert!(alloc.deposit(root).is_none());
+        // SAFETY: The backing memory was initialized, and the deposited root
+        // is a `Root`.
+        unsafe {
+            alloc
+                .save_to::<Root<'_, GhostMut<'_, StaticToken>>, _>(&mut saved)
+                .unwrap()
+        };
+    });
+    saved
+}
+
+#[test]
+fn deterministic_bytes() {
+    let first = build(0xaa);
+    let second = build(0x55);
+    assert_eq!(first, second);
+}
+
+type BTreeItems<'a, U> = RelBTreeSet<I32, RelSlabAllocator<'a, U>>;
+type BTreeRoot<'a, U> = RelBox<BTreeItems<'a, U>, RelSlabAllocator<'a, U>>;
+
+fn build_btree_set(garbage: u8) -> Vec<u8> {
+    let mut backing = Align16(MaybeUninit::new([garbage; 1024]));
+    let mut saved = Vec::new();
+    StaticToken::acquire(|mut token| {
+        let bytes = Slot::new(&mut backing.0).unsize();
+        let alloc =
+            SlabAllocator::<_>::try_new_in(bytes, GhostMut::new(&mut token))
+                .unwrap();
+
+        // Enough values are inserted to split nodes, which leaves the moved
+        // keys behind in their unused key slots.
+        let mut set =
+            btree_set::New(alloc).emplace_in::<BTreeItems<'_, _>>(alloc);
+        for i in 0..20 {
+            RelBTreeSet::insert(set.as_mut(), i);
+        }
+        let mut root = set.emplace_in::<BTreeRoot<'_, _>>(alloc);
+        ZeroPadding::zero_padding(root.as_mut());
+
+        assert!(alloc.deposit(root).is_none());
+        // SAFETY: The backing memory was initialized, and the deposited root
+        // is a `BTreeRoot`.
+        unsafe {
+            alloc
+                .save_to::<BTreeRoot<'_, GhostMut<'_, StaticToken>>, _>(
+                    &mut saved,
+                )
+                .unwrap()
+        };
+    });
+    saved
+}
+
+#[test]
+fn deterministic_btree_set_bytes() {
+    let first = build_btree_set(0xaa);
+    let second = build_btree_set(0x55);
+    assert_eq!(first, second);
+}
+
+type HashItems<'a, U> = RelHashSet<I32, RelSlabAllocator<'a, U>>;
+type HashRoot<'a, U> = RelBox<HashItems<'a, U>, RelSlabAllocator<'a, U>>;
+
+fn build_hash_set(garbage: u8) -> Vec<u8> {
+    let mut backing = Align16(MaybeUninit::new([garbage; 512]));
+    let mut saved = Vec::new();
+    StaticToken::acquire(|mut token| {
+        let bytes = Slot::new(&mut backing.0).unsize();
+        let alloc =
+            SlabAllocator::<_>::try_new_in(bytes, GhostMut::new(&mut token))
+                .unwrap();
+
+        // The set has spare capacity, which holds the duplicate value while it
+        // is compared.
+        let mut set = hash_set::WithCapacity(alloc, 8)
+            .emplace_in::<HashItems<'_, _>>(alloc);
+        for i in [1, 2, 3, 2] {
+            RelHashSet::insert(set.as_mut(), i);
+        }
+        let mut root = set.emplace_in::<HashRoot<'_, _>>(alloc);
+        ZeroPadding::zero_padding(root.as_mut());
+
+        assert!(alloc.deposit(root).is_none());
+        // SAFETY: The backing memory was initialized, and the deposited root
+        // is a `HashRoot`.
+        unsafe {
+            alloc
+                .save_to::<HashRoot<'_, GhostMut<'_, StaticToken>>, _>(
+                    &mut saved,
+                )
+                .unwrap()
+        };
+    });
+    saved
+}
+
+#[test]
+fn deterministic_hash_set_bytes() {
+    let first = build_hash_set(0xaa);
+    let second = build_hash_set(0x55);
+    assert_eq!(first, second);
+}
@@ -0,0 +1,127 @@
+use ::core::{cell::Cell, mem::forget};
+use ::mischief::{In, Region, Slot, StaticToken};
+use ::ptr_meta::Pointee;
+use ::rel_alloc::{vec, EmplaceIn, RelVec};
+use ::rel_allocators::{
+    prefix::{Prefix, RelPrefix},
+    slab::Slab,
+};
+use ::rel_core::{clone::Clone, CloneRaw, Emplace, Move, Portable};
+use ::rel_util::Align16;
+use ::situ::{DropRaw, Mut, Ref};
+use ::std::panic::{catch_unwind, AssertUnwindSafe};
+
+const PANIC: u8 = u8::MAX;
+const CLONE_PANIC: u8 = u8::MAX - 1;
+
+::std::thread_local! {
+    static DROPS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn take_drops() -> usize {
+    DROPS.with(|drops| drops.replace(0))
+}
+
+#[derive(Move, Portable)]
+#[repr(C)]
+struct Counted(u8);
+
+impl DropRaw for Counted {
+    unsafe fn drop_raw(_: Mut<'_, Self>) {
+        DROPS.with(|drops| drops.set(drops.get() + 1));
+    }
+}
+
+unsafe impl<R: Region> CloneRaw<R> for Counted {
+    unsafe fn clone_raw_unchecked(
+        this: Ref<'_, Self>,
+        out: In<Slot<'_, Self>, R>,
+    ) {
+        assert_ne!(this.0, CLONE_PANIC);
+        In::into_inner(out).write(Counted(this.0));
+    }
+}
+
+struct MakeCounted(u8);
+
+unsafe impl<R: Region> Emplace<Counted, R> for MakeCounted {
+    fn emplaced_meta(&self) -> <Counted as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(self, out: In<Slot<'_, Counted>, R>) {
+        assert_ne!(self.0, PANIC);
+        In::into_inner(out).write(Counted(self.0));
+    }
+}
+
+#[test]
+fn extend_panic() {
+    let mut bytes = Align16::frame(1_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let mut v = vec::New(alloc)
+            .emplace_in::<RelVec<Counted, RelPrefix<Slab, _>>>(alloc);
+        let values = [0, 1, PANIC, 3].into_iter().map(MakeCounted);
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            RelVec::extend(v.as_mut(), values)
+        }));
+        assert!(result.is_err());
+        assert_eq!(v.len(), 2);
+
+        drop(v);
+        assert_eq!(take_drops(), 2);
+    });
+}
+
+#[test]
+fn from_iter_panic() {
+    let mut bytes = Align16::frame(1_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let values = [0, 1, PANIC, 3].into_iter().map(MakeCounted);
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            forget(
+                vec::FromIter(alloc, values)
+                    .emplace_in::<RelVec<Counted, RelPrefix<Slab, _>>>(alloc),
+            );
+        }));
+        assert!(result.is_err());
+        assert_eq!(take_drops(), 2);
+    });
+}
+
+#[test]
+fn clone_panic() {
+    let mut bytes = Align16::frame(1_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let values = [0, 1, CLONE_PANIC, 3].into_iter().map(MakeCounted);
+        let v = vec::FromIter(alloc, values)
+            .emplace_in::<RelVec<Counted, RelPrefix<Slab, _>>>(alloc);
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            forget(
+                Clone(v.as_ref())
+                    .emplace_in::<RelVec<Counted, RelPrefix<Slab, _>>>(alloc),
+            );
+        }));
+        assert!(result.is_err());
+        assert_eq!(take_drops(), 2);
+
+        drop(v);
+        assert_eq!(take_drops(), 4);
+    });
+}
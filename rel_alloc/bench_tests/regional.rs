@@ -0,0 +1,106 @@
+use ::core::alloc::Layout;
+use ::heresy::alloc::Allocator;
+use ::mischief::{In, RegionalAllocatorExt, StaticToken};
+use ::rel_alloc::{EmplaceIn, RelBox};
+use ::rel_allocators::{
+    prefix::{Prefix, RelPrefix},
+    slab::Slab,
+};
+use ::rel_core::I32;
+use ::rel_util::Align16;
+use ::situ::{
+    alloc::{RawAllocator, RawRegionalAllocatorExt},
+    OwnedValExt,
+};
+
+#[test]
+fn allocate_in() {
+    let mut bytes = Align16::frame(10_000);
+    StaticToken::acquire(|mut token| {
+        let (alloc, region) = Prefix::<Slab, _>::try_new_in_runtime_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+        let layout = Layout::new::<[u32; 4]>();
+
+        let ptr = In::into_inner(alloc.allocate_in(layout).unwrap());
+        assert!(region.contains(ptr.as_ptr()));
+        let zeroed = In::into_inner(alloc.allocate_zeroed_in(layout).unwrap());
+        assert!(region.contains(zeroed.as_ptr()));
+        // SAFETY: `zeroed` was just allocated with `layout`, so it is valid for
+        // reads of `layout.size()` bytes.
+        let contents = unsafe { zeroed.as_ref() };
+        assert!(contents.iter().all(|&b| b == 0));
+
+        // SAFETY: Both blocks were allocated by `alloc` with `layout`.
+        unsafe {
+            alloc.deallocate(ptr.cast(), layout);
+            alloc.deallocate(zeroed.cast(), layout);
+        }
+    });
+}
+
+#[test]
+fn raw_allocate_in() {
+    let mut bytes = Align16::frame(10_000);
+    StaticToken::acquire(|mut token| {
+        let (alloc, region) = Prefix::<Slab, _>::try_new_in_runtime_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+        let layout = Layout::new::<[u32; 4]>();
+
+        let rel = alloc.emplace_in::<RelPrefix<Slab, _>>(alloc);
+        let ptr = RelPrefix::raw_allocate_in(rel.as_ref(), layout).unwrap();
+        let ptr = In::into_inner(ptr);
+        assert!(region.contains(ptr.as_ptr()));
+        let zeroed =
+            RelPrefix::raw_allocate_zeroed_in(rel.as_ref(), layout).unwrap();
+        let zeroed = In::into_inner(zeroed);
+        assert!(region.contains(zeroed.as_ptr()));
+        // SAFETY: `zeroed` was just allocated with `layout`, so it is valid for
+        // reads of `layout.size()` bytes.
+        let contents = unsafe { zeroed.as_ref() };
+        assert!(contents.iter().all(|&b| b == 0));
+
+        // SAFETY: Both blocks were allocated by `rel` with `layout`.
+        unsafe {
+            RelPrefix::raw_deallocate(rel.as_ref(), ptr.cast(), layout);
+            RelPrefix::raw_deallocate(rel.as_ref(), zeroed.cast(), layout);
+        }
+    });
+}
+
+#[test]
+fn deref_in() {
+    let mut bytes = Align16::frame(10_000);
+    StaticToken::acquire(|mut token| {
+        let (alloc, region) = Prefix::<Slab, _>::try_new_in_runtime_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let mut value = 1.emplace_in::<I32>(alloc);
+        let value_ref = value.deref_in();
+        assert!(region.contains(value_ref.ptr().as_ptr()));
+        assert_eq!(value_ref.to_ne(), 1);
+        let mut value_mut = value.deref_mut_in();
+        assert!(region.contains(value_mut.ptr().as_ptr()));
+        *value_mut = I32::from_ne(2);
+        assert_eq!(value.to_ne(), 2);
+
+        let mut boxed = 3
+            .emplace_in::<I32>(alloc)
+            .emplace_in::<RelBox<I32, RelPrefix<Slab, _>>>(alloc);
+        let boxed_ref = RelBox::deref_in(boxed.as_ref());
+        assert!(region.contains(boxed_ref.ptr().as_ptr()));
+        assert_eq!(boxed_ref.to_ne(), 3);
+        let mut boxed_mut = RelBox::deref_mut_in(boxed.as_mut());
+        assert!(region.contains(boxed_mut.ptr().as_ptr()));
+        *boxed_mut = I32::from_ne(4);
+        assert_eq!(RelBox::deref_in(boxed.as_ref()).to_ne(), 4);
+    });
+}
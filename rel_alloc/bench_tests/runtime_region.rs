@@ -0,0 +1,67 @@
+use ::core::{alloc::Layout, cell::Cell, ptr::NonNull};
+use ::heresy::alloc::{AllocError, Allocator, Global};
+use ::mischief::{In, StaticToken};
+use ::rel_allocators::{
+    adapters::RuntimeChecked,
+    brand::Brand,
+    prefix::Prefix,
+    slab::Slab,
+};
+use ::rel_util::Align16;
+
+/// An allocator that counts its live allocations.
+#[derive(Clone, Copy)]
+struct Counting<'a> {
+    live: &'a Cell<usize>,
+}
+
+// SAFETY: `Counting` forwards every call to `Global`.
+unsafe impl Allocator for Counting<'_> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let ptr = Global.allocate(layout)?;
+        self.live.set(self.live.get() + 1);
+        Ok(ptr)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.live.set(self.live.get() - 1);
+        // SAFETY: `ptr` was allocated by `Global` with `layout`.
+        unsafe { Global.deallocate(ptr, layout) }
+    }
+}
+
+#[test]
+fn rejects_outside_allocations() {
+    let mut bytes = Align16::frame(10_000);
+    let live = Cell::new(0);
+    StaticToken::acquire(|mut token| {
+        let (alloc, region) = Prefix::<Slab, _>::try_new_in_runtime_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+        let layout = Layout::new::<u64>();
+
+        // Memory from the prefix allocator is located in its runtime region.
+        let inside =
+            Brand::new_runtime(alloc, region).allocate(layout).unwrap();
+        assert!(In::try_new(inside.as_ptr(), &region).is_some());
+
+        // Memory from anywhere else is not, so it is freed and rejected.
+        let counting = Counting { live: &live };
+        let checked = RuntimeChecked::new(counting, region);
+        assert!(checked.allocate(layout).is_err());
+        assert!(checked.allocate_zeroed(layout).is_err());
+        assert_eq!(live.get(), 0);
+
+        let branded = Brand::new_runtime(counting, region);
+        assert!(branded.allocate(layout).is_err());
+        assert_eq!(live.get(), 0);
+
+        let outside = counting.allocate(layout).unwrap();
+        assert!(In::try_new(outside.as_ptr(), &region).is_none());
+        // SAFETY: `outside` was just allocated by `counting` with `layout`.
+        unsafe { counting.deallocate(outside.cast(), layout) }
+        assert_eq!(live.get(), 0);
+    });
+}
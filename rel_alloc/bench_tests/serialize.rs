@@ -0,0 +1,176 @@
+use ::mischief::{In, RegionalAllocator, Slot, StaticToken};
+use ::munge::munge;
+use ::ptr_meta::Pointee;
+use ::rel_alloc::{
+    alloc::RelAllocator,
+    de::deserialize_in,
+    string,
+    vec,
+    EmplaceIn,
+    RelBox,
+    RelString,
+    RelVec,
+};
+use ::rel_allocators::{
+    prefix::{Prefix, RelPrefix},
+    slab::Slab,
+};
+use ::rel_core::{
+    option::RelOption,
+    rel_tuple::RelTuple3,
+    ser::{SerializeRaw, SerializeRef},
+    Emplace,
+    EmplaceExt,
+    Move,
+    Portable,
+    I32,
+};
+use ::rel_util::Align16;
+use ::situ::{alloc::RawRegionalAllocator, DropRaw};
+
+#[derive(DropRaw, Move, Portable, SerializeRaw)]
+#[repr(C)]
+struct RelItem<A: RawRegionalAllocator> {
+    name: RelString<A>,
+    counts: RelVec<I32, A>,
+    parent: RelOption<I32>,
+}
+
+struct Item<'a, A> {
+    alloc: A,
+    name: &'a str,
+    counts: &'a [i32],
+    parent: Option<i32>,
+}
+
+unsafe impl<E, A> Emplace<RelItem<E>, A::Region> for Item<'_, A>
+where
+    E: DropRaw + Move<A::Region> + RawRegionalAllocator<Region = A::Region>,
+    A: Clone + RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    fn emplaced_meta(&self) -> <RelItem<E> as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelItem<E>>, A::Region>,
+    ) {
+        munge!(let RelItem { name, counts, parent } = out);
+
+        string::Clone(self.alloc.clone(), self.name).emplace(name);
+        vec::FromIter(self.alloc, self.counts.iter().copied()).emplace(counts);
+        self.parent.emplace(parent);
+    }
+}
+
+#[test]
+fn serialize_json() {
+    let mut bytes = Align16::frame(1_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let item = Item {
+            alloc,
+            name: "widget",
+            counts: &[1, 2, 3],
+            parent: None,
+        }
+        .emplace_in::<RelItem<RelPrefix<Slab, _>>>(alloc);
+
+        let json =
+            ::serde_json::to_string(&SerializeRef(item.as_ref())).unwrap();
+        assert_eq!(json, r#"{"name":"widget","counts":[1,2,3],"parent":null}"#,);
+    });
+}
+
+#[test]
+fn deserialize_json() {
+    let mut bytes = Align16::frame(1_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let json = r#"[["a","bc"],["def"],[]]"#;
+        let mut deserializer = ::serde_json::Deserializer::from_str(json);
+        let strings = deserialize_in::<
+            RelVec<
+                RelVec<RelString<RelPrefix<Slab, _>>, RelPrefix<Slab, _>>,
+                RelPrefix<Slab, _>,
+            >,
+            _,
+            _,
+        >(&mut deserializer, alloc)
+        .unwrap();
+        assert_eq!(
+            ::serde_json::to_string(&SerializeRef(strings.as_ref())).unwrap(),
+            json,
+        );
+
+        let json = "[1,null,3]";
+        let mut deserializer = ::serde_json::Deserializer::from_str(json);
+        let boxed = deserialize_in::<
+            RelBox<
+                RelVec<RelOption<I32>, RelPrefix<Slab, _>>,
+                RelPrefix<Slab, _>,
+            >,
+            _,
+            _,
+        >(&mut deserializer, alloc)
+        .unwrap();
+        assert_eq!(
+            ::serde_json::to_string(&SerializeRef(boxed.as_ref())).unwrap(),
+            json,
+        );
+
+        let mut deserializer =
+            ::serde_json::Deserializer::from_str("[1,\"x\"]");
+        assert!(deserialize_in::<RelVec<I32, RelPrefix<Slab, _>>, _, _>(
+            &mut deserializer,
+            alloc,
+        )
+        .is_err());
+    });
+}
+
+#[test]
+fn deserialize_tuple_json() {
+    let mut bytes = Align16::frame(1_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        type Tuple<A> = RelTuple3<I32, RelString<A>, RelOption<RelBox<I32, A>>>;
+
+        let json = r#"[1,"two",3]"#;
+        let mut deserializer = ::serde_json::Deserializer::from_str(json);
+        let tuple = deserialize_in::<Tuple<RelPrefix<Slab, _>>, _, _>(
+            &mut deserializer,
+            alloc,
+        )
+        .unwrap();
+        assert_eq!(
+            ::serde_json::to_string(&SerializeRef(tuple.as_ref())).unwrap(),
+            json,
+        );
+
+        // Tuples with too few elements are rejected, and the fields that were
+        // already deserialized are dropped.
+        for json in [r#"[1,"two"]"#, r#"[1,"two",null,4]"#] {
+            let mut deserializer = ::serde_json::Deserializer::from_str(json);
+            assert!(deserialize_in::<Tuple<RelPrefix<Slab, _>>, _, _>(
+                &mut deserializer,
+                alloc,
+            )
+            .is_err());
+        }
+    });
+}
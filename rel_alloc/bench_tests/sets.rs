@@ -0,0 +1,273 @@
+use ::core::mem::MaybeUninit;
+use ::mischief::{GhostMut, Slot, StaticToken};
+use ::rand::Rng;
+use ::rel_alloc::{btree_set, hash_set, EmplaceIn, RelBTreeSet, RelHashSet};
+use ::rel_allocators::{
+    legacy::{RelSlabAllocator, SlabAllocator},
+    prefix::{Prefix, RelPrefix},
+    slab::Slab,
+};
+use ::rel_core::{EmplaceError, I32};
+use ::rel_util::Align16;
+use ::situ::{alloc::RawRegionalAllocator, Ref};
+use ::std::collections::BTreeSet;
+
+use crate::gen::default_rng;
+
+fn key(value: &I32) -> Ref<'_, I32> {
+    // SAFETY: `value` is a shared reference, so it is non-null, properly
+    // aligned, initialized, and not mutated for its lifetime.
+    unsafe { Ref::new_unchecked(value) }
+}
+
+fn hash_contents<A: RawRegionalAllocator>(
+    set: Ref<'_, RelHashSet<I32, A>>,
+) -> BTreeSet<i32> {
+    RelHashSet::iter(set).map(|value| value.to_ne()).collect()
+}
+
+fn btree_contents<A: RawRegionalAllocator>(
+    set: Ref<'_, RelBTreeSet<I32, A>>,
+) -> Vec<i32> {
+    RelBTreeSet::iter(set).map(|value| value.to_ne()).collect()
+}
+
+#[test]
+fn hash_set() {
+    let mut bytes = Align16::frame(100_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let mut set = hash_set::New(alloc)
+            .emplace_in::<RelHashSet<I32, RelPrefix<Slab, _>>>(alloc);
+        let mut expected = BTreeSet::new();
+        let mut rng = default_rng();
+        for _ in 0..500 {
+            let value = rng.gen_range(0..300);
+            assert_eq!(
+                RelHashSet::insert(set.as_mut(), value),
+                expected.insert(value),
+            );
+        }
+        for _ in 0..200 {
+            let value = rng.gen_range(0..300);
+            assert_eq!(
+                RelHashSet::remove(set.as_mut(), key(&I32::from_ne(value))),
+                expected.remove(&value),
+            );
+        }
+
+        assert_eq!(set.len(), expected.len());
+        assert_eq!(hash_contents(set.as_ref()), expected);
+        for value in 0..300 {
+            assert_eq!(
+                RelHashSet::contains(set.as_ref(), key(&I32::from_ne(value))),
+                expected.contains(&value),
+            );
+        }
+
+        RelHashSet::clear(set.as_mut());
+        assert!(set.is_empty());
+        assert!(!RelHashSet::contains(set.as_ref(), key(&I32::from_ne(1))));
+
+        let a = hash_set::FromIter(alloc, [1, 2, 3, 2, 4].into_iter())
+            .emplace_in::<RelHashSet<I32, RelPrefix<Slab, _>>>(alloc);
+        let b = hash_set::FromIter(alloc, [3, 4, 5].into_iter())
+            .emplace_in::<RelHashSet<I32, RelPrefix<Slab, _>>>(alloc);
+        assert_eq!(a.len(), 4);
+        assert_eq!(format!("{:?}", a.as_ref()), "{1, 2, 3, 4}");
+
+        let union = RelHashSet::union(a.as_ref(), b.as_ref(), alloc)
+            .emplace_in::<RelHashSet<I32, RelPrefix<Slab, _>>>(alloc);
+        assert_eq!(
+            hash_contents(union.as_ref()),
+            BTreeSet::from([1, 2, 3, 4, 5]),
+        );
+        let intersection =
+            RelHashSet::intersection(a.as_ref(), b.as_ref(), alloc)
+                .emplace_in::<RelHashSet<I32, RelPrefix<Slab, _>>>(alloc);
+        assert_eq!(
+            hash_contents(intersection.as_ref()),
+            BTreeSet::from([3, 4])
+        );
+        let difference =
+            RelHashSet::difference(a.as_ref(), b.as_ref(), alloc)
+                .emplace_in::<RelHashSet<I32, RelPrefix<Slab, _>>>(alloc);
+        assert_eq!(hash_contents(difference.as_ref()), BTreeSet::from([1, 2]));
+        let symmetric_difference =
+            RelHashSet::symmetric_difference(a.as_ref(), b.as_ref(), alloc)
+                .emplace_in::<RelHashSet<I32, RelPrefix<Slab, _>>>(alloc);
+        assert_eq!(
+            hash_contents(symmetric_difference.as_ref()),
+            BTreeSet::from([1, 2, 5]),
+        );
+    });
+}
+
+#[test]
+fn hash_set_duplicate_keeps_capacity() {
+    let mut bytes = Align16::frame(10_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let mut set = hash_set::WithCapacity(alloc, 4)
+            .emplace_in::<RelHashSet<I32, RelPrefix<Slab, _>>>(alloc);
+        for value in 0..3 {
+            assert!(RelHashSet::try_insert(set.as_mut(), value).unwrap());
+        }
+
+        // Inserting a value which is already present into a set with spare
+        // capacity does not reserve space.
+        let capacity = set.capacity();
+        assert!(!RelHashSet::try_insert(set.as_mut(), 2).unwrap());
+        assert_eq!(set.capacity(), capacity);
+
+        assert!(RelHashSet::try_insert(set.as_mut(), 3).unwrap());
+        assert_eq!(set.capacity(), capacity);
+        assert!(RelHashSet::try_insert(set.as_mut(), 4).unwrap());
+        assert!(set.capacity() > capacity);
+        assert_eq!(
+            hash_contents(set.as_ref()),
+            BTreeSet::from([0, 1, 2, 3, 4])
+        );
+    });
+}
+
+#[test]
+fn hash_set_table_alloc_fails() {
+    let mut backing = Align16(MaybeUninit::new([0u8; 124]));
+    StaticToken::acquire(|mut token| {
+        let bytes = Slot::new(&mut backing.0).unsize();
+        let alloc =
+            SlabAllocator::<_>::try_new_in(bytes, GhostMut::new(&mut token))
+                .unwrap();
+
+        let mut set = hash_set::WithCapacity(alloc, 4)
+            .emplace_in::<RelHashSet<I32, RelSlabAllocator<'_, _>>>(alloc);
+        for value in 0..4 {
+            assert!(RelHashSet::try_insert(set.as_mut(), value).unwrap());
+        }
+
+        // The elements can grow, but there is no space left for the larger
+        // table. The set stays full so that probing always finds an empty
+        // bucket.
+        assert_eq!(
+            RelHashSet::try_insert(set.as_mut(), 4),
+            Err(EmplaceError::AllocFailed),
+        );
+        assert_eq!(set.capacity(), 4);
+        for value in 4..8 {
+            assert!(RelHashSet::try_insert(set.as_mut(), value).is_err());
+        }
+        assert_eq!(set.len(), 4);
+        assert!(!RelHashSet::contains(set.as_ref(), key(&I32::from_ne(9))));
+        assert_eq!(hash_contents(set.as_ref()), BTreeSet::from([0, 1, 2, 3]));
+    });
+}
+
+#[test]
+fn btree_set() {
+    let mut bytes = Align16::frame(200_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let mut set = btree_set::New(alloc)
+            .emplace_in::<RelBTreeSet<I32, RelPrefix<Slab, _>>>(alloc);
+        assert!(!RelBTreeSet::remove(set.as_mut(), key(&I32::from_ne(1))));
+
+        // Enough values are inserted and removed to split and merge nodes
+        // across several levels of the tree.
+        let mut expected = BTreeSet::new();
+        let mut rng = default_rng();
+        for _ in 0..2_000 {
+            let value = rng.gen_range(0..1_000);
+            assert_eq!(
+                RelBTreeSet::insert(set.as_mut(), value),
+                expected.insert(value),
+            );
+        }
+        assert_eq!(
+            btree_contents(set.as_ref()),
+            expected.iter().copied().collect::<Vec<_>>(),
+        );
+        for _ in 0..2_000 {
+            let value = rng.gen_range(0..1_000);
+            assert_eq!(
+                RelBTreeSet::remove(set.as_mut(), key(&I32::from_ne(value))),
+                expected.remove(&value),
+            );
+        }
+
+        assert_eq!(set.len(), expected.len());
+        assert_eq!(RelBTreeSet::iter(set.as_ref()).len(), expected.len());
+        assert_eq!(
+            btree_contents(set.as_ref()),
+            expected.iter().copied().collect::<Vec<_>>(),
+        );
+        for value in 0..1_000 {
+            assert_eq!(
+                RelBTreeSet::contains(set.as_ref(), key(&I32::from_ne(value))),
+                expected.contains(&value),
+            );
+        }
+
+        for value in expected.iter().copied().collect::<Vec<_>>() {
+            assert!(RelBTreeSet::remove(
+                set.as_mut(),
+                key(&I32::from_ne(value))
+            ));
+        }
+        assert!(set.is_empty());
+        assert!(btree_contents(set.as_ref()).is_empty());
+
+        RelBTreeSet::insert(set.as_mut(), 1);
+        RelBTreeSet::clear(set.as_mut());
+        assert!(set.is_empty());
+        assert!(RelBTreeSet::insert(set.as_mut(), 2));
+        assert_eq!(btree_contents(set.as_ref()), [2]);
+
+        // Clearing a tree with internal nodes frees all of its nodes.
+        for value in 0..100 {
+            RelBTreeSet::insert(set.as_mut(), value);
+        }
+        RelBTreeSet::clear(set.as_mut());
+        assert!(set.is_empty());
+        assert!(RelBTreeSet::try_insert(set.as_mut(), 3).unwrap());
+        assert!(!RelBTreeSet::try_insert(set.as_mut(), 3).unwrap());
+        assert_eq!(btree_contents(set.as_ref()), [3]);
+
+        let a = btree_set::FromIter(alloc, [4, 2, 3, 2, 1].into_iter())
+            .emplace_in::<RelBTreeSet<I32, RelPrefix<Slab, _>>>(alloc);
+        let b = btree_set::FromIter(alloc, [5, 4, 3].into_iter())
+            .emplace_in::<RelBTreeSet<I32, RelPrefix<Slab, _>>>(alloc);
+        assert_eq!(a.len(), 4);
+        assert_eq!(format!("{:?}", a.as_ref()), "{1, 2, 3, 4}");
+
+        let union = RelBTreeSet::union(a.as_ref(), b.as_ref(), alloc)
+            .emplace_in::<RelBTreeSet<I32, RelPrefix<Slab, _>>>(alloc);
+        assert_eq!(btree_contents(union.as_ref()), [1, 2, 3, 4, 5]);
+        let intersection =
+            RelBTreeSet::intersection(a.as_ref(), b.as_ref(), alloc)
+                .emplace_in::<RelBTreeSet<I32, RelPrefix<Slab, _>>>(alloc);
+        assert_eq!(btree_contents(intersection.as_ref()), [3, 4]);
+        let difference = RelBTreeSet::difference(a.as_ref(), b.as_ref(), alloc)
+            .emplace_in::<RelBTreeSet<I32, RelPrefix<Slab, _>>>(alloc);
+        assert_eq!(btree_contents(difference.as_ref()), [1, 2]);
+        let symmetric_difference =
+            RelBTreeSet::symmetric_difference(a.as_ref(), b.as_ref(), alloc)
+                .emplace_in::<RelBTreeSet<I32, RelPrefix<Slab, _>>>(alloc);
+        assert_eq!(btree_contents(symmetric_difference.as_ref()), [1, 2, 5]);
+    });
+}
@@ -1,9 +1,25 @@
 pub mod benchmarks;
+mod base_ptr;
+mod clone;
+mod cmp;
+mod container;
 pub mod from_data;
 pub mod gen;
+mod grow_slab;
 mod log;
 mod mc_savedata;
 mod mesh;
+mod padding;
+mod panic_safety;
+mod regional;
+mod runtime_region;
+#[cfg(feature = "serde")]
+mod serialize;
+mod sets;
+mod transcode;
+mod vec;
+mod vec_deque;
+mod version;
 
 fn test_benchmarks<I>(mut benchmarks: benchmarks::Benchmarks<'_, I>) {
     for benchmark in benchmarks.benches {
@@ -0,0 +1,145 @@
+use ::core::{mem::forget, ptr::addr_of};
+use ::mischief::{In, RegionalAllocator, Slot, StaticToken};
+use ::munge::munge;
+use ::ptr_meta::Pointee;
+use ::rel_alloc::{
+    alloc::RelAllocator,
+    string,
+    vec,
+    EmplaceIn,
+    RelString,
+    RelVec,
+};
+use ::rel_allocators::{
+    prefix::{Prefix, RelPrefix},
+    slab::Slab,
+};
+use ::rel_core::{
+    option::RelOption,
+    transcode::{Endianness, Transcode, Transcoded, Transcoder},
+    Basis,
+    Basis16,
+    Basis64,
+    DefaultBasis,
+    Emplace,
+    EmplaceError,
+    EmplaceExt,
+    Portable,
+    I32,
+};
+use ::rel_util::Align16;
+use ::situ::{alloc::RawRegionalAllocator, DropRaw};
+
+#[derive(DropRaw, Portable, Transcode)]
+#[allocator = "A"]
+#[basis = "B"]
+#[repr(C)]
+struct RelRecord<A: RawRegionalAllocator, B: Basis> {
+    name: RelString<A, B>,
+    values: RelVec<I32, A, B>,
+    count: B::Usize,
+    parent: RelOption<I32>,
+}
+
+struct Record<'a, A> {
+    alloc: A,
+    name: &'a str,
+    values: &'a [i32],
+    parent: Option<i32>,
+}
+
+unsafe impl<E, A> Emplace<RelRecord<E, DefaultBasis>, A::Region>
+    for Record<'_, A>
+where
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    A: Clone + RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    fn emplaced_meta(
+        &self,
+    ) -> <RelRecord<E, DefaultBasis> as Pointee>::Metadata {
+    }
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelRecord<E, DefaultBasis>>, A::Region>,
+    ) {
+        munge!(let RelRecord { name, values, count, parent } = out);
+
+        string::Clone(self.alloc.clone(), self.name).emplace(name);
+        vec::FromIter(self.alloc, self.values.iter().copied()).emplace(values);
+        In::into_inner(count)
+            .write(DefaultBasis::from_native_usize(self.values.len()).unwrap());
+        self.parent.emplace(parent);
+    }
+}
+
+#[test]
+fn transcode_basis() {
+    let mut bytes = Align16::frame(1_000);
+    let mut target_bytes = Align16::frame(1_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+        let record = Record {
+            alloc,
+            name: "sensor",
+            values: &[1, -2, 3],
+            parent: Some(4),
+        }
+        .emplace_in::<RelRecord<RelPrefix<Slab, _>, DefaultBasis>>(alloc);
+
+        StaticToken::acquire(|mut token| {
+            let alloc = Prefix::<Slab, _>::try_new_in_region(
+                target_bytes.slot().as_bytes(),
+                &mut token,
+            )
+            .unwrap();
+
+            let transcoder =
+                Transcoder::<Basis64, _, RelPrefix<Slab, _>>::new(alloc);
+            let wide = Transcoded(record.as_ref(), &transcoder)
+                .try_emplace_in::<RelRecord<_, Basis64>>(alloc)
+                .unwrap();
+            munge!(let RelRecord { name, values, count, parent } = wide.as_ref());
+            assert_eq!(&*RelString::as_str(name), "sensor");
+            let values =
+                RelVec::iter(values).map(|v| v.to_ne()).collect::<Vec<_>>();
+            assert_eq!(values, [1, -2, 3]);
+            assert_eq!(count.to_ne(), 3);
+            assert!(matches!(*parent, RelOption::Some(p) if p.to_ne() == 4));
+
+            // The record does not fit in a 16-bit basis if its count is too
+            // large.
+            let mut record = record;
+            munge!(let RelRecord { mut count, .. } = record.as_mut());
+            *count = DefaultBasis::from_native_usize(70_000).unwrap();
+            let transcoder =
+                Transcoder::<Basis16, _, RelPrefix<Slab, _>>::new(alloc);
+            let result = Transcoded(record.as_ref(), &transcoder)
+                .try_emplace_in::<RelRecord<_, Basis16>>(alloc);
+            assert_eq!(result.err(), Some(EmplaceError::IntOverflow));
+
+            // SAFETY: The transcoded value is forgotten without being accessed.
+            let transcoder = unsafe {
+                Transcoder::<Basis64, _, RelPrefix<Slab, _>>::with_endianness(
+                    alloc,
+                    Endianness::Big,
+                )
+            };
+            let big = Transcoded(record.as_ref(), &transcoder)
+                .try_emplace_in::<RelRecord<_, Basis64>>(alloc)
+                .unwrap();
+            let big_ptr = big.as_ref().as_ptr();
+            // SAFETY: `big_ptr` points to an initialized `RelRecord`, so it is
+            // valid to project to its `count` field.
+            let count_ptr =
+                unsafe { addr_of!((*big_ptr).count) }.cast::<[u8; 8]>();
+            // SAFETY: `count_ptr` points to the `count` field of `big`.
+            assert_eq!(unsafe { count_ptr.read() }, 70_000u64.to_be_bytes());
+            forget(big);
+        });
+    });
+}
@@ -0,0 +1,200 @@
+use ::mischief::{Region, StaticToken};
+use ::rel_alloc::{vec, EmplaceIn, RelBox, RelVec};
+use ::rel_allocators::{
+    prefix::{Prefix, RelPrefix},
+    slab::Slab,
+};
+use ::rel_core::{
+    Basis16,
+    DefaultBasis,
+    EmplaceError,
+    Portable,
+    RelTaggedPtr,
+    I32,
+    U64,
+};
+use ::rel_util::Align16;
+use ::situ::alloc::RawRegionalAllocator;
+
+type Tagged<A> =
+    RelTaggedPtr<I32, <A as RawRegionalAllocator>::Region, DefaultBasis, 2>;
+type TaggedPtrs<A> = RelVec<Tagged<A>, A>;
+type Boxes<A> = RelVec<RelBox<I32, A>, A>;
+
+struct TestRegion;
+
+// SAFETY: `TestRegion` is only used to name the types of vectors, which are
+// never created.
+unsafe impl Region for TestRegion {}
+
+#[test]
+fn schema_hash_covers_elements() {
+    type Ints<T> = RelVec<T, RelPrefix<'static, Slab, TestRegion>>;
+
+    assert_ne!(
+        <Ints<I32> as Portable>::SCHEMA.hash(),
+        <Ints<U64> as Portable>::SCHEMA.hash(),
+    );
+    assert_eq!(
+        <Ints<I32> as Portable>::SCHEMA.hash(),
+        <Ints<I32> as Portable>::SCHEMA.hash(),
+    );
+}
+
+#[test]
+fn try_reserve_errors() {
+    let mut bytes = Align16::frame(100_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let mut v = vec::New(alloc)
+            .emplace_in::<RelVec<I32, RelPrefix<Slab, _>, Basis16>>(alloc);
+        RelVec::push(v.as_mut(), 1);
+        assert_eq!(
+            RelVec::try_reserve(v.as_mut(), usize::MAX),
+            Err(EmplaceError::CapacityOverflow),
+        );
+        assert_eq!(
+            RelVec::try_reserve(v.as_mut(), 70_000),
+            Err(EmplaceError::MetadataOverflow),
+        );
+        assert_eq!(
+            RelVec::try_reserve(v.as_mut(), 30_000),
+            Err(EmplaceError::AllocFailed),
+        );
+
+        // Any new buffer has to be placed after this one, which is too far
+        // away for a 16-bit offset to reach.
+        let _filler = vec::WithCapacity(alloc, 50_000)
+            .emplace_in::<RelVec<u8, RelPrefix<Slab, _>>>(alloc);
+        assert_eq!(
+            RelVec::try_reserve(v.as_mut(), 4),
+            Err(EmplaceError::OffsetOverflow),
+        );
+
+        // Failed reservations leave the vector unchanged.
+        assert_eq!(v.len(), 1);
+        assert_eq!(v.capacity(), 1);
+        assert_eq!(RelVec::as_slice(v.as_ref())[0].to_ne(), 1);
+    });
+}
+
+#[test]
+fn try_push_errors() {
+    let mut bytes = Align16::frame(10_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        type Nested<A> = RelVec<RelVec<I32, A>, A>;
+        let mut v = vec::WithCapacity(alloc, 2)
+            .emplace_in::<Nested<RelPrefix<Slab, _>>>(alloc);
+        assert_eq!(RelVec::try_push(v.as_mut(), vec::New(alloc)), Ok(()));
+
+        // Errors from emplacing the value are returned instead of panicking.
+        assert_eq!(
+            RelVec::try_push(v.as_mut(), vec::WithCapacity(alloc, usize::MAX)),
+            Err(EmplaceError::CapacityOverflow),
+        );
+        assert_eq!(
+            RelVec::try_push(v.as_mut(), vec::WithCapacity(alloc, 100_000)),
+            Err(EmplaceError::AllocFailed),
+        );
+        assert_eq!(v.len(), 1);
+
+        // So are errors from reserving space for it.
+        let mut bytes =
+            vec::New(alloc).emplace_in::<RelVec<u8, RelPrefix<Slab, _>>>(alloc);
+        RelVec::try_extend(bytes.as_mut(), (0..4_096).map(|i| i as u8))
+            .unwrap();
+        assert_eq!(
+            RelVec::try_extend(bytes.as_mut(), 0..100),
+            Err(EmplaceError::AllocFailed),
+        );
+        assert_eq!(bytes.len(), 4_096);
+        assert_eq!(
+            RelVec::try_push(bytes.as_mut(), 0),
+            Err(EmplaceError::AllocFailed),
+        );
+        assert_eq!(bytes.len(), 4_096);
+    });
+}
+
+#[test]
+fn try_emplace_in_errors() {
+    let mut bytes = Align16::frame(10_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec::WithCapacity(alloc, usize::MAX)
+                .try_emplace_in::<RelVec<I32, RelPrefix<Slab, _>>>(alloc)
+                .err(),
+            Some(EmplaceError::CapacityOverflow),
+        );
+        assert_eq!(
+            vec::WithCapacity(alloc, 70_000)
+                .try_emplace_in::<RelVec<u8, RelPrefix<Slab, _>, Basis16>>(
+                    alloc,
+                )
+                .err(),
+            Some(EmplaceError::MetadataOverflow),
+        );
+        assert_eq!(
+            vec::WithCapacity(alloc, 20_000)
+                .try_emplace_in::<RelVec<u8, RelPrefix<Slab, _>>>(alloc)
+                .err(),
+            Some(EmplaceError::AllocFailed),
+        );
+
+        let v = vec::FromIter(alloc, [1, 2, 3].into_iter())
+            .try_emplace_in::<RelVec<I32, RelPrefix<Slab, _>>>(alloc)
+            .unwrap();
+        assert_eq!(v.len(), 3);
+    });
+}
+
+#[test]
+fn grow_tagged_ptrs() {
+    let mut bytes = Align16::frame(10_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let mut targets = vec::WithCapacity(alloc, 10)
+            .emplace_in::<Boxes<RelPrefix<Slab, _>>>(alloc);
+        for i in 0..10 {
+            RelVec::push(targets.as_mut(), i.emplace_in::<I32>(alloc));
+        }
+        let mut v = vec::WithCapacity(alloc, 1)
+            .emplace_in::<TaggedPtrs<RelPrefix<Slab, _>>>(alloc);
+        for (i, target) in RelVec::iter_mut(targets.as_mut()).enumerate() {
+            let ptr = RelBox::deref_mut_in(target).as_raw();
+            RelVec::push(v.as_mut(), (ptr, i % 4));
+        }
+
+        // Growing the vector moves every tagged pointer, which must keep both
+        // its tag and its target.
+        assert!(v.capacity() >= 10);
+        for (i, ptr) in RelVec::iter(v.as_ref()).enumerate() {
+            assert_eq!(ptr.tag(), i % 4);
+            let target = RelTaggedPtr::as_ptr(ptr).unwrap();
+            // SAFETY: Each target is a boxed value that is still alive.
+            assert_eq!(unsafe { &*target }.to_ne(), i32::try_from(i).unwrap());
+        }
+    });
+}
@@ -0,0 +1,162 @@
+use ::core::alloc::Layout;
+use ::heresy::alloc::Allocator;
+use ::mischief::{In, Slot, StaticToken};
+use ::rel_alloc::{vec_deque, EmplaceIn, RelVecDeque};
+use ::rel_allocators::{
+    prefix::{Prefix, RelPrefix},
+    slab::Slab,
+};
+use ::rel_core::I32;
+use ::rel_util::Align16;
+use ::situ::{alloc::RawRegionalAllocator, ops::IndexRaw, Ref};
+
+use crate::grow_slab::GrowSlab;
+
+fn contents<A: RawRegionalAllocator>(
+    deque: Ref<'_, RelVecDeque<I32, A>>,
+) -> Vec<i32> {
+    RelVecDeque::iter(deque)
+        .map(|value| value.to_ne())
+        .collect()
+}
+
+#[test]
+fn ring_buffer() {
+    let mut bytes = Align16::frame(1_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let mut q = vec_deque::WithCapacity(alloc, 4)
+            .emplace_in::<RelVecDeque<I32, RelPrefix<Slab, _>>>(alloc);
+        assert!(q.is_empty());
+        assert!(RelVecDeque::pop_front(q.as_mut()).is_none());
+
+        for i in 1..=3 {
+            RelVecDeque::push_back(q.as_mut(), i);
+        }
+        let first = RelVecDeque::pop_front(q.as_mut()).unwrap();
+        assert_eq!(first.to_ne(), 1);
+        drop(first);
+        assert_eq!(RelVecDeque::pop_front(q.as_mut()).unwrap().to_ne(), 2);
+
+        // The back of the queue wraps around to the start of the buffer.
+        for i in 4..=6 {
+            RelVecDeque::push_back(q.as_mut(), i);
+        }
+        assert_eq!(q.capacity(), 4);
+        let (front, back) = RelVecDeque::as_slices(q.as_ref());
+        assert_eq!(front.len(), 2);
+        assert_eq!(back.len(), 2);
+        assert_eq!(contents(q.as_ref()), [3, 4, 5, 6]);
+        assert_eq!(IndexRaw::index_raw(q.as_ref(), 2).to_ne(), 5);
+        assert!(RelVecDeque::get(q.as_ref(), 4).is_none());
+
+        // A second allocation keeps the buffer from growing in place.
+        let mut other = vec_deque::New(alloc)
+            .emplace_in::<RelVecDeque<I32, RelPrefix<Slab, _>>>(alloc);
+        RelVecDeque::push_front(other.as_mut(), 7);
+        assert_eq!(contents(other.as_ref()), [7]);
+
+        RelVecDeque::push_front(q.as_mut(), 2);
+        assert!(q.capacity() > 4);
+        assert_eq!(contents(q.as_ref()), [2, 3, 4, 5, 6]);
+
+        assert_eq!(RelVecDeque::pop_back(q.as_mut()).unwrap().to_ne(), 6);
+        let out = alloc.allocate(Layout::new::<I32>()).unwrap();
+        let out_ptr = out.as_ptr().cast::<I32>();
+        // SAFETY: `out_ptr` was just allocated with the layout of an `I32`,
+        // and is not aliased.
+        let slot = unsafe { Slot::new_unchecked(out_ptr) };
+        // SAFETY: `out_ptr` was allocated by `alloc`, so it is located in its
+        // region.
+        let slot = unsafe { In::new_unchecked(slot) };
+        assert!(RelVecDeque::pop_back_into(q.as_mut(), slot));
+        // SAFETY: `pop_back_into` returned `true`, so `out_ptr` was
+        // initialized.
+        assert_eq!(unsafe { out_ptr.read() }.to_ne(), 5);
+
+        RelVecDeque::clear(q.as_mut());
+        assert!(q.is_empty());
+        RelVecDeque::push_back(q.as_mut(), 8);
+        assert_eq!(contents(q.as_ref()), [8]);
+    });
+}
+
+#[test]
+fn grow_wrapped_in_place() {
+    // The back of the queue fits after the old capacity, so it is moved there.
+    let mut bytes = Align16::frame(1_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<GrowSlab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        // The buffer of the queue is the last allocation, so it can grow in
+        // place.
+        let mut q = vec_deque::WithCapacity(alloc, 4)
+            .emplace_in::<RelVecDeque<I32, RelPrefix<GrowSlab, _>>>(alloc);
+        for i in 1..=4 {
+            RelVecDeque::push_back(q.as_mut(), i);
+        }
+        for _ in 0..3 {
+            RelVecDeque::pop_front(q.as_mut());
+        }
+        for i in 5..=7 {
+            RelVecDeque::push_back(q.as_mut(), i);
+        }
+        let (front, back) = RelVecDeque::as_slices(q.as_ref());
+        assert_eq!((front.len(), back.len()), (1, 3));
+        let buffer = back.as_ptr().cast::<I32>();
+
+        assert_eq!(RelVecDeque::try_push_back(q.as_mut(), 8), Ok(()));
+        assert_eq!(q.capacity(), 8);
+        assert_eq!(contents(q.as_ref()), [4, 5, 6, 7, 8]);
+        let (front, back) = RelVecDeque::as_slices(q.as_ref());
+        assert_eq!((front.len(), back.len()), (5, 0));
+        // SAFETY: The buffer has a capacity of 8, so the offset pointer is in
+        // bounds.
+        assert_eq!(front.as_ptr().cast::<I32>(), unsafe { buffer.add(3) });
+    });
+
+    // The back of the queue doesn't fit after the old capacity, so the front
+    // is moved to the new end of the buffer instead.
+    let mut bytes = Align16::frame(1_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<GrowSlab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+
+        let mut q = vec_deque::WithCapacity(alloc, 6)
+            .emplace_in::<RelVecDeque<I32, RelPrefix<GrowSlab, _>>>(alloc);
+        for i in 1..=6 {
+            RelVecDeque::push_back(q.as_mut(), i);
+        }
+        for _ in 0..3 {
+            RelVecDeque::pop_front(q.as_mut());
+        }
+        for i in 7..=9 {
+            RelVecDeque::push_back(q.as_mut(), i);
+        }
+        let (front, back) = RelVecDeque::as_slices(q.as_ref());
+        assert_eq!((front.len(), back.len()), (3, 3));
+        let buffer = back.as_ptr().cast::<I32>();
+
+        assert_eq!(RelVecDeque::try_push_front(q.as_mut(), 3), Ok(()));
+        assert_eq!(q.capacity(), 8);
+        assert_eq!(contents(q.as_ref()), [3, 4, 5, 6, 7, 8, 9]);
+        let (front, back) = RelVecDeque::as_slices(q.as_ref());
+        assert_eq!((front.len(), back.len()), (4, 3));
+        assert_eq!(back.as_ptr().cast::<I32>(), buffer);
+        // SAFETY: The buffer has a capacity of 8, so the offset pointer is in
+        // bounds.
+        assert_eq!(front.as_ptr().cast::<I32>(), unsafe { buffer.add(4) });
+    });
+}
@@ -0,0 +1,170 @@
+use ::mischief::{In, Region, RegionalAllocator, Slot, StaticToken};
+use ::munge::munge;
+use ::ptr_meta::Pointee;
+use ::rel_alloc::{
+    alloc::RelAllocator,
+    string,
+    EmplaceIn,
+    RelString,
+    RelVersionedBox,
+};
+use ::rel_allocators::{
+    prefix::{Prefix, RelPrefix},
+    slab::Slab,
+};
+use ::rel_core::{
+    transcode::{Transcode, Transcoder},
+    version::{RelVersioned, UpgradeVersioned, VersionedRef, WithVersion},
+    DefaultBasis,
+    Emplace,
+    EmplaceExt,
+    Portable,
+    I32,
+    U16,
+};
+use ::rel_util::Align16;
+use ::situ::{alloc::RawRegionalAllocator, DropRaw, Ref};
+
+#[derive(DropRaw, Portable, Transcode)]
+#[repr(C)]
+#[version = "1"]
+struct SettingsV1 {
+    volume: U16,
+}
+
+#[derive(DropRaw, Portable, Transcode)]
+#[repr(C)]
+#[version = "2"]
+struct Settings {
+    volume: U16,
+    #[since = "2"]
+    #[default_value = "true"]
+    muted: bool,
+}
+
+#[derive(DropRaw, Portable, Transcode)]
+#[allocator = "A"]
+#[repr(C)]
+#[version = "1"]
+struct ProfileV1<A: RawRegionalAllocator> {
+    name: RelString<A>,
+    settings: RelVersionedBox<SettingsV1, A>,
+}
+
+#[derive(DropRaw, Portable, Transcode)]
+#[allocator = "A"]
+#[repr(C)]
+#[version = "2"]
+struct Profile<A: RawRegionalAllocator> {
+    name: RelString<A>,
+    settings: RelVersionedBox<Settings, A>,
+    #[since = "2"]
+    #[default_value = "7i32"]
+    level: I32,
+}
+
+struct NewSettingsV1(u16);
+
+unsafe impl<R: Region> Emplace<SettingsV1, R> for NewSettingsV1 {
+    fn emplaced_meta(&self) -> <SettingsV1 as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, SettingsV1>, R>,
+    ) {
+        munge!(let SettingsV1 { volume } = out);
+        self.0.emplace(volume);
+    }
+}
+
+struct NewProfileV1<'a, A> {
+    alloc: A,
+    name: &'a str,
+    volume: u16,
+}
+
+unsafe impl<E, A> Emplace<ProfileV1<E>, A::Region> for NewProfileV1<'_, A>
+where
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    A: Clone + RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    fn emplaced_meta(&self) -> <ProfileV1<E> as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, ProfileV1<E>>, A::Region>,
+    ) {
+        munge!(let ProfileV1 { name, settings } = out);
+
+        string::Clone(self.alloc.clone(), self.name).emplace(name);
+        WithVersion(NewSettingsV1(self.volume))
+            .emplace_in::<RelVersioned<SettingsV1>>(self.alloc)
+            .emplace(settings);
+    }
+}
+
+/// Reads a profile written by version 1 as the current version.
+fn as_current<'a, A: RawRegionalAllocator + DropRaw + Portable>(
+    old: Ref<'a, RelVersioned<ProfileV1<A>>>,
+) -> VersionedRef<'a, Profile<A>> {
+    let ptr = old.as_ptr().cast::<RelVersioned<Profile<A>>>();
+    // SAFETY: `ptr` points to a `RelVersioned<Profile>` written by version 1
+    // which is valid for reads of the layout of version 1. `old` is a shared
+    // reference, so it is not mutably aliased for `'a`.
+    unsafe { RelVersioned::get_raw(ptr) }
+}
+
+#[test]
+fn migrate_into_new_region() {
+    let mut bytes = Align16::frame(1_000);
+    let mut target_bytes = Align16::frame(1_000);
+    StaticToken::acquire(|mut token| {
+        let alloc = Prefix::<Slab, _>::try_new_in_region(
+            bytes.slot().as_bytes(),
+            &mut token,
+        )
+        .unwrap();
+        let old = WithVersion(NewProfileV1 {
+            alloc,
+            name: "player",
+            volume: 5,
+        })
+        .emplace_in::<RelVersioned<ProfileV1<RelPrefix<Slab, _>>>>(alloc);
+
+        // A profile written by version 1 is read as the current version. Its
+        // settings were also written by version 1.
+        let from = as_current(old.as_ref());
+        assert_eq!(VersionedRef::version(from), 1);
+        assert!(Profile::versioned_level(from).is_none());
+
+        StaticToken::acquire(|mut token| {
+            let (alloc, region) = Prefix::<Slab, _>::try_new_in_runtime_region(
+                target_bytes.slot().as_bytes(),
+                &mut token,
+            )
+            .unwrap();
+
+            let transcoder =
+                Transcoder::<DefaultBasis, _, RelPrefix<Slab, _>>::new(alloc);
+            let new = UpgradeVersioned(from, &transcoder)
+                .try_emplace_in::<RelVersioned<Profile<_>>>(alloc)
+                .unwrap();
+            assert_eq!(RelVersioned::version(new.as_ref()), 2);
+
+            let profile =
+                VersionedRef::get(RelVersioned::get(new.as_ref())).unwrap();
+            munge!(let Profile { name, settings, level } = profile);
+            assert_eq!(&*RelString::as_str(name), "player");
+            assert_eq!(level.to_ne(), 7);
+
+            // The settings were migrated into the new region as well.
+            assert_eq!(RelVersionedBox::version(settings), 2);
+            let settings = RelVersionedBox::get(settings);
+            let settings = VersionedRef::get(settings).unwrap();
+            assert!(region.contains(settings.as_ptr()));
+            munge!(let Settings { volume, muted } = settings);
+            assert_eq!(volume.to_ne(), 5);
+            assert!(*muted);
+        });
+    });
+}
@@ -1,23 +1,39 @@
 //! A pointer type for heap allocation.
 
-use ::core::{alloc::Layout, fmt, mem::MaybeUninit};
+use ::core::{
+    alloc::Layout,
+    cmp::Ordering,
+    fmt,
+    hash::Hasher,
+    mem::{offset_of, size_of, MaybeUninit},
+};
 use ::mischief::{In, RegionalAllocator, Slot};
 use ::munge::munge;
-use ::ptr_meta::Pointee;
+use ::ptr_meta::{from_raw_parts_mut, metadata, Pointee};
 use ::rel_core::{
+    clone,
+    padding::{zero_gaps, ZeroPadding},
+    transcode::{Transcode, Transcoder},
     Basis,
     BasisPointee,
+    CloneRaw,
     DefaultBasis,
     Emplace,
+    EmplaceError,
     EmplaceExt,
     Move,
     Portable,
     RelPtr,
+    TryEmplace,
+    TryEmplaceExt,
 };
 use ::situ::{
     alloc::RawRegionalAllocator,
+    cmp::{EqRaw, OrdRaw, PartialEqRaw, PartialOrdRaw},
     fmt::{DebugRaw, DisplayRaw},
+    hash::HashRaw,
     ops::{DerefMutRaw, DerefRaw, IndexMutRaw, IndexRaw},
+    slice::{self, IterMutRaw, IterRaw},
     DropRaw,
     Mut,
     OwnedVal,
@@ -25,7 +41,7 @@ use ::situ::{
     Val,
 };
 
-use crate::alloc::RelAllocator;
+use crate::{alloc::RelAllocator, EmplaceIn};
 
 /// A relative counterpart to `Box`.
 #[derive(Move, Portable)]
@@ -86,6 +102,70 @@ where
         munge!(let RelBox { alloc, .. } = this);
         alloc
     }
+
+    /// Returns a `Ref` of the boxed value in its allocator's region.
+    #[inline]
+    pub fn deref_in(this: Ref<'_, Self>) -> In<Ref<'_, T>, A::Region> {
+        // SAFETY: The boxed value is allocated in `A`, so it is located in
+        // `A::Region`.
+        unsafe { In::new_unchecked(DerefRaw::deref_raw(this)) }
+    }
+
+    /// Returns a `Mut` of the boxed value in its allocator's region.
+    #[inline]
+    pub fn deref_mut_in(this: Mut<'_, Self>) -> In<Mut<'_, T>, A::Region> {
+        // SAFETY: The boxed value is allocated in `A`, so it is located in
+        // `A::Region`.
+        unsafe { In::new_unchecked(DerefMutRaw::deref_mut_raw(this)) }
+    }
+
+    /// Emplaces a `RelBox` which owns the value at `ptr` into `out`.
+    ///
+    /// If this returns `Err`, `out` is not initialized and the value at `ptr`
+    /// is still owned by the caller.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must point to an initialized value which was allocated by the
+    ///   allocator that `alloc` emplaces.
+    /// - The layout used to allocate `ptr` must exactly match the return value
+    ///   of `Layout::for_value`.
+    #[cfg(feature = "serde")]
+    pub(crate) unsafe fn try_emplace_raw_parts<E>(
+        ptr: In<*mut T, A::Region>,
+        alloc: E,
+        out: In<Slot<'_, Self>, A::Region>,
+    ) -> Result<(), EmplaceError>
+    where
+        A: DropRaw,
+        E: Emplace<A, A::Region>,
+    {
+        munge!(let RelBox { ptr: out_ptr, alloc: out_alloc } = out);
+
+        ptr.try_emplace(out_ptr)?;
+        alloc.emplace(out_alloc);
+
+        Ok(())
+    }
+}
+
+impl<T, A, B> RelBox<[T], A, B>
+where
+    A: RawRegionalAllocator,
+    B: Basis,
+{
+    /// Returns an iterator over the elements of the boxed slice.
+    #[inline]
+    pub fn iter(this: Ref<'_, Self>) -> IterRaw<'_, T> {
+        slice::iter(DerefRaw::deref_raw(this))
+    }
+
+    /// Returns an iterator that allows modifying each element of the boxed
+    /// slice.
+    #[inline]
+    pub fn iter_mut(this: Mut<'_, Self>) -> IterMutRaw<'_, T> {
+        slice::iter_mut(DerefMutRaw::deref_mut_raw(this))
+    }
 }
 
 impl<T, A, B> DerefRaw for RelBox<T, A, B>
@@ -155,6 +235,92 @@ where
     }
 }
 
+#[cfg(feature = "serde")]
+impl<T, A, B> ::rel_core::ser::SerializeRaw for RelBox<T, A, B>
+where
+    T: BasisPointee<B> + ::rel_core::ser::SerializeRaw + ?Sized,
+    A: RawRegionalAllocator,
+    B: Basis,
+{
+    fn serialize_raw<S: ::rel_core::export::serde::Serializer>(
+        this: Ref<'_, Self>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        ::rel_core::ser::SerializeRaw::serialize_raw(
+            DerefRaw::deref_raw(this),
+            serializer,
+        )
+    }
+}
+
+impl<T, U, A1, A2, B1, B2> PartialEqRaw<RelBox<U, A2, B2>> for RelBox<T, A1, B1>
+where
+    T: BasisPointee<B1> + PartialEqRaw<U> + ?Sized,
+    U: BasisPointee<B2> + ?Sized,
+    A1: RawRegionalAllocator,
+    A2: RawRegionalAllocator,
+    B1: Basis,
+    B2: Basis,
+{
+    fn eq_raw(this: Ref<'_, Self>, other: Ref<'_, RelBox<U, A2, B2>>) -> bool {
+        PartialEqRaw::eq_raw(
+            DerefRaw::deref_raw(this),
+            DerefRaw::deref_raw(other),
+        )
+    }
+}
+
+impl<T, A, B> EqRaw for RelBox<T, A, B>
+where
+    T: BasisPointee<B> + EqRaw + ?Sized,
+    A: RawRegionalAllocator,
+    B: Basis,
+{
+}
+
+impl<T, U, A1, A2, B1, B2> PartialOrdRaw<RelBox<U, A2, B2>>
+    for RelBox<T, A1, B1>
+where
+    T: BasisPointee<B1> + PartialOrdRaw<U> + ?Sized,
+    U: BasisPointee<B2> + ?Sized,
+    A1: RawRegionalAllocator,
+    A2: RawRegionalAllocator,
+    B1: Basis,
+    B2: Basis,
+{
+    fn partial_cmp_raw(
+        this: Ref<'_, Self>,
+        other: Ref<'_, RelBox<U, A2, B2>>,
+    ) -> Option<Ordering> {
+        PartialOrdRaw::partial_cmp_raw(
+            DerefRaw::deref_raw(this),
+            DerefRaw::deref_raw(other),
+        )
+    }
+}
+
+impl<T, A, B> OrdRaw for RelBox<T, A, B>
+where
+    T: BasisPointee<B> + OrdRaw + ?Sized,
+    A: RawRegionalAllocator,
+    B: Basis,
+{
+    fn cmp_raw(this: Ref<'_, Self>, other: Ref<'_, Self>) -> Ordering {
+        OrdRaw::cmp_raw(DerefRaw::deref_raw(this), DerefRaw::deref_raw(other))
+    }
+}
+
+impl<T, A, B> HashRaw for RelBox<T, A, B>
+where
+    T: BasisPointee<B> + HashRaw + ?Sized,
+    A: RawRegionalAllocator,
+    B: Basis,
+{
+    fn hash_raw<H: Hasher>(this: Ref<'_, Self>, state: &mut H) {
+        HashRaw::hash_raw(DerefRaw::deref_raw(this), state)
+    }
+}
+
 impl<T, A, B, Idx> IndexRaw<Idx> for RelBox<T, A, B>
 where
     T: BasisPointee<B> + IndexRaw<Idx> + ?Sized,
@@ -204,6 +370,150 @@ where
     }
 }
 
+// SAFETY: `clone_raw_unchecked` initializes its `out` parameter by allocating
+// new memory, cloning the boxed value into it, and emplacing to each field.
+unsafe impl<T, A, B> CloneRaw<A::Region> for RelBox<T, A, B>
+where
+    T: BasisPointee<B> + CloneRaw<A::Region> + ?Sized,
+    A: RawRegionalAllocator + CloneRaw<A::Region>,
+    B: Basis,
+{
+    /// # Panics
+    ///
+    /// Panics if the allocator fails to allocate memory for the cloned value.
+    unsafe fn clone_raw_unchecked(
+        this: Ref<'_, Self>,
+        out: In<Slot<'_, Self>, A::Region>,
+    ) {
+        let value = DerefRaw::deref_raw(this);
+        let alloc = Self::allocator(this);
+
+        let data = A::raw_allocate(alloc, Layout::for_value(&*value))
+            .unwrap()
+            .as_ptr()
+            .cast::<()>();
+        let ptr = from_raw_parts_mut::<T>(data, metadata(value.as_ptr()));
+        // SAFETY:
+        // - `ptr` was returned from `raw_allocate`, so it must be non-null. It
+        //   is guaranteed to be properly aligned and valid for reads and writes
+        //   of the layout of `value`, which has the same metadata as `ptr`.
+        // - `ptr` is freshly-allocated, so it is not currently aliased by any
+        //   other pointers.
+        let slot = unsafe { Slot::new_unchecked(ptr) };
+        // SAFETY: `ptr` is allocated in `alloc`, and since `A` implements
+        // `RawRegionalAllocator`, it guarantees that memory it allocates is
+        // located in its region.
+        let slot = unsafe { In::new_unchecked(slot) };
+        // SAFETY: `slot` was created with the metadata of `value`, which is the
+        // metadata returned by `emplaced_meta`.
+        unsafe {
+            clone::Clone(value).emplace_unsized_unchecked(slot);
+        }
+        // SAFETY: `ptr` is allocated in `alloc`, and since `A` implements
+        // `RawRegionalAllocator`, it guarantees that memory it allocates is
+        // located in its region.
+        let ptr = unsafe { In::new_unchecked(ptr) };
+
+        munge!(let RelBox { ptr: out_ptr, alloc: out_alloc } = out);
+
+        ptr.emplace(out_ptr);
+        clone::Clone(alloc).emplace(out_alloc);
+    }
+}
+
+// SAFETY: `transcode` initializes its `out` parameter by allocating a new
+// value, transcoding and encoding the boxed value into it, and emplacing to
+// each field.
+unsafe impl<T, E1, B1, B2, A, E2> Transcode<B2, A, E2> for RelBox<T, E1, B1>
+where
+    T: Transcode<B2, A, E2>,
+    E1: RawRegionalAllocator + DropRaw,
+    B1: Basis,
+    B2: Basis,
+    A: Clone + RegionalAllocator + RelAllocator<E2, A::Region>,
+    E2: RawRegionalAllocator<Region = A::Region> + DropRaw,
+    RelBox<T::Target, E2, B2>: Portable,
+{
+    type Target = RelBox<T::Target, E2, B2>;
+
+    fn transcode(
+        this: Ref<'_, Self>,
+        transcoder: &Transcoder<B2, A, E2>,
+        out: In<Slot<'_, Self::Target>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        let ptr = transcoder
+            .alloc()
+            .allocate(Layout::new::<T::Target>())
+            .map_err(|_| EmplaceError::AllocFailed)?
+            .as_ptr()
+            .cast::<T::Target>();
+        // SAFETY:
+        // - `ptr` was returned from `allocate`, so it must be non-null. It is
+        //   guaranteed to be properly aligned and valid for reads and writes of
+        //   a `T::Target`.
+        // - `ptr` is freshly-allocated, so it is not currently aliased by any
+        //   other pointers.
+        let slot = unsafe { Slot::new_unchecked(ptr) };
+        // SAFETY: `ptr` is allocated in `transcoder.alloc()`, and since `A`
+        // implements `RegionalAllocator`, it guarantees that memory it
+        // allocates is located in its region.
+        let slot = unsafe { In::new_unchecked(slot) };
+        T::transcode(DerefRaw::deref_raw(this), transcoder, slot)?;
+        // SAFETY: The boxed value was just transcoded and is not accessed
+        // again.
+        unsafe {
+            transcoder.encode(ptr);
+        }
+        // SAFETY: `ptr` is allocated in `transcoder.alloc()`, and since `A`
+        // implements `RegionalAllocator`, it guarantees that memory it
+        // allocates is located in its region.
+        let ptr = unsafe { In::new_unchecked(ptr) };
+
+        munge!(let RelBox { ptr: out_ptr, alloc: out_alloc } = out);
+
+        ptr.try_emplace(out_ptr)?;
+        transcoder.alloc().clone().emplace(out_alloc);
+
+        Ok(())
+    }
+}
+
+// SAFETY: `zero_padding` zeroes the bytes of the `RelBox` which are not covered
+// by its fields, then zeroes the padding of its fields and of the value it
+// owns.
+unsafe impl<T, A, B> ZeroPadding for RelBox<T, A, B>
+where
+    T: BasisPointee<B> + ZeroPadding + ?Sized,
+    A: RawRegionalAllocator + ZeroPadding,
+    B: Basis,
+    RelPtr<T, A::Region, B>: ZeroPadding,
+{
+    fn zero_padding(mut this: Mut<'_, Self>) {
+        // SAFETY: `this` points to a `RelBox`, and the bytes which are not
+        // covered by any of its fields are padding.
+        unsafe {
+            zero_gaps(
+                this.as_ptr().cast(),
+                0,
+                size_of::<Self>(),
+                &[
+                    (
+                        offset_of!(Self, ptr),
+                        size_of::<RelPtr<T, A::Region, B>>(),
+                    ),
+                    (offset_of!(Self, alloc), size_of::<A>()),
+                ],
+            );
+        }
+
+        T::zero_padding(Self::deref_mut_raw(this.as_mut()));
+
+        munge!(let RelBox { ptr, alloc } = this);
+        ZeroPadding::zero_padding(ptr);
+        ZeroPadding::zero_padding(alloc);
+    }
+}
+
 impl<T, A, B> RelBox<MaybeUninit<T>, A, B>
 where
     T: DropRaw,
@@ -239,15 +549,92 @@ where
 
     unsafe fn emplace_unsized_unchecked(
         self,
-        mut out: In<Slot<'_, RelBox<T, E, B>>, A::Region>,
+        out: In<Slot<'_, RelBox<T, E, B>>, A::Region>,
     ) {
+        // SAFETY: `RelBox` is `Sized`, so `out` must have the metadata returned
+        // by `emplaced_meta`.
+        unsafe { self.try_emplace_unsized_unchecked(out).unwrap() }
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter if
+// it returns `Ok`.
+unsafe impl<T, E, B, A> TryEmplace<RelBox<T, E, B>, A::Region>
+    for OwnedVal<T, A>
+where
+    T: BasisPointee<B> + DropRaw + ?Sized,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        mut out: In<Slot<'_, RelBox<T, E, B>>, A::Region>,
+    ) -> Result<(), EmplaceError> {
         let this = In::new(self);
         let ptr = this.as_raw();
-        let (_, alloc) = OwnedVal::into_raw_parts(In::into_inner(this));
 
         munge!(let RelBox { ptr: out_ptr, alloc: out_alloc } = out.as_mut());
 
-        ptr.emplace(out_ptr);
+        // If this fails, `this` is dropped and frees the value.
+        ptr.try_emplace(out_ptr)?;
+        let (_, alloc) = OwnedVal::into_raw_parts(In::into_inner(this));
         alloc.emplace(out_alloc);
+
+        Ok(())
+    }
+}
+
+/// An emplacer for a boxed slice that emplaces a clone of each emplacer in a
+/// slice.
+///
+/// If emplacing an element panics, the elements that were already emplaced are
+/// dropped and the allocation is freed.
+pub struct FromSlice<'a, A, E>(pub A, pub &'a [E]);
+
+// SAFETY:
+// - `RelBox` is `Sized` and always has metadata `()`, so `emplaced_meta` always
+//   returns valid metadata for it.
+// - `emplace_unsized_unchecked` initializes its `out` parameter by emplacing
+//   the slice in a new allocation and emplacing that allocation.
+unsafe impl<T, E, EA, B, A> Emplace<RelBox<[T], EA, B>, A::Region>
+    for FromSlice<'_, A, E>
+where
+    T: DropRaw,
+    E: Clone + Emplace<T, A::Region>,
+    EA: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    A: RegionalAllocator + RelAllocator<EA, A::Region>,
+{
+    #[inline]
+    fn emplaced_meta(&self) -> <RelBox<[T], EA, B> as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelBox<[T], EA, B>>, A::Region>,
+    ) {
+        let Self(alloc, values) = self;
+        values.emplace_in::<[T]>(alloc).emplace(out);
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter by
+// emplacing the slice in a new allocation and emplacing that allocation if it
+// returns `Ok`.
+unsafe impl<T, E, EA, B, A> TryEmplace<RelBox<[T], EA, B>, A::Region>
+    for FromSlice<'_, A, E>
+where
+    T: DropRaw,
+    E: Clone + TryEmplace<T, A::Region>,
+    EA: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    A: RegionalAllocator + RelAllocator<EA, A::Region>,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelBox<[T], EA, B>>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        let Self(alloc, values) = self;
+        values.try_emplace_in::<[T]>(alloc)?.try_emplace(out)
     }
 }
@@ -0,0 +1,1338 @@
+//! An ordered set based on a B-tree, written `RelBTreeSet<T>`.
+
+use ::core::{
+    alloc::Layout,
+    cmp::Ordering,
+    fmt,
+    iter::FusedIterator,
+    marker::PhantomData,
+    mem::{offset_of, size_of, MaybeUninit},
+    ptr::{self, NonNull},
+};
+use ::mischief::{In, Region, RegionalAllocator, Slot};
+use ::munge::munge;
+use ::ptr_meta::Pointee;
+use ::rel_core::{
+    clone::Clone,
+    padding::{zero_gaps, ZeroPadding},
+    rel_ptr::Null,
+    Basis,
+    DefaultBasis,
+    Emplace,
+    EmplaceError,
+    EmplaceExt,
+    Move,
+    MoveExt,
+    Portable,
+    RelPtr,
+    TryEmplace,
+    TryEmplaceExt,
+};
+use ::situ::{
+    alloc::RawRegionalAllocator,
+    cmp::OrdRaw,
+    fmt::DebugRaw,
+    DropRaw,
+    Mut,
+    Ref,
+    Val,
+};
+
+use crate::alloc::RelAllocator;
+
+/// The minimum number of children of each internal node other than the root.
+const MIN_DEGREE: usize = 6;
+/// The maximum number of keys in each node.
+const MAX_KEYS: usize = 2 * MIN_DEGREE - 1;
+/// The maximum height of a tree. Each level multiplies the number of keys by
+/// at least `MIN_DEGREE`, so no tree can fit in memory and be this tall.
+const MAX_HEIGHT: usize = 32;
+
+/// A node of a `RelBTreeSet`.
+///
+/// Each node has one more key slot than it can hold keys. The spare key of the
+/// root is used to hold a value while it is being inserted or removed. Leaves
+/// are allocated as just a `Node`, and internal nodes are allocated as an
+/// [`Internal`] so that leaves don't take up space for children.
+#[derive(Portable)]
+#[repr(C)]
+struct Node<T, R: Region, B: Basis> {
+    len: u8,
+    keys: [MaybeUninit<T>; MAX_KEYS + 1],
+    _phantom: PhantomData<(R, B)>,
+}
+
+/// An internal node of a `RelBTreeSet`, which is a node followed by its
+/// children.
+#[derive(Portable)]
+#[repr(C)]
+struct Internal<T, R: Region, B: Basis> {
+    node: Node<T, R, B>,
+    children: [MaybeUninit<NodePtr<T, R, B>>; MAX_KEYS + 1],
+}
+
+type NodePtr<T, R, B> = RelPtr<Node<T, R, B>, R, B>;
+
+/// The key to search for when removing a key from a subtree.
+enum Search<'a, T> {
+    Key(Ref<'a, T>),
+    First,
+    Last,
+}
+
+/// Moves the key at `from` to the uninitialized key slot at `to`.
+///
+/// # Safety
+///
+/// `from` must be initialized and not accessed again until it is reinitialized,
+/// and `to` must be valid for writes and not aliased. Both must be located in
+/// `R`.
+unsafe fn move_key<T: Move<R>, R: Region>(from: *mut T, to: *mut T) {
+    // SAFETY: The caller has guaranteed that `from` is initialized and is not
+    // accessed again until it is reinitialized, and that `to` is valid for
+    // writes. Both are located in `R`.
+    unsafe {
+        let value = In::new_unchecked(Val::new_unchecked(from));
+        let out = In::new_unchecked(Slot::new_unchecked(to));
+        T::r#move(value, out);
+    }
+}
+
+// The functions on `Node` require that all node pointers passed to them point
+// to live nodes, and that any index is in bounds of the keys or children being
+// accessed. Only internal nodes have children.
+impl<T, R: Region, B: Basis> Node<T, R, B> {
+    /// Returns the layout of a node at the given height.
+    fn layout(height: usize) -> Layout {
+        if height == 0 {
+            Layout::new::<Self>()
+        } else {
+            Layout::new::<Internal<T, R, B>>()
+        }
+    }
+
+    unsafe fn len(this: *const Self) -> usize {
+        // SAFETY: The caller has guaranteed that `this` points to a live node.
+        usize::from(unsafe { (*this).len })
+    }
+
+    unsafe fn set_len(this: *mut Self, len: usize) {
+        // SAFETY: The caller has guaranteed that `this` points to a live node.
+        unsafe {
+            (*this).len = u8::try_from(len).unwrap();
+        }
+    }
+
+    unsafe fn key(this: *const Self, index: usize) -> *mut T {
+        debug_assert!(index <= MAX_KEYS);
+        // SAFETY: The caller has guaranteed that `this` points to a live node
+        // and that `index` is in bounds of its keys.
+        unsafe {
+            ptr::addr_of!((*this).keys)
+                .cast::<T>()
+                .cast_mut()
+                .add(index)
+        }
+    }
+
+    unsafe fn key_ref<'a>(this: *const Self, index: usize) -> Ref<'a, T> {
+        // SAFETY: The caller has guaranteed that the key at `index` is
+        // initialized and not mutated while the returned `Ref` is alive.
+        unsafe { Ref::new_unchecked(Self::key(this, index)) }
+    }
+
+    unsafe fn child_ptr(
+        this: *const Self,
+        index: usize,
+    ) -> *mut NodePtr<T, R, B> {
+        debug_assert!(index <= MAX_KEYS);
+        // SAFETY: The caller has guaranteed that `this` points to a live
+        // internal node, which starts with its node, and that `index` is in
+        // bounds of its children.
+        unsafe {
+            ptr::addr_of!((*this.cast::<Internal<T, R, B>>()).children)
+                .cast::<NodePtr<T, R, B>>()
+                .cast_mut()
+                .add(index)
+        }
+    }
+
+    unsafe fn child(this: *const Self, index: usize) -> *mut Self {
+        // SAFETY: The caller has guaranteed that `this` points to a live
+        // internal node and that the child at `index` is initialized.
+        unsafe {
+            let child = Self::child_ptr(this, index);
+            RelPtr::as_ptr_unchecked(Ref::new_unchecked(child)).cast_mut()
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `child` is too far away from `this` to point to with `B`.
+    unsafe fn set_child(this: *mut Self, index: usize, child: *mut Self) {
+        // SAFETY:
+        // - The caller has guaranteed that `this` points to a live internal
+        //   node and that `index` is in bounds of its children. `RelPtr` has
+        //   no drop glue, so the old child can be overwritten.
+        // - Nodes are always allocated in `R`, so both `this` and `child` are
+        //   located in `R`.
+        unsafe {
+            let slot = Self::child_ptr(this, index);
+            let slot = In::<_, R>::new_unchecked(Slot::new_unchecked(slot));
+            In::<_, R>::new_unchecked(child).emplace(slot);
+        }
+    }
+
+    /// Finds the key equal to `value` in this node.
+    ///
+    /// Returns `Ok` with the index of the key if one was found, or `Err` with
+    /// the index of the child which may contain it.
+    unsafe fn search(
+        this: *const Self,
+        value: Ref<'_, T>,
+    ) -> Result<usize, usize>
+    where
+        T: OrdRaw,
+    {
+        // SAFETY: The caller has guaranteed that `this` points to a live node.
+        let len = unsafe { Self::len(this) };
+        for i in 0..len {
+            // SAFETY: `i` is less than `len`, so the key is initialized.
+            match T::cmp_raw(value, unsafe { Self::key_ref(this, i) }) {
+                Ordering::Less => return Err(i),
+                Ordering::Equal => return Ok(i),
+                Ordering::Greater => (),
+            }
+        }
+        Err(len)
+    }
+
+    /// Splits the full child at `index` of `this`, moving its upper half to
+    /// `new` and its middle key to `this`. `this` must not be full, and
+    /// `height` is the height of `this`.
+    unsafe fn split_child(
+        this: *mut Self,
+        index: usize,
+        height: usize,
+        new: *mut Self,
+    ) where
+        T: Move<R>,
+    {
+        // SAFETY: The caller has guaranteed that `this` is an internal node
+        // which is not full, that its child at `index` is full, and that `new`
+        // is a new node. All of the keys and children accessed are in bounds
+        // and initialized when they are read.
+        unsafe {
+            let child = Self::child(this, index);
+            for i in 0..MIN_DEGREE - 1 {
+                move_key(Self::key(child, MIN_DEGREE + i), Self::key(new, i));
+            }
+            if height > 1 {
+                for i in 0..MIN_DEGREE {
+                    Self::set_child(new, i, Self::child(child, MIN_DEGREE + i));
+                }
+            }
+            Self::set_len(new, MIN_DEGREE - 1);
+
+            let len = Self::len(this);
+            for i in (index + 1..=len).rev() {
+                Self::set_child(this, i + 1, Self::child(this, i));
+            }
+            Self::set_child(this, index + 1, new);
+            for i in (index..len).rev() {
+                move_key(Self::key(this, i), Self::key(this, i + 1));
+            }
+            move_key(Self::key(child, MIN_DEGREE - 1), Self::key(this, index));
+
+            Self::set_len(child, MIN_DEGREE - 1);
+            Self::set_len(this, len + 1);
+        }
+    }
+
+    /// Merges the child at `index + 1` of `this` and the key between them into
+    /// the child at `index`, then frees the right child. Both children must
+    /// have the minimum number of keys, and `height` is the height of `this`.
+    unsafe fn merge<A>(
+        alloc: Ref<'_, A>,
+        this: *mut Self,
+        index: usize,
+        height: usize,
+    ) where
+        T: Move<R>,
+        A: RawRegionalAllocator<Region = R>,
+    {
+        // SAFETY: The caller has guaranteed that `this` is an internal node,
+        // that `index` is less than its length, and that both children have
+        // `MIN_DEGREE - 1` keys. All of the keys and children accessed are in
+        // bounds and initialized when they are read. The right child was
+        // allocated in `alloc` and is not pointed to after it is freed.
+        unsafe {
+            let left = Self::child(this, index);
+            let right = Self::child(this, index + 1);
+            let left_len = Self::len(left);
+            let right_len = Self::len(right);
+
+            move_key(Self::key(this, index), Self::key(left, left_len));
+            for i in 0..right_len {
+                move_key(
+                    Self::key(right, i),
+                    Self::key(left, left_len + 1 + i),
+                );
+            }
+            if height > 1 {
+                for i in 0..=right_len {
+                    Self::set_child(
+                        left,
+                        left_len + 1 + i,
+                        Self::child(right, i),
+                    );
+                }
+            }
+            Self::set_len(left, left_len + 1 + right_len);
+
+            let len = Self::len(this);
+            for i in index + 1..len {
+                move_key(Self::key(this, i), Self::key(this, i - 1));
+            }
+            for i in index + 2..=len {
+                Self::set_child(this, i - 1, Self::child(this, i));
+            }
+            Self::set_len(this, len - 1);
+
+            Self::deallocate(alloc, right, height - 1);
+        }
+    }
+
+    /// Makes sure that the child at `index` of `this` has more than the minimum
+    /// number of keys by moving a key from a sibling or merging it with one.
+    /// Returns the new index of the child. `height` is the height of `this`.
+    unsafe fn fill<A>(
+        alloc: Ref<'_, A>,
+        this: *mut Self,
+        index: usize,
+        height: usize,
+    ) -> usize
+    where
+        T: Move<R>,
+        A: RawRegionalAllocator<Region = R>,
+    {
+        // SAFETY: The caller has guaranteed that `this` is an internal node
+        // and that `index` is at most its length. All of the keys and children
+        // accessed are in bounds and initialized when they are read.
+        unsafe {
+            let len = Self::len(this);
+            let child = Self::child(this, index);
+            let child_len = Self::len(child);
+            if child_len >= MIN_DEGREE {
+                return index;
+            }
+
+            if index > 0 {
+                let left = Self::child(this, index - 1);
+                let left_len = Self::len(left);
+                if left_len >= MIN_DEGREE {
+                    // Rotate the last key of the left sibling through `this`.
+                    for i in (0..child_len).rev() {
+                        move_key(Self::key(child, i), Self::key(child, i + 1));
+                    }
+                    if height > 1 {
+                        for i in (0..=child_len).rev() {
+                            Self::set_child(
+                                child,
+                                i + 1,
+                                Self::child(child, i),
+                            );
+                        }
+                        Self::set_child(child, 0, Self::child(left, left_len));
+                    }
+                    move_key(Self::key(this, index - 1), Self::key(child, 0));
+                    move_key(
+                        Self::key(left, left_len - 1),
+                        Self::key(this, index - 1),
+                    );
+                    Self::set_len(left, left_len - 1);
+                    Self::set_len(child, child_len + 1);
+                    return index;
+                }
+            }
+
+            if index < len {
+                let right = Self::child(this, index + 1);
+                let right_len = Self::len(right);
+                if right_len >= MIN_DEGREE {
+                    // Rotate the first key of the right sibling through `this`.
+                    move_key(
+                        Self::key(this, index),
+                        Self::key(child, child_len),
+                    );
+                    move_key(Self::key(right, 0), Self::key(this, index));
+                    for i in 1..right_len {
+                        move_key(Self::key(right, i), Self::key(right, i - 1));
+                    }
+                    if height > 1 {
+                        Self::set_child(
+                            child,
+                            child_len + 1,
+                            Self::child(right, 0),
+                        );
+                        for i in 1..=right_len {
+                            Self::set_child(
+                                right,
+                                i - 1,
+                                Self::child(right, i),
+                            );
+                        }
+                    }
+                    Self::set_len(right, right_len - 1);
+                    Self::set_len(child, child_len + 1);
+                    return index;
+                }
+                Self::merge(alloc, this, index, height);
+                index
+            } else {
+                Self::merge(alloc, this, index - 1, height);
+                index - 1
+            }
+        }
+    }
+
+    /// Removes the key found by `search` from the subtree rooted at `this`,
+    /// and moves it to `out`. Returns whether a key was removed.
+    ///
+    /// `this` must have more than the minimum number of keys unless it is the
+    /// root, and `height` is the height of `this`.
+    unsafe fn remove<A>(
+        alloc: Ref<'_, A>,
+        this: *mut Self,
+        height: usize,
+        search: Search<'_, T>,
+        out: *mut T,
+    ) -> bool
+    where
+        T: Move<R> + OrdRaw,
+        A: RawRegionalAllocator<Region = R>,
+    {
+        // SAFETY: The caller has guaranteed that `this` points to a live node
+        // with enough keys to remove one from its subtree. All of the keys and
+        // children accessed are in bounds and initialized when they are read.
+        unsafe {
+            let len = Self::len(this);
+            let found = match search {
+                Search::Key(value) => Self::search(this, value),
+                Search::First if height == 0 => Ok(0),
+                Search::First => Err(0),
+                Search::Last if height == 0 => Ok(len - 1),
+                Search::Last => Err(len),
+            };
+
+            match found {
+                Ok(index) if height == 0 => {
+                    move_key(Self::key(this, index), out);
+                    for i in index + 1..len {
+                        move_key(Self::key(this, i), Self::key(this, i - 1));
+                    }
+                    Self::set_len(this, len - 1);
+                    true
+                }
+                Err(_) if height == 0 => false,
+                Ok(index) => {
+                    // Replace the key with its predecessor or successor if
+                    // either of the children next to it can spare a key.
+                    // Otherwise, merge the key down and remove it from there.
+                    let left = Self::child(this, index);
+                    let right = Self::child(this, index + 1);
+                    let key = Self::key(this, index);
+                    if Self::len(left) >= MIN_DEGREE {
+                        move_key(key, out);
+                        Self::remove(alloc, left, height - 1, Search::Last, key)
+                    } else if Self::len(right) >= MIN_DEGREE {
+                        move_key(key, out);
+                        Self::remove(
+                            alloc,
+                            right,
+                            height - 1,
+                            Search::First,
+                            key,
+                        )
+                    } else {
+                        Self::merge(alloc, this, index, height);
+                        Self::remove(alloc, left, height - 1, search, out)
+                    }
+                }
+                Err(index) => {
+                    let index = Self::fill(alloc, this, index, height);
+                    let child = Self::child(this, index);
+                    Self::remove(alloc, child, height - 1, search, out)
+                }
+            }
+        }
+    }
+
+    /// Allocates a new, empty node at the given height.
+    fn allocate<A>(
+        alloc: Ref<'_, A>,
+        height: usize,
+    ) -> Result<*mut Self, EmplaceError>
+    where
+        A: RawRegionalAllocator<Region = R>,
+    {
+        let node = A::raw_allocate(alloc, Self::layout(height))
+            .map_err(|_| EmplaceError::AllocFailed)?
+            .as_ptr()
+            .cast::<Self>();
+        // SAFETY: `node` was just allocated with the layout of a node at
+        // `height`, which starts with a `Node`.
+        unsafe {
+            Self::set_len(node, 0);
+        }
+        Ok(node)
+    }
+
+    unsafe fn deallocate<A>(alloc: Ref<'_, A>, this: *mut Self, height: usize)
+    where
+        A: RawRegionalAllocator<Region = R>,
+    {
+        // SAFETY: The caller has guaranteed that `this` was allocated in
+        // `alloc` as a node at `height` and is not accessed again.
+        unsafe {
+            A::raw_deallocate(
+                alloc,
+                NonNull::new_unchecked(this.cast()),
+                Self::layout(height),
+            );
+        }
+    }
+
+    /// Drops all of the keys in the subtree rooted at `this` and frees all of
+    /// its nodes. `this` itself is only freed if `free_this` is `true`.
+    unsafe fn drop_subtree<A>(
+        alloc: Ref<'_, A>,
+        this: *mut Self,
+        height: usize,
+        free_this: bool,
+    ) where
+        T: DropRaw,
+        A: RawRegionalAllocator<Region = R>,
+    {
+        // SAFETY: The caller has guaranteed that `this` points to a live node
+        // of the given height, which was allocated in `alloc` along with all
+        // of its descendants. None of the keys or nodes are accessed again.
+        unsafe {
+            let len = Self::len(this);
+            for i in 0..len {
+                DropRaw::drop_raw(Mut::new_unchecked(Self::key(this, i)));
+            }
+            if height > 0 {
+                for i in 0..=len {
+                    Self::drop_subtree(
+                        alloc,
+                        Self::child(this, i),
+                        height - 1,
+                        true,
+                    );
+                }
+            }
+            Self::set_len(this, 0);
+            if free_this {
+                Self::deallocate(alloc, this, height);
+            }
+        }
+    }
+
+    /// Writes zeroes to the padding bytes and unused key and child slots of
+    /// `this` and all of its descendants. `height` is the height of `this`.
+    unsafe fn zero_padding(this: *mut Self, height: usize)
+    where
+        T: ZeroPadding,
+        NodePtr<T, R, B>: ZeroPadding,
+    {
+        // SAFETY: The caller has guaranteed that `this` points to a live node
+        // of the given height. Only the first `len` keys and, for internal
+        // nodes, the first `len + 1` children are initialized, so the rest of
+        // the node is padding or unused slots.
+        unsafe {
+            let len = Self::len(this);
+            let keys = (offset_of!(Self, keys), len * size_of::<T>());
+            if height == 0 {
+                zero_gaps(
+                    this.cast(),
+                    0,
+                    size_of::<Self>(),
+                    &[(offset_of!(Self, len), 1), keys],
+                );
+            } else {
+                zero_gaps(
+                    this.cast(),
+                    0,
+                    size_of::<Internal<T, R, B>>(),
+                    &[
+                        (offset_of!(Self, len), 1),
+                        keys,
+                        (
+                            offset_of!(Internal<T, R, B>, children),
+                            (len + 1) * size_of::<NodePtr<T, R, B>>(),
+                        ),
+                    ],
+                );
+            }
+
+            for i in 0..len {
+                T::zero_padding(Mut::new_unchecked(Self::key(this, i)));
+            }
+            if height > 0 {
+                for i in 0..=len {
+                    Self::zero_padding(Self::child(this, i), height - 1);
+                    ZeroPadding::zero_padding(Mut::new_unchecked(
+                        Self::child_ptr(this, i),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// A relative counterpart to `BTreeSet`.
+///
+/// The elements are stored in order in the nodes of a B-tree, which are each
+/// allocated separately.
+#[derive(Move, Portable)]
+#[repr(C)]
+pub struct RelBTreeSet<T, A: RawRegionalAllocator, B: Basis = DefaultBasis> {
+    root: NodePtr<T, A::Region, B>,
+    height: B::Usize,
+    len: B::Usize,
+    alloc: A,
+}
+
+impl<T, A, B> DropRaw for RelBTreeSet<T, A, B>
+where
+    T: DropRaw,
+    A: RawRegionalAllocator + DropRaw,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+{
+    #[inline]
+    unsafe fn drop_raw(this: Mut<'_, Self>) {
+        let height = this.height();
+        if let Some(root) = Self::root(this.as_ref()) {
+            // SAFETY: `root` is the root of the tree, which has a height of
+            // `height`. All of its nodes were allocated in `alloc`, and they
+            // are not accessed again.
+            unsafe {
+                Node::drop_subtree(
+                    Self::allocator(this.as_ref()),
+                    root,
+                    height,
+                    true,
+                );
+            }
+        }
+
+        munge!(let RelBTreeSet { root, height, len, alloc } = this);
+
+        // SAFETY: `root`, `height`, `len`, and `alloc` are always valid for
+        // dropping and are not accessed again.
+        unsafe {
+            DropRaw::drop_raw(root);
+            DropRaw::drop_raw(height);
+            DropRaw::drop_raw(len);
+            DropRaw::drop_raw(alloc);
+        }
+    }
+}
+
+// SAFETY: `zero_padding` writes zeroes to the padding bytes of the set, its
+// nodes, and its elements. The only other bytes it writes are the unused key
+// and child slots of its nodes, which do not contain any values.
+unsafe impl<T, A, B> ZeroPadding for RelBTreeSet<T, A, B>
+where
+    T: ZeroPadding,
+    A: RawRegionalAllocator + ZeroPadding,
+    B: Basis,
+    NodePtr<T, A::Region, B>: ZeroPadding,
+{
+    fn zero_padding(this: Mut<'_, Self>) {
+        // SAFETY: `this` points to a `RelBTreeSet`, and the bytes which are not
+        // covered by any of its fields are padding.
+        unsafe {
+            zero_gaps(
+                this.as_ptr().cast(),
+                0,
+                size_of::<Self>(),
+                &[
+                    (
+                        offset_of!(Self, root),
+                        size_of::<NodePtr<T, A::Region, B>>(),
+                    ),
+                    (offset_of!(Self, height), size_of::<B::Usize>()),
+                    (offset_of!(Self, len), size_of::<B::Usize>()),
+                    (offset_of!(Self, alloc), size_of::<A>()),
+                ],
+            );
+        }
+
+        if let Some(root) = Self::root(this.as_ref()) {
+            // SAFETY: `root` is the root of the tree, which has a height of
+            // `this.height()`, and it is not aliased because `this` is a `Mut`.
+            unsafe {
+                Node::zero_padding(root, this.height());
+            }
+        }
+
+        munge!(let RelBTreeSet { root, alloc, .. } = this);
+        ZeroPadding::zero_padding(root);
+        ZeroPadding::zero_padding(alloc);
+    }
+}
+
+impl<T, A: RawRegionalAllocator, B: Basis> RelBTreeSet<T, A, B> {
+    /// Returns `true` if the `RelBTreeSet` contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements in the `RelBTreeSet`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        B::to_native_usize(self.len).unwrap()
+    }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(this: Ref<'_, Self>) -> Ref<'_, A> {
+        munge!(let RelBTreeSet { alloc, .. } = this);
+        alloc
+    }
+
+    /// Returns an iterator over the elements of the `RelBTreeSet` in
+    /// ascending order.
+    #[inline]
+    pub fn iter(this: Ref<'_, Self>) -> Iter<'_, T, A::Region, B> {
+        Iter::new(Self::root(this), this.height(), this.len())
+    }
+
+    fn height(&self) -> usize {
+        B::to_native_usize(self.height).unwrap()
+    }
+
+    fn root(this: Ref<'_, Self>) -> Option<*mut Node<T, A::Region, B>> {
+        munge!(let RelBTreeSet { root, .. } = this);
+        RelPtr::as_ptr(root).map(<*const _>::cast_mut)
+    }
+
+    fn set_root(this: Mut<'_, Self>, node: *mut Node<T, A::Region, B>) {
+        munge!(let RelBTreeSet { root, .. } = this);
+        // SAFETY: Nodes are always allocated in the allocator of the set, and
+        // since `A` implements `RawRegionalAllocator`, it guarantees that
+        // memory it allocates is located in its region.
+        RelPtr::set(root, unsafe { In::new_unchecked(node) });
+    }
+
+    fn set_height(this: Mut<'_, Self>, new_height: usize) {
+        munge!(let RelBTreeSet { mut height, .. } = this);
+        *height = B::from_native_usize(new_height).unwrap();
+    }
+
+    fn set_len(this: Mut<'_, Self>, new_len: usize) {
+        munge!(let RelBTreeSet { mut len, .. } = this);
+        *len = B::from_native_usize(new_len).unwrap();
+    }
+
+    /// Returns `true` if the `RelBTreeSet` contains an element equal to
+    /// `value`.
+    pub fn contains(this: Ref<'_, Self>, value: Ref<'_, T>) -> bool
+    where
+        T: OrdRaw,
+    {
+        let Some(mut node) = Self::root(this) else {
+            return false;
+        };
+        for height in (0..=this.height()).rev() {
+            // SAFETY: `node` is a node of the tree at `height`, so it is live
+            // and its children are initialized if it is an internal node.
+            unsafe {
+                match Node::search(node, value) {
+                    Ok(_) => return true,
+                    Err(_) if height == 0 => (),
+                    Err(index) => node = Node::child(node, index),
+                }
+            }
+        }
+        false
+    }
+
+    /// Adds a value to the `RelBTreeSet`.
+    ///
+    /// Returns whether the value was newly inserted. If the set already
+    /// contained an equal value, the new value is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if allocating space for the new value fails, or if a new node is
+    /// too far away from the node that points to it to represent in `B`.
+    pub fn insert<E>(this: Mut<'_, Self>, value: E) -> bool
+    where
+        T: Move<A::Region> + OrdRaw,
+        E: Emplace<T, A::Region>,
+    {
+        Self::try_insert_with(this, |out| {
+            value.emplace(out);
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    /// Tries to add a value to the `RelBTreeSet`.
+    ///
+    /// Returns whether the value was newly inserted. If the set already
+    /// contained an equal value, the new value is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if emplacing the value or allocating the nodes needed
+    /// to insert it fails. If an error is returned, the value is dropped and
+    /// the elements of the `RelBTreeSet` are left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a new node is too far away from the node that points to it to
+    /// represent in `B`.
+    pub fn try_insert<E>(
+        this: Mut<'_, Self>,
+        value: E,
+    ) -> Result<bool, EmplaceError>
+    where
+        T: Move<A::Region> + OrdRaw,
+        E: TryEmplace<T, A::Region>,
+    {
+        Self::try_insert_with(this, |out| value.try_emplace(out))
+    }
+
+    /// Adds the value emplaced by `emplace` to the `RelBTreeSet`.
+    fn try_insert_with<F>(
+        mut this: Mut<'_, Self>,
+        emplace: F,
+    ) -> Result<bool, EmplaceError>
+    where
+        T: Move<A::Region> + OrdRaw,
+        F: FnOnce(In<Slot<'_, T>, A::Region>) -> Result<(), EmplaceError>,
+    {
+        let root = match Self::root(this.as_ref()) {
+            Some(root) => root,
+            None => {
+                let root = Node::allocate(Self::allocator(this.as_ref()), 0)?;
+                Self::set_root(this.as_mut(), root);
+                root
+            }
+        };
+        let mut height = this.height();
+        let len = this.len();
+
+        // The value is emplaced into the spare key of the root so it can be
+        // compared to the elements of the set. It is moved to its place in
+        // a leaf only if no equal element is found.
+        // SAFETY: `root` is a live node, so its spare key is in bounds.
+        let value_ptr = unsafe { Node::key(root, MAX_KEYS) };
+        // SAFETY: The spare key of the root is never initialized outside of
+        // `try_insert` and `remove`, so it is valid for writes and not aliased.
+        // Nodes are allocated in `A::Region`.
+        let slot = unsafe { In::new_unchecked(Slot::new_unchecked(value_ptr)) };
+        emplace(slot)?;
+        // SAFETY: `value_ptr` was just initialized, and is not mutated until
+        // it is moved into place.
+        let value_ref = unsafe { Ref::new_unchecked(value_ptr) };
+
+        // Each full node on the path to the value is split on the way down,
+        // and splitting the root also takes a new root. All of the new nodes
+        // are allocated up front so that an allocation failure leaves the
+        // tree unchanged. `split_heights` holds the height of each new node in
+        // the order they are used.
+        let mut split_heights = [0; MAX_HEIGHT + 1];
+        let mut splits = 0;
+        // SAFETY: `root` is the root of the tree, which has a height of
+        // `height`.
+        if unsafe { Node::len(root) } == MAX_KEYS {
+            split_heights[0] = height + 1;
+            splits += 1;
+        }
+        let mut node = root;
+        for level in (0..=height).rev() {
+            // SAFETY: `node` is a node of the tree at `level`, so it is live
+            // and its children are initialized if it is an internal node.
+            unsafe {
+                match Node::search(node, value_ref) {
+                    Ok(_) => {
+                        DropRaw::drop_raw(Mut::new_unchecked(value_ptr));
+                        return Ok(false);
+                    }
+                    Err(index) => {
+                        if Node::len(node) == MAX_KEYS {
+                            split_heights[splits] = level;
+                            splits += 1;
+                        }
+                        if level > 0 {
+                            node = Node::child(node, index);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut new_nodes = [ptr::null_mut(); MAX_HEIGHT + 1];
+        for i in 0..splits {
+            match Node::allocate(
+                Self::allocator(this.as_ref()),
+                split_heights[i],
+            ) {
+                Ok(node) => new_nodes[i] = node,
+                Err(e) => {
+                    // SAFETY: The new nodes were just allocated at their
+                    // heights and are not part of the tree, and the value is
+                    // not part of the set.
+                    unsafe {
+                        for j in 0..i {
+                            Node::deallocate(
+                                Self::allocator(this.as_ref()),
+                                new_nodes[j],
+                                split_heights[j],
+                            );
+                        }
+                        DropRaw::drop_raw(Mut::new_unchecked(value_ptr));
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        let mut new_nodes = new_nodes[..splits].iter().copied();
+
+        // SAFETY: All of the nodes accessed are nodes of the tree at the given
+        // heights, and a new node was allocated for every split.
+        unsafe {
+            let mut node = root;
+            if Node::len(root) == MAX_KEYS {
+                node = new_nodes.next().unwrap();
+                Node::set_child(node, 0, root);
+                height += 1;
+                Node::split_child(node, 0, height, new_nodes.next().unwrap());
+                Self::set_root(this.as_mut(), node);
+                Self::set_height(this.as_mut(), height);
+            }
+            for level in (1..=height).rev() {
+                let mut index = Node::search(node, value_ref).unwrap_err();
+                if Node::len(Node::child(node, index)) == MAX_KEYS {
+                    let new = new_nodes.next().unwrap();
+                    Node::split_child(node, index, level, new);
+                    let middle = Node::key_ref(node, index);
+                    if T::cmp_raw(value_ref, middle) == Ordering::Greater {
+                        index += 1;
+                    }
+                }
+                node = Node::child(node, index);
+            }
+
+            let index = Node::search(node, value_ref).unwrap_err();
+            let node_len = Node::len(node);
+            for i in (index..node_len).rev() {
+                move_key(Node::key(node, i), Node::key(node, i + 1));
+            }
+            move_key(value_ptr, Node::key(node, index));
+            Node::set_len(node, node_len + 1);
+        }
+        Self::set_len(this, len + 1);
+
+        Ok(true)
+    }
+
+    /// Removes the element equal to `value` from the `RelBTreeSet`.
+    ///
+    /// Returns whether an element was removed.
+    pub fn remove(mut this: Mut<'_, Self>, value: Ref<'_, T>) -> bool
+    where
+        T: Move<A::Region> + OrdRaw,
+    {
+        let Some(root) = Self::root(this.as_ref()) else {
+            return false;
+        };
+        let height = this.height();
+
+        // The removed element is moved to the spare key of the root and
+        // dropped from there.
+        // SAFETY: `root` is the root of the tree, which has a height of
+        // `height`. The spare key of the root is never initialized outside of
+        // `try_insert` and `remove`.
+        let removed = unsafe {
+            let out = Node::key(root, MAX_KEYS);
+            let removed = Node::remove(
+                Self::allocator(this.as_ref()),
+                root,
+                height,
+                Search::Key(value),
+                out,
+            );
+            if removed {
+                DropRaw::drop_raw(Mut::new_unchecked(out));
+            }
+            removed
+        };
+        if !removed {
+            return false;
+        }
+
+        // SAFETY: `root` is still the root of the tree. If it has no keys left
+        // and is an internal node, its only child becomes the new root and it
+        // is not accessed again.
+        unsafe {
+            if Node::len(root) == 0 && height > 0 {
+                Self::set_root(this.as_mut(), Node::child(root, 0));
+                Self::set_height(this.as_mut(), height - 1);
+                Node::deallocate(Self::allocator(this.as_ref()), root, height);
+            }
+        }
+        let len = this.len();
+        Self::set_len(this, len - 1);
+
+        true
+    }
+
+    /// Clears the `RelBTreeSet`, removing all values.
+    ///
+    /// If the root node of the set is a leaf, it is kept allocated. Otherwise,
+    /// all of the nodes are freed.
+    pub fn clear(mut this: Mut<'_, Self>)
+    where
+        T: DropRaw,
+    {
+        let Some(root) = Self::root(this.as_ref()) else {
+            return;
+        };
+        let height = this.height();
+        // SAFETY: `root` is the root of the tree, which has a height of
+        // `height`. All of its nodes were allocated in `alloc`. A leaf root is
+        // kept allocated and becomes empty, and an internal root is freed and
+        // not accessed again.
+        unsafe {
+            Node::drop_subtree(
+                Self::allocator(this.as_ref()),
+                root,
+                height,
+                height > 0,
+            );
+        }
+        if height > 0 {
+            munge!(let RelBTreeSet { root, .. } = this.as_mut());
+            // SAFETY: `root` comes from a `Mut`, so it is non-null, properly
+            // aligned, valid for writes, and not aliased. It is part of the
+            // set, so it is located in `A::Region`. `RelPtr` has no drop glue,
+            // so it can be overwritten.
+            let out = unsafe {
+                In::new_unchecked(Slot::new_unchecked(root.as_ptr()))
+            };
+            Null.emplace(out);
+        }
+        Self::set_height(this.as_mut(), 0);
+        Self::set_len(this, 0);
+    }
+
+    /// Returns an emplacer for a new `RelBTreeSet` containing the elements
+    /// that are in `this`, `other`, or both.
+    pub fn union<'a, N>(
+        this: Ref<'a, Self>,
+        other: Ref<'a, Self>,
+        alloc: N,
+    ) -> FromIter<N, impl Iterator<Item = Clone<'a, T>>>
+    where
+        T: OrdRaw,
+    {
+        let values = Self::iter(this)
+            .chain(Self::iter(other).filter(move |v| !Self::contains(this, *v)))
+            .map(Clone);
+        FromIter(alloc, values)
+    }
+
+    /// Returns an emplacer for a new `RelBTreeSet` containing the elements
+    /// that are in both `this` and `other`.
+    pub fn intersection<'a, N>(
+        this: Ref<'a, Self>,
+        other: Ref<'a, Self>,
+        alloc: N,
+    ) -> FromIter<N, impl Iterator<Item = Clone<'a, T>>>
+    where
+        T: OrdRaw,
+    {
+        let values = Self::iter(this)
+            .filter(move |v| Self::contains(other, *v))
+            .map(Clone);
+        FromIter(alloc, values)
+    }
+
+    /// Returns an emplacer for a new `RelBTreeSet` containing the elements
+    /// that are in `this` but not in `other`.
+    pub fn difference<'a, N>(
+        this: Ref<'a, Self>,
+        other: Ref<'a, Self>,
+        alloc: N,
+    ) -> FromIter<N, impl Iterator<Item = Clone<'a, T>>>
+    where
+        T: OrdRaw,
+    {
+        let values = Self::iter(this)
+            .filter(move |v| !Self::contains(other, *v))
+            .map(Clone);
+        FromIter(alloc, values)
+    }
+
+    /// Returns an emplacer for a new `RelBTreeSet` containing the elements
+    /// that are in `this` or `other`, but not both.
+    pub fn symmetric_difference<'a, N>(
+        this: Ref<'a, Self>,
+        other: Ref<'a, Self>,
+        alloc: N,
+    ) -> FromIter<N, impl Iterator<Item = Clone<'a, T>>>
+    where
+        T: OrdRaw,
+    {
+        let values = Self::iter(this)
+            .filter(move |v| !Self::contains(other, *v))
+            .chain(Self::iter(other).filter(move |v| !Self::contains(this, *v)))
+            .map(Clone);
+        FromIter(alloc, values)
+    }
+}
+
+impl<T, A, B> DebugRaw for RelBTreeSet<T, A, B>
+where
+    T: DebugRaw,
+    A: RawRegionalAllocator,
+    B: Basis,
+{
+    fn fmt_raw(
+        this: Ref<'_, Self>,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        f.debug_set().entries(Self::iter(this)).finish()
+    }
+}
+
+/// An iterator over the elements of a `RelBTreeSet` in ascending order.
+pub struct Iter<'a, T, R: Region, B: Basis> {
+    /// The path from the root to the current node, along with the index of the
+    /// next key to return from each node.
+    stack: [(*const Node<T, R, B>, usize); MAX_HEIGHT],
+    depth: usize,
+    height: usize,
+    remaining: usize,
+    _phantom: PhantomData<Ref<'a, T>>,
+}
+
+impl<'a, T, R: Region, B: Basis> Iter<'a, T, R, B> {
+    fn new(
+        root: Option<*mut Node<T, R, B>>,
+        height: usize,
+        len: usize,
+    ) -> Self {
+        let mut result = Self {
+            stack: [(ptr::null(), 0); MAX_HEIGHT],
+            depth: 0,
+            height,
+            remaining: len,
+            _phantom: PhantomData,
+        };
+        if let Some(root) = root {
+            // SAFETY: `root` is the root of a tree with a height of `height`.
+            unsafe {
+                result.descend(root);
+            }
+        }
+        result
+    }
+
+    /// Pushes `node` and its leftmost descendants onto the stack.
+    ///
+    /// # Safety
+    ///
+    /// `node` must be a node of the tree at the height of the next level of
+    /// the stack.
+    unsafe fn descend(&mut self, mut node: *const Node<T, R, B>) {
+        loop {
+            self.stack[self.depth] = (node, 0);
+            self.depth += 1;
+            if self.depth > self.height {
+                break;
+            }
+            // SAFETY: `node` is an internal node because it is above the
+            // leaves of the tree, so its first child is initialized.
+            node = unsafe { Node::child(node, 0) };
+        }
+    }
+}
+
+impl<'a, T, R: Region, B: Basis> Iterator for Iter<'a, T, R, B> {
+    type Item = Ref<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        loop {
+            let (node, index) = self.stack[self.depth - 1];
+            // SAFETY: All of the nodes on the stack are live nodes of the tree,
+            // which is borrowed for `'a`. The key at `index` is initialized
+            // because `index` is less than the length of the node.
+            unsafe {
+                if index < Node::len(node) {
+                    self.stack[self.depth - 1].1 = index + 1;
+                    if self.depth <= self.height {
+                        self.descend(Node::child(node, index + 1));
+                    }
+                    self.remaining -= 1;
+                    return Some(Node::key_ref(node, index));
+                }
+            }
+            self.depth -= 1;
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T, R: Region, B: Basis> ExactSizeIterator for Iter<'_, T, R, B> {}
+
+impl<T, R: Region, B: Basis> FusedIterator for Iter<'_, T, R, B> {}
+
+/// An emplacer for a new, empty `RelBTreeSet`.
+///
+/// The nodes of the set are not allocated until the first value is inserted.
+pub struct New<A>(pub A);
+
+// SAFETY:
+// - `RelBTreeSet` is `Sized` and always has metadata `()`, so `emplaced_meta`
+//   always returns valid metadata for it.
+// - `emplace_unsized_unchecked` initializes its `out` parameter by emplacing
+//   and writing to each field.
+unsafe impl<T, E, B, A> Emplace<RelBTreeSet<T, E, B>, A::Region> for New<A>
+where
+    T: DropRaw,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    fn emplaced_meta(&self) -> <RelBTreeSet<T, E, B> as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelBTreeSet<T, E, B>>, A::Region>,
+    ) {
+        munge!(
+            let RelBTreeSet {
+                root: out_root,
+                height: out_height,
+                len: out_len,
+                alloc: out_alloc,
+            } = out;
+        );
+
+        Null.emplace(out_root);
+        In::into_inner(out_height).write(B::from_native_usize(0).unwrap());
+        In::into_inner(out_len).write(B::from_native_usize(0).unwrap());
+        self.0.emplace(out_alloc);
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` always initializes its `out`
+// parameter.
+unsafe impl<T, E, B, A> TryEmplace<RelBTreeSet<T, E, B>, A::Region> for New<A>
+where
+    T: DropRaw,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelBTreeSet<T, E, B>>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        self.emplace(out);
+        Ok(())
+    }
+}
+
+/// An emplacer for a `RelBTreeSet` that inserts each of the values from an
+/// iterator.
+///
+/// Values which are equal to a value that was already inserted are dropped.
+pub struct FromIter<A, I>(pub A, pub I);
+
+// SAFETY:
+// - `RelBTreeSet` is `Sized` and always has metadata `()`, so `emplaced_meta`
+//   always returns valid metadata for it.
+// - `emplace_unsized_unchecked` initializes its `out` parameter by emplacing a
+//   new `RelBTreeSet` and inserting each value from the iterator into it.
+unsafe impl<T, E, B, A, I> Emplace<RelBTreeSet<T, E, B>, A::Region>
+    for FromIter<A, I>
+where
+    T: DropRaw + Move<A::Region> + OrdRaw,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+    I: Iterator,
+    I::Item: Emplace<T, A::Region>,
+{
+    fn emplaced_meta(&self) -> <RelBTreeSet<T, E, B> as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelBTreeSet<T, E, B>>, A::Region>,
+    ) {
+        let Self(alloc, values) = self;
+
+        // If an emplacer panics, dropping `set` will drop all of the values
+        // inserted so far and free its nodes.
+        let mut set = In::into_inner(New(alloc).emplace_val(out));
+        for value in values {
+            RelBTreeSet::insert(set.as_mut(), value);
+        }
+        Val::leak(set);
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter by
+// emplacing a new `RelBTreeSet` and inserting each value from the iterator into
+// it if it returns `Ok`.
+unsafe impl<T, E, B, A, I> TryEmplace<RelBTreeSet<T, E, B>, A::Region>
+    for FromIter<A, I>
+where
+    T: DropRaw + Move<A::Region> + OrdRaw,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+    I: Iterator,
+    I::Item: Emplace<T, A::Region>,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelBTreeSet<T, E, B>>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        let Self(alloc, values) = self;
+
+        // If inserting a value fails or panics, dropping `set` will drop all of
+        // the values inserted so far and free its nodes.
+        let mut set = In::into_inner(New(alloc).emplace_val(out));
+        for value in values {
+            RelBTreeSet::try_insert_with(set.as_mut(), |out| {
+                value.emplace(out);
+                Ok(())
+            })?;
+        }
+        Val::leak(set);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,623 @@
+//! Deserialization of relative types directly into a region with `serde`.
+
+use ::core::{alloc::Layout, fmt, marker::PhantomData, ptr::NonNull};
+use ::mischief::{Frame, In, RegionalAllocator, Slot};
+use ::munge::munge;
+use ::rel_core::{
+    export::serde::{
+        de::{DeserializeSeed, Error, SeqAccess, Visitor},
+        Deserialize,
+        Deserializer,
+    },
+    option::RelOption,
+    rel_tuple::{
+        RelTuple1,
+        RelTuple10,
+        RelTuple11,
+        RelTuple12,
+        RelTuple13,
+        RelTuple14,
+        RelTuple15,
+        RelTuple16,
+        RelTuple2,
+        RelTuple3,
+        RelTuple4,
+        RelTuple5,
+        RelTuple6,
+        RelTuple7,
+        RelTuple8,
+        RelTuple9,
+    },
+    Basis,
+    Char,
+    EmplaceExt,
+    Move,
+    TryEmplaceExt,
+    F32,
+    F64,
+    I128,
+    I16,
+    I32,
+    I64,
+    U128,
+    U16,
+    U32,
+    U64,
+};
+use ::situ::{alloc::RawRegionalAllocator, DropGuard, DropRaw, OwnedVal, Val};
+
+use crate::{alloc::RelAllocator, string, vec, RelBox, RelString, RelVec};
+
+/// A data structure that can be deserialized directly into a slot in a region.
+///
+/// Any memory the value owns (e.g. the contents of a `RelString` or `RelVec`)
+/// is allocated from the given allocator as it is deserialized, so no
+/// intermediate heap structures are created.
+///
+/// # Safety
+///
+/// `deserialize_in` must initialize `out` if it returns `Ok`. If it returns
+/// `Err`, any memory it allocated must be freed.
+pub unsafe trait DeserializeIn<'de, A: RegionalAllocator>:
+    DropRaw + Sized
+{
+    /// Deserializes a value from the given deserializer into `out`, allocating
+    /// from `alloc` as necessary.
+    fn deserialize_in<D: Deserializer<'de>>(
+        deserializer: D,
+        alloc: A,
+        out: In<Slot<'_, Self>, A::Region>,
+    ) -> Result<(), D::Error>;
+}
+
+/// A `DeserializeSeed` that deserializes a value into a slot with its
+/// `DeserializeIn` implementation.
+///
+/// This can be passed to `SeqAccess`, `MapAccess`, and other seeded
+/// deserialization methods to deserialize nested values. If deserializing the
+/// seed succeeds, its slot is initialized.
+pub struct DeserializeInSeed<'a, T, A: RegionalAllocator> {
+    alloc: A,
+    out: In<Slot<'a, T>, A::Region>,
+}
+
+impl<'a, T, A: RegionalAllocator> DeserializeInSeed<'a, T, A> {
+    /// Returns a new `DeserializeInSeed` which deserializes into `out` and
+    /// allocates from `alloc`.
+    pub fn new(alloc: A, out: In<Slot<'a, T>, A::Region>) -> Self {
+        Self { alloc, out }
+    }
+}
+
+impl<'de, T, A> DeserializeSeed<'de> for DeserializeInSeed<'_, T, A>
+where
+    T: DeserializeIn<'de, A>,
+    A: RegionalAllocator,
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        T::deserialize_in(deserializer, self.alloc, self.out)
+    }
+}
+
+/// Deserializes a value into a new `OwnedVal` allocated from the given
+/// allocator and returns it.
+///
+/// If allocating or deserializing the value fails, any memory allocated for it
+/// is freed and the error is returned.
+pub fn deserialize_in<'de, T, A, D>(
+    deserializer: D,
+    alloc: A,
+) -> Result<OwnedVal<T, A>, D::Error>
+where
+    T: DeserializeIn<'de, A>,
+    A: Clone + RegionalAllocator,
+    D: Deserializer<'de>,
+{
+    // SAFETY: `()` is the only valid metadata for a pointer to a sized type.
+    let frame = unsafe { Frame::try_new_unsized_in((), alloc.clone()) }
+        .map_err(|_| D::Error::custom("memory allocation failed"))?;
+
+    let mut frame = In::new(frame);
+    let slot = frame.slot();
+
+    // If deserialization fails, the frame is dropped and its memory is freed.
+    T::deserialize_in(deserializer, alloc, slot)?;
+
+    // SAFETY: `deserialize_in` returned `Ok`, so it is guaranteed to have
+    // initialized the slot. That slot is from the frame, so the frame is
+    // initialized.
+    Ok(unsafe { OwnedVal::assume_init(In::into_inner(frame)) })
+}
+
+macro_rules! impl_native {
+    ($($ty:ty),*) => {
+        $(
+            // SAFETY: `deserialize_in` initializes `out` by emplacing to it if
+            // it returns `Ok`, and never allocates.
+            unsafe impl<'de, A: RegionalAllocator> DeserializeIn<'de, A>
+                for $ty
+            {
+                #[inline]
+                fn deserialize_in<D: Deserializer<'de>>(
+                    deserializer: D,
+                    _: A,
+                    out: In<Slot<'_, Self>, A::Region>,
+                ) -> Result<(), D::Error> {
+                    <$ty>::deserialize(deserializer)?.emplace(out);
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_native!(i8, u8, bool, ());
+
+macro_rules! impl_primitive {
+    ($($portable:ty => $native:ty),* $(,)?) => {
+        $(
+            // SAFETY: `deserialize_in` initializes `out` by emplacing to it if
+            // it returns `Ok`, and never allocates.
+            unsafe impl<'de, A: RegionalAllocator> DeserializeIn<'de, A>
+                for $portable
+            {
+                #[inline]
+                fn deserialize_in<D: Deserializer<'de>>(
+                    deserializer: D,
+                    _: A,
+                    out: In<Slot<'_, Self>, A::Region>,
+                ) -> Result<(), D::Error> {
+                    <$native>::deserialize(deserializer)?.emplace(out);
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive! {
+    I16 => i16,
+    I32 => i32,
+    I64 => i64,
+    I128 => i128,
+    U16 => u16,
+    U32 => u32,
+    U64 => u64,
+    U128 => u128,
+    F32 => f32,
+    F64 => f64,
+    Char => char,
+}
+
+// SAFETY: `deserialize_in` initializes `out` if `RelOptionVisitor` returns
+// `Ok`. The visitor's value can only be created by the visitor itself, which
+// initializes `out` before returning `Ok`.
+unsafe impl<'de, T, A> DeserializeIn<'de, A> for RelOption<T>
+where
+    T: DeserializeIn<'de, A>,
+    A: RegionalAllocator,
+{
+    fn deserialize_in<D: Deserializer<'de>>(
+        deserializer: D,
+        alloc: A,
+        out: In<Slot<'_, Self>, A::Region>,
+    ) -> Result<(), D::Error> {
+        deserializer.deserialize_option(RelOptionVisitor {
+            alloc,
+            out,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+struct RelOptionVisitor<'a, 'de, T, A: RegionalAllocator> {
+    alloc: A,
+    out: In<Slot<'a, RelOption<T>>, A::Region>,
+    _phantom: PhantomData<&'de ()>,
+}
+
+impl<'de, T, A> Visitor<'de> for RelOptionVisitor<'_, 'de, T, A>
+where
+    T: DeserializeIn<'de, A>,
+    A: RegionalAllocator,
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an option")
+    }
+
+    fn visit_none<E: Error>(self) -> Result<Self::Value, E> {
+        RelOption::emplace_none(self.out);
+        Ok(())
+    }
+
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+        self.visit_none()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        let value = RelOption::emplace_some_slot(self.out);
+        T::deserialize_in(deserializer, self.alloc, value)
+    }
+}
+
+// SAFETY: `deserialize_in` initializes `out` if `RelStringVisitor` returns
+// `Ok`. The visitor's value can only be created by the visitor itself, which
+// initializes `out` before returning `Ok`. If emplacing the string fails, no
+// memory is allocated.
+unsafe impl<'de, E, B, A> DeserializeIn<'de, A> for RelString<E, B>
+where
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    fn deserialize_in<D: Deserializer<'de>>(
+        deserializer: D,
+        alloc: A,
+        out: In<Slot<'_, Self>, A::Region>,
+    ) -> Result<(), D::Error> {
+        deserializer.deserialize_str(RelStringVisitor { alloc, out })
+    }
+}
+
+struct RelStringVisitor<'a, E: RawRegionalAllocator, B: Basis, A>
+where
+    A: RegionalAllocator,
+{
+    alloc: A,
+    out: In<Slot<'a, RelString<E, B>>, A::Region>,
+}
+
+impl<'de, E, B, A> Visitor<'de> for RelStringVisitor<'_, E, B, A>
+where
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_str<Er: Error>(self, v: &str) -> Result<Self::Value, Er> {
+        string::Clone(self.alloc, v)
+            .try_emplace(self.out)
+            .map_err(Er::custom)
+    }
+}
+
+// SAFETY: `deserialize_in` initializes `out` if `RelVecVisitor` returns `Ok`.
+// The visitor's value can only be created by the visitor itself, which
+// initializes `out` before returning `Ok`. If deserializing an element fails,
+// the partially-deserialized `RelVec` is dropped and its memory is freed.
+unsafe impl<'de, T, E, B, A> DeserializeIn<'de, A> for RelVec<T, E, B>
+where
+    T: DeserializeIn<'de, A> + Move<A::Region>,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: Clone + RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    fn deserialize_in<D: Deserializer<'de>>(
+        deserializer: D,
+        alloc: A,
+        out: In<Slot<'_, Self>, A::Region>,
+    ) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(RelVecVisitor {
+            alloc,
+            out,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+struct RelVecVisitor<'a, 'de, T, E: RawRegionalAllocator, B: Basis, A>
+where
+    A: RegionalAllocator,
+{
+    alloc: A,
+    out: In<Slot<'a, RelVec<T, E, B>>, A::Region>,
+    _phantom: PhantomData<&'de ()>,
+}
+
+impl<'de, T, E, B, A> Visitor<'de> for RelVecVisitor<'_, 'de, T, E, B, A>
+where
+    T: DeserializeIn<'de, A> + Move<A::Region>,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: Clone + RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sequence")
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(
+        self,
+        mut seq: S,
+    ) -> Result<Self::Value, S::Error> {
+        // Don't trust the size hint of the sequence too much, it may come from
+        // untrusted input.
+        const MAX_PREALLOC_BYTES: usize = 1024 * 1024;
+        let capacity = seq
+            .size_hint()
+            .unwrap_or(0)
+            .min(MAX_PREALLOC_BYTES / ::core::mem::size_of::<T>().max(1));
+
+        // If deserializing an element fails, `vec` is dropped along with all
+        // of the elements that were already deserialized.
+        let mut vec = In::into_inner(
+            vec::WithCapacity(self.alloc.clone(), capacity)
+                .try_emplace_val(self.out)
+                .map_err(S::Error::custom)?,
+        );
+        loop {
+            let len = vec.len();
+            if len == vec.capacity() {
+                if seq.size_hint() == Some(0) {
+                    break;
+                }
+                RelVec::try_reserve(vec.as_mut(), 1)
+                    .map_err(S::Error::custom)?;
+            }
+
+            // SAFETY: `len` is less than `capacity` because we reserved space
+            // for at least one more element if the `RelVec` was full.
+            let slot = unsafe { RelVec::slot(vec.as_mut(), len) };
+            let seed = DeserializeInSeed::new(self.alloc.clone(), slot);
+            if seq.next_element_seed(seed)?.is_none() {
+                break;
+            }
+
+            // SAFETY: `len + 1` is less than or equal to `capacity`, and the
+            // element at `len` was initialized by deserializing it.
+            unsafe {
+                RelVec::set_len(vec.as_mut(), len + 1);
+            }
+        }
+        Val::leak(vec);
+
+        Ok(())
+    }
+}
+
+// SAFETY: `deserialize_in` initializes `out` by deserializing the boxed value
+// directly into a new allocation, then emplacing a `RelBox` which owns it. If
+// deserializing the boxed value fails, the allocation is freed. If emplacing
+// the `RelBox` fails, the boxed value is dropped and its memory is freed.
+unsafe impl<'de, T, E, B, A> DeserializeIn<'de, A> for RelBox<T, E, B>
+where
+    T: DeserializeIn<'de, A>,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    A: Clone + RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    fn deserialize_in<D: Deserializer<'de>>(
+        deserializer: D,
+        alloc: A,
+        out: In<Slot<'_, Self>, A::Region>,
+    ) -> Result<(), D::Error> {
+        let layout = Layout::new::<T>();
+        let ptr = alloc
+            .allocate(layout)
+            .map_err(|_| D::Error::custom("memory allocation failed"))?
+            .as_ptr()
+            .cast::<T>();
+        // SAFETY:
+        // - `ptr` was returned from `allocate`, so it is non-null, properly
+        //   aligned, and valid for reads and writes of a `T`.
+        // - `ptr` is freshly-allocated, so it is not aliased by any other
+        //   pointers.
+        let slot = unsafe { Slot::new_unchecked(ptr) };
+        // SAFETY: `ptr` is allocated in `alloc`, and since `A` implements
+        // `RegionalAllocator`, it guarantees that memory it allocates is
+        // located in its region.
+        let slot = unsafe { In::new_unchecked(slot) };
+
+        if let Err(e) = T::deserialize_in(deserializer, alloc.clone(), slot) {
+            // SAFETY: `ptr` was allocated by `alloc` with `layout`, and the
+            // value in it was not initialized.
+            unsafe {
+                alloc.deallocate(NonNull::new_unchecked(ptr).cast(), layout);
+            }
+            return Err(e);
+        }
+
+        // SAFETY: `ptr` is allocated in `alloc`, and since `A` implements
+        // `RegionalAllocator`, it guarantees that memory it allocates is
+        // located in its region.
+        let target = unsafe { In::new_unchecked(ptr) };
+        // SAFETY: `ptr` points to a value which was just initialized by
+        // deserializing into it, and it was allocated by `alloc` with the
+        // layout of a `T`.
+        let result = unsafe {
+            RelBox::try_emplace_raw_parts(target, alloc.clone(), out)
+        };
+        if let Err(e) = result {
+            // SAFETY: `ptr` was allocated by `alloc` with the layout of a `T`,
+            // and points to an initialized `T` that is not owned by anything
+            // else.
+            let value = unsafe { OwnedVal::from_raw_in(ptr, alloc) };
+            // Dropping the value frees its memory.
+            drop(value);
+            return Err(D::Error::custom(e));
+        }
+
+        Ok(())
+    }
+}
+
+struct RelTupleVisitor<'a, 'de, T, A: RegionalAllocator> {
+    alloc: A,
+    out: In<Slot<'a, T>, A::Region>,
+    _phantom: PhantomData<&'de ()>,
+}
+
+macro_rules! impl_tuple {
+    (
+        $n:literal,
+        $ident:ident<$($types:ident),*>,
+        $($indices:literal,)*
+    ) => {
+        // SAFETY: `deserialize_in` initializes `out` if `RelTupleVisitor`
+        // returns `Ok`. The visitor's value can only be created by the
+        // visitor itself, which initializes `out` before returning `Ok`.
+        // If deserializing a field fails, the fields that were already
+        // deserialized are dropped and their memory is freed.
+        unsafe impl<'de, $($types,)* A> DeserializeIn<'de, A>
+            for $ident<$($types),*>
+        where
+            $($types: DeserializeIn<'de, A>,)*
+            A: Clone + RegionalAllocator,
+        {
+            fn deserialize_in<D: Deserializer<'de>>(
+                deserializer: D,
+                alloc: A,
+                out: In<Slot<'_, Self>, A::Region>,
+            ) -> Result<(), D::Error> {
+                deserializer.deserialize_tuple(
+                    $n,
+                    RelTupleVisitor {
+                        alloc,
+                        out,
+                        _phantom: PhantomData,
+                    },
+                )
+            }
+        }
+
+        impl<'de, $($types,)* A> Visitor<'de>
+            for RelTupleVisitor<'_, 'de, $ident<$($types),*>, A>
+        where
+            $($types: DeserializeIn<'de, A>,)*
+            A: Clone + RegionalAllocator,
+        {
+            type Value = ();
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(concat!("a tuple of size ", $n))
+            }
+
+            #[allow(non_snake_case)]
+            fn visit_seq<S: SeqAccess<'de>>(
+                self,
+                mut seq: S,
+            ) -> Result<Self::Value, S::Error> {
+                munge!(let $ident($($types,)*) = self.out);
+                // If deserializing a field fails, each guard drops the
+                // field that it guards.
+                $(
+                    let $types = {
+                        let ptr = $types.ptr().as_ptr();
+                        let seed =
+                            DeserializeInSeed::new(self.alloc.clone(), $types);
+                        if seq.next_element_seed(seed)?.is_none() {
+                            return Err(S::Error::invalid_length(
+                                $indices,
+                                &concat!("a tuple of size ", $n),
+                            ));
+                        }
+                        // SAFETY: `ptr` points to a field of `out` that we
+                        // just initialized by deserializing into it. It is
+                        // not accessed again until the guard is forgotten.
+                        unsafe { DropGuard::new(ptr) }
+                    };
+                )*
+                $(
+                    DropGuard::forget($types);
+                )*
+
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_tuple!(1, RelTuple1<TA>, 0,);
+impl_tuple!(
+    2,
+    RelTuple2<TA, TB>,
+    0, 1,
+);
+impl_tuple!(
+    3,
+    RelTuple3<TA, TB, TC>,
+    0, 1, 2,
+);
+impl_tuple!(
+    4,
+    RelTuple4<TA, TB, TC, TD>,
+    0, 1, 2, 3,
+);
+impl_tuple!(
+    5,
+    RelTuple5<TA, TB, TC, TD, TE>,
+    0, 1, 2, 3, 4,
+);
+impl_tuple!(
+    6,
+    RelTuple6<TA, TB, TC, TD, TE, TF>,
+    0, 1, 2, 3, 4, 5,
+);
+impl_tuple!(
+    7,
+    RelTuple7<TA, TB, TC, TD, TE, TF, TG>,
+    0, 1, 2, 3, 4, 5, 6,
+);
+impl_tuple!(
+    8,
+    RelTuple8<TA, TB, TC, TD, TE, TF, TG, TH>,
+    0, 1, 2, 3, 4, 5, 6, 7,
+);
+impl_tuple!(
+    9,
+    RelTuple9<TA, TB, TC, TD, TE, TF, TG, TH, TI>,
+    0, 1, 2, 3, 4, 5, 6, 7, 8,
+);
+impl_tuple!(
+    10,
+    RelTuple10<TA, TB, TC, TD, TE, TF, TG, TH, TI, TJ>,
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9,
+);
+impl_tuple!(
+    11,
+    RelTuple11<TA, TB, TC, TD, TE, TF, TG, TH, TI, TJ, TK>,
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10,
+);
+impl_tuple!(
+    12,
+    RelTuple12<TA, TB, TC, TD, TE, TF, TG, TH, TI, TJ, TK, TL>,
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11,
+);
+impl_tuple!(
+    13,
+    RelTuple13<TA, TB, TC, TD, TE, TF, TG, TH, TI, TJ, TK, TL, TM>,
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12,
+);
+impl_tuple!(
+    14,
+    RelTuple14<TA, TB, TC, TD, TE, TF, TG, TH, TI, TJ, TK, TL, TM, TN>,
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13,
+);
+impl_tuple!(
+    15,
+    RelTuple15<TA, TB, TC, TD, TE, TF, TG, TH, TI, TJ, TK, TL, TM, TN, TO>,
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14,
+);
+impl_tuple!(
+    16,
+    RelTuple16<TA, TB, TC, TD, TE, TF, TG, TH, TI, TJ, TK, TL, TM, TN, TO, TP>,
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+);
@@ -1,6 +1,6 @@
 use ::mischief::{Frame, In, Metadata, RegionalAllocator};
 use ::ptr_meta::Pointee;
-use ::rel_core::Emplace;
+use ::rel_core::{Emplace, EmplaceError, TryEmplace};
 use ::situ::{DropRaw, OwnedVal};
 
 /// An extension trait for `Emplace` that provides an allocating emplacement
@@ -14,6 +14,20 @@ pub trait EmplaceIn<A: RegionalAllocator> {
         T: DropRaw + Pointee + ?Sized,
         <T as Pointee>::Metadata: Metadata<T>,
         Self: Emplace<T, A::Region>;
+
+    /// Attempts to emplace a value into a new `OwnedVal` allocated from the
+    /// given allocator and returns it.
+    ///
+    /// If allocating or emplacing the value fails, any memory allocated for it
+    /// is freed and the error is returned.
+    fn try_emplace_in<T>(
+        self,
+        alloc: A,
+    ) -> Result<OwnedVal<T, A>, EmplaceError>
+    where
+        T: DropRaw + Pointee + ?Sized,
+        <T as Pointee>::Metadata: Metadata<T>,
+        Self: TryEmplace<T, A::Region>;
 }
 
 impl<E, A> EmplaceIn<A> for E
@@ -44,4 +58,30 @@ where
         // from the frame, so the frame is initialized.
         unsafe { OwnedVal::assume_init(In::into_inner(frame)) }
     }
+    fn try_emplace_in<T>(self, alloc: A) -> Result<OwnedVal<T, A>, EmplaceError>
+    where
+        T: DropRaw + Pointee + ?Sized,
+        <T as Pointee>::Metadata: Metadata<T>,
+        Self: TryEmplace<T, A::Region>,
+    {
+        // SAFETY: The pointer metadata is from `emplaced_meta`, which is
+        // guaranteed to be valid for a pointer to `T`.
+        let frame =
+            unsafe { Frame::try_new_unsized_in(self.emplaced_meta(), alloc) }
+                .map_err(|_| EmplaceError::AllocFailed)?;
+
+        let mut frame = In::new(frame);
+        let slot = frame.slot();
+
+        // SAFETY: We just allocated the slot in a frame with the metadata from
+        // `emplaced_meta`. If emplacement fails, the frame is dropped and its
+        // memory is freed.
+        unsafe {
+            self.try_emplace_unsized_unchecked(slot)?;
+        }
+        // SAFETY: `try_emplace_unsized_unchecked` returned `Ok`, so it is
+        // guaranteed to have initialized the slot. That slot is from the
+        // frame, so the frame is initialized.
+        Ok(unsafe { OwnedVal::assume_init(In::into_inner(frame)) })
+    }
 }
@@ -0,0 +1,874 @@
+//! A hash set implemented with linear probing, written `RelHashSet<T>`.
+
+use ::core::{
+    alloc::Layout,
+    fmt,
+    hash::Hasher,
+    ptr::{self, NonNull},
+};
+use ::mischief::{In, RegionalAllocator, Slot};
+use ::munge::munge;
+use ::ptr_meta::Pointee;
+use ::rel_core::{
+    clone::Clone,
+    Basis,
+    DefaultBasis,
+    Emplace,
+    EmplaceError,
+    EmplaceExt,
+    Move,
+    MoveExt,
+    Portable,
+    RelPtr,
+    TryEmplace,
+    TryEmplaceExt,
+};
+use ::situ::{
+    alloc::RawRegionalAllocator,
+    cmp::EqRaw,
+    fmt::DebugRaw,
+    hash::HashRaw,
+    ops::IndexRaw,
+    slice::IterRaw,
+    DropRaw,
+    Mut,
+    Ref,
+    Val,
+};
+
+use crate::{alloc::RelAllocator, vec, RelVec};
+
+/// A relative counterpart to `HashSet`.
+///
+/// The elements are stored densely in a `RelVec` in the order they were
+/// inserted, and a table of buckets maps the hash of each element to its index.
+/// Removing an element moves the last element into its place.
+///
+/// The table is persisted along with the elements, so elements are hashed with
+/// a fixed hasher which produces the same hashes on every platform.
+#[derive(Move, Portable)]
+#[repr(C)]
+#[zero_padding]
+pub struct RelHashSet<T, A: RawRegionalAllocator, B: Basis = DefaultBasis> {
+    entries: RelVec<T, A, B>,
+    table: RelPtr<B::Usize, A::Region, B>,
+    buckets: B::Usize,
+}
+
+impl<T, A, B> DropRaw for RelHashSet<T, A, B>
+where
+    T: DropRaw,
+    A: RawRegionalAllocator + DropRaw,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+{
+    #[inline]
+    unsafe fn drop_raw(mut this: Mut<'_, Self>) {
+        let layout = Layout::array::<B::Usize>(this.buckets()).unwrap();
+        let table_ptr = Self::table_mut_ptr(this.as_mut());
+
+        munge!(let RelHashSet { entries, table, buckets } = this);
+
+        // SAFETY: `table` is never null and always allocated in the allocator
+        // of `entries` with a layout of `layout`.
+        unsafe {
+            A::raw_deallocate(
+                RelVec::allocator(entries.as_ref()),
+                NonNull::new_unchecked(table_ptr.cast()),
+                layout,
+            );
+        }
+
+        // SAFETY: `entries`, `table`, and `buckets` are always valid for
+        // dropping and are not accessed again.
+        unsafe {
+            DropRaw::drop_raw(entries);
+            DropRaw::drop_raw(table);
+            DropRaw::drop_raw(buckets);
+        }
+    }
+}
+
+/// An FNV-1a hasher which hashes integers as little-endian bytes and `usize`s
+/// as `u64`s, so that hashes are the same on every platform.
+struct PortableHasher(u64);
+
+impl PortableHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+}
+
+impl Hasher for PortableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(u64::try_from(i).unwrap());
+    }
+}
+
+fn hash<T: HashRaw>(value: Ref<'_, T>) -> u64 {
+    let mut hasher = PortableHasher(PortableHasher::OFFSET_BASIS);
+    T::hash_raw(value, &mut hasher);
+    hasher.finish()
+}
+
+/// Returns the first bucket to probe for a value with the given hash.
+fn home(hash: u64, buckets: usize) -> usize {
+    let mask = u64::try_from(buckets - 1).unwrap();
+    usize::try_from(hash & mask).unwrap()
+}
+
+/// Returns the number of buckets needed for a set with the given capacity.
+///
+/// Keeping at least two buckets per element keeps probe sequences short and
+/// guarantees that there is always an empty bucket to end them.
+fn buckets_for(capacity: usize) -> Option<usize> {
+    capacity.checked_mul(2)?.checked_next_power_of_two()
+}
+
+impl<T, A: RawRegionalAllocator, B: Basis> RelHashSet<T, A, B> {
+    /// Returns `true` if the `RelHashSet` contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements in the `RelHashSet`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the number of elements the `RelHashSet` can contain before
+    /// resizing.
+    ///
+    /// This is limited by both the capacity of the elements and the number of
+    /// buckets in the table, since the table must always have more buckets
+    /// than elements.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity().min(self.buckets() / 2)
+    }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(this: Ref<'_, Self>) -> Ref<'_, A> {
+        munge!(let RelHashSet { entries, .. } = this);
+        RelVec::allocator(entries)
+    }
+
+    /// Returns an iterator over the elements of the `RelHashSet`.
+    ///
+    /// Elements are returned in the order they were inserted, except that
+    /// removing an element moves the last element into its place.
+    #[inline]
+    pub fn iter(this: Ref<'_, Self>) -> IterRaw<'_, T> {
+        munge!(let RelHashSet { entries, .. } = this);
+        RelVec::iter(entries)
+    }
+
+    fn buckets(&self) -> usize {
+        B::to_native_usize(self.buckets).unwrap()
+    }
+
+    fn table_ptr(this: Ref<'_, Self>) -> *const B::Usize {
+        munge!(let RelHashSet { table, .. } = this);
+        // SAFETY: The table pointer of a `RelHashSet` is never null.
+        unsafe { RelPtr::as_ptr_unchecked(table) }
+    }
+
+    fn table_mut_ptr(this: Mut<'_, Self>) -> *mut B::Usize {
+        munge!(let RelHashSet { table, .. } = this);
+        // SAFETY: The table pointer of a `RelHashSet` is never null.
+        unsafe { RelPtr::as_mut_ptr_unchecked(table) }
+    }
+
+    /// Returns the index of the element in the given bucket, if any.
+    fn bucket(this: Ref<'_, Self>, bucket: usize) -> Option<usize> {
+        debug_assert!(bucket < this.buckets());
+        // SAFETY: `bucket` is less than the number of buckets, so it is in
+        // bounds of the table and initialized.
+        let value = unsafe { *Self::table_ptr(this).add(bucket) };
+        B::to_native_usize(value).unwrap().checked_sub(1)
+    }
+
+    fn set_bucket(this: Mut<'_, Self>, bucket: usize, index: Option<usize>) {
+        debug_assert!(bucket < this.buckets());
+        let value = B::from_native_usize(index.map_or(0, |i| i + 1)).unwrap();
+        // SAFETY: `bucket` is less than the number of buckets, so it is in
+        // bounds of the table.
+        unsafe {
+            *Self::table_mut_ptr(this).add(bucket) = value;
+        }
+    }
+
+    fn entry(this: Ref<'_, Self>, index: usize) -> Ref<'_, T> {
+        munge!(let RelHashSet { entries, .. } = this);
+        IndexRaw::index_raw(RelVec::as_slice(entries), index)
+    }
+
+    /// Finds the bucket containing an element equal to `value`.
+    ///
+    /// Returns `Ok` with the bucket of the element if one was found, or `Err`
+    /// with the empty bucket that ended the probe sequence.
+    fn probe(this: Ref<'_, Self>, value: Ref<'_, T>) -> Result<usize, usize>
+    where
+        T: HashRaw + EqRaw,
+    {
+        let buckets = this.buckets();
+        let mut bucket = home(hash(value), buckets);
+        while let Some(index) = Self::bucket(this, bucket) {
+            if T::eq_raw(Self::entry(this, index), value) {
+                return Ok(bucket);
+            }
+            bucket = (bucket + 1) & (buckets - 1);
+        }
+        Err(bucket)
+    }
+
+    /// Returns `true` if the `RelHashSet` contains an element equal to
+    /// `value`.
+    pub fn contains(this: Ref<'_, Self>, value: Ref<'_, T>) -> bool
+    where
+        T: HashRaw + EqRaw,
+    {
+        Self::probe(this, value).is_ok()
+    }
+
+    /// Reserves capacity for at least `additional` more elements to be inserted
+    /// in the `RelHashSet`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if reserving the new capacity fails. See
+    /// [`try_reserve`](RelHashSet::try_reserve) for the possible failures.
+    pub fn reserve(this: Mut<'_, Self>, additional: usize)
+    where
+        T: Move<A::Region> + HashRaw,
+    {
+        Self::try_reserve(this, additional).unwrap();
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be
+    /// inserted in the `RelHashSet`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reserving capacity for the elements fails (see
+    /// [`RelVec::try_reserve`]), or if the table for the new capacity cannot
+    /// be allocated or pointed to. If an error is returned, the elements of the
+    /// `RelHashSet` are left unchanged.
+    pub fn try_reserve(
+        mut this: Mut<'_, Self>,
+        additional: usize,
+    ) -> Result<(), EmplaceError>
+    where
+        T: Move<A::Region> + HashRaw,
+    {
+        munge!(let RelHashSet { entries, .. } = this.as_mut());
+        RelVec::try_reserve(entries, additional)?;
+        Self::try_grow_table(this)
+    }
+
+    /// Grows the table to the number of buckets needed for the capacity of the
+    /// `RelHashSet`, and rehashes all of the elements into it.
+    fn try_grow_table(mut this: Mut<'_, Self>) -> Result<(), EmplaceError>
+    where
+        T: HashRaw,
+    {
+        let old_buckets = this.buckets();
+        let new_buckets = buckets_for(this.entries.capacity())
+            .ok_or(EmplaceError::CapacityOverflow)?;
+        if new_buckets <= old_buckets {
+            return Ok(());
+        }
+
+        let basis_buckets = B::from_native_usize(new_buckets)
+            .map_err(|_| EmplaceError::MetadataOverflow)?;
+        let old_layout = Layout::array::<B::Usize>(old_buckets).unwrap();
+        let new_layout = Layout::array::<B::Usize>(new_buckets)
+            .map_err(|_| EmplaceError::CapacityOverflow)?;
+
+        let new_bytes =
+            A::raw_allocate(Self::allocator(this.as_ref()), new_layout)
+                .map_err(|_| EmplaceError::AllocFailed)?
+                .cast::<u8>();
+        let new_table = new_bytes.as_ptr().cast::<B::Usize>();
+        let empty = B::from_native_usize(0).unwrap();
+        for bucket in 0..new_buckets {
+            // SAFETY: `new_table` was allocated with enough space for
+            // `new_buckets` buckets.
+            unsafe {
+                new_table.add(bucket).write(empty);
+            }
+        }
+        for index in 0..this.len() {
+            let mut bucket =
+                home(hash(Self::entry(this.as_ref(), index)), new_buckets);
+            // SAFETY: `bucket` is always less than `new_buckets`, and all of
+            // the buckets of `new_table` were initialized. There are more
+            // buckets than elements, so there is always an empty bucket to
+            // find.
+            unsafe {
+                while *new_table.add(bucket) != empty {
+                    bucket = (bucket + 1) & (new_buckets - 1);
+                }
+                new_table
+                    .add(bucket)
+                    .write(B::from_native_usize(index + 1).unwrap());
+            }
+        }
+
+        let old_table = Self::table_mut_ptr(this.as_mut());
+        munge!(let RelHashSet { entries, table, mut buckets } = this);
+        // SAFETY: `new_table` is allocated in the allocator of `entries`, and
+        // since `A` implements `RawRegionalAllocator`, it guarantees that
+        // memory it allocates is located in its region.
+        let new_table_in = unsafe { In::new_unchecked(new_table) };
+        if let Err(e) = RelPtr::try_set(table, new_table_in) {
+            // SAFETY: `new_bytes` was just allocated with `new_layout` and is
+            // not pointed to by the table.
+            unsafe {
+                A::raw_deallocate(
+                    RelVec::allocator(entries.as_ref()),
+                    new_bytes,
+                    new_layout,
+                );
+            }
+            return Err(e);
+        }
+        *buckets = basis_buckets;
+
+        // SAFETY: `old_table` is non-null and was allocated in the allocator
+        // of `entries` with `old_layout`. It is no longer pointed to by the
+        // table.
+        unsafe {
+            A::raw_deallocate(
+                RelVec::allocator(entries.as_ref()),
+                NonNull::new_unchecked(old_table.cast()),
+                old_layout,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Adds a value to the `RelHashSet`.
+    ///
+    /// Returns whether the value was newly inserted. If the set already
+    /// contained an equal value, the new value is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if reserving space for the new value fails. See
+    /// [`try_reserve`](RelHashSet::try_reserve) for the possible failures.
+    pub fn insert<E>(this: Mut<'_, Self>, value: E) -> bool
+    where
+        T: Move<A::Region> + HashRaw + EqRaw,
+        E: Emplace<T, A::Region>,
+    {
+        Self::try_insert_with(this, |out| {
+            value.emplace(out);
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    /// Tries to add a value to the `RelHashSet`.
+    ///
+    /// Returns whether the value was newly inserted. If the set already
+    /// contained an equal value, the new value is dropped.
+    ///
+    /// The value is compared against the elements of the set from its spare
+    /// capacity, so space is reserved for it first if the set is full.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reserving space for the new value fails or if
+    /// emplacing `value` fails. See [`try_reserve`](RelHashSet::try_reserve)
+    /// for the possible failures. If an error is returned, the new value is
+    /// dropped and the elements of the `RelHashSet` are left unchanged.
+    pub fn try_insert<E>(
+        this: Mut<'_, Self>,
+        value: E,
+    ) -> Result<bool, EmplaceError>
+    where
+        T: Move<A::Region> + HashRaw + EqRaw,
+        E: TryEmplace<T, A::Region>,
+    {
+        Self::try_insert_with(this, |out| value.try_emplace(out))
+    }
+
+    /// Adds the value emplaced by `emplace` to the `RelHashSet`.
+    fn try_insert_with<F>(
+        mut this: Mut<'_, Self>,
+        emplace: F,
+    ) -> Result<bool, EmplaceError>
+    where
+        T: Move<A::Region> + HashRaw + EqRaw,
+        F: FnOnce(In<Slot<'_, T>, A::Region>) -> Result<(), EmplaceError>,
+    {
+        if this.len() == this.capacity() {
+            Self::try_reserve(this.as_mut(), 1)?;
+        }
+        let len = this.len();
+
+        // The value is emplaced into the spare capacity of the elements so it
+        // can be hashed and compared. It only becomes part of the set if no
+        // equal element is found.
+        munge!(let RelHashSet { entries, .. } = this.as_mut());
+        // SAFETY: `len` is less than `capacity` because we reserved space for
+        // one additional element if the set was full.
+        let slot = unsafe { RelVec::slot(entries, len) };
+        let value_ptr = slot.ptr().as_ptr();
+        emplace(slot)?;
+
+        // SAFETY: `value_ptr` was just initialized by emplacing to it, and is
+        // outside of the elements of the set so it cannot alias them.
+        let value_ref = unsafe { Ref::new_unchecked(value_ptr) };
+        match Self::probe(this.as_ref(), value_ref) {
+            Ok(_) => {
+                // SAFETY: `value_ptr` is initialized, and is not part of the
+                // set so it is not accessed again.
+                unsafe {
+                    DropRaw::drop_raw(Mut::new_unchecked(value_ptr));
+                }
+                Ok(false)
+            }
+            Err(bucket) => {
+                Self::set_bucket(this.as_mut(), bucket, Some(len));
+                munge!(let RelHashSet { entries, .. } = this);
+                // SAFETY: `len + 1` is less than or equal to `capacity`, and we
+                // just initialized the element at `len` by emplacing to it.
+                unsafe {
+                    RelVec::set_len(entries, len + 1);
+                }
+                Ok(true)
+            }
+        }
+    }
+
+    /// Removes the element equal to `value` from the `RelHashSet`.
+    ///
+    /// Returns whether an element was removed. The last element of the set is
+    /// moved into the place of the removed element.
+    pub fn remove(mut this: Mut<'_, Self>, value: Ref<'_, T>) -> bool
+    where
+        T: Move<A::Region> + HashRaw + EqRaw,
+    {
+        let Ok(bucket) = Self::probe(this.as_ref(), value) else {
+            return false;
+        };
+        let index = Self::bucket(this.as_ref(), bucket).unwrap();
+        let last = this.len() - 1;
+
+        // Shift back the elements after the removed bucket whose probe
+        // sequences pass through it, so that no probe sequence is broken by
+        // the empty bucket.
+        let buckets = this.buckets();
+        let mut hole = bucket;
+        let mut next = bucket;
+        loop {
+            next = (next + 1) & (buckets - 1);
+            let Some(moved) = Self::bucket(this.as_ref(), next) else {
+                break;
+            };
+            let start = home(hash(Self::entry(this.as_ref(), moved)), buckets);
+            let stays = if hole <= next {
+                hole < start && start <= next
+            } else {
+                hole < start || start <= next
+            };
+            if !stays {
+                Self::set_bucket(this.as_mut(), hole, Some(moved));
+                hole = next;
+            }
+        }
+        Self::set_bucket(this.as_mut(), hole, None);
+
+        if index != last {
+            // Point the bucket of the last element to the index it is about to
+            // be moved to.
+            let mut bucket =
+                home(hash(Self::entry(this.as_ref(), last)), buckets);
+            while Self::bucket(this.as_ref(), bucket) != Some(last) {
+                bucket = (bucket + 1) & (buckets - 1);
+            }
+            Self::set_bucket(this.as_mut(), bucket, Some(index));
+        }
+
+        munge!(let RelHashSet { mut entries, .. } = this);
+        let elements = RelVec::as_mut_ptr(entries.as_mut());
+        // SAFETY: `index` is less than `len`, so the element at `index` is
+        // initialized. It is either overwritten or outside of the elements of
+        // the set after this, so it is not accessed again.
+        unsafe {
+            DropRaw::drop_raw(Mut::new_unchecked(elements.add(index)));
+        }
+        if index != last {
+            // SAFETY: `last` is less than `len`, so the element at `last` is
+            // initialized. It is outside of the elements of the set after this,
+            // so it is not accessed again. The elements of a `RelVec` are
+            // allocated in its region.
+            let value = unsafe {
+                In::new_unchecked(Val::new_unchecked(elements.add(last)))
+            };
+            // SAFETY: The element at `index` was just dropped, so it is valid
+            // for writes and not aliased. The elements of a `RelVec` are
+            // allocated in its region.
+            let out = unsafe {
+                In::new_unchecked(Slot::new_unchecked(elements.add(index)))
+            };
+            T::r#move(value, out);
+        }
+        // SAFETY: `last` is less than the current length, and the elements
+        // before it are all initialized.
+        unsafe {
+            RelVec::set_len(entries, last);
+        }
+
+        true
+    }
+
+    /// Clears the `RelHashSet`, removing all values.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the
+    /// `RelHashSet`.
+    pub fn clear(mut this: Mut<'_, Self>)
+    where
+        T: DropRaw,
+    {
+        let table = Self::table_mut_ptr(this.as_mut());
+        let buckets = this.buckets();
+        // SAFETY: The table has `buckets` buckets, and a bucket of all zeroes
+        // is empty.
+        unsafe {
+            ptr::write_bytes(table, 0, buckets);
+        }
+        munge!(let RelHashSet { entries, .. } = this);
+        RelVec::clear(entries);
+    }
+
+    /// Returns an emplacer for a new `RelHashSet` containing the elements that
+    /// are in `this`, `other`, or both.
+    pub fn union<'a, N>(
+        this: Ref<'a, Self>,
+        other: Ref<'a, Self>,
+        alloc: N,
+    ) -> FromIter<N, impl Iterator<Item = Clone<'a, T>>>
+    where
+        T: HashRaw + EqRaw,
+    {
+        let values = Self::iter(this)
+            .chain(Self::iter(other).filter(move |v| !Self::contains(this, *v)))
+            .map(Clone);
+        FromIter(alloc, values)
+    }
+
+    /// Returns an emplacer for a new `RelHashSet` containing the elements that
+    /// are in both `this` and `other`.
+    pub fn intersection<'a, N>(
+        this: Ref<'a, Self>,
+        other: Ref<'a, Self>,
+        alloc: N,
+    ) -> FromIter<N, impl Iterator<Item = Clone<'a, T>>>
+    where
+        T: HashRaw + EqRaw,
+    {
+        let values = Self::iter(this)
+            .filter(move |v| Self::contains(other, *v))
+            .map(Clone);
+        FromIter(alloc, values)
+    }
+
+    /// Returns an emplacer for a new `RelHashSet` containing the elements that
+    /// are in `this` but not in `other`.
+    pub fn difference<'a, N>(
+        this: Ref<'a, Self>,
+        other: Ref<'a, Self>,
+        alloc: N,
+    ) -> FromIter<N, impl Iterator<Item = Clone<'a, T>>>
+    where
+        T: HashRaw + EqRaw,
+    {
+        let values = Self::iter(this)
+            .filter(move |v| !Self::contains(other, *v))
+            .map(Clone);
+        FromIter(alloc, values)
+    }
+
+    /// Returns an emplacer for a new `RelHashSet` containing the elements that
+    /// are in `this` or `other`, but not both.
+    pub fn symmetric_difference<'a, N>(
+        this: Ref<'a, Self>,
+        other: Ref<'a, Self>,
+        alloc: N,
+    ) -> FromIter<N, impl Iterator<Item = Clone<'a, T>>>
+    where
+        T: HashRaw + EqRaw,
+    {
+        let values = Self::iter(this)
+            .filter(move |v| !Self::contains(other, *v))
+            .chain(Self::iter(other).filter(move |v| !Self::contains(this, *v)))
+            .map(Clone);
+        FromIter(alloc, values)
+    }
+}
+
+impl<T, A, B> DebugRaw for RelHashSet<T, A, B>
+where
+    T: DebugRaw,
+    A: RawRegionalAllocator,
+    B: Basis,
+{
+    fn fmt_raw(
+        this: Ref<'_, Self>,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        f.debug_set().entries(Self::iter(this)).finish()
+    }
+}
+
+/// An emplacer for a new, empty `RelHashSet`.
+pub struct New<A>(pub A);
+
+// SAFETY:
+// - `RelHashSet` is `Sized` and always has metadata `()`, so `emplaced_meta`
+//   always returns valid metadata for it.
+// - `emplace_unsized_unchecked` initializes its `out` parameter.
+unsafe impl<T, E, B, A> Emplace<RelHashSet<T, E, B>, A::Region> for New<A>
+where
+    T: DropRaw,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    fn emplaced_meta(&self) -> <RelHashSet<T, E, B> as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelHashSet<T, E, B>>, A::Region>,
+    ) {
+        WithCapacity(self.0, 0).emplace(out);
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter if
+// it returns `Ok`.
+unsafe impl<T, E, B, A> TryEmplace<RelHashSet<T, E, B>, A::Region> for New<A>
+where
+    T: DropRaw,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelHashSet<T, E, B>>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        WithCapacity(self.0, 0).try_emplace(out)
+    }
+}
+
+/// An emplacer for a new `RelHashSet` with an initial capacity.
+pub struct WithCapacity<A>(pub A, pub usize);
+
+// SAFETY:
+// - `RelHashSet` is `Sized` and always has metadata `()`, so `emplaced_meta`
+//   always returns valid metadata for it.
+// - `emplace_unsized_unchecked` initializes its `out` parameter by emplacing
+//   and writing to each field.
+unsafe impl<T, E, B, A> Emplace<RelHashSet<T, E, B>, A::Region>
+    for WithCapacity<A>
+where
+    T: DropRaw,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    fn emplaced_meta(&self) -> <RelHashSet<T, E, B> as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelHashSet<T, E, B>>, A::Region>,
+    ) {
+        // SAFETY: `RelHashSet` is `Sized`, so `out` must have the metadata
+        // returned by `emplaced_meta`.
+        unsafe { self.try_emplace_unsized_unchecked(out).unwrap() }
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter by
+// emplacing and writing to each field if it returns `Ok`.
+unsafe impl<T, E, B, A> TryEmplace<RelHashSet<T, E, B>, A::Region>
+    for WithCapacity<A>
+where
+    T: DropRaw,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelHashSet<T, E, B>>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        let Self(alloc, cap) = self;
+
+        let buckets = buckets_for(cap).ok_or(EmplaceError::CapacityOverflow)?;
+        let basis_buckets = B::from_native_usize(buckets)
+            .map_err(|_| EmplaceError::MetadataOverflow)?;
+        let layout = Layout::array::<B::Usize>(buckets)
+            .map_err(|_| EmplaceError::CapacityOverflow)?;
+
+        munge!(
+            let RelHashSet {
+                entries: out_entries,
+                table: out_table,
+                buckets: out_buckets,
+            } = out;
+        );
+
+        // If allocating the table fails, dropping `entries` frees its
+        // allocation.
+        let entries = In::into_inner(
+            vec::WithCapacity(alloc, cap).try_emplace_val(out_entries)?,
+        );
+        let bytes =
+            E::raw_allocate(RelVec::allocator(entries.as_ref()), layout)
+                .map_err(|_| EmplaceError::AllocFailed)?
+                .cast::<u8>();
+        let table = bytes.as_ptr().cast::<B::Usize>();
+        // SAFETY: `table` was allocated with enough space for `buckets`
+        // buckets, and a bucket of all zeroes is empty.
+        unsafe {
+            ptr::write_bytes(table, 0, buckets);
+        }
+        // SAFETY: `table` is allocated in the allocator of `entries`, and since
+        // `E` implements `RawRegionalAllocator`, it guarantees that memory it
+        // allocates is located in its region.
+        let table = unsafe { In::new_unchecked(table) };
+
+        if let Err(e) = table.try_emplace(out_table) {
+            // SAFETY: `bytes` was just allocated with `layout` and has not been
+            // used.
+            unsafe {
+                E::raw_deallocate(
+                    RelVec::allocator(entries.as_ref()),
+                    bytes,
+                    layout,
+                );
+            }
+            return Err(e);
+        }
+        In::into_inner(out_buckets).write(basis_buckets);
+        Val::leak(entries);
+
+        Ok(())
+    }
+}
+
+/// An emplacer for a `RelHashSet` that inserts each of the values from an
+/// iterator.
+///
+/// Values which are equal to a value that was already inserted are dropped.
+pub struct FromIter<A, I>(pub A, pub I);
+
+// SAFETY:
+// - `RelHashSet` is `Sized` and always has metadata `()`, so `emplaced_meta`
+//   always returns valid metadata for it.
+// - `emplace_unsized_unchecked` initializes its `out` parameter by emplacing a
+//   new `RelHashSet` and inserting each value from the iterator into it.
+unsafe impl<T, E, B, A, I> Emplace<RelHashSet<T, E, B>, A::Region>
+    for FromIter<A, I>
+where
+    T: DropRaw + Move<A::Region> + HashRaw + EqRaw,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+    I: Iterator,
+    I::Item: Emplace<T, A::Region>,
+{
+    fn emplaced_meta(&self) -> <RelHashSet<T, E, B> as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelHashSet<T, E, B>>, A::Region>,
+    ) {
+        let Self(alloc, values) = self;
+        let cap = values.size_hint().0;
+
+        // If an emplacer panics, dropping `set` will drop all of the values
+        // inserted so far and free its allocations.
+        let mut set = In::into_inner(WithCapacity(alloc, cap).emplace_val(out));
+        for value in values {
+            RelHashSet::insert(set.as_mut(), value);
+        }
+        Val::leak(set);
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter by
+// emplacing a new `RelHashSet` and inserting each value from the iterator into
+// it if it returns `Ok`.
+unsafe impl<T, E, B, A, I> TryEmplace<RelHashSet<T, E, B>, A::Region>
+    for FromIter<A, I>
+where
+    T: DropRaw + Move<A::Region> + HashRaw + EqRaw,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+    I: Iterator,
+    I::Item: Emplace<T, A::Region>,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelHashSet<T, E, B>>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        let Self(alloc, values) = self;
+        let cap = values.size_hint().0;
+
+        // If inserting a value fails or panics, dropping `set` will drop all of
+        // the values inserted so far and free its allocations.
+        let mut set =
+            In::into_inner(WithCapacity(alloc, cap).try_emplace_val(out)?);
+        for value in values {
+            RelHashSet::try_insert_with(set.as_mut(), |out| {
+                value.emplace(out);
+                Ok(())
+            })?;
+        }
+        Val::leak(set);
+
+        Ok(())
+    }
+}
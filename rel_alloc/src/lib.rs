@@ -14,13 +14,23 @@
 
 pub mod alloc;
 pub mod boxed;
+pub mod btree_set;
+#[cfg(feature = "serde")]
+pub mod de;
 mod emplace_in;
+pub mod hash_set;
 pub mod string;
 pub mod vec;
+pub mod vec_deque;
+pub mod versioned_box;
 
 pub use self::{
     boxed::RelBox,
+    btree_set::RelBTreeSet,
     emplace_in::EmplaceIn,
+    hash_set::RelHashSet,
     string::RelString,
     vec::RelVec,
+    vec_deque::RelVecDeque,
+    versioned_box::RelVersionedBox,
 };
@@ -1,14 +1,27 @@
 //! A UTF-8 encoded, growable string.
 
-use ::core::{fmt, ptr::copy_nonoverlapping};
+use ::core::{cmp::Ordering, fmt, hash::Hasher, ptr::copy_nonoverlapping};
 use ::mischief::{In, RegionalAllocator, Slot};
 use ::munge::munge;
 use ::ptr_meta::Pointee;
-use ::rel_core::{Basis, DefaultBasis, Emplace, EmplaceExt, Move, Portable};
+use ::rel_core::{
+    transcode::Transcode,
+    Basis,
+    CloneRaw,
+    DefaultBasis,
+    Emplace,
+    EmplaceError,
+    Move,
+    Portable,
+    TryEmplace,
+    TryEmplaceExt,
+};
 use ::situ::{
     alloc::RawRegionalAllocator,
+    cmp::{EqRaw, OrdRaw, PartialEqRaw, PartialOrdRaw},
     fmt::{DebugRaw, DisplayRaw},
-    ops::{DerefMutRaw, DerefRaw},
+    hash::HashRaw,
+    ops::{DerefMutRaw, DerefRaw, IndexMutRaw, IndexRaw, SliceIndexRaw},
     str::{from_raw_utf8_unchecked, from_raw_utf8_unchecked_mut},
     DropRaw,
     Mut,
@@ -18,8 +31,11 @@ use ::situ::{
 use crate::{alloc::RelAllocator, vec, RelVec};
 
 /// A relative counterpart to `String`.
-#[derive(DropRaw, Move, Portable)]
+#[derive(CloneRaw, DropRaw, Move, Portable, Transcode)]
+#[allocator = "A"]
+#[basis = "B"]
 #[repr(C)]
+#[zero_padding]
 pub struct RelString<A: RawRegionalAllocator, B: Basis = DefaultBasis> {
     vec: RelVec<u8, A, B>,
 }
@@ -100,6 +116,106 @@ impl<A: RawRegionalAllocator, B: Basis> RelString<A, B> {
     pub fn is_empty(&self) -> bool {
         self.vec.is_empty()
     }
+
+    /// Reserves capacity for at least `additional` bytes more than the current
+    /// length. The allocator may reserve more space to speculatively avoid
+    /// frequent allocations. After calling `reserve`, capacity will be greater
+    /// than or equal to `self.len() + additional`. Does nothing if capacity is
+    /// already sufficient.
+    ///
+    /// # Panics
+    ///
+    /// Panics if reserving the new capacity fails. See
+    /// [`RelVec::try_reserve`] for the possible failures.
+    pub fn reserve(this: Mut<'_, Self>, additional: usize) {
+        Self::try_reserve(this, additional).unwrap();
+    }
+
+    /// Tries to reserve capacity for at least `additional` bytes more than the
+    /// current length. After calling `try_reserve`, capacity will be greater
+    /// than or equal to `self.len() + additional` if it returns `Ok`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reserving the new capacity fails. See
+    /// [`RelVec::try_reserve`] for the possible failures.
+    pub fn try_reserve(
+        this: Mut<'_, Self>,
+        additional: usize,
+    ) -> Result<(), EmplaceError> {
+        munge!(let RelString { vec } = this);
+        RelVec::try_reserve(vec, additional)
+    }
+
+    /// Appends the given `char` to the end of this `RelString`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if reserving space for the `char` fails.
+    pub fn push(this: Mut<'_, Self>, ch: char) {
+        Self::try_push(this, ch).unwrap();
+    }
+
+    /// Tries to append the given `char` to the end of this `RelString`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reserving space for the `char` fails. If an error
+    /// is returned, the `RelString` is left unchanged.
+    pub fn try_push(this: Mut<'_, Self>, ch: char) -> Result<(), EmplaceError> {
+        Self::try_push_str(this, ch.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Appends the given string slice to the end of this `RelString`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if reserving space for the string fails.
+    pub fn push_str(this: Mut<'_, Self>, string: &str) {
+        Self::try_push_str(this, string).unwrap();
+    }
+
+    /// Tries to append the given string slice to the end of this `RelString`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reserving space for the string fails. If an error
+    /// is returned, the `RelString` is left unchanged.
+    pub fn try_push_str(
+        this: Mut<'_, Self>,
+        string: &str,
+    ) -> Result<(), EmplaceError> {
+        munge!(let RelString { mut vec } = this);
+        RelVec::try_reserve(vec.as_mut(), string.len())?;
+
+        let len = vec.len();
+        // SAFETY:
+        // - `string.as_ptr()` is valid for reads of `string.len()` bytes
+        //   because it is a pointer to a `&str` of that length.
+        // - The `RelVec` is valid for writes of `string.len()` bytes past its
+        //   length because we just reserved that much additional capacity.
+        // - Both `str` and `RelVec<u8>` are allocated with the proper alignment
+        //   for `u8`.
+        // - The two regions of memory cannot overlap because `vec` is mutably
+        //   borrowed and so cannot be aliased by `string`.
+        unsafe {
+            copy_nonoverlapping(
+                string.as_ptr(),
+                RelVec::as_mut_ptr(vec.as_mut()).add(len),
+                string.len(),
+            );
+        }
+        // SAFETY:
+        // - `len + string.len()` is less than or equal to the capacity because
+        //   we reserved space for `string.len()` additional bytes.
+        // - We initialized the new bytes by copying the bytes of `string` to
+        //   them, and they are valid UTF-8 because `string` is.
+        unsafe {
+            RelVec::set_len(vec, len + string.len());
+        }
+
+        Ok(())
+    }
 }
 
 impl<A: RawRegionalAllocator, B: Basis> DerefRaw for RelString<A, B> {
@@ -110,6 +226,50 @@ impl<A: RawRegionalAllocator, B: Basis> DerefRaw for RelString<A, B> {
     }
 }
 
+impl<A, B, I> IndexRaw<I> for RelString<A, B>
+where
+    A: RawRegionalAllocator,
+    B: Basis,
+    I: SliceIndexRaw<str>,
+{
+    type Output = I::Output;
+
+    fn index_raw(this: Ref<'_, Self>, index: I) -> Ref<'_, Self::Output> {
+        IndexRaw::index_raw(Self::as_str(this), index)
+    }
+
+    unsafe fn index_raw_unchecked(
+        this: Ref<'_, Self>,
+        index: I,
+    ) -> Ref<'_, Self::Output> {
+        // SAFETY: The caller has guaranteed that `index` is in bounds for
+        // indexing.
+        unsafe { IndexRaw::index_raw_unchecked(Self::as_str(this), index) }
+    }
+}
+
+impl<A, B, I> IndexMutRaw<I> for RelString<A, B>
+where
+    A: RawRegionalAllocator,
+    B: Basis,
+    I: SliceIndexRaw<str>,
+{
+    fn index_mut_raw(this: Mut<'_, Self>, index: I) -> Mut<'_, Self::Output> {
+        IndexMutRaw::index_mut_raw(Self::as_mut_str(this), index)
+    }
+
+    unsafe fn index_mut_raw_unchecked(
+        this: Mut<'_, Self>,
+        index: I,
+    ) -> Mut<'_, Self::Output> {
+        // SAFETY: The caller has guaranteed that `index` is in bounds for
+        // indexing.
+        unsafe {
+            IndexMutRaw::index_mut_raw_unchecked(Self::as_mut_str(this), index)
+        }
+    }
+}
+
 /// An emplacer for a `RelString` that copies its bytes from a `str`.
 pub struct Clone<'a, A>(pub A, pub &'a str);
 
@@ -130,11 +290,30 @@ where
         self,
         out: In<Slot<'_, RelString<E, B>>, A::Region>,
     ) {
+        // SAFETY: `RelString` is `Sized`, so `out` must have the metadata
+        // returned by `emplaced_meta`.
+        unsafe { self.try_emplace_unsized_unchecked(out).unwrap() }
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter by
+// emplacing to each field if it returns `Ok`.
+unsafe impl<E, B, A> TryEmplace<RelString<E, B>, A::Region> for Clone<'_, A>
+where
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelString<E, B>>, A::Region>,
+    ) -> Result<(), EmplaceError> {
         let len = self.1.len();
 
         munge!(let RelString { vec: out_vec } = out);
-        let mut vec =
-            In::into_inner(vec::WithCapacity(self.0, len).emplace_mut(out_vec));
+        let mut vec = In::into_inner(
+            vec::WithCapacity(self.0, len).try_emplace_mut(out_vec)?,
+        );
         // SAFETY:
         // - `src.1.as_ptr()` is valid for reads of `len` bytes because it is a
         //   pointer to a `&str` of length `len`.
@@ -158,6 +337,52 @@ where
         unsafe {
             RelVec::set_len(vec, len);
         }
+
+        Ok(())
+    }
+}
+
+impl<A1, A2, B1, B2> PartialEqRaw<RelString<A2, B2>> for RelString<A1, B1>
+where
+    A1: RawRegionalAllocator,
+    A2: RawRegionalAllocator,
+    B1: Basis,
+    B2: Basis,
+{
+    fn eq_raw(this: Ref<'_, Self>, other: Ref<'_, RelString<A2, B2>>) -> bool {
+        PartialEqRaw::eq_raw(Self::as_str(this), RelString::as_str(other))
+    }
+}
+
+impl<A: RawRegionalAllocator, B: Basis> EqRaw for RelString<A, B> {}
+
+impl<A1, A2, B1, B2> PartialOrdRaw<RelString<A2, B2>> for RelString<A1, B1>
+where
+    A1: RawRegionalAllocator,
+    A2: RawRegionalAllocator,
+    B1: Basis,
+    B2: Basis,
+{
+    fn partial_cmp_raw(
+        this: Ref<'_, Self>,
+        other: Ref<'_, RelString<A2, B2>>,
+    ) -> Option<Ordering> {
+        PartialOrdRaw::partial_cmp_raw(
+            Self::as_str(this),
+            RelString::as_str(other),
+        )
+    }
+}
+
+impl<A: RawRegionalAllocator, B: Basis> OrdRaw for RelString<A, B> {
+    fn cmp_raw(this: Ref<'_, Self>, other: Ref<'_, Self>) -> Ordering {
+        OrdRaw::cmp_raw(Self::as_str(this), Self::as_str(other))
+    }
+}
+
+impl<A: RawRegionalAllocator, B: Basis> HashRaw for RelString<A, B> {
+    fn hash_raw<H: Hasher>(this: Ref<'_, Self>, state: &mut H) {
+        HashRaw::hash_raw(Self::as_str(this), state)
     }
 }
 
@@ -178,3 +403,17 @@ impl<A: RawRegionalAllocator, B: Basis> DisplayRaw for RelString<A, B> {
         fmt::Display::fmt(&*Self::as_str(this), f)
     }
 }
+
+#[cfg(feature = "serde")]
+impl<A, B> ::rel_core::ser::SerializeRaw for RelString<A, B>
+where
+    A: RawRegionalAllocator,
+    B: Basis,
+{
+    fn serialize_raw<S: ::rel_core::export::serde::Serializer>(
+        this: Ref<'_, Self>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&Self::as_str(this))
+    }
+}
@@ -1,26 +1,53 @@
 //! A contiguous growable array type with heap-allocated contents, written
 //! `RelVec<T>`.
 
-use ::core::{alloc::Layout, fmt, ptr};
+use ::core::{
+    alloc::Layout,
+    cmp::Ordering,
+    fmt,
+    hash::Hasher,
+    mem::{offset_of, size_of},
+    ptr,
+};
 use ::mischief::{In, RegionalAllocator, Slot};
 use ::munge::munge;
 use ::ptr_meta::Pointee;
 use ::rel_core::{
+    clone::Clone,
+    padding::{zero_gaps, ZeroPadding},
+    rel_ptr::Null,
+    transcode::{Transcode, Transcoder},
     Basis,
+    CloneRaw,
     DefaultBasis,
     Emplace,
+    EmplaceError,
     EmplaceExt,
     Move,
     MoveExt,
     Portable,
     RelPtr,
+    TryEmplace,
+    TryEmplaceExt,
 };
 use ::situ::{
     alloc::{RawAllocator, RawRegionalAllocator},
+    cmp::{
+        iter_cmp_raw,
+        iter_eq_raw,
+        iter_partial_cmp_raw,
+        EqRaw,
+        OrdRaw,
+        PartialEqRaw,
+        PartialOrdRaw,
+    },
     fmt::DebugRaw,
-    ops::{DerefMutRaw, DerefRaw, IndexMutRaw, IndexRaw},
+    hash::HashRaw,
+    ops::{DerefMutRaw, DerefRaw, IndexMutRaw, IndexRaw, SliceIndexRaw},
+    slice::{self, IterMutRaw, IterRaw},
     DropRaw,
     Mut,
+    PrefixDropGuard,
     Ref,
     Val,
 };
@@ -28,6 +55,9 @@ use ::situ::{
 use crate::alloc::RelAllocator;
 
 /// A relative counterpart to `Vec`.
+///
+/// A `RelVec` with no capacity may not own a buffer, in which case its pointer
+/// is null.
 #[derive(Move, Portable)]
 #[repr(C)]
 pub struct RelVec<T, A: RawRegionalAllocator, B: Basis = DefaultBasis> {
@@ -59,10 +89,12 @@ where
 
         munge!(let RelVec { ptr, len, cap, alloc } = this);
 
-        // SAFETY: `ptr` is never null and always allocated in `alloc` with a
-        // layout of `layout`.
-        unsafe {
-            A::raw_deallocate(alloc.as_ref(), inner_ptr.cast(), layout);
+        if !ptr.is_null() {
+            // SAFETY: A non-null `ptr` is always allocated in `alloc` with a
+            // layout of `layout`.
+            unsafe {
+                A::raw_deallocate(alloc.as_ref(), inner_ptr.cast(), layout);
+            }
         }
 
         // SAFETY: `ptr` and `alloc` are always valid for dropping and are not
@@ -95,8 +127,7 @@ impl<T, A: RawRegionalAllocator, B: Basis> RelVec<T, A, B> {
     pub fn as_ptr(this: Ref<'_, Self>) -> *const T {
         munge!(let RelVec { ptr, .. } = this);
 
-        // SAFETY: The relative pointer of a `RelVec` is never null.
-        unsafe { RelPtr::as_ptr_unchecked(ptr) }
+        RelPtr::as_ptr(ptr).unwrap_or(ptr::NonNull::dangling().as_ptr())
     }
 
     /// Returns an unsafe mutable pointer to the `RelVec`'s buffer, or a
@@ -106,8 +137,7 @@ impl<T, A: RawRegionalAllocator, B: Basis> RelVec<T, A, B> {
     pub fn as_mut_ptr(this: Mut<'_, Self>) -> *mut T {
         munge!(let RelVec { ptr, .. } = this);
 
-        // SAFETY: The relative pointer of a `RelVec` is never null.
-        unsafe { RelPtr::as_mut_ptr_unchecked(ptr) }
+        RelPtr::as_mut_ptr(ptr).unwrap_or(ptr::NonNull::dangling().as_ptr())
     }
 
     /// Forces the length of the vector to `new_len`.
@@ -141,19 +171,37 @@ impl<T, A: RawRegionalAllocator, B: Basis> RelVec<T, A, B> {
         DerefRaw::deref_raw(this)
     }
 
+    /// Returns a `Mut` to a slice of the elements in the `RelVec`.
+    #[inline]
+    pub fn as_mut_slice(this: Mut<'_, Self>) -> Mut<'_, [T]> {
+        DerefMutRaw::deref_mut_raw(this)
+    }
+
+    /// Returns an iterator over the elements of the `RelVec`.
+    #[inline]
+    pub fn iter(this: Ref<'_, Self>) -> IterRaw<'_, T> {
+        slice::iter(Self::as_slice(this))
+    }
+
+    /// Returns an iterator that allows modifying each element of the `RelVec`.
+    #[inline]
+    pub fn iter_mut(this: Mut<'_, Self>) -> IterMutRaw<'_, T> {
+        slice::iter_mut(Self::as_mut_slice(this))
+    }
+
     /// # Safety
     ///
     /// `index` must be less than `capacity`.
-    unsafe fn slot(
+    pub(crate) unsafe fn slot(
         this: Mut<'_, Self>,
         index: usize,
     ) -> In<Slot<'_, T>, A::Region> {
-        munge!(let RelVec { ptr, .. } = this);
-        // SAFETY: The `ptr` of a `RelVec` is always non-null.
-        let ptr = unsafe { RelPtr::as_mut_ptr_unchecked(ptr) };
-        // SAFETY: The `ptr` of a `RelVec` is always non-null, properly aligned,
-        // and valid for reads and writes. Because `this` is mutably borrowed
-        // for `'_`, the created reference cannot be aliased for `'_`.
+        let ptr = Self::as_mut_ptr(this);
+        // SAFETY: The caller has guaranteed that `index` is less than the
+        // capacity, so the `RelVec` owns a buffer and its `ptr` is non-null,
+        // properly aligned, and valid for reads and writes of the element at
+        // `index`. Because `this` is mutably borrowed for `'_`, the created
+        // reference cannot be aliased for `'_`.
         let slot = unsafe { Slot::new_unchecked(ptr.add(index)) };
         // SAFETY: All slots of the `RelVec` are allocated in `self.alloc`, and
         // since `A` implements `RawRegionalAllocator`, it guarantees that the
@@ -193,99 +241,162 @@ impl<T, A: RawRegionalAllocator, B: Basis> RelVec<T, A, B> {
     ///
     /// # Panics
     ///
-    /// Panics if the new capacity exceeds `isize::MAX` bytes.
-    pub fn reserve(mut this: Mut<'_, Self>, additional: usize)
+    /// Panics if reserving the new capacity fails. See
+    /// [`try_reserve`](RelVec::try_reserve) for the possible failures.
+    pub fn reserve(this: Mut<'_, Self>, additional: usize)
+    where
+        T: Move<A::Region>,
+    {
+        Self::try_reserve(this, additional).unwrap();
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be
+    /// inserted in the given `RelVec<T>`. The collection may reserve more space
+    /// to speculatively avoid frequent reallocations. After calling
+    /// `try_reserve`, the capacity will be greater than or equal to
+    /// `self.len() + additional` if it returns `Ok`. Does nothing if capacity
+    /// is already sufficient.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the new capacity exceeds `isize::MAX` bytes or
+    /// cannot be represented in `B`, if the allocator fails to allocate the new
+    /// buffer, or if the new buffer is too far away to point to. If an error is
+    /// returned, the `RelVec` is left unchanged.
+    pub fn try_reserve(
+        mut this: Mut<'_, Self>,
+        additional: usize,
+    ) -> Result<(), EmplaceError>
     where
         T: Move<A::Region>,
     {
-        let min_cap = this.len() + additional;
-        if min_cap > this.capacity() {
-            let new_cap = min_cap.checked_next_power_of_two().unwrap();
+        let min_cap = this
+            .len()
+            .checked_add(additional)
+            .ok_or(EmplaceError::CapacityOverflow)?;
+        if min_cap <= this.capacity() {
+            return Ok(());
+        }
 
-            let old_layout = Layout::array::<T>(this.capacity()).unwrap();
-            let new_layout = Layout::array::<T>(new_cap).unwrap();
+        let new_cap = min_cap
+            .checked_next_power_of_two()
+            .ok_or(EmplaceError::CapacityOverflow)?;
+        let basis_cap = B::from_native_usize(new_cap)
+            .map_err(|_| EmplaceError::MetadataOverflow)?;
 
-            let ptr = Self::as_mut_ptr(this.as_mut());
-            // SAFETY: The pointer of a `RelVec` is always non-null.
-            let old_ptr = unsafe { ptr::NonNull::new_unchecked(ptr.cast()) };
+        let old_layout = Layout::array::<T>(this.capacity()).unwrap();
+        let new_layout = Layout::array::<T>(new_cap)
+            .map_err(|_| EmplaceError::CapacityOverflow)?;
 
-            // SAFETY:
-            // - `old_ptr` is the memory for the `RelVec`, which was allocated
-            //   with `old_layout`.
-            // - `new_layout` has a strictly larger size than `old_layout`
-            //   because `new_cap` is greater than `min_cap`, which is greater
-            //   than `this.capacity()`.
-            let grew_in_place = unsafe {
+        let has_buffer = !this.ptr.is_null();
+        let old_ptr = Self::as_mut_ptr(this.as_mut());
+        // SAFETY: `as_mut_ptr` always returns a non-null pointer.
+        let old_bytes = unsafe { ptr::NonNull::new_unchecked(old_ptr.cast()) };
+
+        // SAFETY:
+        // - `old_bytes` is the memory for the `RelVec`, which was allocated
+        //   with `old_layout` because the `RelVec` has a buffer.
+        // - `new_layout` has a strictly larger size than `old_layout` because
+        //   `new_cap` is greater than `min_cap`, which is greater than
+        //   `this.capacity()`.
+        let grew_in_place = has_buffer
+            && unsafe {
                 RawAllocator::raw_grow_in_place(
                     Self::allocator(this.as_ref()),
-                    old_ptr,
+                    old_bytes,
                     old_layout,
                     new_layout,
                 )
                 .is_ok()
             };
 
-            if !grew_in_place {
-                let allocation = RawAllocator::raw_allocate(
-                    Self::allocator(this.as_ref()),
-                    new_layout,
-                );
-                let new_ptr = allocation.unwrap().as_ptr().cast::<T>();
-                for i in 0..this.len() {
-                    // SAFETY:
-                    // - `new_ptr` is the pointer of a `NonNull`, so it must be
-                    //   non-null. It is guaranteed to be aligned to
-                    //   `new_layout.align()` by the implementation of
-                    //   `RawAllocator`, which is at least `align_of::<T>()`. It
-                    //   is also guaranteed to be valid for reads and writes of
-                    //   at least `new_layout.size()` bytes, which covers every
-                    //   element slot in `new_ptr`.
-                    // - `new_ptr` is freshly-allocated, so only we have access
-                    //   to it. It is not currently aliased by any other
-                    //   pointers.
-                    let out = unsafe { Slot::new_unchecked(new_ptr.add(i)) };
-                    // SAFETY: `new_ptr` is allocated in `this.alloc`, and since
-                    // `A` implements `RawRegionalAllocator`, it guarantees that
-                    // memory it allocates is located in its region.
-                    let out = unsafe { In::new_unchecked(out) };
-                    // SAFETY: `i` is less than `len` and we move out of it then
-                    // free the backing storage so it can't be accessed
-                    // afterward.
-                    let value = unsafe { Self::take(this.as_mut(), i) };
-                    T::r#move(value, out);
+        if !grew_in_place {
+            let new_bytes = RawAllocator::raw_allocate(
+                Self::allocator(this.as_ref()),
+                new_layout,
+            )
+            .map_err(|_| EmplaceError::AllocFailed)?
+            .cast::<u8>();
+            let new_ptr = new_bytes.as_ptr().cast::<T>();
+
+            munge!(let RelVec { ptr, alloc, .. } = this.as_mut());
+            let new_ptr_in =
+                // SAFETY: `new_ptr` is allocated in `this.alloc`, and since `A`
+                // implements `RawRegionalAllocator` it guarantees that memory
+                // it allocates is located in its region.
+                unsafe { In::<_, A::Region>::new_unchecked(new_ptr) };
+            if let Err(e) = RelPtr::try_set(ptr, new_ptr_in) {
+                // SAFETY: `new_bytes` was just allocated by `alloc` with
+                // `new_layout` and has not been used.
+                unsafe {
+                    RawAllocator::raw_deallocate(
+                        alloc.as_ref(),
+                        new_bytes,
+                        new_layout,
+                    );
                 }
+                return Err(e);
+            }
 
-                munge!(let RelVec { ptr, alloc, .. } = this.as_mut());
-                let new_ptr =
-                    // SAFETY: `new_ptr` is allocated in `this.alloc`, and
-                    // since `A` implements `RawRegionalAllocator` it guarantees
-                    // that memory it allocates is located in its region.
-                    unsafe { In::<_, A::Region>::new_unchecked(new_ptr) };
-                RelPtr::set(ptr, new_ptr);
+            for i in 0..this.len() {
+                // SAFETY:
+                // - `old_ptr` points to the old buffer, which holds `len`
+                //   initialized elements. `i` is less than `len`, so the
+                //   element at `i` is non-null, properly aligned, valid for
+                //   reads and writes, and initialized.
+                // - The old buffer is only accessed through `old_ptr` now, and
+                //   each element is moved out exactly once before the buffer is
+                //   freed, so the value cannot be aliased or accessed again.
+                let value = unsafe { Val::new_unchecked(old_ptr.add(i)) };
+                // SAFETY: The old buffer was allocated in `this.alloc`, and
+                // since `A` implements `RawRegionalAllocator`, it guarantees
+                // that memory it allocates is located in its region.
+                let value = unsafe { In::new_unchecked(value) };
+                // SAFETY:
+                // - `new_ptr` is the pointer of a `NonNull`, so it must be
+                //   non-null. It is guaranteed to be aligned to
+                //   `new_layout.align()` by the implementation of
+                //   `RawAllocator`, which is at least `align_of::<T>()`. It is
+                //   also guaranteed to be valid for reads and writes of at
+                //   least `new_layout.size()` bytes, which covers every
+                //   element slot in `new_ptr`.
+                // - `new_ptr` is freshly-allocated, so only we have access to
+                //   it. It is not currently aliased by any other pointers.
+                let out = unsafe { Slot::new_unchecked(new_ptr.add(i)) };
+                // SAFETY: `new_ptr` is allocated in `this.alloc`, and since `A`
+                // implements `RawRegionalAllocator`, it guarantees that memory
+                // it allocates is located in its region.
+                let out = unsafe { In::new_unchecked(out) };
+                T::r#move(value, out);
+            }
 
+            if has_buffer {
                 // SAFETY:
-                // - `old_ptr` is currently allocated because it was previously
-                //   allocated and `grow_in_place` failed.
-                // - `old_layout` was the layout used to allocate `old_ptr`.
+                // - `old_bytes` is currently allocated because the `RelVec` has
+                //   a buffer and `grow_in_place` failed.
+                // - `old_layout` was the layout used to allocate `old_bytes`.
                 unsafe {
                     RawAllocator::raw_deallocate(
-                        alloc.as_ref(),
-                        old_ptr,
+                        Self::allocator(this.as_ref()),
+                        old_bytes,
                         old_layout,
                     );
                 }
             }
-
-            munge!(let RelVec { mut cap, .. } = this);
-            *cap = B::from_native_usize(new_cap).unwrap();
         }
+
+        munge!(let RelVec { mut cap, .. } = this);
+        *cap = basis_cap;
+
+        Ok(())
     }
 
     /// Appends an element to the back of a collection.
     ///
     /// # Panics
     ///
-    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    /// Panics if reserving space for the new element fails. See
+    /// [`try_reserve`](RelVec::try_reserve) for the possible failures.
     pub fn push<E>(mut this: Mut<'_, Self>, value: E)
     where
         T: Move<A::Region>,
@@ -308,6 +419,42 @@ impl<T, A: RawRegionalAllocator, B: Basis> RelVec<T, A, B> {
         }
     }
 
+    /// Tries to append an element to the back of a collection.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reserving space for the new element fails or if
+    /// emplacing `value` fails. See [`try_reserve`](RelVec::try_reserve) for
+    /// the possible reservation failures. If an error is returned, the length
+    /// and contents of the `RelVec` are unchanged, but its capacity may have
+    /// grown.
+    pub fn try_push<E>(
+        mut this: Mut<'_, Self>,
+        value: E,
+    ) -> Result<(), EmplaceError>
+    where
+        T: Move<A::Region>,
+        E: TryEmplace<T, A::Region>,
+    {
+        Self::try_reserve(this.as_mut(), 1)?;
+        let len = this.len();
+
+        // SAFETY: `len` is definitely less than `capacity` because we reserved
+        // one slot at the end of our storage and `len` is equal to the length
+        // of the `RelVec`.
+        let slot = unsafe { Self::slot(this.as_mut(), len) };
+        value.try_emplace(slot)?;
+
+        // SAFETY: `len + 1` must be less than or equal to `capacity` because we
+        // reserved space for one additional element. We just initialized that
+        // element by emplacing to it.
+        unsafe {
+            Self::set_len(this, len + 1);
+        }
+
+        Ok(())
+    }
+
     /// Extends the `RelVec` with the contents of an iterator.
     pub fn extend<I>(mut this: Mut<'_, Self>, mut values: I)
     where
@@ -317,31 +464,26 @@ impl<T, A: RawRegionalAllocator, B: Basis> RelVec<T, A, B> {
     {
         // We can avoid setting our length every time we push a new element by
         // reserving the iterator's estimated size and pushing as many as we can
-        // up to that limit. Then
+        // up to that limit. The length is set when `guard` is dropped, even if
+        // an emplacer panics.
         let reserve = values.size_hint().0;
-        let mut new_len = this.len();
-
         Self::reserve(this.as_mut(), reserve);
-        while new_len < this.capacity() {
+
+        let mut guard = SetLenOnDrop {
+            len: this.len(),
+            vec: this.as_mut(),
+        };
+        while guard.len < guard.vec.capacity() {
             if let Some(value) = values.next() {
-                // SAFETY: `len + i` must be less than `capacity` because we
-                // reserved `reserve` slots at the end of our storage, `len`
-                // is equal to the length of the `RelVec`, and `i` is less than
-                // `reserve`.
-                let slot = unsafe { Self::slot(this.as_mut(), new_len) };
-                new_len += 1;
+                // SAFETY: `guard.len` is less than `capacity`.
+                let slot = unsafe { Self::slot(guard.vec.as_mut(), guard.len) };
                 value.emplace(slot);
+                guard.len += 1;
             } else {
                 break;
             }
         }
-
-        // SAFETY: `len + reserve` must be less than or equal to `capacity`
-        // because we reserved space for `reserve` additional elements. We just
-        // initialized those element by emplacing to them.
-        unsafe {
-            Self::set_len(this.as_mut(), new_len);
-        }
+        drop(guard);
 
         // The `size_hint` from `values` isn't required to be accurate, so we
         // have to slowly push any remaining values.
@@ -350,6 +492,52 @@ impl<T, A: RawRegionalAllocator, B: Basis> RelVec<T, A, B> {
         }
     }
 
+    /// Tries to extend the `RelVec` with the contents of an iterator.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reserving space for the values fails or if
+    /// emplacing any of the values fails. See
+    /// [`try_reserve`](RelVec::try_reserve) for the possible reservation
+    /// failures. If an error is returned, the values emplaced before the
+    /// failure remain in the `RelVec`.
+    pub fn try_extend<I>(
+        mut this: Mut<'_, Self>,
+        mut values: I,
+    ) -> Result<(), EmplaceError>
+    where
+        T: Move<A::Region>,
+        I: Iterator,
+        I::Item: TryEmplace<T, A::Region>,
+    {
+        // This mirrors `extend`: the length is set when `guard` is dropped,
+        // even if an emplacer fails or panics.
+        let reserve = values.size_hint().0;
+        Self::try_reserve(this.as_mut(), reserve)?;
+
+        let mut guard = SetLenOnDrop {
+            len: this.len(),
+            vec: this.as_mut(),
+        };
+        while guard.len < guard.vec.capacity() {
+            if let Some(value) = values.next() {
+                // SAFETY: `guard.len` is less than `capacity`.
+                let slot = unsafe { Self::slot(guard.vec.as_mut(), guard.len) };
+                value.try_emplace(slot)?;
+                guard.len += 1;
+            } else {
+                break;
+            }
+        }
+        drop(guard);
+
+        for value in values {
+            Self::try_push(this.as_mut(), value)?;
+        }
+
+        Ok(())
+    }
+
     /// Clears the `RelVec`, removing all values.
     ///
     /// Note that this method has no effect on the allocated capacity of the
@@ -373,6 +561,25 @@ impl<T, A: RawRegionalAllocator, B: Basis> RelVec<T, A, B> {
     }
 }
 
+/// Sets the length of a `RelVec` when dropped.
+///
+/// This keeps the elements emplaced by `extend` from leaking if an emplacer
+/// panics.
+struct SetLenOnDrop<'a, T, A: RawRegionalAllocator, B: Basis> {
+    vec: Mut<'a, RelVec<T, A, B>>,
+    len: usize,
+}
+
+impl<T, A: RawRegionalAllocator, B: Basis> Drop for SetLenOnDrop<'_, T, A, B> {
+    fn drop(&mut self) {
+        // SAFETY: `len` is only incremented after the element at `len` has
+        // been initialized, and never exceeds the capacity of `vec`.
+        unsafe {
+            RelVec::set_len(self.vec.as_mut(), self.len);
+        }
+    }
+}
+
 impl<T, A: RawRegionalAllocator, B: Basis> DerefRaw for RelVec<T, A, B> {
     type Target = [T];
 
@@ -408,20 +615,21 @@ impl<T, A: RawRegionalAllocator, B: Basis> DerefMutRaw for RelVec<T, A, B> {
     }
 }
 
-impl<T, A, B> IndexRaw<usize> for RelVec<T, A, B>
+impl<T, A, B, I> IndexRaw<I> for RelVec<T, A, B>
 where
     A: RawRegionalAllocator,
     B: Basis,
+    I: SliceIndexRaw<[T]>,
 {
-    type Output = T;
+    type Output = I::Output;
 
-    fn index_raw(this: Ref<'_, Self>, index: usize) -> Ref<'_, Self::Output> {
+    fn index_raw(this: Ref<'_, Self>, index: I) -> Ref<'_, Self::Output> {
         IndexRaw::index_raw(DerefRaw::deref_raw(this), index)
     }
 
     unsafe fn index_raw_unchecked(
         this: Ref<'_, Self>,
-        index: usize,
+        index: I,
     ) -> Ref<'_, Self::Output> {
         // SAFETY: The caller has guaranteed that `index` is in bounds for
         // indexing.
@@ -431,21 +639,19 @@ where
     }
 }
 
-impl<T, A, B> IndexMutRaw<usize> for RelVec<T, A, B>
+impl<T, A, B, I> IndexMutRaw<I> for RelVec<T, A, B>
 where
     A: RawRegionalAllocator,
     B: Basis,
+    I: SliceIndexRaw<[T]>,
 {
-    fn index_mut_raw(
-        this: Mut<'_, Self>,
-        index: usize,
-    ) -> Mut<'_, Self::Output> {
+    fn index_mut_raw(this: Mut<'_, Self>, index: I) -> Mut<'_, Self::Output> {
         IndexMutRaw::index_mut_raw(DerefMutRaw::deref_mut_raw(this), index)
     }
 
     unsafe fn index_mut_raw_unchecked(
         this: Mut<'_, Self>,
-        index: usize,
+        index: I,
     ) -> Mut<'_, Self::Output> {
         // SAFETY: The caller has guaranteed that `index` is in bounds for
         // indexing.
@@ -458,6 +664,59 @@ where
     }
 }
 
+impl<T, U, A1, A2, B1, B2> PartialEqRaw<RelVec<U, A2, B2>> for RelVec<T, A1, B1>
+where
+    T: PartialEqRaw<U>,
+    A1: RawRegionalAllocator,
+    A2: RawRegionalAllocator,
+    B1: Basis,
+    B2: Basis,
+{
+    fn eq_raw(this: Ref<'_, Self>, other: Ref<'_, RelVec<U, A2, B2>>) -> bool {
+        this.len() == other.len()
+            && iter_eq_raw(Self::iter(this), RelVec::iter(other))
+    }
+}
+
+impl<T: EqRaw, A: RawRegionalAllocator, B: Basis> EqRaw for RelVec<T, A, B> {}
+
+impl<T, U, A1, A2, B1, B2> PartialOrdRaw<RelVec<U, A2, B2>>
+    for RelVec<T, A1, B1>
+where
+    T: PartialOrdRaw<U>,
+    A1: RawRegionalAllocator,
+    A2: RawRegionalAllocator,
+    B1: Basis,
+    B2: Basis,
+{
+    fn partial_cmp_raw(
+        this: Ref<'_, Self>,
+        other: Ref<'_, RelVec<U, A2, B2>>,
+    ) -> Option<Ordering> {
+        iter_partial_cmp_raw(Self::iter(this), RelVec::iter(other))
+    }
+}
+
+impl<T: OrdRaw, A: RawRegionalAllocator, B: Basis> OrdRaw for RelVec<T, A, B> {
+    fn cmp_raw(this: Ref<'_, Self>, other: Ref<'_, Self>) -> Ordering {
+        iter_cmp_raw(Self::iter(this), Self::iter(other))
+    }
+}
+
+impl<T, A, B> HashRaw for RelVec<T, A, B>
+where
+    T: HashRaw,
+    A: RawRegionalAllocator,
+    B: Basis,
+{
+    fn hash_raw<H: Hasher>(this: Ref<'_, Self>, state: &mut H) {
+        state.write_usize(this.len());
+        for value in Self::iter(this) {
+            HashRaw::hash_raw(value, state);
+        }
+    }
+}
+
 impl<T, A, B> DebugRaw for RelVec<T, A, B>
 where
     T: DebugRaw,
@@ -468,9 +727,250 @@ where
         this: Ref<'_, Self>,
         f: &mut fmt::Formatter<'_>,
     ) -> Result<(), fmt::Error> {
-        f.debug_list()
-            .entries((0..this.len()).map(|i| IndexRaw::index_raw(this, i)))
-            .finish()
+        f.debug_list().entries(Self::iter(this)).finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, A, B> ::rel_core::ser::SerializeRaw for RelVec<T, A, B>
+where
+    T: ::rel_core::ser::SerializeRaw,
+    A: RawRegionalAllocator,
+    B: Basis,
+{
+    fn serialize_raw<S: ::rel_core::export::serde::Serializer>(
+        this: Ref<'_, Self>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        ::rel_core::ser::SerializeRaw::serialize_raw(
+            DerefRaw::deref_raw(this),
+            serializer,
+        )
+    }
+}
+
+// SAFETY: `clone_raw_unchecked` and `try_clone_raw_unchecked` initialize their
+// `out` parameter by emplacing to each field, then cloning each element into
+// the new buffer.
+unsafe impl<T, A, B> CloneRaw<A::Region> for RelVec<T, A, B>
+where
+    T: CloneRaw<A::Region>,
+    A: RawRegionalAllocator + CloneRaw<A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+{
+    /// # Panics
+    ///
+    /// Panics if the allocator fails to allocate memory for the cloned buffer,
+    /// or if cloning an element fails.
+    unsafe fn clone_raw_unchecked(
+        this: Ref<'_, Self>,
+        out: In<Slot<'_, Self>, A::Region>,
+    ) {
+        // SAFETY: `RelVec` is `Sized`, so `out` must have the same metadata as
+        // `this`.
+        unsafe { Self::try_clone_raw_unchecked(this, out).unwrap() }
+    }
+
+    unsafe fn try_clone_raw_unchecked(
+        this: Ref<'_, Self>,
+        mut out: In<Slot<'_, Self>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        let len = this.len();
+        let alloc = Self::allocator(this);
+
+        munge!(
+            let RelVec {
+                ptr: out_ptr,
+                len: out_len,
+                cap: out_cap,
+                alloc: out_alloc,
+            } = out.as_mut();
+        );
+
+        // An empty clone does not need a buffer.
+        if len == 0 {
+            Null.emplace(out_ptr);
+            In::into_inner(out_len).write(B::from_native_usize(0).unwrap());
+            In::into_inner(out_cap).write(B::from_native_usize(0).unwrap());
+            Clone(alloc).emplace(out_alloc);
+            return Ok(());
+        }
+
+        let layout = Layout::array::<T>(len).unwrap();
+        let bytes = A::raw_allocate(alloc, layout)
+            .map_err(|_| EmplaceError::AllocFailed)?
+            .cast::<u8>();
+        let ptr = bytes.as_ptr().cast::<T>();
+        // SAFETY: `ptr` is allocated in `alloc`, and since `A` implements
+        // `RawRegionalAllocator`, it guarantees that memory it allocates is
+        // located in its region.
+        let ptr_in = unsafe { In::new_unchecked(ptr) };
+        if let Err(e) = ptr_in.try_emplace(out_ptr) {
+            // SAFETY: `bytes` was just allocated by `alloc` with `layout` and
+            // has not been used.
+            unsafe {
+                A::raw_deallocate(alloc, bytes, layout);
+            }
+            return Err(e);
+        }
+        In::into_inner(out_len).write(B::from_native_usize(0).unwrap());
+        In::into_inner(out_cap).write(B::from_native_usize(len).unwrap());
+        Clone(alloc).emplace(out_alloc);
+
+        // SAFETY: Every field of `out` has been initialized, and an empty
+        // `RelVec` with a buffer is valid.
+        let mut vec = unsafe { Val::from_slot_unchecked(In::into_inner(out)) };
+        // SAFETY: `ptr` was returned from `raw_allocate`, so it must be
+        // non-null. It is guaranteed to be aligned to `align_of::<T>()` and
+        // valid for reads and writes of `len` elements. The buffer is not
+        // accessed again except to clone to each element, so the initialized
+        // prefix does not alias any other accessible references.
+        let mut guard = unsafe { PrefixDropGuard::new(ptr) };
+        // If cloning an element fails or panics, `guard` drops the elements
+        // cloned so far and then dropping `vec` frees the buffer.
+        for (i, value) in Self::iter(this).enumerate() {
+            // SAFETY: `i` is less than `len`, which is the capacity of `vec`.
+            let slot = unsafe { Self::slot(vec.as_mut(), i) };
+            Clone(value).try_emplace(slot)?;
+            // SAFETY: The first `i + 1` elements of the buffer have been
+            // initialized by cloning to them.
+            unsafe {
+                guard.set_len(i + 1);
+            }
+        }
+        PrefixDropGuard::forget(guard);
+        // SAFETY: `len` is the capacity of `vec`, and all of its elements have
+        // been initialized.
+        unsafe {
+            Self::set_len(vec.as_mut(), len);
+        }
+        Val::leak(vec);
+
+        Ok(())
+    }
+}
+
+// SAFETY: `transcode` initializes its `out` parameter by allocating a new
+// buffer, transcoding and encoding each element into it, and emplacing to each
+// field.
+unsafe impl<T, E1, B1, B2, A, E2> Transcode<B2, A, E2> for RelVec<T, E1, B1>
+where
+    T: Transcode<B2, A, E2>,
+    E1: RawRegionalAllocator + DropRaw,
+    B1: Basis,
+    <B1 as Basis>::Usize: DropRaw,
+    B2: Basis,
+    <B2 as Basis>::Usize: DropRaw,
+    A: ::core::clone::Clone + RegionalAllocator + RelAllocator<E2, A::Region>,
+    E2: RawRegionalAllocator<Region = A::Region> + DropRaw,
+    RelVec<T::Target, E2, B2>: Portable,
+{
+    type Target = RelVec<T::Target, E2, B2>;
+
+    fn transcode(
+        this: Ref<'_, Self>,
+        transcoder: &Transcoder<B2, A, E2>,
+        out: In<Slot<'_, Self::Target>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        let len = this.len();
+        let layout = Layout::array::<T::Target>(len)
+            .map_err(|_| EmplaceError::CapacityOverflow)?;
+        let basis_len = B2::from_native_usize(len)
+            .map_err(|_| EmplaceError::MetadataOverflow)?;
+
+        let ptr = transcoder
+            .alloc()
+            .allocate(layout)
+            .map_err(|_| EmplaceError::AllocFailed)?
+            .as_ptr()
+            .cast::<T::Target>();
+        for (i, value) in Self::iter(this).enumerate() {
+            // SAFETY:
+            // - `ptr` was returned from `allocate`, so it must be non-null. It
+            //   is guaranteed to be aligned to `align_of::<T::Target>()` and
+            //   valid for reads and writes of `len` elements, which covers `i`.
+            // - `ptr` is freshly-allocated, so it is not currently aliased by
+            //   any other pointers.
+            let slot = unsafe { Slot::new_unchecked(ptr.add(i)) };
+            // SAFETY: `ptr` is allocated in `transcoder.alloc()`, and since `A`
+            // implements `RegionalAllocator`, it guarantees that memory it
+            // allocates is located in its region.
+            let slot = unsafe { In::new_unchecked(slot) };
+            T::transcode(value, transcoder, slot)?;
+            // SAFETY: The element at `i` was just transcoded and is not
+            // accessed again.
+            unsafe {
+                transcoder.encode(ptr.add(i));
+            }
+        }
+        // SAFETY: `ptr` is allocated in `transcoder.alloc()`, and since `A`
+        // implements `RegionalAllocator`, it guarantees that memory it
+        // allocates is located in its region.
+        let ptr = unsafe { In::new_unchecked(ptr) };
+
+        munge!(
+            let RelVec {
+                ptr: out_ptr,
+                len: out_len,
+                cap: out_cap,
+                alloc: out_alloc,
+            } = out;
+        );
+
+        ptr.try_emplace(out_ptr)?;
+        In::into_inner(out_len).write(basis_len);
+        In::into_inner(out_cap).write(basis_len);
+        transcoder.alloc().clone().emplace(out_alloc);
+
+        Ok(())
+    }
+}
+
+// SAFETY: `zero_padding` zeroes the bytes of the `RelVec` which are not covered
+// by its fields, then zeroes the padding of its fields and elements. The spare
+// capacity after its elements does not contain any values, so it is zeroed as
+// well.
+unsafe impl<T, A, B> ZeroPadding for RelVec<T, A, B>
+where
+    T: ZeroPadding,
+    A: RawRegionalAllocator + ZeroPadding,
+    B: Basis,
+    RelPtr<T, A::Region, B>: ZeroPadding,
+{
+    fn zero_padding(mut this: Mut<'_, Self>) {
+        // SAFETY: `this` points to a `RelVec`, and the bytes which are not
+        // covered by any of its fields are padding.
+        unsafe {
+            zero_gaps(
+                this.as_ptr().cast(),
+                0,
+                size_of::<Self>(),
+                &[
+                    (
+                        offset_of!(Self, ptr),
+                        size_of::<RelPtr<T, A::Region, B>>(),
+                    ),
+                    (offset_of!(Self, len), size_of::<B::Usize>()),
+                    (offset_of!(Self, cap), size_of::<B::Usize>()),
+                    (offset_of!(Self, alloc), size_of::<A>()),
+                ],
+            );
+        }
+
+        let len = this.len();
+        let spare = this.capacity() - len;
+        let elements = Self::as_mut_ptr(this.as_mut());
+        // SAFETY: The elements from `len` to `capacity` are allocated but not
+        // initialized, so they are valid for writes and contain no values.
+        unsafe {
+            ptr::write_bytes(elements.add(len), 0, spare);
+        }
+        <[T]>::zero_padding(Self::deref_mut_raw(this.as_mut()));
+
+        munge!(let RelVec { ptr, alloc, .. } = this);
+        ZeroPadding::zero_padding(ptr);
+        ZeroPadding::zero_padding(alloc);
     }
 }
 
@@ -499,6 +999,24 @@ where
     }
 }
 
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter if
+// it returns `Ok`.
+unsafe impl<T, E, B, A> TryEmplace<RelVec<T, E, B>, A::Region> for New<A>
+where
+    T: DropRaw,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelVec<T, E, B>>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        WithCapacity(self.0, 0).try_emplace(out)
+    }
+}
+
 /// An emplacer for a new `RelVec` with an initial capacity.
 pub struct WithCapacity<A>(pub A, pub usize);
 
@@ -524,16 +1042,41 @@ where
         self,
         out: In<Slot<'_, RelVec<T, E, B>>, A::Region>,
     ) {
+        // SAFETY: `RelVec` is `Sized`, so `out` must have the metadata returned
+        // by `emplaced_meta`.
+        unsafe { self.try_emplace_unsized_unchecked(out).unwrap() }
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter by
+// emplacing and writing to each field if it returns `Ok`.
+unsafe impl<T, E, B, A> TryEmplace<RelVec<T, E, B>, A::Region>
+    for WithCapacity<A>
+where
+    T: DropRaw,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelVec<T, E, B>>, A::Region>,
+    ) -> Result<(), EmplaceError> {
         let Self(alloc, cap) = self;
 
-        let ptr = alloc
-            .allocate(Layout::array::<T>(cap).unwrap())
-            .unwrap()
-            .cast()
-            .as_ptr();
+        let layout = Layout::array::<T>(cap)
+            .map_err(|_| EmplaceError::CapacityOverflow)?;
+        let basis_cap = B::from_native_usize(cap)
+            .map_err(|_| EmplaceError::MetadataOverflow)?;
+
+        let bytes = alloc
+            .allocate(layout)
+            .map_err(|_| EmplaceError::AllocFailed)?
+            .cast::<u8>();
         // SAFETY: The pointer returned from `allocate` is guaranteed to be in
         // the region of `R`.
-        let ptr = unsafe { In::new_unchecked(ptr) };
+        let ptr = unsafe { In::new_unchecked(bytes.as_ptr().cast::<T>()) };
 
         munge!(
             let RelVec {
@@ -544,9 +1087,113 @@ where
             } = out;
         );
 
-        ptr.emplace(out_ptr);
+        if let Err(e) = ptr.try_emplace(out_ptr) {
+            // SAFETY: `bytes` was just allocated by `alloc` with `layout` and
+            // has not been used.
+            unsafe {
+                alloc.deallocate(bytes, layout);
+            }
+            return Err(e);
+        }
         In::into_inner(out_len).write(B::from_native_usize(0).unwrap());
-        In::into_inner(out_cap).write(B::from_native_usize(cap).unwrap());
+        In::into_inner(out_cap).write(basis_cap);
         alloc.emplace(out_alloc);
+
+        Ok(())
+    }
+}
+
+/// An emplacer for a `RelVec` that emplaces each of the values from an
+/// iterator.
+///
+/// The `RelVec` is allocated once with enough capacity for all of the values.
+/// If emplacing a value panics, the values that were already emplaced are
+/// dropped and the allocation is freed.
+pub struct FromIter<A, I>(pub A, pub I);
+
+// SAFETY:
+// - `RelVec` is `Sized` and always has metadata `()`, so `emplaced_meta` always
+//   returns valid metadata for it.
+// - `emplace_unsized_unchecked` initializes its `out` parameter by emplacing a
+//   new `RelVec` and pushing each value from the iterator to it.
+unsafe impl<T, E, B, A, I> Emplace<RelVec<T, E, B>, A::Region>
+    for FromIter<A, I>
+where
+    T: DropRaw,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+    I: ExactSizeIterator,
+    I::Item: Emplace<T, A::Region>,
+{
+    fn emplaced_meta(&self) -> <RelVec<T, E, B> as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelVec<T, E, B>>, A::Region>,
+    ) {
+        let Self(alloc, values) = self;
+        let cap = values.len();
+
+        // If an emplacer panics, dropping `vec` will drop all of the values
+        // emplaced so far and free its allocation.
+        let mut vec = In::into_inner(WithCapacity(alloc, cap).emplace_val(out));
+        for value in values.take(cap) {
+            let len = vec.len();
+            // SAFETY: `len` is less than `cap` because we take at most `cap`
+            // values from the iterator.
+            let slot = unsafe { RelVec::slot(vec.as_mut(), len) };
+            value.emplace(slot);
+            // SAFETY: `len + 1` is less than or equal to `cap`, and we just
+            // initialized the element at `len` by emplacing to it.
+            unsafe {
+                RelVec::set_len(vec.as_mut(), len + 1);
+            }
+        }
+        Val::leak(vec);
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter by
+// emplacing a new `RelVec` and pushing each value from the iterator to it if
+// it returns `Ok`.
+unsafe impl<T, E, B, A, I> TryEmplace<RelVec<T, E, B>, A::Region>
+    for FromIter<A, I>
+where
+    T: DropRaw,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+    I: ExactSizeIterator,
+    I::Item: TryEmplace<T, A::Region>,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelVec<T, E, B>>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        let Self(alloc, values) = self;
+        let cap = values.len();
+
+        // If emplacing a value fails or panics, dropping `vec` will drop all of
+        // the values emplaced so far and free its allocation.
+        let mut vec =
+            In::into_inner(WithCapacity(alloc, cap).try_emplace_val(out)?);
+        for value in values.take(cap) {
+            let len = vec.len();
+            // SAFETY: `len` is less than `cap` because we take at most `cap`
+            // values from the iterator.
+            let slot = unsafe { RelVec::slot(vec.as_mut(), len) };
+            value.try_emplace(slot)?;
+            // SAFETY: `len + 1` is less than or equal to `cap`, and we just
+            // initialized the element at `len` by emplacing to it.
+            unsafe {
+                RelVec::set_len(vec.as_mut(), len + 1);
+            }
+        }
+        Val::leak(vec);
+
+        Ok(())
     }
 }
@@ -0,0 +1,986 @@
+//! A double-ended queue implemented with a growable ring buffer, written
+//! `RelVecDeque<T>`.
+
+use ::core::{
+    alloc::Layout,
+    fmt,
+    iter::Chain,
+    mem::{offset_of, size_of},
+    ops::Range,
+    ptr,
+};
+use ::mischief::{In, RegionalAllocator, Slot};
+use ::munge::munge;
+use ::ptr_meta::Pointee;
+use ::rel_core::{
+    padding::{zero_gaps, ZeroPadding},
+    Basis,
+    DefaultBasis,
+    Emplace,
+    EmplaceError,
+    EmplaceExt,
+    Move,
+    MoveExt,
+    Portable,
+    RelPtr,
+    TryEmplace,
+    TryEmplaceExt,
+};
+use ::situ::{
+    alloc::{RawAllocator, RawRegionalAllocator},
+    cmp::{iter_eq_raw, EqRaw, PartialEqRaw},
+    fmt::DebugRaw,
+    ops::{IndexMutRaw, IndexRaw},
+    slice::{self, IterRaw},
+    DropRaw,
+    Mut,
+    Ref,
+    Val,
+};
+
+use crate::alloc::RelAllocator;
+
+/// A relative counterpart to `VecDeque`.
+///
+/// The elements are stored in a ring buffer starting at `head`, so the
+/// elements at the end of the queue may wrap around to the start of the
+/// buffer.
+#[derive(Move, Portable)]
+#[repr(C)]
+pub struct RelVecDeque<T, A: RawRegionalAllocator, B: Basis = DefaultBasis> {
+    ptr: RelPtr<T, A::Region, B>,
+    head: B::Usize,
+    len: B::Usize,
+    cap: B::Usize,
+    alloc: A,
+}
+
+impl<T, A, B> DropRaw for RelVecDeque<T, A, B>
+where
+    T: DropRaw,
+    A: RawRegionalAllocator + DropRaw,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+{
+    #[inline]
+    unsafe fn drop_raw(mut this: Mut<'_, Self>) {
+        let layout = Layout::array::<T>(this.capacity()).unwrap();
+        let inner_ptr = Self::as_mut_ptr(this.as_mut());
+        let (front, back) = Self::as_mut_slices(this.as_mut());
+
+        // SAFETY: The elements contained in the `RelVecDeque` are always valid
+        // for dropping. This drop call has the last reference to them, so they
+        // will never be accessed again.
+        unsafe {
+            DropRaw::drop_raw(front);
+            DropRaw::drop_raw(back);
+        }
+
+        munge!(let RelVecDeque { ptr, head, len, cap, alloc } = this);
+
+        // SAFETY: `ptr` is never null and always allocated in `alloc` with a
+        // layout of `layout`.
+        unsafe {
+            A::raw_deallocate(
+                alloc.as_ref(),
+                ptr::NonNull::new_unchecked(inner_ptr.cast()),
+                layout,
+            );
+        }
+
+        // SAFETY: The fields of the `RelVecDeque` are always valid for
+        // dropping and are not accessed again.
+        unsafe {
+            DropRaw::drop_raw(ptr);
+            DropRaw::drop_raw(head);
+            DropRaw::drop_raw(len);
+            DropRaw::drop_raw(cap);
+            DropRaw::drop_raw(alloc);
+        }
+    }
+}
+
+impl<T, A: RawRegionalAllocator, B: Basis> RelVecDeque<T, A, B> {
+    /// Returns `true` if the `RelVecDeque` contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements in the `RelVecDeque`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        B::to_native_usize(self.len).unwrap()
+    }
+
+    /// Returns the maximum number of elements the `RelVecDeque` can contain
+    /// before resizing.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        B::to_native_usize(self.cap).unwrap()
+    }
+
+    #[inline]
+    fn head(&self) -> usize {
+        B::to_native_usize(self.head).unwrap()
+    }
+
+    /// Returns the index in the buffer of the element at `index` in the queue.
+    #[inline]
+    fn physical_index(&self, index: usize) -> usize {
+        let index = self.head() + index;
+        if index >= self.capacity() {
+            index - self.capacity()
+        } else {
+            index
+        }
+    }
+
+    /// Returns the ranges of the buffer which hold the front and back of the
+    /// queue.
+    fn ranges(&self) -> (Range<usize>, Range<usize>) {
+        let head = self.head();
+        let end = head + self.len();
+        if end <= self.capacity() {
+            (head..end, 0..0)
+        } else {
+            (head..self.capacity(), 0..end - self.capacity())
+        }
+    }
+
+    fn set_head_and_len(this: Mut<'_, Self>, new_head: usize, new_len: usize) {
+        munge!(let RelVecDeque { mut head, mut len, .. } = this);
+        *head = B::from_native_usize(new_head).unwrap();
+        *len = B::from_native_usize(new_len).unwrap();
+    }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(this: Ref<'_, Self>) -> Ref<'_, A> {
+        munge!(let RelVecDeque { alloc, .. } = this);
+        alloc
+    }
+
+    fn as_ptr(this: Ref<'_, Self>) -> *const T {
+        munge!(let RelVecDeque { ptr, .. } = this);
+
+        // SAFETY: The relative pointer of a `RelVecDeque` is never null.
+        unsafe { RelPtr::as_ptr_unchecked(ptr) }
+    }
+
+    fn as_mut_ptr(this: Mut<'_, Self>) -> *mut T {
+        munge!(let RelVecDeque { ptr, .. } = this);
+
+        // SAFETY: The relative pointer of a `RelVecDeque` is never null.
+        unsafe { RelPtr::as_mut_ptr_unchecked(ptr) }
+    }
+
+    /// Returns a pair of slices which contain the contents of the
+    /// `RelVecDeque` in order.
+    ///
+    /// The first slice contains the front of the queue. If the elements wrap
+    /// around the end of the buffer, the second slice contains the rest of
+    /// them. Otherwise, it is empty.
+    pub fn as_slices(this: Ref<'_, Self>) -> (Ref<'_, [T]>, Ref<'_, [T]>) {
+        let (front, back) = this.ranges();
+        let ptr = Self::as_ptr(this);
+        let slice = |range: Range<usize>| {
+            // SAFETY: `range` is within the capacity of the buffer, so the
+            // offset pointer is in bounds.
+            let start = unsafe { ptr.add(range.start) };
+            let slice_ptr = ptr::slice_from_raw_parts(start, range.len());
+            // SAFETY:
+            // - The pointer of a `RelVecDeque` is never null, and is always
+            //   properly aligned and valid for reads.
+            // - The elements in `range` are initialized because `range` is one
+            //   of the ranges of the queue.
+            // - `this` is borrowed for `'_` so it cannot alias any other
+            //   mutable references for `'_`.
+            unsafe { Ref::new_unchecked(slice_ptr) }
+        };
+        (slice(front), slice(back))
+    }
+
+    /// Returns a pair of mutable slices which contain the contents of the
+    /// `RelVecDeque` in order.
+    ///
+    /// See [`as_slices`](RelVecDeque::as_slices) for more details.
+    pub fn as_mut_slices(
+        mut this: Mut<'_, Self>,
+    ) -> (Mut<'_, [T]>, Mut<'_, [T]>) {
+        let (front, back) = this.ranges();
+        let ptr = Self::as_mut_ptr(this.as_mut());
+        let slice = |range: Range<usize>| {
+            // SAFETY: `range` is within the capacity of the buffer, so the
+            // offset pointer is in bounds.
+            let start = unsafe { ptr.add(range.start) };
+            let slice_ptr = ptr::slice_from_raw_parts_mut(start, range.len());
+            // SAFETY:
+            // - The pointer of a `RelVecDeque` is never null, and is always
+            //   properly aligned and valid for reads and writes.
+            // - The elements in `range` are initialized because `range` is one
+            //   of the ranges of the queue.
+            // - The two ranges of the queue never overlap, and `this` is
+            //   borrowed for `'_`, so the slices cannot alias each other or any
+            //   other accessible references for `'_`.
+            unsafe { Mut::new_unchecked(slice_ptr) }
+        };
+        (slice(front), slice(back))
+    }
+
+    /// Returns an iterator over the elements of the `RelVecDeque` from front
+    /// to back.
+    #[inline]
+    pub fn iter(this: Ref<'_, Self>) -> Chain<IterRaw<'_, T>, IterRaw<'_, T>> {
+        let (front, back) = Self::as_slices(this);
+        slice::iter(front).chain(slice::iter(back))
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if `index` is
+    /// out of bounds. The element at index `0` is the front of the queue.
+    pub fn get(this: Ref<'_, Self>, index: usize) -> Option<Ref<'_, T>> {
+        if index < this.len() {
+            // SAFETY: We checked that `index` is less than `len`.
+            Some(unsafe { IndexRaw::index_raw_unchecked(this, index) })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the element at `index`, or `None` if
+    /// `index` is out of bounds. The element at index `0` is the front of the
+    /// queue.
+    pub fn get_mut(this: Mut<'_, Self>, index: usize) -> Option<Mut<'_, T>> {
+        if index < this.len() {
+            // SAFETY: We checked that `index` is less than `len`.
+            Some(unsafe { IndexMutRaw::index_mut_raw_unchecked(this, index) })
+        } else {
+            None
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `index` must be less than `capacity`.
+    unsafe fn slot(
+        this: Mut<'_, Self>,
+        index: usize,
+    ) -> In<Slot<'_, T>, A::Region> {
+        let ptr = Self::as_mut_ptr(this);
+        // SAFETY: The `ptr` of a `RelVecDeque` is always non-null, properly
+        // aligned, and valid for reads and writes of `capacity` elements.
+        // Because `this` is mutably borrowed for `'_`, the created reference
+        // cannot be aliased for `'_`.
+        let slot = unsafe { Slot::new_unchecked(ptr.add(index)) };
+        // SAFETY: All slots of the `RelVecDeque` are allocated in
+        // `self.alloc`, and since `A` implements `RawRegionalAllocator`, it
+        // guarantees that the memory it allocates is located in its region.
+        unsafe { In::new_unchecked(slot) }
+    }
+
+    /// # Safety
+    ///
+    /// `index` must be less than `capacity`, and the slot at `index` must hold
+    /// an initialized element which is no longer part of the queue.
+    unsafe fn take(
+        this: Mut<'_, Self>,
+        index: usize,
+    ) -> In<Val<'_, T>, A::Region>
+    where
+        T: DropRaw,
+    {
+        // SAFETY: The caller has guaranteed that `index` is less than
+        // `capacity`.
+        let slot = unsafe { Self::slot(this, index) };
+        // SAFETY: The caller has guaranteed that the slot at `index` is
+        // initialized, and it is not part of the queue so it will not be
+        // accessed or dropped again.
+        let initialize = |s| unsafe { Val::from_slot_unchecked(s) };
+        // SAFETY: `initialize` returns a `Val` of the given `Slot`, which is
+        // always located in the same region as the `Slot` it is derived from.
+        unsafe { In::map_unchecked(slot, initialize) }
+    }
+
+    /// Moves the element at `from` into the uninitialized slot at `to`.
+    ///
+    /// # Safety
+    ///
+    /// - `from` and `to` must point to elements in buffers allocated in
+    ///   `self.alloc`.
+    /// - `from` must be initialized and `to` must be uninitialized, and neither
+    ///   may be accessed through any other pointers during the move.
+    unsafe fn relocate(from: *mut T, to: *mut T)
+    where
+        T: Move<A::Region>,
+    {
+        // SAFETY: The caller has guaranteed that `from` points to an
+        // initialized element which is not accessed through any other
+        // pointers.
+        let value = unsafe { Val::new_unchecked(from) };
+        // SAFETY: The caller has guaranteed that `from` is allocated in
+        // `self.alloc`, and since `A` implements `RawRegionalAllocator`, it
+        // guarantees that memory it allocates is located in its region.
+        let value = unsafe { In::new_unchecked(value) };
+        // SAFETY: The caller has guaranteed that `to` points to an
+        // uninitialized slot which is not accessed through any other pointers.
+        let out = unsafe { Slot::new_unchecked(to) };
+        // SAFETY: The caller has guaranteed that `to` is allocated in
+        // `self.alloc`, which is located in its region.
+        let out = unsafe { In::new_unchecked(out) };
+        T::r#move(value, out);
+    }
+
+    /// Reserves capacity for at least `additional` more elements to be inserted
+    /// in the given `RelVecDeque<T>`. After calling `reserve`, the capacity
+    /// will be greater than or equal to `self.len() + additional`. Does nothing
+    /// if capacity is already sufficient.
+    ///
+    /// # Panics
+    ///
+    /// Panics if reserving the new capacity fails. See
+    /// [`try_reserve`](RelVecDeque::try_reserve) for the possible failures.
+    pub fn reserve(this: Mut<'_, Self>, additional: usize)
+    where
+        T: Move<A::Region>,
+    {
+        Self::try_reserve(this, additional).unwrap();
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be
+    /// inserted in the given `RelVecDeque<T>`. After calling `try_reserve`, the
+    /// capacity will be greater than or equal to `self.len() + additional` if
+    /// it returns `Ok`. Does nothing if capacity is already sufficient.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the new capacity exceeds `isize::MAX` bytes or
+    /// cannot be represented in `B`, if the allocator fails to allocate the new
+    /// buffer, or if the new buffer is too far away to point to. If an error is
+    /// returned, the `RelVecDeque` is left unchanged.
+    pub fn try_reserve(
+        mut this: Mut<'_, Self>,
+        additional: usize,
+    ) -> Result<(), EmplaceError>
+    where
+        T: Move<A::Region>,
+    {
+        let len = this.len();
+        let old_cap = this.capacity();
+        let min_cap = len
+            .checked_add(additional)
+            .ok_or(EmplaceError::CapacityOverflow)?;
+        if min_cap <= old_cap {
+            return Ok(());
+        }
+
+        let new_cap = min_cap
+            .checked_next_power_of_two()
+            .ok_or(EmplaceError::CapacityOverflow)?;
+        let basis_cap = B::from_native_usize(new_cap)
+            .map_err(|_| EmplaceError::MetadataOverflow)?;
+
+        let old_layout = Layout::array::<T>(old_cap).unwrap();
+        let new_layout = Layout::array::<T>(new_cap)
+            .map_err(|_| EmplaceError::CapacityOverflow)?;
+
+        let old_ptr = Self::as_mut_ptr(this.as_mut());
+        // SAFETY: The pointer of a `RelVecDeque` is always non-null.
+        let old_bytes = unsafe { ptr::NonNull::new_unchecked(old_ptr.cast()) };
+        let (front, back) = this.ranges();
+
+        // SAFETY:
+        // - `old_bytes` is the memory for the `RelVecDeque`, which was
+        //   allocated with `old_layout`.
+        // - `new_layout` has a strictly larger size than `old_layout` because
+        //   `new_cap` is greater than `min_cap`, which is greater than
+        //   `old_cap`.
+        let grew_in_place = unsafe {
+            RawAllocator::raw_grow_in_place(
+                Self::allocator(this.as_ref()),
+                old_bytes,
+                old_layout,
+                new_layout,
+            )
+            .is_ok()
+        };
+
+        let new_head = if grew_in_place {
+            // The front of the queue stays in place. If the queue wrapped
+            // around, either the back is moved after the old end of the buffer
+            // or the front is moved to the new end of the buffer.
+            if back.is_empty() {
+                front.start
+            } else if back.len() <= new_cap - old_cap {
+                for (i, from) in back.enumerate() {
+                    // SAFETY: The back of the queue is initialized, and the
+                    // slots after the old capacity are within the new capacity
+                    // and uninitialized.
+                    unsafe {
+                        Self::relocate(
+                            old_ptr.add(from),
+                            old_ptr.add(old_cap + i),
+                        );
+                    }
+                }
+                front.start
+            } else {
+                let offset = new_cap - old_cap;
+                for from in front.clone().rev() {
+                    // SAFETY: The front of the queue is initialized. It is
+                    // moved from the back so each slot is moved out of before
+                    // it is moved into, and `from + offset` is less than
+                    // `new_cap`.
+                    unsafe {
+                        Self::relocate(
+                            old_ptr.add(from),
+                            old_ptr.add(from + offset),
+                        );
+                    }
+                }
+                front.start + offset
+            }
+        } else {
+            let new_bytes = RawAllocator::raw_allocate(
+                Self::allocator(this.as_ref()),
+                new_layout,
+            )
+            .map_err(|_| EmplaceError::AllocFailed)?
+            .cast::<u8>();
+            let new_ptr = new_bytes.as_ptr().cast::<T>();
+
+            munge!(let RelVecDeque { ptr, alloc, .. } = this.as_mut());
+            let new_ptr_in =
+                // SAFETY: `new_ptr` is allocated in `this.alloc`, and since `A`
+                // implements `RawRegionalAllocator` it guarantees that memory
+                // it allocates is located in its region.
+                unsafe { In::<_, A::Region>::new_unchecked(new_ptr) };
+            if let Err(e) = RelPtr::try_set(ptr, new_ptr_in) {
+                // SAFETY: `new_bytes` was just allocated by `alloc` with
+                // `new_layout` and has not been used.
+                unsafe {
+                    RawAllocator::raw_deallocate(
+                        alloc.as_ref(),
+                        new_bytes,
+                        new_layout,
+                    );
+                }
+                return Err(e);
+            }
+
+            // The elements are moved to the start of the new buffer in order,
+            // so the queue no longer wraps around.
+            for (i, from) in front.chain(back).enumerate() {
+                // SAFETY: The elements of the queue in the old buffer are
+                // initialized, and the new buffer is freshly-allocated with
+                // room for at least `len` elements.
+                unsafe {
+                    Self::relocate(old_ptr.add(from), new_ptr.add(i));
+                }
+            }
+
+            // SAFETY:
+            // - `old_bytes` is currently allocated because it was previously
+            //   allocated and `grow_in_place` failed.
+            // - `old_layout` was the layout used to allocate `old_bytes`.
+            unsafe {
+                RawAllocator::raw_deallocate(
+                    Self::allocator(this.as_ref()),
+                    old_bytes,
+                    old_layout,
+                );
+            }
+
+            0
+        };
+
+        Self::set_head_and_len(this.as_mut(), new_head, len);
+        munge!(let RelVecDeque { mut cap, .. } = this);
+        *cap = basis_cap;
+
+        Ok(())
+    }
+
+    /// Appends an element to the back of the queue.
+    ///
+    /// # Panics
+    ///
+    /// Panics if reserving space for the new element fails. See
+    /// [`try_reserve`](RelVecDeque::try_reserve) for the possible failures.
+    pub fn push_back<E>(mut this: Mut<'_, Self>, value: E)
+    where
+        T: Move<A::Region>,
+        E: Emplace<T, A::Region>,
+    {
+        Self::reserve(this.as_mut(), 1);
+        let head = this.head();
+        let len = this.len();
+        let index = this.physical_index(len);
+
+        // SAFETY: `index` is less than `capacity` because it is a physical
+        // index, and the slot is not part of the queue because we reserved
+        // space for one more element.
+        let slot = unsafe { Self::slot(this.as_mut(), index) };
+        value.emplace(slot);
+
+        Self::set_head_and_len(this, head, len + 1);
+    }
+
+    /// Tries to append an element to the back of the queue.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reserving space for the new element fails or if
+    /// emplacing `value` fails. See [`try_reserve`](RelVecDeque::try_reserve)
+    /// for the possible reservation failures. If an error is returned, the
+    /// length and contents of the `RelVecDeque` are unchanged, but its capacity
+    /// may have grown.
+    pub fn try_push_back<E>(
+        mut this: Mut<'_, Self>,
+        value: E,
+    ) -> Result<(), EmplaceError>
+    where
+        T: Move<A::Region>,
+        E: TryEmplace<T, A::Region>,
+    {
+        Self::try_reserve(this.as_mut(), 1)?;
+        let head = this.head();
+        let len = this.len();
+        let index = this.physical_index(len);
+
+        // SAFETY: `index` is less than `capacity` because it is a physical
+        // index, and the slot is not part of the queue because we reserved
+        // space for one more element.
+        let slot = unsafe { Self::slot(this.as_mut(), index) };
+        value.try_emplace(slot)?;
+
+        Self::set_head_and_len(this, head, len + 1);
+
+        Ok(())
+    }
+
+    /// Prepends an element to the front of the queue.
+    ///
+    /// # Panics
+    ///
+    /// Panics if reserving space for the new element fails. See
+    /// [`try_reserve`](RelVecDeque::try_reserve) for the possible failures.
+    pub fn push_front<E>(mut this: Mut<'_, Self>, value: E)
+    where
+        T: Move<A::Region>,
+        E: Emplace<T, A::Region>,
+    {
+        Self::reserve(this.as_mut(), 1);
+        let len = this.len();
+        let new_head = match this.head() {
+            0 => this.capacity() - 1,
+            head => head - 1,
+        };
+
+        // SAFETY: `new_head` is less than `capacity`, and the slot is not part
+        // of the queue because we reserved space for one more element.
+        let slot = unsafe { Self::slot(this.as_mut(), new_head) };
+        value.emplace(slot);
+
+        Self::set_head_and_len(this, new_head, len + 1);
+    }
+
+    /// Tries to prepend an element to the front of the queue.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reserving space for the new element fails or if
+    /// emplacing `value` fails. See [`try_reserve`](RelVecDeque::try_reserve)
+    /// for the possible reservation failures. If an error is returned, the
+    /// length and contents of the `RelVecDeque` are unchanged, but its capacity
+    /// may have grown.
+    pub fn try_push_front<E>(
+        mut this: Mut<'_, Self>,
+        value: E,
+    ) -> Result<(), EmplaceError>
+    where
+        T: Move<A::Region>,
+        E: TryEmplace<T, A::Region>,
+    {
+        Self::try_reserve(this.as_mut(), 1)?;
+        let len = this.len();
+        let new_head = match this.head() {
+            0 => this.capacity() - 1,
+            head => head - 1,
+        };
+
+        // SAFETY: `new_head` is less than `capacity`, and the slot is not part
+        // of the queue because we reserved space for one more element.
+        let slot = unsafe { Self::slot(this.as_mut(), new_head) };
+        value.try_emplace(slot)?;
+
+        Self::set_head_and_len(this, new_head, len + 1);
+
+        Ok(())
+    }
+
+    /// Removes the first element of the queue and returns it, or `None` if the
+    /// queue is empty.
+    ///
+    /// The returned `Val` drops the element when it is dropped unless it is
+    /// moved out first.
+    pub fn pop_front(
+        mut this: Mut<'_, Self>,
+    ) -> Option<In<Val<'_, T>, A::Region>>
+    where
+        T: DropRaw,
+    {
+        let len = this.len();
+        if len == 0 {
+            return None;
+        }
+
+        let index = this.head();
+        let new_head = this.physical_index(1);
+        Self::set_head_and_len(this.as_mut(), new_head, len - 1);
+        // SAFETY: `index` was the head of the queue, so it is less than
+        // `capacity` and initialized. It was just removed from the queue.
+        Some(unsafe { Self::take(this, index) })
+    }
+
+    /// Removes the last element of the queue and returns it, or `None` if the
+    /// queue is empty.
+    ///
+    /// The returned `Val` drops the element when it is dropped unless it is
+    /// moved out first.
+    pub fn pop_back(
+        mut this: Mut<'_, Self>,
+    ) -> Option<In<Val<'_, T>, A::Region>>
+    where
+        T: DropRaw,
+    {
+        let len = this.len();
+        if len == 0 {
+            return None;
+        }
+
+        let head = this.head();
+        let index = this.physical_index(len - 1);
+        Self::set_head_and_len(this.as_mut(), head, len - 1);
+        // SAFETY: `index` was the last element of the queue, so it is less
+        // than `capacity` and initialized. It was just removed from the queue.
+        Some(unsafe { Self::take(this, index) })
+    }
+
+    /// Removes the first element of the queue and moves it into `out`.
+    ///
+    /// Returns `false` and leaves `out` uninitialized if the queue is empty.
+    pub fn pop_front_into(
+        this: Mut<'_, Self>,
+        out: In<Slot<'_, T>, A::Region>,
+    ) -> bool
+    where
+        T: Move<A::Region>,
+    {
+        match Self::pop_front(this) {
+            Some(value) => {
+                T::r#move(value, out);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the last element of the queue and moves it into `out`.
+    ///
+    /// Returns `false` and leaves `out` uninitialized if the queue is empty.
+    pub fn pop_back_into(
+        this: Mut<'_, Self>,
+        out: In<Slot<'_, T>, A::Region>,
+    ) -> bool
+    where
+        T: Move<A::Region>,
+    {
+        match Self::pop_back(this) {
+            Some(value) => {
+                T::r#move(value, out);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Clears the `RelVecDeque`, removing all values.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the
+    /// `RelVecDeque`.
+    pub fn clear(mut this: Mut<'_, Self>)
+    where
+        T: DropRaw,
+    {
+        while let Some(value) = Self::pop_front(this.as_mut()) {
+            drop(value);
+        }
+        Self::set_head_and_len(this, 0, 0);
+    }
+}
+
+impl<T, A: RawRegionalAllocator, B: Basis> IndexRaw<usize>
+    for RelVecDeque<T, A, B>
+{
+    type Output = T;
+
+    fn index_raw(this: Ref<'_, Self>, index: usize) -> Ref<'_, T> {
+        Self::get(this, index).expect("index out of bounds")
+    }
+
+    unsafe fn index_raw_unchecked(
+        this: Ref<'_, Self>,
+        index: usize,
+    ) -> Ref<'_, T> {
+        let index = this.physical_index(index);
+        // SAFETY: The caller has guaranteed that `index` is less than `len`,
+        // so the physical index is less than `capacity` and the element at it
+        // is initialized. `this` is borrowed for `'_` so it cannot alias any
+        // other mutable references for `'_`.
+        unsafe { Ref::new_unchecked(Self::as_ptr(this).add(index)) }
+    }
+}
+
+impl<T, A: RawRegionalAllocator, B: Basis> IndexMutRaw<usize>
+    for RelVecDeque<T, A, B>
+{
+    fn index_mut_raw(this: Mut<'_, Self>, index: usize) -> Mut<'_, T> {
+        Self::get_mut(this, index).expect("index out of bounds")
+    }
+
+    unsafe fn index_mut_raw_unchecked(
+        this: Mut<'_, Self>,
+        index: usize,
+    ) -> Mut<'_, T> {
+        let index = this.physical_index(index);
+        // SAFETY: The caller has guaranteed that `index` is less than `len`,
+        // so the physical index is less than `capacity` and the element at it
+        // is initialized. `this` is borrowed for `'_` so it cannot alias any
+        // other accessible references for `'_`.
+        unsafe { Mut::new_unchecked(Self::as_mut_ptr(this).add(index)) }
+    }
+}
+
+impl<T, U, A1, A2, B1, B2> PartialEqRaw<RelVecDeque<U, A2, B2>>
+    for RelVecDeque<T, A1, B1>
+where
+    T: PartialEqRaw<U>,
+    A1: RawRegionalAllocator,
+    A2: RawRegionalAllocator,
+    B1: Basis,
+    B2: Basis,
+{
+    fn eq_raw(
+        this: Ref<'_, Self>,
+        other: Ref<'_, RelVecDeque<U, A2, B2>>,
+    ) -> bool {
+        this.len() == other.len()
+            && iter_eq_raw(Self::iter(this), RelVecDeque::iter(other))
+    }
+}
+
+impl<T: EqRaw, A: RawRegionalAllocator, B: Basis> EqRaw
+    for RelVecDeque<T, A, B>
+{
+}
+
+impl<T, A, B> DebugRaw for RelVecDeque<T, A, B>
+where
+    T: DebugRaw,
+    A: RawRegionalAllocator,
+    B: Basis,
+{
+    fn fmt_raw(
+        this: Ref<'_, Self>,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        f.debug_list().entries(Self::iter(this)).finish()
+    }
+}
+
+// SAFETY: `zero_padding` zeroes the bytes of the `RelVecDeque` which are not
+// covered by its fields, then zeroes the padding of its fields and elements.
+// The slots of the buffer which are not part of the queue do not contain any
+// values, so they are zeroed as well.
+unsafe impl<T, A, B> ZeroPadding for RelVecDeque<T, A, B>
+where
+    T: ZeroPadding,
+    A: RawRegionalAllocator + ZeroPadding,
+    B: Basis,
+    RelPtr<T, A::Region, B>: ZeroPadding,
+{
+    fn zero_padding(mut this: Mut<'_, Self>) {
+        // SAFETY: `this` points to a `RelVecDeque`, and the bytes which are not
+        // covered by any of its fields are padding.
+        unsafe {
+            zero_gaps(
+                this.as_ptr().cast(),
+                0,
+                size_of::<Self>(),
+                &[
+                    (
+                        offset_of!(Self, ptr),
+                        size_of::<RelPtr<T, A::Region, B>>(),
+                    ),
+                    (offset_of!(Self, head), size_of::<B::Usize>()),
+                    (offset_of!(Self, len), size_of::<B::Usize>()),
+                    (offset_of!(Self, cap), size_of::<B::Usize>()),
+                    (offset_of!(Self, alloc), size_of::<A>()),
+                ],
+            );
+        }
+
+        let (front, back) = this.ranges();
+        let (unused_start, unused_end) = if back.is_empty() {
+            (front.end, front.start + this.capacity())
+        } else {
+            (back.end, front.start)
+        };
+        let cap = this.capacity();
+        let elements = Self::as_mut_ptr(this.as_mut());
+        for i in unused_start..unused_end {
+            // SAFETY: The slots which are not part of the queue are allocated
+            // but not initialized, so they are valid for writes and contain no
+            // values. `i % cap` is less than `capacity`.
+            unsafe {
+                ptr::write_bytes(elements.add(i % cap), 0, 1);
+            }
+        }
+        let (front, back) = Self::as_mut_slices(this.as_mut());
+        <[T]>::zero_padding(front);
+        <[T]>::zero_padding(back);
+
+        munge!(let RelVecDeque { ptr, alloc, .. } = this);
+        ZeroPadding::zero_padding(ptr);
+        ZeroPadding::zero_padding(alloc);
+    }
+}
+
+/// An emplacer for a new, empty `RelVecDeque`.
+pub struct New<A>(pub A);
+
+// SAFETY:
+// - `RelVecDeque` is `Sized` and always has metadata `()`, so `emplaced_meta`
+//   always returns valid metadata for it.
+// - `emplace_unsized_unchecked` initializes its `out` parameter.
+unsafe impl<T, E, B, A> Emplace<RelVecDeque<T, E, B>, A::Region> for New<A>
+where
+    T: DropRaw,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    fn emplaced_meta(&self) -> <RelVecDeque<T, E, B> as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelVecDeque<T, E, B>>, A::Region>,
+    ) {
+        WithCapacity(self.0, 0).emplace(out);
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter if
+// it returns `Ok`.
+unsafe impl<T, E, B, A> TryEmplace<RelVecDeque<T, E, B>, A::Region> for New<A>
+where
+    T: DropRaw,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelVecDeque<T, E, B>>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        WithCapacity(self.0, 0).try_emplace(out)
+    }
+}
+
+/// An emplacer for a new `RelVecDeque` with an initial capacity.
+pub struct WithCapacity<A>(pub A, pub usize);
+
+// SAFETY:
+// - `RelVecDeque` is `Sized` and always has metadata `()`, so `emplaced_meta`
+//   always returns valid metadata for it.
+// - `emplace_unsized_unchecked` initializes its `out` parameter by emplacing
+//   and writing to each field.
+unsafe impl<T, E, B, A> Emplace<RelVecDeque<T, E, B>, A::Region>
+    for WithCapacity<A>
+where
+    T: DropRaw,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    fn emplaced_meta(&self) -> <RelVecDeque<T, E, B> as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelVecDeque<T, E, B>>, A::Region>,
+    ) {
+        // SAFETY: `RelVecDeque` is `Sized`, so `out` must have the metadata
+        // returned by `emplaced_meta`.
+        unsafe { self.try_emplace_unsized_unchecked(out).unwrap() }
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter by
+// emplacing and writing to each field if it returns `Ok`.
+unsafe impl<T, E, B, A> TryEmplace<RelVecDeque<T, E, B>, A::Region>
+    for WithCapacity<A>
+where
+    T: DropRaw,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    <B as Basis>::Usize: DropRaw,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelVecDeque<T, E, B>>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        let Self(alloc, cap) = self;
+
+        let layout = Layout::array::<T>(cap)
+            .map_err(|_| EmplaceError::CapacityOverflow)?;
+        let basis_cap = B::from_native_usize(cap)
+            .map_err(|_| EmplaceError::MetadataOverflow)?;
+
+        let bytes = alloc
+            .allocate(layout)
+            .map_err(|_| EmplaceError::AllocFailed)?
+            .cast::<u8>();
+        // SAFETY: The pointer returned from `allocate` is guaranteed to be in
+        // the region of `R`.
+        let ptr = unsafe { In::new_unchecked(bytes.as_ptr().cast::<T>()) };
+
+        munge!(
+            let RelVecDeque {
+                ptr: out_ptr,
+                head: out_head,
+                len: out_len,
+                cap: out_cap,
+                alloc: out_alloc,
+            } = out;
+        );
+
+        if let Err(e) = ptr.try_emplace(out_ptr) {
+            // SAFETY: `bytes` was just allocated by `alloc` with `layout` and
+            // has not been used.
+            unsafe {
+                alloc.deallocate(bytes, layout);
+            }
+            return Err(e);
+        }
+        In::into_inner(out_head).write(B::from_native_usize(0).unwrap());
+        In::into_inner(out_len).write(B::from_native_usize(0).unwrap());
+        In::into_inner(out_cap).write(basis_cap);
+        alloc.emplace(out_alloc);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+//! A pointer type for versioned structs which may have been written by an
+//! older version.
+
+use ::core::ptr::NonNull;
+use ::mischief::{In, RegionalAllocator, Slot};
+use ::munge::munge;
+use ::ptr_meta::Pointee;
+use ::rel_core::{
+    transcode::{Transcode, Transcoder},
+    version::{
+        Migrate,
+        RelVersioned,
+        UpgradeVersioned,
+        Versioned,
+        VersionedRef,
+    },
+    Basis,
+    DefaultBasis,
+    Emplace,
+    EmplaceError,
+    EmplaceExt,
+    Move,
+    Portable,
+    RelPtr,
+    TryEmplace,
+    TryEmplaceExt,
+};
+use ::situ::{alloc::RawRegionalAllocator, DropRaw, Mut, OwnedVal, Ref};
+
+use crate::alloc::RelAllocator;
+
+/// A relative counterpart to `Box` for a [`RelVersioned`] value.
+///
+/// A value written by an older version of `T` is smaller than the current
+/// layout of `RelVersioned<T>`, so unlike a `RelBox` the allocation is sized by
+/// the version stored in it. The value is only read through a
+/// [`VersionedRef`], and is freed with the layout of its version.
+#[derive(Move, Portable)]
+#[repr(C)]
+pub struct RelVersionedBox<T, A: RawRegionalAllocator, B: Basis = DefaultBasis>
+{
+    ptr: RelPtr<RelVersioned<T>, A::Region, B>,
+    alloc: A,
+}
+
+impl<T, A, B> DropRaw for RelVersionedBox<T, A, B>
+where
+    T: Versioned,
+    A: RawRegionalAllocator + DropRaw,
+    B: Basis,
+{
+    #[inline]
+    unsafe fn drop_raw(this: Mut<'_, Self>) {
+        let version = Self::version(this.as_ref());
+        let layout = RelVersioned::<T>::layout_of_version(version);
+
+        munge!(let RelVersionedBox { mut ptr, alloc } = this);
+        // SAFETY: The pointer of a `RelVersionedBox` is never null.
+        let inner_ptr = unsafe { RelPtr::as_mut_ptr_unchecked(ptr.as_mut()) };
+        // SAFETY: `inner_ptr` points to an initialized value of `version`
+        // which is valid for reads and writes of its layout. We own it, and it
+        // is never accessed again because `this` is never accessed again.
+        unsafe {
+            RelVersioned::drop_version_raw(inner_ptr);
+        }
+
+        // SAFETY: `inner_ptr` is never null and always allocated in `alloc`
+        // with the layout of its version.
+        unsafe {
+            A::raw_deallocate(
+                alloc.as_ref(),
+                NonNull::new_unchecked(inner_ptr.cast()),
+                layout,
+            );
+        }
+
+        // SAFETY: `ptr` and `alloc` are always valid for dropping and are not
+        // accessed again.
+        unsafe {
+            DropRaw::drop_raw(ptr);
+            DropRaw::drop_raw(alloc);
+        }
+    }
+}
+
+impl<T, A, B> RelVersionedBox<T, A, B>
+where
+    T: Versioned,
+    A: RawRegionalAllocator,
+    B: Basis,
+{
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(this: Ref<'_, Self>) -> Ref<'_, A> {
+        munge!(let RelVersionedBox { alloc, .. } = this);
+        alloc
+    }
+
+    fn as_ptr(this: Ref<'_, Self>) -> *const RelVersioned<T> {
+        munge!(let RelVersionedBox { ptr, .. } = this);
+        // SAFETY: The pointer of a `RelVersionedBox` is never null.
+        unsafe { RelPtr::as_ptr_unchecked(ptr) }
+    }
+
+    /// Returns the version that the boxed value was written in.
+    #[inline]
+    pub fn version(this: Ref<'_, Self>) -> u16 {
+        // SAFETY: The value pointed to by a `RelVersionedBox` is always
+        // properly aligned, initialized, and valid for reads of its version.
+        unsafe { RelVersioned::version_raw(Self::as_ptr(this)) }
+    }
+
+    /// Returns a `VersionedRef` to the boxed value.
+    #[inline]
+    pub fn get(this: Ref<'_, Self>) -> VersionedRef<'_, T> {
+        // SAFETY:
+        // - The value pointed to by a `RelVersionedBox` is always properly
+        //   aligned, initialized, and valid for reads of the layout of its
+        //   version.
+        // - Because `this` is a shared reference, the value cannot be mutably
+        //   aliased for `'_`. It is owned by the box, so it is immovable.
+        unsafe { RelVersioned::get_raw(Self::as_ptr(this)) }
+    }
+}
+
+// SAFETY:
+// - `RelVersionedBox` is `Sized` and always has metadata `()`, so
+//   `emplaced_meta` always returns valid metadata for it.
+// - `emplace_unsized_unchecked` initializes its `out` parameter.
+unsafe impl<T, E, B, A> Emplace<RelVersionedBox<T, E, B>, A::Region>
+    for OwnedVal<RelVersioned<T>, A>
+where
+    T: Versioned,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    #[inline]
+    fn emplaced_meta(&self) -> <RelVersionedBox<T, E, B> as Pointee>::Metadata {
+    }
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelVersionedBox<T, E, B>>, A::Region>,
+    ) {
+        // SAFETY: `RelVersionedBox` is `Sized`, so `out` must have the metadata
+        // returned by `emplaced_meta`.
+        unsafe { self.try_emplace_unsized_unchecked(out).unwrap() }
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter if
+// it returns `Ok`. An `OwnedVal<RelVersioned<T>>` is allocated with the layout
+// of `RelVersioned<T>`, which is the layout of the current version.
+unsafe impl<T, E, B, A> TryEmplace<RelVersionedBox<T, E, B>, A::Region>
+    for OwnedVal<RelVersioned<T>, A>
+where
+    T: Versioned,
+    E: DropRaw + RawRegionalAllocator<Region = A::Region>,
+    B: Basis,
+    A: RegionalAllocator + RelAllocator<E, A::Region>,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        mut out: In<Slot<'_, RelVersionedBox<T, E, B>>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        let this = In::new(self);
+        let ptr = this.as_raw();
+
+        munge!(
+            let RelVersionedBox { ptr: out_ptr, alloc: out_alloc } =
+                out.as_mut()
+        );
+
+        // If this fails, `this` is dropped and frees the value.
+        ptr.try_emplace(out_ptr)?;
+        let (_, alloc) = OwnedVal::into_raw_parts(In::into_inner(this));
+        alloc.emplace(out_alloc);
+
+        Ok(())
+    }
+}
+
+// SAFETY: `transcode` initializes its `out` parameter by allocating a value of
+// the current version, migrating the boxed value into it, and emplacing to each
+// field. `UpgradeVersioned` converts the migrated value to the endianness of
+// the transcoder.
+unsafe impl<T, E1, B1, B2, A, E2> Transcode<B2, A, E2>
+    for RelVersionedBox<T, E1, B1>
+where
+    T: Migrate<B2, A, E2>,
+    E1: RawRegionalAllocator + DropRaw,
+    B1: Basis,
+    B2: Basis,
+    A: Clone + RegionalAllocator + RelAllocator<E2, A::Region>,
+    E2: RawRegionalAllocator<Region = A::Region> + DropRaw,
+    RelVersionedBox<T::Target, E2, B2>: Portable,
+{
+    type Target = RelVersionedBox<T::Target, E2, B2>;
+
+    fn transcode(
+        this: Ref<'_, Self>,
+        transcoder: &Transcoder<B2, A, E2>,
+        out: In<Slot<'_, Self::Target>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        let layout =
+            RelVersioned::<T::Target>::layout_of_version(T::Target::VERSION);
+        let ptr = transcoder
+            .alloc()
+            .allocate(layout)
+            .map_err(|_| EmplaceError::AllocFailed)?
+            .as_ptr()
+            .cast::<RelVersioned<T::Target>>();
+        // SAFETY:
+        // - `ptr` was returned from `allocate`, so it must be non-null. It is
+        //   guaranteed to be properly aligned and valid for reads and writes of
+        //   the layout of the current version, which is the layout of a
+        //   `RelVersioned<T::Target>`.
+        // - `ptr` is freshly-allocated, so it is not currently aliased by any
+        //   other pointers.
+        let slot = unsafe { Slot::new_unchecked(ptr) };
+        // SAFETY: `ptr` is allocated in `transcoder.alloc()`, and since `A`
+        // implements `RegionalAllocator`, it guarantees that memory it
+        // allocates is located in its region.
+        let slot = unsafe { In::new_unchecked(slot) };
+        UpgradeVersioned(Self::get(this), transcoder).try_emplace(slot)?;
+        // SAFETY: `ptr` is allocated in `transcoder.alloc()`, and since `A`
+        // implements `RegionalAllocator`, it guarantees that memory it
+        // allocates is located in its region.
+        let ptr = unsafe { In::new_unchecked(ptr) };
+
+        munge!(let RelVersionedBox { ptr: out_ptr, alloc: out_alloc } = out);
+
+        ptr.try_emplace(out_ptr)?;
+        transcoder.alloc().clone().emplace(out_alloc);
+
+        Ok(())
+    }
+}
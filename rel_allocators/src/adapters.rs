@@ -1,10 +1,10 @@
 use ::core::{alloc::Layout, ops::Deref, ptr::NonNull};
 use ::heresy::alloc::{AllocError, Allocator};
-use ::mischief::{In, Region, Slot, Static, StaticRef};
+use ::mischief::{In, Region, RuntimeRegion, Slot, Static, StaticRef, Unique};
 use ::munge::munge;
 use ::ptr_meta::Pointee;
 use ::rel_alloc::alloc::RelAllocator;
-use ::rel_core::{Emplace, EmplaceExt, Move, Portable};
+use ::rel_core::{CloneRaw, Emplace, EmplaceExt, Move, Portable};
 use ::situ::{alloc::RawAllocator, ops::DerefRaw, DropRaw, Ref};
 
 use crate::{ContiguousAllocator, RawContiguousAllocator};
@@ -148,8 +148,92 @@ where
 {
 }
 
-#[derive(DropRaw, Portable, Move)]
+/// An allocator adapter that only returns memory located in a runtime region.
+///
+/// Allocations that the inner allocator places outside of the region are
+/// deallocated and reported as failures. Growing and shrinking always allocate
+/// new memory through the adapter so that the result is checked as well.
+pub struct RuntimeChecked<'a, A, U> {
+    alloc: A,
+    region: RuntimeRegion<'a, U>,
+}
+
+impl<'a, A, U: Unique> RuntimeChecked<'a, A, U> {
+    pub fn new(alloc: A, region: RuntimeRegion<'a, U>) -> Self {
+        Self { alloc, region }
+    }
+
+    pub fn inner(&self) -> &A {
+        &self.alloc
+    }
+
+    pub fn region(&self) -> &RuntimeRegion<'a, U> {
+        &self.region
+    }
+
+    fn check(
+        &self,
+        ptr: Result<NonNull<[u8]>, AllocError>,
+        layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError>
+    where
+        A: Allocator,
+    {
+        let ptr = ptr?;
+        if self.region.contains(ptr.as_ptr()) {
+            Ok(ptr)
+        } else {
+            unsafe { self.alloc.deallocate(ptr.cast(), layout) }
+            Err(AllocError)
+        }
+    }
+}
+
+impl<A: Clone, U> Clone for RuntimeChecked<'_, A, U> {
+    fn clone(&self) -> Self {
+        Self {
+            alloc: self.alloc.clone(),
+            region: self.region,
+        }
+    }
+}
+
+impl<A: Copy, U> Copy for RuntimeChecked<'_, A, U> {}
+
+unsafe impl<A: Allocator, U: Unique> Allocator for RuntimeChecked<'_, A, U> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.check(self.alloc.allocate(layout), layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { self.alloc.deallocate(ptr, layout) }
+    }
+
+    fn allocate_zeroed(
+        &self,
+        layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.check(self.alloc.allocate_zeroed(layout), layout)
+    }
+
+    unsafe fn shrink_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        unsafe { self.alloc.shrink_in_place(ptr, old_layout, new_layout) }
+    }
+}
+
+unsafe impl<A: Allocator, U: Unique> ContiguousAllocator
+    for RuntimeChecked<'_, A, U>
+{
+}
+
+#[derive(CloneRaw, DropRaw, Portable, Move)]
 #[repr(transparent)]
+#[zero_padding]
 pub struct RelDerefAdapter<P> {
     ptr: P,
 }
@@ -1,10 +1,10 @@
 use ::core::{alloc::Layout, marker::PhantomData, ops::Deref, ptr::NonNull};
 use ::heresy::alloc::{AllocError, Allocator};
-use ::mischief::{In, Region, RegionalAllocator, Slot, Unique};
+use ::mischief::{In, Region, RegionalAllocator, RuntimeRegion, Slot, Unique};
 use ::munge::munge;
 use ::ptr_meta::Pointee;
 use ::rel_alloc::alloc::RelAllocator;
-use ::rel_core::{Emplace, EmplaceExt, Move, Portable};
+use ::rel_core::{CloneRaw, Emplace, EmplaceExt, Move, Portable};
 use ::situ::{
     alloc::{RawAllocator, RawRegionalAllocator},
     DropRaw,
@@ -12,7 +12,7 @@ use ::situ::{
 };
 
 use crate::{
-    adapters::DerefAdapter,
+    adapters::{DerefAdapter, RuntimeChecked},
     unique_region::UniqueRegion,
     ContiguousAllocator,
     RawContiguousAllocator,
@@ -79,6 +79,21 @@ impl<'a, P, U: Unique> Brand<DerefAdapter<P>, UniqueRegion<'a, U>> {
     }
 }
 
+impl<'a, A, U: Unique> Brand<RuntimeChecked<'a, A, U>, RuntimeRegion<'a, U>> {
+    /// Brands `alloc` with a runtime region. Every allocation is checked to
+    /// be located in `region`, and allocations outside of it fail.
+    pub fn new_runtime(alloc: A, region: RuntimeRegion<'a, U>) -> Self {
+        Self {
+            alloc: RuntimeChecked::new(alloc, region),
+            region: PhantomData,
+        }
+    }
+
+    pub fn runtime_region(&self) -> &RuntimeRegion<'a, U> {
+        self.alloc.region()
+    }
+}
+
 impl<A: Clone, R> Clone for Brand<A, R> {
     fn clone(&self) -> Self {
         Self {
@@ -188,8 +203,9 @@ where
     }
 }
 
-#[derive(DropRaw, Portable, Move)]
+#[derive(CloneRaw, DropRaw, Portable, Move)]
 #[repr(C)]
+#[zero_padding]
 pub struct RelBrand<A, R> {
     alloc: A,
     region: PhantomData<R>,
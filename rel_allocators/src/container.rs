@@ -0,0 +1,255 @@
+//! A self-describing file format for saved buffers.
+//!
+//! A container starts with a fixed-size header which identifies its contents,
+//! followed by the payload bytes of the buffer. The header is always encoded
+//! in little-endian byte order, regardless of the endianness of the payload:
+//!
+//! | Offset | Size | Field                                       |
+//! |--------|------|---------------------------------------------|
+//! | 0      | 4    | Magic bytes, `b"RELB"`                      |
+//! | 4      | 2    | Format version                              |
+//! | 6      | 1    | Basis width in bits                         |
+//! | 7      | 1    | Endianness, `0` for little and `1` for big  |
+//! | 8      | 8    | Schema hash of the root type                |
+//! | 16     | 8    | Offset of the root from the payload start   |
+//! | 24     | 8    | Length of the payload                       |
+//! | 32     | 4    | CRC-32 of the payload                       |
+//! | 36     | 12   | Reserved, always zero                       |
+//!
+//! The header is a multiple of 16 bytes long, so a container loaded into
+//! memory aligned to 16 bytes has a payload which is also aligned to 16 bytes.
+
+use ::core::fmt;
+use ::rel_core::{transcode::Endianness, Basis, Portable};
+
+/// The magic bytes at the start of every container.
+pub const MAGIC: [u8; 4] = *b"RELB";
+
+/// The version of the container format written by this crate.
+pub const FORMAT_VERSION: u16 = 1;
+
+/// The size of a container header in bytes.
+pub const HEADER_SIZE: usize = 48;
+
+/// An error that occurred while loading a container.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ContainerError {
+    /// The container is shorter than its header or payload.
+    TooShort,
+    /// The container does not start with the magic bytes.
+    BadMagic,
+    /// The container was written with an unsupported format version.
+    UnsupportedVersion(u16),
+    /// The basis of the container does not match the expected basis.
+    BasisMismatch {
+        /// The width of the expected basis in bits.
+        expected: u8,
+        /// The width of the container's basis in bits.
+        found: u8,
+    },
+    /// The endianness of the container does not match the expected
+    /// endianness.
+    EndiannessMismatch,
+    /// The schema hash of the root type does not match the expected type.
+    SchemaMismatch,
+    /// The checksum of the payload does not match the checksum in the header.
+    ChecksumMismatch,
+    /// The payload is aligned improperly for the allocator loading it.
+    Misaligned,
+    /// The header, payload, or root of the container is invalid.
+    Invalid,
+}
+
+impl fmt::Display for ContainerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort => write!(f, "container is truncated"),
+            Self::BadMagic => write!(f, "not a container"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported container format version {version}")
+            }
+            Self::BasisMismatch { expected, found } => write!(
+                f,
+                "expected a {expected}-bit basis but found a {found}-bit basis"
+            ),
+            Self::EndiannessMismatch => {
+                write!(f, "container endianness mismatch")
+            }
+            Self::SchemaMismatch => write!(f, "root type schema mismatch"),
+            Self::ChecksumMismatch => write!(f, "payload checksum mismatch"),
+            Self::Misaligned => write!(f, "payload is misaligned"),
+            Self::Invalid => write!(f, "container is invalid"),
+        }
+    }
+}
+
+impl ::std::error::Error for ContainerError {}
+
+/// The header of a container.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Header {
+    /// The version of the container format.
+    pub version: u16,
+    /// The width in bits of the basis of the payload.
+    pub basis_bits: u8,
+    /// The endianness of the payload.
+    pub endianness: Endianness,
+    /// The schema hash of the root type.
+    pub schema_hash: u64,
+    /// The offset of the root from the start of the payload.
+    pub root_offset: u64,
+    /// The length of the payload in bytes.
+    pub payload_len: u64,
+    /// The CRC-32 of the payload.
+    pub checksum: u32,
+}
+
+impl Header {
+    /// Returns a header for a payload with a root of type `T` in the basis `B`
+    /// and the current endianness.
+    pub fn new<T: Portable, B: Basis>(
+        root_offset: usize,
+        payload: &[u8],
+    ) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            basis_bits: basis_bits::<B>(),
+            endianness: Endianness::CURRENT,
+            schema_hash: T::SCHEMA.hash(),
+            root_offset: root_offset.try_into().unwrap(),
+            payload_len: payload.len().try_into().unwrap(),
+            checksum: crc32(payload),
+        }
+    }
+
+    /// Encodes the header as bytes.
+    pub fn to_bytes(&self) -> [u8; HEADER_SIZE] {
+        let mut bytes = [0; HEADER_SIZE];
+        bytes[0..4].copy_from_slice(&MAGIC);
+        bytes[4..6].copy_from_slice(&self.version.to_le_bytes());
+        bytes[6] = self.basis_bits;
+        bytes[7] = match self.endianness {
+            Endianness::Little => 0,
+            Endianness::Big => 1,
+        };
+        bytes[8..16].copy_from_slice(&self.schema_hash.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.root_offset.to_le_bytes());
+        bytes[24..32].copy_from_slice(&self.payload_len.to_le_bytes());
+        bytes[32..36].copy_from_slice(&self.checksum.to_le_bytes());
+        bytes
+    }
+
+    /// Decodes a header from the start of the given bytes.
+    ///
+    /// This only checks that the bytes are a header of a supported version.
+    /// Use [`check`](Header::check) to check the contents of the header.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ContainerError> {
+        let bytes = bytes.get(..HEADER_SIZE).ok_or(ContainerError::TooShort)?;
+        if bytes[0..4] != MAGIC {
+            return Err(ContainerError::BadMagic);
+        }
+
+        let version = u16::from_le_bytes(bytes[4..6].try_into().unwrap());
+        if version != FORMAT_VERSION {
+            return Err(ContainerError::UnsupportedVersion(version));
+        }
+        let endianness = match bytes[7] {
+            0 => Endianness::Little,
+            1 => Endianness::Big,
+            _ => return Err(ContainerError::Invalid),
+        };
+        let u64_at =
+            |i: usize| u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap());
+
+        Ok(Self {
+            version,
+            basis_bits: bytes[6],
+            endianness,
+            schema_hash: u64_at(8),
+            root_offset: u64_at(16),
+            payload_len: u64_at(24),
+            checksum: u32::from_le_bytes(bytes[32..36].try_into().unwrap()),
+        })
+    }
+
+    /// Checks that the header describes a payload with a root of type `T` in
+    /// the basis `B` and the current endianness, and returns the payload from
+    /// the bytes following the header.
+    ///
+    /// The payload must match the checksum in the header, and the root must be
+    /// aligned for `T` and lie within the payload.
+    pub fn check<'a, T: Portable, B: Basis>(
+        &self,
+        bytes: &'a [u8],
+    ) -> Result<&'a [u8], ContainerError> {
+        let expected = basis_bits::<B>();
+        if self.basis_bits != expected {
+            return Err(ContainerError::BasisMismatch {
+                expected,
+                found: self.basis_bits,
+            });
+        }
+        if self.endianness != Endianness::CURRENT {
+            return Err(ContainerError::EndiannessMismatch);
+        }
+        if self.schema_hash != T::SCHEMA.hash() {
+            return Err(ContainerError::SchemaMismatch);
+        }
+
+        let payload_len = usize::try_from(self.payload_len)
+            .map_err(|_| ContainerError::TooShort)?;
+        let payload = bytes
+            .get(HEADER_SIZE..)
+            .and_then(|bytes| bytes.get(..payload_len))
+            .ok_or(ContainerError::TooShort)?;
+        if crc32(payload) != self.checksum {
+            return Err(ContainerError::ChecksumMismatch);
+        }
+
+        let root_offset = usize::try_from(self.root_offset)
+            .map_err(|_| ContainerError::Invalid)?;
+        if root_offset % ::core::mem::align_of::<T>() != 0 {
+            return Err(ContainerError::Invalid);
+        }
+        let root_end = root_offset.checked_add(::core::mem::size_of::<T>());
+        if !matches!(root_end, Some(end) if end <= payload_len) {
+            return Err(ContainerError::Invalid);
+        }
+
+        Ok(payload)
+    }
+}
+
+fn basis_bits<B: Basis>() -> u8 {
+    (::core::mem::size_of::<B::Usize>() * 8).try_into().unwrap()
+}
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Returns the CRC-32 (IEEE) of the given bytes.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        let index = (crc ^ u32::from(byte)) & 0xff;
+        crc = (crc >> 8) ^ CRC32_TABLE[index as usize];
+    }
+    !crc
+}
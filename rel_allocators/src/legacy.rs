@@ -2,8 +2,9 @@ use ::core::{
     alloc::Layout,
     cell::Cell,
     marker::{PhantomData, PhantomPinned},
-    mem::forget,
-    ptr::{slice_from_raw_parts_mut, NonNull},
+    mem::{align_of, forget, size_of},
+    ptr::{slice_from_raw_parts_mut, write_bytes, NonNull},
+    slice,
 };
 use ::heresy::alloc::{AllocError, Allocator};
 use ::mischief::{In, Region, RegionalAllocator, Slot, Unique};
@@ -12,6 +13,7 @@ use ::ptr_meta::Pointee;
 use ::rel_alloc::alloc::RelAllocator;
 use ::rel_core::{
     Basis,
+    CloneRaw,
     DefaultBasis,
     Emplace,
     EmplaceExt,
@@ -27,6 +29,9 @@ use ::situ::{
     Pinned,
     Ref,
 };
+use ::std::io::{self, Write};
+
+use crate::container::{ContainerError, Header, HEADER_SIZE};
 
 #[derive(Debug)]
 pub struct SlabError;
@@ -64,16 +69,21 @@ impl<U, B: Basis> SlabControl<U, B> {
         if layout.align() > Self::LAYOUT.align() {
             Err(AllocError)
         } else {
-            let start =
-                (this.len() + layout.align() - 1) & !(layout.align() - 1);
+            let len = this.len();
+            let start = (len + layout.align() - 1) & !(layout.align() - 1);
             let available = this.cap() - start;
             if available < layout.size() {
                 Err(AllocError)
             } else {
                 this.len
                     .set(B::from_native_usize(start + layout.size()).unwrap());
-                let address =
-                    unsafe { this.as_ptr().cast_mut().cast::<u8>().add(start) };
+                let base = this.as_ptr().cast_mut().cast::<u8>();
+                // Zero the alignment gap so that the used bytes of the slab
+                // are deterministic.
+                unsafe {
+                    write_bytes(base.add(len), 0, start - len);
+                }
+                let address = unsafe { base.add(start) };
                 let slice_ptr =
                     slice_from_raw_parts_mut(address, layout.size());
                 Ok(unsafe { NonNull::new_unchecked(slice_ptr) })
@@ -88,6 +98,8 @@ impl<U, B: Basis> SlabControl<U, B> {
         let max_cap = bytes.len();
         let mut out = Self::try_cast_slot_from_bytes(bytes)?;
 
+        // Zero the control first so that its padding bytes are deterministic.
+        out.zero();
         munge!(
             let SlabControl {
                 root: mut out_root,
@@ -239,6 +251,86 @@ impl<'a, U, B: Basis> SlabAllocator<'a, U, B> {
         let result = unsafe { SlabControl::withdraw(self.inner) };
         result.map(|ptr| unsafe { OwnedVal::from_raw_in(ptr, *self) })
     }
+
+    /// Shrinks the allocator to fit and writes its memory to `writer` as a
+    /// [container](crate::container) with a root of type `T`.
+    ///
+    /// The allocator can't allocate any more memory after it has been saved.
+    /// Returns an error of kind `InvalidInput` if no root object has been
+    /// deposited.
+    ///
+    /// # Safety
+    ///
+    /// - All of the memory used by the allocator must be initialized. This is
+    ///   always the case if the bytes the allocator was created in were zeroed.
+    /// - The deposited root object must be of type `T`.
+    pub unsafe fn save_to<T, W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        T: Portable,
+        W: Write,
+    {
+        let root = self.inner.root();
+        if root == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "no root object has been deposited",
+            ));
+        }
+
+        self.shrink_to_fit();
+        // SAFETY: The first `len` bytes of the slab are the memory used by the
+        // allocator, which the caller has guaranteed is initialized. The
+        // allocator can't allocate any more memory, so it is not modified
+        // while `payload` is alive.
+        let payload = unsafe {
+            slice::from_raw_parts(
+                self.inner.as_ptr().cast::<u8>(),
+                self.inner.len(),
+            )
+        };
+        let header = Header::new::<T, B>(root, payload);
+        writer.write_all(&header.to_bytes())?;
+        writer.write_all(payload)
+    }
+
+    /// Loads an allocator from a [container](crate::container) with a root of
+    /// type `T`.
+    ///
+    /// The container is checked before the allocator is created: its basis,
+    /// endianness, and root schema hash must match, its payload must match its
+    /// checksum, and its root must be aligned for `T` and lie within its
+    /// payload. The payload must be aligned to at least 8 bytes, which is
+    /// always the case if `bytes` is aligned to 16 bytes.
+    pub fn load_from<T: Portable>(
+        bytes: &'a mut [u8],
+        unique: U,
+    ) -> Result<Self, ContainerError> {
+        let header = Header::from_bytes(bytes)?;
+        let len = header.check::<T, B>(bytes)?.len();
+
+        let payload = &mut bytes[HEADER_SIZE..HEADER_SIZE + len];
+        let align = SlabControl::<U, B>::LAYOUT.align();
+        if payload.as_ptr() as usize & (align - 1) != 0 {
+            return Err(ContainerError::Misaligned);
+        }
+        // SAFETY: `payload` is a mutable borrow for `'a`, so it is non-null,
+        // properly aligned, valid for reads and writes, and does not alias
+        // any other accessible references for `'a`.
+        let slot = unsafe { Slot::new_unchecked(payload as *mut [u8]) };
+        let inner = SlabControl::try_from_bytes(slot, unique)
+            .map_err(|_| ContainerError::Invalid)?;
+
+        let root = inner.root();
+        if root == 0
+            || u64::try_from(root) != Ok(header.root_offset)
+            || root % align_of::<T>() != 0
+            || root + size_of::<T>() > inner.len()
+        {
+            return Err(ContainerError::Invalid);
+        }
+
+        Ok(Self { inner })
+    }
 }
 
 impl<U, B: Basis> Clone for SlabAllocator<'_, U, B> {
@@ -295,8 +387,9 @@ where
 {
 }
 
-#[derive(DropRaw, Portable)]
+#[derive(CloneRaw, DropRaw, Portable)]
 #[repr(C)]
+#[zero_padding]
 pub struct RelSlabAllocator<
     'a,
     U: Unique,
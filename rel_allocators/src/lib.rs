@@ -2,6 +2,7 @@
 
 pub mod adapters;
 pub mod brand;
+pub mod container;
 mod control;
 pub mod external;
 pub mod legacy;
@@ -7,12 +7,23 @@ use ::core::{
     ptr::{slice_from_raw_parts_mut, NonNull},
 };
 use ::heresy::alloc::{AllocError, Allocator};
-use ::mischief::{In, Region, RegionalAllocator, Slot, Unique};
+use ::mischief::{
+    BaseAllocator,
+    In,
+    Region,
+    RegionBase,
+    RegionalAllocator,
+    RuntimeRegion,
+    Slot,
+    Unique,
+};
 use ::munge::munge;
 use ::ptr_meta::Pointee;
 use ::rel_alloc::alloc::RelAllocator;
 use ::rel_core::{
+    schema::Schema,
     Basis,
+    CloneRaw,
     DefaultBasis,
     Emplace,
     EmplaceExt,
@@ -85,6 +96,19 @@ impl<C, B: Basis> PrefixHeader<C, B> {
     }
 }
 
+/// Returns the schema of the header that a `Prefix` allocator with control `C`
+/// places at the beginning of its memory.
+///
+/// The header is followed immediately by the memory that the allocator manages,
+/// so the first allocation made by the allocator is located at an offset of the
+/// size of the header from the start of the buffer.
+pub fn header_schema<C: Portable, B: Basis>() -> &'static Schema
+where
+    B::Usize: Portable,
+{
+    <PrefixHeader<C, B> as Portable>::SCHEMA
+}
+
 pub struct Prefix<'a, C, R: Region, B: Basis = DefaultBasis> {
     header: In<Ref<'a, PrefixHeader<C, B>>, R>,
 }
@@ -121,6 +145,8 @@ impl<'a, C: 'a, R: Region, B: 'a + Basis> Prefix<'a, C, R, B> {
         let suffix = unsafe { NonNull::new_unchecked(suffix.as_ptr()) };
         let control = unsafe { C::new(suffix) };
 
+        // Zero the header first so that its padding bytes are deterministic.
+        prefix.zero();
         munge!(
             let PrefixHeader {
                 cap: mut out_cap,
@@ -167,6 +193,21 @@ impl<'a, C: 'a + Control, U: Unique, B: 'a + Basis>
     }
 }
 
+impl<'a, C: 'a + Control, U: Unique, B: 'a + Basis>
+    Prefix<'a, C, RuntimeRegion<'a, U>, B>
+{
+    /// Creates a `Prefix` allocator in a fresh runtime region made from
+    /// `bytes`. The returned region can be used to check whether pointers are
+    /// located in the allocator's memory.
+    pub fn try_new_in_runtime_region(
+        bytes: Slot<'a, [u8]>,
+        unique: &'a mut U,
+    ) -> Result<(Self, RuntimeRegion<'a, U>), PrefixError> {
+        let (region, bytes) = RuntimeRegion::new(bytes, unique);
+        Ok((Self::try_new_in(bytes)?, region))
+    }
+}
+
 pub struct PrefixRegion<U> {
     _phantom: PhantomData<U>,
 }
@@ -302,6 +343,32 @@ where
     type Region = R;
 }
 
+// `Prefix` is intentionally the only `BaseAllocator`. A `UniqueRegion` made by
+// `try_new_in_region` is exactly the byte slice the allocator manages, and the
+// header is always at its start, so the base can be found without any extra
+// state. A `Brand` may share its region with memory it does not manage, a
+// `RuntimeChecked` allocator's base is already available from
+// `RuntimeRegion::base`, and the legacy `SlabAllocator` is only kept for
+// compatibility, so none of them implement it.
+//
+// SAFETY: A `UniqueRegion` is created by `try_new_in_region` from the entire
+// byte slice that the allocator manages, and the header is placed at the start
+// of that slice.
+unsafe impl<'a, C, U, B> BaseAllocator for Prefix<'a, C, UniqueRegion<'a, U>, B>
+where
+    C: Control,
+    U: Unique,
+    B: Basis,
+{
+    fn region_base(&self) -> RegionBase<UniqueRegion<'a, U>> {
+        let header = In::into_inner(self.header);
+        let ptr = header.as_ptr().cast::<u8>().cast_mut();
+        // SAFETY: `ptr` is derived from a reference to the header, so it is
+        // non-null. The header is at the start of the region, as required.
+        unsafe { RegionBase::new_unchecked(NonNull::new_unchecked(ptr)) }
+    }
+}
+
 unsafe impl<'a, C, R, BH, BA> Emplace<RelPrefix<'a, C, R, BH, BA>, R>
     for Prefix<'a, C, R, BH>
 where
@@ -323,8 +390,9 @@ where
     }
 }
 
-#[derive(DropRaw, Move, Portable)]
+#[derive(CloneRaw, DropRaw, Move, Portable)]
 #[repr(C)]
+#[zero_padding]
 pub struct RelPrefix<
     'a,
     C,
@@ -1,7 +1,7 @@
 use ::core::{
     alloc::Layout,
     cell::Cell,
-    ptr::{slice_from_raw_parts_mut, NonNull},
+    ptr::{slice_from_raw_parts_mut, write_bytes, NonNull},
 };
 use ::heresy::alloc::AllocError;
 use ::ptr_meta::PtrExt;
@@ -55,6 +55,11 @@ where
             } else {
                 self.len
                     .set(B::from_native_usize(start + layout.size()).unwrap());
+                // Zero the alignment gap so that the used bytes of the slab
+                // are deterministic.
+                unsafe {
+                    write_bytes(ptr.cast::<u8>().add(len), 0, start - len);
+                }
                 let address = unsafe { ptr.cast::<u8>().add(start) };
                 let slice_ptr =
                     slice_from_raw_parts_mut(address, layout.size());
@@ -0,0 +1,235 @@
+//! Pointers that store offsets from the base of their region.
+
+use ::core::marker::PhantomData;
+use ::mischief::{In, Region, RegionBase, Slot};
+use ::ptr_meta::{metadata, Pointee};
+use ::situ::{DropRaw, Mut, Ref, Val};
+
+use crate::{
+    schema::PointsTo,
+    Basis,
+    BasisPointee,
+    CloneRaw,
+    DefaultBasis,
+    Emplace,
+    EmplaceError,
+    Move,
+    Portable,
+    TryEmplace,
+};
+
+/// A pointer that stores the offset of its pointee from the base of its region.
+///
+/// Unlike [`RelPtr`](crate::RelPtr), a `BasePtr` does not depend on its own
+/// location, so moving it is a plain copy and it may be located outside of its
+/// region. Resolving it requires the [`RegionBase`] of its region, which a
+/// [`BaseAllocator`](::mischief::BaseAllocator) can provide.
+///
+/// Base pointers are emplaced from a region base and a pointer, like
+/// `(RegionBase<R>, In<*mut T, R>)`.
+#[repr(C)]
+#[derive(Portable)]
+#[rel_core = "crate"]
+#[zero_padding]
+pub struct BasePtr<T, R, B = DefaultBasis>
+where
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+{
+    offset: B::Usize,
+    metadata: <T as BasisPointee<B>>::BasisMetadata,
+    _phantom: PhantomData<(*mut T, R)>,
+    _pointee: PointsTo<T>,
+}
+
+impl<T, R, B> Clone for BasePtr<T, R, B>
+where
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, R, B> Copy for BasePtr<T, R, B>
+where
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+{
+}
+
+impl<T, R, B> DropRaw for BasePtr<T, R, B>
+where
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+{
+    #[inline]
+    unsafe fn drop_raw(_: Mut<'_, Self>) {}
+}
+
+impl<T, R, B> BasePtr<T, R, B>
+where
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+{
+    /// Returns the offset of the base pointer's target from the base of its
+    /// region.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        B::to_native_usize(self.offset).unwrap()
+    }
+
+    /// Returns the metadata of the base pointer's pointee.
+    #[inline]
+    pub fn metadata(&self) -> T::Metadata {
+        T::to_native_metadata(self.metadata).unwrap()
+    }
+
+    /// Returns the target of the base pointer in the region with the given
+    /// base.
+    pub fn resolve(&self, base: RegionBase<R>) -> In<*mut T, R> {
+        // SAFETY: The offset was computed from a pointer contained in `R`, so
+        // adding it to the start of the allocated object that corresponds to
+        // `R` stays in bounds of that allocated object.
+        let data_address = unsafe { base.as_ptr().add(self.offset()) };
+        let ptr = ::ptr_meta::from_raw_parts_mut(
+            data_address.cast(),
+            self.metadata(),
+        );
+        // SAFETY: The target of the base pointer was contained in `R` when it
+        // was emplaced, and its offset from the base of `R` is unchanged.
+        unsafe { In::new_unchecked(ptr) }
+    }
+
+    /// Returns a `Ref` to the target of the base pointer in the region with
+    /// the given base.
+    ///
+    /// # Safety
+    ///
+    /// - The target must be properly aligned and valid for reads.
+    /// - The target must not alias any other mutable references for `'a`.
+    /// - The value pointed to by the target must be initialized.
+    pub unsafe fn as_ref<'a>(&self, base: RegionBase<R>) -> Ref<'a, T> {
+        let ptr = In::into_inner(self.resolve(base));
+        // SAFETY: `ptr` is non-null because it is derived from the base of a
+        // region. The caller has guaranteed that it is properly aligned, valid
+        // for reads, does not alias any other mutable references for `'a`, and
+        // points to an initialized value.
+        unsafe { Ref::new_unchecked(ptr) }
+    }
+
+    /// Returns a `Mut` to the target of the base pointer in the region with
+    /// the given base.
+    ///
+    /// # Safety
+    ///
+    /// - The target must be properly aligned and valid for reads and writes.
+    /// - The target must not alias any other accessible references for `'a`.
+    /// - The value pointed to by the target must be initialized and immovable.
+    pub unsafe fn as_mut<'a>(&self, base: RegionBase<R>) -> Mut<'a, T> {
+        let ptr = In::into_inner(self.resolve(base));
+        // SAFETY: `ptr` is non-null because it is derived from the base of a
+        // region. The caller has guaranteed that it is properly aligned, valid
+        // for reads and writes, does not alias any other accessible references
+        // for `'a`, and points to an initialized and immovable value.
+        unsafe { Mut::new_unchecked(ptr) }
+    }
+}
+
+// SAFETY:
+// - `BasePtr` is `Sized` and always has metadata `()`, so `emplaced_meta`
+//   always returns valid metadata for it.
+// - `emplace_unsized_unchecked` initializes its `out` parameter.
+unsafe impl<T, R, B, RO> Emplace<BasePtr<T, R, B>, RO>
+    for (RegionBase<R>, In<*mut T, R>)
+where
+    T: BasisPointee<B> + Pointee + ?Sized,
+    R: Region,
+    B: Basis,
+    RO: Region,
+{
+    fn emplaced_meta(&self) -> <BasePtr<T, R, B> as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, BasePtr<T, R, B>>, RO>,
+    ) {
+        // SAFETY: The caller has guaranteed that `out` has the metadata
+        // returned by `emplaced_meta`.
+        unsafe { self.try_emplace_unsized_unchecked(out).unwrap() }
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter if
+// it returns `Ok`.
+unsafe impl<T, R, B, RO> TryEmplace<BasePtr<T, R, B>, RO>
+    for (RegionBase<R>, In<*mut T, R>)
+where
+    T: BasisPointee<B> + Pointee + ?Sized,
+    R: Region,
+    B: Basis,
+    RO: Region,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, BasePtr<T, R, B>>, RO>,
+    ) -> Result<(), EmplaceError> {
+        let (base, ptr) = self;
+
+        let offset = B::from_native_usize(base.offset_of(ptr))
+            .map_err(|_| EmplaceError::OffsetOverflow)?;
+        let ptr = In::into_inner(ptr);
+        let metadata = T::from_native_metadata(metadata(ptr))
+            .map_err(|_| EmplaceError::MetadataOverflow)?;
+
+        In::into_inner(out).write(BasePtr {
+            offset,
+            metadata,
+            _phantom: PhantomData,
+            _pointee: PointsTo::new(),
+        });
+
+        Ok(())
+    }
+}
+
+// SAFETY: `move_unsized_unchecked` initializes its `out` parameter by copying
+// the base pointer into it.
+unsafe impl<T, R, B, RO> Move<RO> for BasePtr<T, R, B>
+where
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+    RO: Region,
+{
+    unsafe fn move_unsized_unchecked(
+        this: In<Val<'_, Self>, RO>,
+        out: In<Slot<'_, Self>, RO>,
+    ) {
+        In::into_inner(out).write(*In::into_inner(this));
+    }
+}
+
+// SAFETY: `clone_raw_unchecked` initializes its `out` parameter by copying the
+// base pointer into it.
+unsafe impl<T, R, B, RO> CloneRaw<RO> for BasePtr<T, R, B>
+where
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+    RO: Region,
+{
+    unsafe fn clone_raw_unchecked(
+        this: Ref<'_, Self>,
+        out: In<Slot<'_, Self>, RO>,
+    ) {
+        In::into_inner(out).write(*this);
+    }
+}
@@ -1,11 +1,13 @@
-use ::core::{convert::Infallible, fmt::Debug, hash::Hash};
+use ::core::{convert::Infallible, fmt::Debug, hash::Hash, mem::align_of};
 use ::ptr_meta::Pointee;
 use ::situ::DropRaw;
 
+use crate::Offset;
+
 /// A selection of types to use in place of `isize` and `usize`.
 pub trait Basis {
     /// The type to use in place of `isize`.
-    type Isize: Copy + DropRaw + Send + Sync + Ord + Hash + Unpin;
+    type Isize: Offset + Copy + DropRaw + Send + Sync + Ord + Hash + Unpin;
     /// The type to use in place of `usize`.
     type Usize: Copy + DropRaw + Send + Sync + Ord + Hash + Unpin;
     /// An error occurred during type conversion from a native `isize` or
@@ -50,6 +52,9 @@ pub trait BasisPointee<B: Basis>: Pointee {
     /// representation.
     type ToNativeError: Debug;
 
+    /// The minimum alignment of any pointee of this type.
+    const ALIGN: usize;
+
     /// Returns the pointer metadata in `B` corresponding to the given native
     /// pointer metadata, or `Err` if the conversion failed.
     fn from_native_metadata(
@@ -68,6 +73,8 @@ impl<T, B: Basis> BasisPointee<B> for T {
     type FromNativeError = Infallible;
     type ToNativeError = Infallible;
 
+    const ALIGN: usize = align_of::<T>();
+
     #[inline]
     fn from_native_metadata(
         _: Self::Metadata,
@@ -88,6 +95,8 @@ impl<T, B: Basis> BasisPointee<B> for [T] {
     type FromNativeError = B::FromNativeError;
     type ToNativeError = B::ToNativeError;
 
+    const ALIGN: usize = align_of::<T>();
+
     #[inline]
     fn from_native_metadata(
         metadata: Self::Metadata,
@@ -108,6 +117,8 @@ impl<B: Basis> BasisPointee<B> for str {
     type FromNativeError = B::FromNativeError;
     type ToNativeError = B::ToNativeError;
 
+    const ALIGN: usize = 1;
+
     #[inline]
     fn from_native_metadata(
         metadata: Self::Metadata,
@@ -287,3 +298,66 @@ impl Basis for DefaultBasis {
         }
     }
 }
+
+macro_rules! define_fixed_basis {
+    ($(#[$attr:meta])* $ident:ident, $isize:ident, $usize:ident) => {
+        $(#[$attr])*
+        ///
+        /// Unlike [`DefaultBasis`], this basis does not depend on the enabled
+        /// feature flags. This makes it possible to work with values from
+        /// several bases at once, for example while transcoding them.
+        pub struct $ident;
+
+        impl Basis for $ident {
+            type Isize = crate::primitive::$isize;
+            type Usize = crate::primitive::$usize;
+            type FromNativeError = ::core::num::TryFromIntError;
+            type ToNativeError = ::core::num::TryFromIntError;
+
+            #[inline]
+            fn from_native_isize(
+                value: isize,
+            ) -> Result<Self::Isize, Self::FromNativeError> {
+                Ok(Self::Isize::from_ne(value.try_into()?))
+            }
+
+            #[inline]
+            fn to_native_isize(
+                value: Self::Isize,
+            ) -> Result<isize, Self::ToNativeError> {
+                // The conversion is infallible on some targets, so its error
+                // type has to be converted.
+                #[allow(clippy::needless_question_mark)]
+                Ok(value.to_ne().try_into()?)
+            }
+
+            #[inline]
+            fn from_native_usize(
+                value: usize,
+            ) -> Result<Self::Usize, Self::FromNativeError> {
+                Ok(Self::Usize::from_ne(value.try_into()?))
+            }
+
+            #[inline]
+            fn to_native_usize(
+                value: Self::Usize,
+            ) -> Result<usize, Self::ToNativeError> {
+                #[allow(clippy::needless_question_mark)]
+                Ok(value.to_ne().try_into()?)
+            }
+        }
+    };
+}
+
+define_fixed_basis!(
+    /// A [`Basis`] with 16-bit `Isize` and `Usize` types.
+    Basis16, I16, U16
+);
+define_fixed_basis!(
+    /// A [`Basis`] with 32-bit `Isize` and `Usize` types.
+    Basis32, I32, U32
+);
+define_fixed_basis!(
+    /// A [`Basis`] with 64-bit `Isize` and `Usize` types.
+    Basis64, I64, U64
+);
@@ -0,0 +1,110 @@
+use ::core::marker::PhantomData;
+use ::mischief::{In, Region, Slot};
+use ::situ::{ops::IndexRaw, PrefixDropGuard, Ref};
+
+use super::{Clone, CloneRaw};
+use crate::EmplaceExt;
+
+macro_rules! impl_builtin {
+    ($($ty:ty),*) => {
+        $(
+            // SAFETY: `clone_raw_unchecked` initializes `out` by writing to it.
+            unsafe impl<R: Region> CloneRaw<R> for $ty {
+                #[inline]
+                unsafe fn clone_raw_unchecked(
+                    this: Ref<'_, Self>,
+                    out: In<Slot<'_, Self>, R>,
+                ) {
+                    In::into_inner(out).write(*this);
+                }
+            }
+        )*
+    };
+}
+
+impl_builtin!(i8, u8, bool, ());
+
+// SAFETY: `PhantomData<T>` is a zero-sized type and so is already initialized.
+unsafe impl<T: ?Sized, R: Region> CloneRaw<R> for PhantomData<T> {
+    #[inline]
+    unsafe fn clone_raw_unchecked(_: Ref<'_, Self>, _: In<Slot<'_, Self>, R>) {}
+}
+
+// SAFETY: `clone_raw_unchecked` initializes its `out` parameter by cloning to
+// every element in it.
+unsafe impl<T, R: Region, const N: usize> CloneRaw<R> for [T; N]
+where
+    T: CloneRaw<R>,
+{
+    unsafe fn clone_raw_unchecked(
+        this: Ref<'_, Self>,
+        out: In<Slot<'_, Self>, R>,
+    ) {
+        let mut out = In::into_inner(out);
+        // SAFETY: `out` is a slot for an array of `T`, so its pointer is
+        // non-null, properly aligned, and valid for reads and writes of all of
+        // its elements. `out` is not accessed again except to clone to each
+        // element, so the initialized prefix does not alias any other
+        // accessible references.
+        let mut guard =
+            unsafe { PrefixDropGuard::new(out.as_ptr().cast::<T>()) };
+        for i in 0..N {
+            // SAFETY: `i` is in bounds because it must be less than the length
+            // of the array, `N`.
+            let this_i = unsafe { IndexRaw::index_raw_unchecked(this, i) };
+            // SAFETY: `i` is in bounds because it must be less than the length
+            // of the array, `N`.
+            let out_i = unsafe { out.as_mut().get_unchecked(i) };
+            // SAFETY: `out_i` is an element of `out`, which is located in `R`,
+            // so `out_i` must also be located in `R`.
+            let out_i = unsafe { In::new_unchecked(out_i) };
+            Clone(this_i).emplace(out_i);
+            // SAFETY: The first `i + 1` elements of `out` have been initialized
+            // by cloning to them.
+            unsafe {
+                guard.set_len(i + 1);
+            }
+        }
+        PrefixDropGuard::forget(guard);
+    }
+}
+
+// SAFETY: `clone_raw_unchecked` initializes its `out` parameter by cloning to
+// every element in it.
+unsafe impl<T, R: Region> CloneRaw<R> for [T]
+where
+    T: CloneRaw<R>,
+{
+    unsafe fn clone_raw_unchecked(
+        this: Ref<'_, Self>,
+        out: In<Slot<'_, Self>, R>,
+    ) {
+        let mut out = In::into_inner(out);
+        // SAFETY: `out` is a slot for a slice of `T`, so its pointer is
+        // non-null, properly aligned, and valid for reads and writes of all of
+        // its elements. `out` is not accessed again except to clone to each
+        // element, so the initialized prefix does not alias any other
+        // accessible references.
+        let mut guard =
+            unsafe { PrefixDropGuard::new(out.as_ptr().cast::<T>()) };
+        for i in 0..this.len() {
+            // SAFETY: `i` is in bounds because it must be less than the length
+            // of the slice.
+            let this_i = unsafe { IndexRaw::index_raw_unchecked(this, i) };
+            // SAFETY: The caller has guaranteed that `out` has the same
+            // metadata as `this`, so it has the same length and `i` must also
+            // be in bounds for it.
+            let out_i = unsafe { out.as_mut().get_unchecked(i) };
+            // SAFETY: `out_i` is an element of `out`, which is located in `R`,
+            // so `out_i` must also be located in `R`.
+            let out_i = unsafe { In::new_unchecked(out_i) };
+            Clone(this_i).emplace(out_i);
+            // SAFETY: The first `i + 1` elements of `out` have been initialized
+            // by cloning to them.
+            unsafe {
+                guard.set_len(i + 1);
+            }
+        }
+        PrefixDropGuard::forget(guard);
+    }
+}
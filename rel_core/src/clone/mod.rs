@@ -0,0 +1,108 @@
+//! Cloning values within a region.
+
+mod impls;
+
+use ::mischief::{In, Region, Slot};
+use ::ptr_meta::{metadata, Pointee};
+pub use ::rel_core_derive::CloneRaw;
+use ::situ::{DropRaw, Ref};
+
+use crate::{Emplace, EmplaceError, TryEmplace};
+
+/// An emplaced value that can be cloned into another location in the same
+/// region.
+///
+/// # Safety
+///
+/// `clone_raw_unchecked` must initialize its `out` parameter, and
+/// `try_clone_raw_unchecked` must initialize its `out` parameter if it returns
+/// `Ok`.
+pub unsafe trait CloneRaw<R: Region>: DropRaw {
+    /// Clones a value into a given slot within some memory region.
+    ///
+    /// # Safety
+    ///
+    /// `out` must have the same metadata as `this`.
+    unsafe fn clone_raw_unchecked(
+        this: Ref<'_, Self>,
+        out: In<Slot<'_, Self>, R>,
+    );
+
+    /// Attempts to clone a value into a given slot within some memory region.
+    ///
+    /// By default, this calls `clone_raw_unchecked` and always succeeds. Types
+    /// which allocate when cloned should override it to return an error
+    /// instead of panicking. If this returns an error, `out` is left
+    /// uninitialized and any resources acquired while cloning are released.
+    ///
+    /// # Safety
+    ///
+    /// `out` must have the same metadata as `this`.
+    unsafe fn try_clone_raw_unchecked(
+        this: Ref<'_, Self>,
+        out: In<Slot<'_, Self>, R>,
+    ) -> Result<(), EmplaceError> {
+        // SAFETY: The caller has guaranteed that `out` has the same metadata
+        // as `this`.
+        unsafe {
+            Self::clone_raw_unchecked(this, out);
+        }
+        Ok(())
+    }
+}
+
+/// An emplacer that clones the value behind a `Ref`.
+///
+/// This can emplace a copy of any value that implements `CloneRaw<R>` into
+/// region `R`.
+///
+/// # Panics
+///
+/// Emplacing panics if cloning the value panics. In particular, cloning an
+/// owning container like `RelVec` or `RelBox` panics if its allocator fails to
+/// allocate memory for the clone. Use `try_emplace` to get an error instead
+/// from containers which support it.
+pub struct Clone<'a, T: ?Sized>(pub Ref<'a, T>);
+
+// SAFETY:
+// - `emplaced_meta` returns the metadata of the value being cloned, which is
+//   the metadata of the clone.
+// - `emplace_unsized_unchecked` initializes its `out` parameter by cloning into
+//   it.
+unsafe impl<T, R> Emplace<T, R> for Clone<'_, T>
+where
+    T: CloneRaw<R> + Pointee + ?Sized,
+    R: Region,
+{
+    #[inline]
+    fn emplaced_meta(&self) -> <T as Pointee>::Metadata {
+        metadata(self.0.as_ptr())
+    }
+
+    #[inline]
+    unsafe fn emplace_unsized_unchecked(self, out: In<Slot<'_, T>, R>) {
+        // SAFETY: The caller has guaranteed that `out` has the metadata
+        // returned by `emplaced_meta`, which is the metadata of `self.0`.
+        unsafe {
+            T::clone_raw_unchecked(self.0, out);
+        }
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter by
+// cloning into it if it returns `Ok`.
+unsafe impl<T, R> TryEmplace<T, R> for Clone<'_, T>
+where
+    T: CloneRaw<R> + Pointee + ?Sized,
+    R: Region,
+{
+    #[inline]
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, T>, R>,
+    ) -> Result<(), EmplaceError> {
+        // SAFETY: The caller has guaranteed that `out` has the metadata
+        // returned by `emplaced_meta`, which is the metadata of `self.0`.
+        unsafe { T::try_clone_raw_unchecked(self.0, out) }
+    }
+}
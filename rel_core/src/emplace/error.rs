@@ -0,0 +1,40 @@
+use ::core::fmt;
+
+/// An error that occurred while emplacing a value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EmplaceError {
+    /// The allocator could not allocate memory for the value.
+    AllocFailed,
+    /// The requested capacity exceeded `isize::MAX` bytes.
+    CapacityOverflow,
+    /// The offset of a relative pointer could not be represented in its offset
+    /// type.
+    OffsetOverflow,
+    /// Some pointer metadata, length, or capacity could not be represented in
+    /// its basis.
+    MetadataOverflow,
+    /// An `Isize` or `Usize` value could not be represented in its basis.
+    IntOverflow,
+}
+
+impl fmt::Display for EmplaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmplaceError::AllocFailed => {
+                f.write_str("memory allocation failed")
+            }
+            EmplaceError::CapacityOverflow => {
+                f.write_str("capacity overflowed `isize::MAX` bytes")
+            }
+            EmplaceError::OffsetOverflow => {
+                f.write_str("pointer offset overflowed its offset type")
+            }
+            EmplaceError::MetadataOverflow => {
+                f.write_str("pointer metadata overflowed its basis")
+            }
+            EmplaceError::IntOverflow => {
+                f.write_str("integer value overflowed its basis")
+            }
+        }
+    }
+}
@@ -1,9 +1,9 @@
 use ::core::{marker::PhantomData, mem::ManuallyDrop, ptr};
 use ::mischief::{GhostRef, In, Region, Slot, Static, StaticRef};
 use ::ptr_meta::Pointee;
-use ::situ::DropRaw;
+use ::situ::{DropRaw, PrefixDropGuard};
 
-use crate::{Emplace, EmplaceExt};
+use crate::{Emplace, EmplaceError, EmplaceExt, TryEmplace, TryEmplaceExt};
 
 macro_rules! impl_builtin {
     ($($ty:ty),*) => {
@@ -23,6 +23,18 @@ macro_rules! impl_builtin {
                     In::into_inner(out).write(self);
                 }
             }
+
+            // SAFETY: `try_emplace_unsized_unchecked` always initializes its
+            // `out` parameter by writing to it.
+            unsafe impl<R: Region> TryEmplace<$ty, R> for $ty {
+                unsafe fn try_emplace_unsized_unchecked(
+                    self,
+                    out: In<Slot<'_, $ty>, R>,
+                ) -> Result<(), EmplaceError> {
+                    In::into_inner(out).write(self);
+                    Ok(())
+                }
+            }
         )*
     }
 }
@@ -60,6 +72,13 @@ where
     unsafe fn emplace_unsized_unchecked(self, out: In<Slot<'_, [T; N]>, R>) {
         let emplacers = ManuallyDrop::new(self);
         let mut out = In::into_inner(out);
+        // SAFETY: `out` is a slot for an array of `T`, so its pointer is
+        // non-null, properly aligned, and valid for reads and writes of all of
+        // its elements. `out` is not accessed again except to emplace to each
+        // element, so the initialized prefix does not alias any other
+        // accessible references.
+        let mut guard =
+            unsafe { PrefixDropGuard::new(out.as_ptr().cast::<T>()) };
         for i in 0..N {
             // SAFETY: `i` is in bounds because it must be less than the length
             // of the array, `N`.
@@ -72,7 +91,98 @@ where
             // value.
             let emplacer_i = unsafe { ptr::read(&emplacers[i]) };
             emplacer_i.emplace(out_i);
+            // SAFETY: The first `i + 1` elements of `out` have been initialized
+            // by emplacing to them.
+            unsafe {
+                guard.set_len(i + 1);
+            }
+        }
+        PrefixDropGuard::forget(guard);
+    }
+}
+
+// SAFETY:
+// - `emplaced_meta` returns the length of the slice of emplacers, which is the
+//   length of the emplaced slice.
+// - `emplace_unsized_unchecked` emplaces a clone of each emplacer to every
+//   element of the `out` slot, which initializes it.
+unsafe impl<E, T, R> Emplace<[T], R> for &[E]
+where
+    E: Clone + Emplace<T, R>,
+    T: DropRaw,
+    R: Region,
+{
+    fn emplaced_meta(&self) -> <[T] as Pointee>::Metadata {
+        self.len()
+    }
+
+    unsafe fn emplace_unsized_unchecked(self, out: In<Slot<'_, [T]>, R>) {
+        let mut out = In::into_inner(out);
+        // SAFETY: `out` is a slot for a slice of `T`, so its pointer is
+        // non-null, properly aligned, and valid for reads and writes of all of
+        // its elements. `out` is not accessed again except to emplace to each
+        // element, so the initialized prefix does not alias any other
+        // accessible references.
+        let mut guard =
+            unsafe { PrefixDropGuard::new(out.as_ptr().cast::<T>()) };
+        for (i, emplacer) in self.iter().enumerate() {
+            // SAFETY: The caller has guaranteed that `out` has the metadata
+            // returned by `emplaced_meta`, so it has the same length as `self`
+            // and `i` is in bounds.
+            let out_i = unsafe { out.as_mut().get_unchecked(i) };
+            // SAFETY: `out_i` is located in `R` because `out` is located in `R`
+            // and `out_i` is an element of `out`.
+            let out_i = unsafe { In::new_unchecked(out_i) };
+            emplacer.clone().emplace(out_i);
+            // SAFETY: The first `i + 1` elements of `out` have been initialized
+            // by emplacing to them.
+            unsafe {
+                guard.set_len(i + 1);
+            }
+        }
+        PrefixDropGuard::forget(guard);
+    }
+}
+
+// SAFETY: If `try_emplace_unsized_unchecked` returns `Ok`, it has emplaced a
+// clone of each emplacer to every element of the `out` slot, which initializes
+// it.
+unsafe impl<E, T, R> TryEmplace<[T], R> for &[E]
+where
+    E: Clone + TryEmplace<T, R>,
+    T: DropRaw,
+    R: Region,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, [T]>, R>,
+    ) -> Result<(), EmplaceError> {
+        let mut out = In::into_inner(out);
+        // SAFETY: `out` is a slot for a slice of `T`, so its pointer is
+        // non-null, properly aligned, and valid for reads and writes of all of
+        // its elements. `out` is not accessed again except to emplace to each
+        // element, so the initialized prefix does not alias any other
+        // accessible references.
+        let mut guard =
+            unsafe { PrefixDropGuard::new(out.as_ptr().cast::<T>()) };
+        for (i, emplacer) in self.iter().enumerate() {
+            // SAFETY: The caller has guaranteed that `out` has the metadata
+            // returned by `emplaced_meta`, so it has the same length as `self`
+            // and `i` is in bounds.
+            let out_i = unsafe { out.as_mut().get_unchecked(i) };
+            // SAFETY: `out_i` is located in `R` because `out` is located in `R`
+            // and `out_i` is an element of `out`.
+            let out_i = unsafe { In::new_unchecked(out_i) };
+            // If this fails, `guard` drops the elements emplaced so far.
+            emplacer.clone().try_emplace(out_i)?;
+            // SAFETY: The first `i + 1` elements of `out` have been initialized
+            // by emplacing to them.
+            unsafe {
+                guard.set_len(i + 1);
+            }
         }
+        PrefixDropGuard::forget(guard);
+        Ok(())
     }
 }
 
@@ -95,3 +205,173 @@ unsafe impl<S: Static, R: Region> Emplace<Self, R> for StaticRef<'_, S> {
 
     unsafe fn emplace_unsized_unchecked(self, _: In<Slot<'_, Self>, R>) {}
 }
+
+#[cfg(test)]
+mod tests {
+    use ::core::{cell::Cell, mem::MaybeUninit};
+    use ::mischief::{In, Region, Slot};
+    use ::ptr_meta::Pointee;
+    use ::situ::{DropRaw, Mut, Ref, Val};
+    use ::std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use crate::{
+        clone::Clone,
+        rel_tuple::RelTuple3,
+        CloneRaw,
+        Emplace,
+        EmplaceExt,
+        Move,
+        MoveExt,
+    };
+
+    const PANIC: u8 = u8::MAX;
+
+    ::std::thread_local! {
+        static DROPS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn take_drops() -> usize {
+        DROPS.with(|drops| drops.replace(0))
+    }
+
+    struct TestRegion;
+
+    // SAFETY: `TestRegion` is only used to emplace into local slots.
+    unsafe impl Region for TestRegion {}
+
+    /// A value that counts how many times it is dropped and panics when it is
+    /// emplaced, moved, or cloned with the value `PANIC`.
+    struct Counted(u8);
+
+    impl DropRaw for Counted {
+        unsafe fn drop_raw(_: Mut<'_, Self>) {
+            DROPS.with(|drops| drops.set(drops.get() + 1));
+        }
+    }
+
+    #[derive(::core::clone::Clone)]
+    struct MakeCounted(u8);
+
+    // SAFETY:
+    // - `emplaced_meta` returns `()`, the only valid metadata for `Sized`
+    //   types.
+    // - `emplace_unsized_unchecked` initializes `out` by writing to it.
+    unsafe impl<R: Region> Emplace<Counted, R> for MakeCounted {
+        fn emplaced_meta(&self) -> <Counted as Pointee>::Metadata {}
+
+        unsafe fn emplace_unsized_unchecked(
+            self,
+            out: In<Slot<'_, Counted>, R>,
+        ) {
+            assert_ne!(self.0, PANIC);
+            In::into_inner(out).write(Counted(self.0));
+        }
+    }
+
+    // SAFETY: `move_unsized_unchecked` initializes `out` by writing to it.
+    unsafe impl<R: Region> Move<R> for Counted {
+        unsafe fn move_unsized_unchecked(
+            this: In<Val<'_, Self>, R>,
+            out: In<Slot<'_, Self>, R>,
+        ) {
+            let value = Val::read(In::into_inner(this));
+            MakeCounted(value.0).emplace(out);
+        }
+    }
+
+    // SAFETY: `clone_raw_unchecked` initializes `out` by writing to it.
+    unsafe impl<R: Region> CloneRaw<R> for Counted {
+        unsafe fn clone_raw_unchecked(
+            this: Ref<'_, Self>,
+            out: In<Slot<'_, Self>, R>,
+        ) {
+            MakeCounted(this.0).emplace(out);
+        }
+    }
+
+    #[derive(CloneRaw, DropRaw, Move)]
+    #[rel_core = "crate"]
+    struct Pair {
+        a: Counted,
+        b: Counted,
+    }
+
+    fn emplace_panics<T, E>(emplacer: E) -> bool
+    where
+        T: DropRaw,
+        E: Emplace<T, TestRegion>,
+    {
+        let mut out = MaybeUninit::<T>::uninit();
+        let slot = Slot::new(&mut out);
+        // SAFETY: All local slots are considered to be in `TestRegion`.
+        let slot = unsafe { In::<_, TestRegion>::new_unchecked(slot) };
+        catch_unwind(AssertUnwindSafe(|| emplacer.emplace(slot))).is_err()
+    }
+
+    #[test]
+    fn emplace_array_panic() {
+        assert!(emplace_panics::<[Counted; 4], _>([
+            MakeCounted(0),
+            MakeCounted(1),
+            MakeCounted(PANIC),
+            MakeCounted(3),
+        ]));
+        assert_eq!(take_drops(), 2);
+    }
+
+    #[test]
+    fn emplace_slice_panic() {
+        let emplacers = [MakeCounted(0), MakeCounted(PANIC), MakeCounted(2)];
+        let mut out = MaybeUninit::<[Counted; 3]>::uninit();
+        let slot = Slot::new(&mut out).unsize();
+        // SAFETY: All local slots are considered to be in `TestRegion`.
+        let slot = unsafe { In::<_, TestRegion>::new_unchecked(slot) };
+        let emplacers = &emplacers[..];
+        let result =
+            catch_unwind(AssertUnwindSafe(|| emplacers.emplace_unsized(slot)));
+        assert!(result.is_err());
+        assert_eq!(take_drops(), 1);
+    }
+
+    #[test]
+    fn emplace_tuple_panic() {
+        assert!(emplace_panics::<RelTuple3<Counted, Counted, Counted>, _>((
+            MakeCounted(0),
+            MakeCounted(1),
+            MakeCounted(PANIC),
+        )));
+        assert_eq!(take_drops(), 2);
+    }
+
+    #[test]
+    fn derived_clone_panic() {
+        let mut value = MaybeUninit::new(Pair {
+            a: Counted(0),
+            b: Counted(PANIC),
+        });
+        // SAFETY: `value` is initialized and not moved while borrowed.
+        let value = unsafe { Ref::new_unchecked(value.as_mut_ptr()) };
+        assert!(emplace_panics::<Pair, _>(Clone(value)));
+        assert_eq!(take_drops(), 1);
+    }
+
+    #[test]
+    fn derived_move_panic() {
+        let mut value = MaybeUninit::new(Pair {
+            a: Counted(0),
+            b: Counted(PANIC),
+        });
+        // SAFETY: `value` is initialized and is not accessed again.
+        let value = unsafe { Val::new_unchecked(value.as_mut_ptr()) };
+        // SAFETY: All local values are considered to be in `TestRegion`.
+        let value = unsafe { In::<_, TestRegion>::new_unchecked(value) };
+        let mut out = MaybeUninit::<Pair>::uninit();
+        let slot = Slot::new(&mut out);
+        // SAFETY: All local slots are considered to be in `TestRegion`.
+        let slot = unsafe { In::<_, TestRegion>::new_unchecked(slot) };
+        let result =
+            catch_unwind(AssertUnwindSafe(|| Pair::r#move(value, slot)));
+        assert!(result.is_err());
+        assert_eq!(take_drops(), 1);
+    }
+}
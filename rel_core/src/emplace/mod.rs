@@ -1,9 +1,12 @@
+mod error;
 mod impls;
 
 use ::mischief::{In, Region, Slot};
 use ::ptr_meta::{metadata, Pointee};
 use ::situ::{DropRaw, Mut, Val};
 
+pub use self::error::EmplaceError;
+
 /// A value emplacer.
 ///
 /// # Safety
@@ -165,3 +168,142 @@ where
         unsafe { self.emplace_val_unsized(out) }
     }
 }
+
+/// A value emplacer that may fail.
+///
+/// Fallible emplacers report errors such as running out of space in their
+/// region instead of panicking.
+///
+/// # Safety
+///
+/// If `try_emplace_unsized_unchecked` returns `Ok`, it must have initialized
+/// its `out` parameter.
+pub unsafe trait TryEmplace<T, R: Region>: Emplace<T, R>
+where
+    T: DropRaw + Pointee + ?Sized,
+{
+    /// Attempts to emplace a value into a given slot within some memory
+    /// region.
+    ///
+    /// If this returns an error, `out` is left uninitialized and any resources
+    /// acquired during emplacement are released.
+    ///
+    /// # Safety
+    ///
+    /// `out` must have the metadata returned by `emplaced_meta`.
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, T>, R>,
+    ) -> Result<(), EmplaceError>;
+}
+
+/// An extension trait for `TryEmplace` that provides a variety of convenient
+/// fallible emplacement methods.
+///
+/// # Safety
+///
+/// `try_emplace_val_unsized` and `try_emplace_val` must initialize `out` and
+/// return it as a `Val` if they return `Ok`.
+pub unsafe trait TryEmplaceExt<T, R: Region>: TryEmplace<T, R>
+where
+    T: DropRaw + Pointee + ?Sized,
+{
+    /// Attempts to emplace a sized value into a given slot within some memory
+    /// region.
+    fn try_emplace(self, out: In<Slot<'_, T>, R>) -> Result<(), EmplaceError>
+    where
+        T: Sized;
+
+    /// Attempts to emplace a sized value into a given slot within some memory
+    /// region and returns a mutable reference.
+    fn try_emplace_mut(
+        self,
+        out: In<Slot<'_, T>, R>,
+    ) -> Result<In<Mut<'_, T>, R>, EmplaceError>
+    where
+        T: Sized;
+
+    /// Attempts to emplace a value into a given slot within some memory region
+    /// and returns an initialized value.
+    ///
+    /// # Safety
+    ///
+    /// `out` must have the metadata returned by `emplaced_meta`.
+    unsafe fn try_emplace_val_unsized(
+        self,
+        out: In<Slot<'_, T>, R>,
+    ) -> Result<In<Val<'_, T>, R>, EmplaceError>;
+
+    /// Attempts to emplace a sized value into a given slot within some memory
+    /// region and returns an initialized value.
+    fn try_emplace_val(
+        self,
+        out: In<Slot<'_, T>, R>,
+    ) -> Result<In<Val<'_, T>, R>, EmplaceError>
+    where
+        T: Sized;
+}
+
+// SAFETY: `try_emplace_val_unsized` and `try_emplace_val` initialize `out` and
+// return it as a `Val` if emplacement succeeds.
+unsafe impl<E, T, R> TryEmplaceExt<T, R> for E
+where
+    E: TryEmplace<T, R>,
+    T: DropRaw + Pointee + ?Sized,
+    R: Region,
+{
+    #[inline]
+    fn try_emplace(self, out: In<Slot<'_, T>, R>) -> Result<(), EmplaceError>
+    where
+        T: Sized,
+    {
+        // SAFETY: `out` can only have the metadata `()` and so it must be the
+        // same as the metadata returned from `emplaced_meta`.
+        unsafe { self.try_emplace_unsized_unchecked(out) }
+    }
+
+    #[inline]
+    fn try_emplace_mut(
+        self,
+        out: In<Slot<'_, T>, R>,
+    ) -> Result<In<Mut<'_, T>, R>, EmplaceError>
+    where
+        T: Sized,
+    {
+        let val = self.try_emplace_val(out)?;
+        // SAFETY: `Val::leak` returns a mutable reference to its contained
+        // value. Since the value is allocated in `R`, its contained value must
+        // also be.
+        Ok(unsafe { In::map_unchecked(val, Val::leak) })
+    }
+
+    unsafe fn try_emplace_val_unsized(
+        self,
+        mut out: In<Slot<'_, T>, R>,
+    ) -> Result<In<Val<'_, T>, R>, EmplaceError> {
+        // SAFETY: The caller has guaranteed that `out` has the metadata
+        // returned by `emplaced_meta`.
+        unsafe {
+            self.try_emplace_unsized_unchecked(out.as_mut())?;
+        }
+        // SAFETY: `try_emplace_unsized_unchecked` returned `Ok`, so it is
+        // guaranteed to have initialized `out`.
+        let initialize = |s| unsafe { Val::from_slot_unchecked(s) };
+        // SAFETY: the `Val` created from `out` points to the same memory and so
+        // must be located in the same region.
+        Ok(unsafe { out.map_unchecked(initialize) })
+    }
+
+    #[inline]
+    fn try_emplace_val(
+        self,
+        out: In<Slot<'_, T>, R>,
+    ) -> Result<In<Val<'_, T>, R>, EmplaceError>
+    where
+        T: Sized,
+    {
+        // SAFETY: `out` can only have the metadata `()` and so it must be the
+        // same as the metadata returned from `emplaced_meta`.
+        unsafe { self.try_emplace_val_unsized(out) }
+    }
+}
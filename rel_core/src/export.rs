@@ -1,4 +1,6 @@
 //! Public re-exports of dependencies.
 
 pub use ::mischief;
+#[cfg(feature = "serde")]
+pub use ::serde;
 pub use ::situ;
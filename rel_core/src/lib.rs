@@ -12,24 +12,43 @@
 )]
 #![no_std]
 
+#[cfg(test)]
+extern crate std;
+
+pub mod base_ptr;
 mod basis;
+pub mod clone;
 mod emplace;
 pub mod export;
 mod r#move;
+mod offset;
 pub mod option;
+pub mod padding;
 mod portable;
 mod primitive;
 pub mod rel_mem;
+pub mod rel_mut;
 pub mod rel_ptr;
 pub mod rel_ref;
+pub mod rel_tagged_ptr;
 pub mod rel_tuple;
+pub mod schema;
+#[cfg(feature = "serde")]
+pub mod ser;
+pub mod transcode;
+pub mod version;
 
 pub use self::{
+    base_ptr::BasePtr,
     basis::*,
+    clone::CloneRaw,
     emplace::*,
+    offset::*,
     portable::*,
     primitive::*,
     r#move::*,
+    rel_mut::RelMut,
     rel_ptr::RelPtr,
     rel_ref::RelRef,
+    rel_tagged_ptr::RelTaggedPtr,
 };
@@ -0,0 +1,53 @@
+use ::core::hash::Hash;
+use ::situ::DropRaw;
+
+use crate::{Portable, I16, I32, I64, I8};
+
+/// A signed integer type that can store the offset of a relative pointer.
+///
+/// Relative pointers use their basis's `Isize` as their offset by default.
+/// Choosing a smaller offset makes relative pointers more compact at the cost
+/// of limiting how far away from their targets they can be.
+pub trait Offset:
+    Copy + DropRaw + Portable + Send + Sync + Ord + Hash + Unpin
+{
+    /// Returns the offset corresponding to the given `isize`, or `None` if it
+    /// cannot be represented.
+    fn from_native_isize(value: isize) -> Option<Self>;
+
+    /// Returns the `isize` corresponding to this offset, or `None` if it cannot
+    /// be represented.
+    fn to_native_isize(self) -> Option<isize>;
+}
+
+impl Offset for I8 {
+    #[inline]
+    fn from_native_isize(value: isize) -> Option<Self> {
+        value.try_into().ok()
+    }
+
+    #[inline]
+    fn to_native_isize(self) -> Option<isize> {
+        Some(self.into())
+    }
+}
+
+macro_rules! impl_multibyte_offset {
+    ($($portable:ty),*) => {
+        $(
+            impl Offset for $portable {
+                #[inline]
+                fn from_native_isize(value: isize) -> Option<Self> {
+                    Some(<$portable>::from_ne(value.try_into().ok()?))
+                }
+
+                #[inline]
+                fn to_native_isize(self) -> Option<isize> {
+                    self.to_ne().try_into().ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_multibyte_offset!(I16, I32, I64);
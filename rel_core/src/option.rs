@@ -1,17 +1,44 @@
 //! A value that may or may not exist.
 
-use ::core::{hint::unreachable_unchecked, ptr::addr_of_mut};
+use ::core::{
+    hint::unreachable_unchecked,
+    ptr::{addr_of, addr_of_mut},
+};
 use ::mischief::{In, Region, Slot};
 use ::ptr_meta::Pointee;
 use ::raw_enum_macro::raw_enum;
-use ::situ::DropRaw;
+use ::situ::{
+    cmp::{EqRaw, OrdRaw, PartialEqRaw, PartialOrdRaw},
+    hash::HashRaw,
+    DropRaw,
+    Ref,
+};
 
-use crate::{Emplace, EmplaceExt, Move, Portable};
+use crate::{
+    transcode::Transcode,
+    CloneRaw,
+    Emplace,
+    EmplaceExt,
+    Move,
+    Portable,
+};
 
 /// A relative counterpart to `Option`.
-#[derive(DropRaw, Move, Portable)]
+#[derive(
+    CloneRaw,
+    DropRaw,
+    EqRaw,
+    HashRaw,
+    Move,
+    OrdRaw,
+    PartialEqRaw,
+    PartialOrdRaw,
+    Portable,
+    Transcode,
+)]
 #[rel_core = "crate"]
 #[repr(u8)]
+#[zero_padding]
 #[raw_enum]
 pub enum RelOption<T> {
     /// No value.
@@ -20,6 +47,92 @@ pub enum RelOption<T> {
     Some(T),
 }
 
+impl<T> RelOption<T> {
+    /// Converts from `Ref<RelOption<T>>` to `Option<Ref<T>>`.
+    pub fn as_ref(this: Ref<'_, Self>) -> Option<Ref<'_, T>> {
+        let raw_this = raw_rel_option(Ref::as_ptr(this).cast_mut());
+        match raw_rel_option_variant(raw_this) {
+            RawRelOptionVariants::None(_) => None,
+            RawRelOptionVariants::Some(this_ptr) => {
+                // SAFETY: `this_ptr` points to the `Some` variant of `this`,
+                // so it is valid for projecting to its fields.
+                let value_ptr = unsafe { addr_of!((*this_ptr).1) };
+                // SAFETY:
+                // - `value_ptr` is a pointer into `this`, so it is non-null,
+                //   properly aligned, and valid for reads.
+                // - `value_ptr` is borrowed from `this`, so it cannot alias any
+                //   mutable references for `'_`.
+                // - `this` is the `Some` variant, so the value pointed to by
+                //   `value_ptr` is initialized and immovable.
+                Some(unsafe { Ref::new_unchecked(value_ptr) })
+            }
+        }
+    }
+
+    /// Initializes the given slot with the `None` variant.
+    pub fn emplace_none<R: Region>(out: In<Slot<'_, Self>, R>) {
+        let raw_out = raw_rel_option(out.ptr().as_ptr());
+        let out_discriminant = raw_rel_option_discriminant(raw_out);
+        // SAFETY: `raw_rel_option_discriminant` guarantees that the pointer it
+        // returns is properly aligned and valid for writes.
+        unsafe {
+            out_discriminant.write(RawRelOptionDiscriminant::None);
+        }
+    }
+
+    /// Sets the discriminant of the given slot to the `Some` variant and
+    /// returns a slot for its value.
+    ///
+    /// The `RelOption` is initialized once the returned slot is initialized.
+    pub fn emplace_some_slot<R: Region>(
+        out: In<Slot<'_, Self>, R>,
+    ) -> In<Slot<'_, T>, R> {
+        let raw_out = raw_rel_option(out.ptr().as_ptr());
+        let out_discriminant = raw_rel_option_discriminant(raw_out);
+        // SAFETY: `raw_rel_option_discriminant` guarantees that the pointer it
+        // returns is properly aligned and valid for writes.
+        unsafe {
+            out_discriminant.write(RawRelOptionDiscriminant::Some);
+        }
+        match raw_rel_option_variant(raw_out) {
+            RawRelOptionVariants::Some(out_ptr) => {
+                // SAFETY: `out_ptr` points to the `Some` variant of `out`, so
+                // it is valid for projecting to its fields.
+                let value_ptr = unsafe { addr_of_mut!((*out_ptr).1) };
+                // SAFETY:
+                // - `value_ptr` is a pointer into `out`, so it is non-null,
+                //   properly aligned, and valid for reads and writes.
+                // - `value_ptr` is a disjoint borrow of `out`, which is
+                //   guaranteed not to alias any other accessible references,
+                //   so the returned `Slot` will not either.
+                let slot = unsafe { Slot::new_unchecked(value_ptr) };
+                // SAFETY: `value_ptr` is a pointer into `out`, which is
+                // contained in `R`, so `value_ptr` must be contained in `R` as
+                // well.
+                unsafe { In::new_unchecked(slot) }
+            }
+            // SAFETY: We wrote the `Some` discriminant to `out_discriminant` so
+            // it must be the `Some` variant.
+            _ => unsafe { unreachable_unchecked() },
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: crate::ser::SerializeRaw> crate::ser::SerializeRaw for RelOption<T> {
+    fn serialize_raw<S: ::serde::Serializer>(
+        this: Ref<'_, Self>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match RelOption::as_ref(this) {
+            None => serializer.serialize_none(),
+            Some(value) => {
+                serializer.serialize_some(&crate::ser::SerializeRef(value))
+            }
+        }
+    }
+}
+
 // SAFETY:
 // - `emplaced_meta` returns `()`, the only valid metadata for `Sized` types.
 // - `emplace_unsized_unchecked` initializes its `out` parameter by always
@@ -35,42 +148,10 @@ where
         self,
         out: In<Slot<'_, RelOption<T>>, R>,
     ) {
-        let raw_out = raw_rel_option(out.ptr().as_ptr());
-        let out_discriminant = raw_rel_option_discriminant(raw_out);
-
         match self {
-            // SAFETY: `raw_rel_option_discriminant` guarantees that the pointer
-            // it returns is properly aligned and valid for writes.
-            None => unsafe {
-                out_discriminant.write(RawRelOptionDiscriminant::None);
-            },
+            None => RelOption::emplace_none(out),
             Some(emplacer) => {
-                // SAFETY: `raw_rel_option_discriminant` guarantees that the
-                // pointer it returns is properly aligned and valid for writes.
-                unsafe {
-                    out_discriminant.write(RawRelOptionDiscriminant::Some);
-                }
-                match raw_rel_option_variant(raw_out) {
-                    RawRelOptionVariants::Some(out_ptr) => {
-                        let value_ptr = addr_of_mut!((*out_ptr).1);
-                        // SAFETY:
-                        // - `value_ptr` is a pointer into `out`, so it is
-                        //   non-null, properly aligned, and valid for reads and
-                        //   writes.
-                        // - `value_ptr` is a disjoint borrow of `out`, which is
-                        //   guaranteed not to alias any other accessible
-                        //   references, so the returned `Slot` will not either.
-                        let slot = unsafe { Slot::new_unchecked(value_ptr) };
-                        // SAFETY: `value_ptr` is a pointer into `out`, which is
-                        // contained in `R`, so `value_ptr` must be contained in
-                        // `R` as well.
-                        let slot = unsafe { In::new_unchecked(slot) };
-                        emplacer.emplace(slot);
-                    }
-                    // SAFETY: We wrote the `Some` discriminant to
-                    // `out_discriminant` so it must be the `Some` variant.
-                    _ => unsafe { unreachable_unchecked() },
-                }
+                emplacer.emplace(RelOption::emplace_some_slot(out));
             }
         }
     }
@@ -0,0 +1,296 @@
+//! Zeroing the padding bytes of portable values.
+//!
+//! Emplacing a value only writes its fields, so the padding bytes between and
+//! after them keep whatever was in memory before. Types which implement
+//! [`ZeroPadding`] can have their padding zeroed after they are emplaced, which
+//! makes the bytes of values with the same contents identical.
+//!
+//! `ZeroPadding` can be implemented for a `Portable` type by annotating it with
+//! `#[zero_padding]` when deriving `Portable`:
+//!
+//! ```ignore
+//! #[derive(DropRaw, Portable)]
+//! #[repr(C)]
+//! #[zero_padding]
+//! struct Point {
+//!     tag: u8,
+//!     x: I32,
+//! }
+//! ```
+//!
+//! The [`ZeroPadded`] emplacer zeroes the padding of a value right after
+//! emplacing it.
+
+use ::core::{
+    cell::Cell,
+    marker::{PhantomData, PhantomPinned},
+    mem::MaybeUninit,
+    ptr::{slice_from_raw_parts_mut, write_bytes},
+};
+use ::mischief::{In, Region, Slot};
+use ::ptr_meta::Pointee;
+use ::situ::{DropRaw, Mut};
+
+use crate::{
+    schema::PointsTo,
+    Emplace,
+    EmplaceError,
+    TryEmplace,
+    F32,
+    F64,
+    I128,
+    I16,
+    I32,
+    I64,
+    U128,
+    U16,
+    U32,
+    U64,
+};
+
+/// A type whose padding bytes can be zeroed.
+///
+/// # Safety
+///
+/// `zero_padding` must write zeroes to all of the padding bytes of the value,
+/// including the padding bytes of its fields and of any values that it owns.
+/// It must not modify any bytes which are part of a value. Memory owned by the
+/// value which does not contain any values, like the spare capacity of a
+/// vector, may also be zeroed.
+pub unsafe trait ZeroPadding {
+    /// Writes zeroes to all of the padding bytes of the given value.
+    fn zero_padding(this: Mut<'_, Self>);
+}
+
+/// Writes zeroes to the bytes from `start` to `end` which are not covered by
+/// any of the given fields.
+///
+/// `fields` contains the offset and size of each field, in increasing order of
+/// offset.
+///
+/// # Safety
+///
+/// `ptr` must be valid for writes of `end` bytes, and the bytes which are not
+/// covered by any of the fields must be padding bytes.
+#[doc(hidden)]
+pub unsafe fn zero_gaps(
+    ptr: *mut u8,
+    start: usize,
+    end: usize,
+    fields: &[(usize, usize)],
+) {
+    let mut cursor = start;
+    for &(offset, size) in fields {
+        if offset > cursor {
+            // SAFETY: The caller has guaranteed that `ptr` is valid for writes
+            // of `end` bytes, and that the bytes between fields are padding.
+            unsafe {
+                write_bytes(ptr.add(cursor), 0, offset - cursor);
+            }
+        }
+        cursor = cursor.max(offset + size);
+    }
+    if end > cursor {
+        // SAFETY: The caller has guaranteed that `ptr` is valid for writes of
+        // `end` bytes, and that the bytes after the last field are padding.
+        unsafe {
+            write_bytes(ptr.add(cursor), 0, end - cursor);
+        }
+    }
+}
+
+macro_rules! impl_no_padding {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            // SAFETY: This type has no padding bytes.
+            unsafe impl ZeroPadding for $ty {
+                #[inline]
+                fn zero_padding(_: Mut<'_, Self>) {}
+            }
+        )*
+    };
+}
+
+impl_no_padding!(
+    (),
+    bool,
+    i8,
+    u8,
+    I16,
+    I32,
+    I64,
+    I128,
+    U16,
+    U32,
+    U64,
+    U128,
+    F32,
+    F64,
+    crate::Char,
+    PhantomPinned,
+    str,
+);
+
+// SAFETY: `PhantomData` has no padding bytes.
+unsafe impl<T: ?Sized> ZeroPadding for PhantomData<T> {
+    #[inline]
+    fn zero_padding(_: Mut<'_, Self>) {}
+}
+
+// SAFETY: `PointsTo` has no padding bytes.
+unsafe impl<T: ?Sized> ZeroPadding for PointsTo<T> {
+    #[inline]
+    fn zero_padding(_: Mut<'_, Self>) {}
+}
+
+// SAFETY: The contents of a `MaybeUninit` may not be initialized, so it is not
+// known whether they have padding bytes. Only the padding of initialized values
+// is zeroed.
+unsafe impl<T> ZeroPadding for MaybeUninit<T> {
+    #[inline]
+    fn zero_padding(_: Mut<'_, Self>) {}
+}
+
+// SAFETY: `Cell<T>` is `repr(transparent)`, so it has the same padding bytes
+// as `T`.
+unsafe impl<T: ZeroPadding> ZeroPadding for Cell<T> {
+    #[inline]
+    fn zero_padding(this: Mut<'_, Self>) {
+        // SAFETY: `Cell<T>` is `repr(transparent)`, so `this` is also a valid
+        // `Mut` to the value it contains.
+        let inner = unsafe { this.map_unchecked(|ptr| ptr.cast::<T>()) };
+        T::zero_padding(inner);
+    }
+}
+
+// SAFETY: Arrays have no padding bytes between their elements, so the padding
+// bytes of an array are the padding bytes of its elements.
+unsafe impl<T: ZeroPadding, const N: usize> ZeroPadding for [T; N] {
+    #[inline]
+    fn zero_padding(this: Mut<'_, Self>) {
+        let ptr = slice_from_raw_parts_mut(this.as_ptr().cast::<T>(), N);
+        // SAFETY: `[T; N]` has the same layout as `[T]` with `N` elements, so
+        // `ptr` points to the same initialized elements as `this`.
+        let slice = unsafe { Mut::new_unchecked(ptr) };
+        <[T]>::zero_padding(slice);
+    }
+}
+
+// SAFETY: Slices have no padding bytes between their elements, so the padding
+// bytes of a slice are the padding bytes of its elements.
+unsafe impl<T: ZeroPadding> ZeroPadding for [T] {
+    #[inline]
+    fn zero_padding(this: Mut<'_, Self>) {
+        let ptr = this.as_ptr().cast::<T>();
+        for i in 0..this.as_ptr().len() {
+            // SAFETY: `i` is less than the length of the slice, so `ptr.add(i)`
+            // points to an initialized element. `this` is not accessed while
+            // the returned `Mut` is alive.
+            let element = unsafe { Mut::new_unchecked(ptr.add(i)) };
+            T::zero_padding(element);
+        }
+    }
+}
+
+/// An emplacer which zeroes the padding of the value it emplaces.
+pub struct ZeroPadded<E>(pub E);
+
+// SAFETY:
+// - `emplaced_meta` returns the metadata of the inner emplacer, which is valid
+//   for emplacing it.
+// - `emplace_unsized_unchecked` initializes its `out` parameter by emplacing
+//   the inner emplacer into it, and only modifies its padding bytes after.
+unsafe impl<T, R, E> Emplace<T, R> for ZeroPadded<E>
+where
+    T: DropRaw + Pointee + ZeroPadding + ?Sized,
+    R: Region,
+    E: Emplace<T, R>,
+{
+    #[inline]
+    fn emplaced_meta(&self) -> <T as Pointee>::Metadata {
+        self.0.emplaced_meta()
+    }
+
+    #[inline]
+    unsafe fn emplace_unsized_unchecked(self, out: In<Slot<'_, T>, R>) {
+        let ptr = out.ptr().as_ptr();
+        // SAFETY: The caller has guaranteed that `out` has the metadata
+        // returned by `emplaced_meta`, which is that of the inner emplacer.
+        unsafe {
+            self.0.emplace_unsized_unchecked(out);
+        }
+        // SAFETY: `out` was just initialized and is not accessed again while
+        // the returned `Mut` is alive.
+        T::zero_padding(unsafe { Mut::new_unchecked(ptr) });
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` only returns `Ok` after initializing
+// its `out` parameter with the inner emplacer, and only modifies its padding
+// bytes after.
+unsafe impl<T, R, E> TryEmplace<T, R> for ZeroPadded<E>
+where
+    T: DropRaw + Pointee + ZeroPadding + ?Sized,
+    R: Region,
+    E: TryEmplace<T, R>,
+{
+    #[inline]
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, T>, R>,
+    ) -> Result<(), EmplaceError> {
+        let ptr = out.ptr().as_ptr();
+        // SAFETY: The caller has guaranteed that `out` has the metadata
+        // returned by `emplaced_meta`, which is that of the inner emplacer.
+        unsafe {
+            self.0.try_emplace_unsized_unchecked(out)?;
+        }
+        // SAFETY: `out` was just initialized and is not accessed again while
+        // the returned `Mut` is alive.
+        T::zero_padding(unsafe { Mut::new_unchecked(ptr) });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::core::mem::{size_of, MaybeUninit};
+    use ::mischief::{In, Region, Slot};
+
+    use super::ZeroPadded;
+    use crate::{option::RelOption, rel_tuple::RelTuple2, EmplaceExt, I32};
+
+    struct TestRegion;
+
+    // SAFETY: `TestRegion` is only used to emplace into local slots.
+    unsafe impl Region for TestRegion {}
+
+    type Padded = RelOption<RelTuple2<u8, I32>>;
+
+    fn emplace_into_garbage(value: Option<(u8, i32)>) -> [u8; 12] {
+        assert_eq!(size_of::<Padded>(), 12);
+
+        let mut out = MaybeUninit::<Padded>::uninit();
+        // SAFETY: `out` is valid for writes of `size_of::<Padded>()` bytes.
+        unsafe {
+            out.as_mut_ptr().cast::<u8>().write_bytes(0xff, 12);
+        }
+        let slot = Slot::new(&mut out);
+        // SAFETY: All local slots are considered to be in `TestRegion`.
+        let slot = unsafe { In::<_, TestRegion>::new_unchecked(slot) };
+        ZeroPadded(value).emplace_mut(slot);
+
+        // SAFETY: `out` was initialized, and all of its padding bytes were
+        // zeroed.
+        unsafe { out.as_ptr().cast::<[u8; 12]>().read() }
+    }
+
+    #[test]
+    fn zero_padded() {
+        let some = emplace_into_garbage(Some((1, -1)));
+        assert_eq!(some, [1, 0, 0, 0, 1, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]);
+
+        let none = emplace_into_garbage(None);
+        assert_eq!(none, [0; 12]);
+    }
+}
@@ -1,7 +1,7 @@
 use ::core::{
     cell::Cell,
     marker::{PhantomData, PhantomPinned},
-    mem::MaybeUninit,
+    mem::{align_of, size_of, MaybeUninit},
 };
 use ::mischief::{
     GhostMut,
@@ -14,13 +14,38 @@ use ::mischief::{
 };
 pub use ::rel_core_derive::Portable;
 
+use crate::schema::{
+    schema_of,
+    Field,
+    PointsTo,
+    Primitive,
+    Repr,
+    Schema,
+    SchemaKind,
+};
+
 /// A type that has the same representation on all targets.
 ///
 /// # Safety
 ///
-/// `Portable` types must have the same layout and bytewise representation on
-/// all targets.
-pub unsafe trait Portable {}
+/// - `Portable` types must have the same layout and bytewise representation on
+///   all targets.
+/// - `SCHEMA` must accurately describe the layout of the type.
+pub unsafe trait Portable {
+    /// A description of the layout of the type.
+    const SCHEMA: &'static Schema;
+}
+
+macro_rules! sized_schema {
+    ($name:expr, $kind:expr) => {
+        &Schema {
+            name: $name,
+            size: Some(size_of::<Self>()),
+            align: align_of::<Self>(),
+            kind: $kind,
+        }
+    };
+}
 
 // Sources:
 // https://doc.rust-lang.org/reference/types/boolean.html
@@ -29,66 +54,148 @@ pub unsafe trait Portable {}
 
 // SAFETY: `bool` has a size and alignment of 1 each. `false` has the bit
 // pattern `0x00` and `true` has the bit pattern `0x01`.
-unsafe impl Portable for bool {}
+unsafe impl Portable for bool {
+    const SCHEMA: &'static Schema =
+        sized_schema!("bool", SchemaKind::Primitive(Primitive::Bool));
+}
 
 // SAFETY: `u8` has a size and alignment of 1 each. Values are represented as
 // unsigned integers.
-unsafe impl Portable for u8 {}
+unsafe impl Portable for u8 {
+    const SCHEMA: &'static Schema =
+        sized_schema!("u8", SchemaKind::Primitive(Primitive::U8));
+}
 
 // SAFETY: `i8` has a size and alignment of 1 each. Values are represented as
 // signed two's complement integers.
-unsafe impl Portable for i8 {}
+unsafe impl Portable for i8 {
+    const SCHEMA: &'static Schema =
+        sized_schema!("i8", SchemaKind::Primitive(Primitive::I8));
+}
 
 // SAFETY: `()` has a size of 0 and an alignment of 1. It has no bit patterns.
-unsafe impl Portable for () {}
+unsafe impl Portable for () {
+    const SCHEMA: &'static Schema =
+        sized_schema!("()", SchemaKind::Primitive(Primitive::Unit));
+}
 
 // SAFETY: `[T; N]` has a size of `size_of::<T>() * N`, an alignment of
 // `align_of::<T>()`, and contains its `Portable` elements in order.
-unsafe impl<T: Portable, const N: usize> Portable for [T; N] {}
+unsafe impl<T: Portable, const N: usize> Portable for [T; N] {
+    const SCHEMA: &'static Schema = sized_schema!(
+        "array",
+        SchemaKind::Array {
+            element: T::SCHEMA,
+            len: N,
+        }
+    );
+}
 
 // SAFETY: `[T]` has a size of `size_of::<T>() * len`, an alignment of
 // `align_of::<T>()`, and contains its `Portable` elements in order.
-unsafe impl<T: Portable> Portable for [T] {}
+unsafe impl<T: Portable> Portable for [T] {
+    const SCHEMA: &'static Schema = &Schema {
+        name: "slice",
+        size: None,
+        align: T::SCHEMA.align,
+        kind: SchemaKind::Slice { element: T::SCHEMA },
+    };
+}
 
 // SAFETY: `str` is `Portable` because `[u8]` is `Portable`.
-unsafe impl Portable for str {}
+unsafe impl Portable for str {
+    const SCHEMA: &'static Schema = &Schema {
+        name: "str",
+        size: None,
+        align: 1,
+        kind: SchemaKind::Str,
+    };
+}
 
 // SAFETY: `PhantomData` has a size of 0 and an alignment of 1. It has no bit
 // patterns.
-unsafe impl<T: ?Sized> Portable for PhantomData<T> {}
+unsafe impl<T: ?Sized> Portable for PhantomData<T> {
+    const SCHEMA: &'static Schema =
+        sized_schema!("PhantomData", SchemaKind::Marker);
+}
+
+// SAFETY: `PointsTo` has a size of 0 and an alignment of 1. It has no bit
+// patterns.
+unsafe impl<T: Portable + ?Sized> Portable for PointsTo<T> {
+    const SCHEMA: &'static Schema =
+        sized_schema!("PointsTo", SchemaKind::Pointee(schema_of::<T>));
+}
 
 // SAFETY: `PhantomPinned` has a size of 0 and an alignment of 1. It has no bit
 // patterns.
-unsafe impl Portable for PhantomPinned {}
+unsafe impl Portable for PhantomPinned {
+    const SCHEMA: &'static Schema =
+        sized_schema!("PhantomPinned", SchemaKind::Marker);
+}
 
 // SAFETY: `MaybeUninit<T>` has the same size, alignment, and bit patterns as
 // `T`.
-unsafe impl<T: Portable> Portable for MaybeUninit<T> {}
+unsafe impl<T: Portable> Portable for MaybeUninit<T> {
+    const SCHEMA: &'static Schema =
+        sized_schema!("MaybeUninit", SchemaKind::MaybeUninit(T::SCHEMA));
+}
 
 // SAFETY: `Cell<T>` is `Portable` if `T` is `Portable` because it is
 // `repr(transparent)`.
-unsafe impl<T: Portable + ?Sized> Portable for Cell<T> {}
+unsafe impl<T: Portable + ?Sized> Portable for Cell<T> {
+    const SCHEMA: &'static Schema = &Schema {
+        name: "Cell",
+        size: T::SCHEMA.size,
+        align: T::SCHEMA.align,
+        kind: SchemaKind::Struct {
+            repr: Repr::Transparent,
+            fields: &[Field {
+                name: "value",
+                offset: 0,
+                schema: T::SCHEMA,
+            }],
+        },
+    };
+}
 
 // SAFETY: `GhostMut` has a size of 0 and an alignment of 1. It has no bit
 // patterns.
-unsafe impl<T> Portable for GhostMut<'_, T> {}
+unsafe impl<T> Portable for GhostMut<'_, T> {
+    const SCHEMA: &'static Schema =
+        sized_schema!("GhostMut", SchemaKind::Marker);
+}
 
 // SAFETY: `GhostRef` has a size of 0 and an alignment of 1. It has no bit
 // patterns.
-unsafe impl<T> Portable for GhostRef<'_, T> {}
+unsafe impl<T> Portable for GhostRef<'_, T> {
+    const SCHEMA: &'static Schema =
+        sized_schema!("GhostRef", SchemaKind::Marker);
+}
 
 // SAFETY: `StaticVal` has a size of 0 and an alignment of 1. It has no bit
 // patterns.
-unsafe impl<S: Static> Portable for StaticVal<'_, S> {}
+unsafe impl<S: Static> Portable for StaticVal<'_, S> {
+    const SCHEMA: &'static Schema =
+        sized_schema!("StaticVal", SchemaKind::Marker);
+}
 
 // SAFETY: `StaticMut` has a size of 0 and an alignment of 1. It has no bit
 // patterns.
-unsafe impl<S: Static> Portable for StaticMut<'_, S> {}
+unsafe impl<S: Static> Portable for StaticMut<'_, S> {
+    const SCHEMA: &'static Schema =
+        sized_schema!("StaticMut", SchemaKind::Marker);
+}
 
 // SAFETY: `StaticRef` has a size of 0 and an alignment of 1. It has no bit
 // patterns.
-unsafe impl<S: Static> Portable for StaticRef<'_, S> {}
+unsafe impl<S: Static> Portable for StaticRef<'_, S> {
+    const SCHEMA: &'static Schema =
+        sized_schema!("StaticRef", SchemaKind::Marker);
+}
 
 // SAFETY: `StaticToken` has a size of 0 and an alignment of 1. It has no bit
 // patterns.
-unsafe impl Portable for StaticToken<'_> {}
+unsafe impl Portable for StaticToken<'_> {
+    const SCHEMA: &'static Schema =
+        sized_schema!("StaticToken", SchemaKind::Marker);
+}
@@ -2,12 +2,21 @@ use ::core::{
     cmp::Ordering,
     fmt,
     hash::{Hash, Hasher},
+    mem::{align_of, size_of},
 };
 use ::mischief::{In, Region, Slot};
 use ::ptr_meta::Pointee;
-use ::situ::{DropRaw, Mut, Val};
-
-use crate::{Emplace, Move, Portable};
+use ::situ::{DropRaw, Mut, Ref, Val};
+
+use crate::{
+    schema::{Primitive, Schema, SchemaKind},
+    CloneRaw,
+    Emplace,
+    EmplaceError,
+    Move,
+    Portable,
+    TryEmplace,
+};
 
 /// Alias for `i8`.
 pub type I8 = i8;
@@ -82,6 +91,20 @@ macro_rules! impl_primitive {
             unsafe fn drop_raw(_: Mut<'_, Self>) {}
         }
 
+        #[cfg(feature = "serde")]
+        impl crate::ser::SerializeRaw for $portable
+        where
+            $native: ::serde::Serialize,
+        {
+            #[inline]
+            fn serialize_raw<S: ::serde::Serializer>(
+                this: Ref<'_, Self>,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                ::serde::Serialize::serialize(&this.to_ne(), serializer)
+            }
+        }
+
         // SAFETY:
         // - All primitives are `Sized` and always have metadata `()`, so
         //  `emplaced_meta` always returns valid metadata for them.
@@ -97,6 +120,18 @@ macro_rules! impl_primitive {
             }
         }
 
+        // SAFETY: `try_emplace_unsized_unchecked` always initializes `out` by
+        // writing to it.
+        unsafe impl<R: Region> TryEmplace<$portable, R> for $native {
+            unsafe fn try_emplace_unsized_unchecked(
+                self,
+                out: In<Slot<'_, $portable>, R>,
+            ) -> Result<(), EmplaceError> {
+                In::into_inner(out).write(<$portable>::from_ne(self));
+                Ok(())
+            }
+        }
+
         // SAFETY: `move_unsized_unchecked` initializes its `out` parameter by
         // writing to it.
         unsafe impl<R: Region> Move<R> for $portable {
@@ -107,6 +142,18 @@ macro_rules! impl_primitive {
                 In::into_inner(out).write(Val::read(In::into_inner(this)));
             }
         }
+
+        // SAFETY: `clone_raw_unchecked` initializes its `out` parameter by
+        // writing to it.
+        unsafe impl<R: Region> CloneRaw<R> for $portable {
+            #[inline]
+            unsafe fn clone_raw_unchecked(
+                this: Ref<'_, Self>,
+                out: In<Slot<'_, Self>, R>,
+            ) {
+                In::into_inner(out).write(*this);
+            }
+        }
     };
     ($portable:ty, $native:ty) => {
         impl_primitive!(@base $portable, $native);
@@ -150,7 +197,14 @@ macro_rules! impl_multibyte_integer {
         // SAFETY: Multibyte integers have a well-defined size, an alignment
         // equal to their size, and their bit patterns are adjusted for
         // endianness.
-        unsafe impl Portable for $portable {}
+        unsafe impl Portable for $portable {
+            const SCHEMA: &'static Schema = &Schema {
+                name: stringify!($portable),
+                size: Some(size_of::<Self>()),
+                align: align_of::<Self>(),
+                kind: SchemaKind::Primitive(Primitive::$portable),
+            };
+        }
 
         impl $portable {
             #[doc = "Returns the `"]
@@ -214,7 +268,17 @@ pub struct F32 {
 
 // SAFETY: `U32` is `Portable` and `F32` is `repr(transparent)`, so `F32` has
 // the same layout and bytewise representation guarantees as `U32`.
-unsafe impl Portable for F32 where U32: Portable {}
+unsafe impl Portable for F32
+where
+    U32: Portable,
+{
+    const SCHEMA: &'static Schema = &Schema {
+        name: "F32",
+        size: Some(size_of::<Self>()),
+        align: align_of::<Self>(),
+        kind: SchemaKind::Primitive(Primitive::F32),
+    };
+}
 
 impl F32 {
     /// Returns the `F32` corresponding to the given `f32`.
@@ -243,7 +307,17 @@ pub struct F64 {
 
 // SAFETY: `U64` is `Portable` and `F64` is `repr(transparent)`, so `F64` has
 // the same layout and bytewise representation guarantees as `U64`.
-unsafe impl Portable for F64 where U64: Portable {}
+unsafe impl Portable for F64
+where
+    U64: Portable,
+{
+    const SCHEMA: &'static Schema = &Schema {
+        name: "F64",
+        size: Some(size_of::<Self>()),
+        align: align_of::<Self>(),
+        kind: SchemaKind::Primitive(Primitive::F64),
+    };
+}
 
 impl F64 {
     /// Returns the `F64` corresponding to the given `f64`.
@@ -272,7 +346,17 @@ pub struct Char {
 
 // SAFETY: `U32` is `Portable` and `Char` is `repr(transparent)` so `Char` has
 // the same layout and bytewise representation guarantees as `U32`.
-unsafe impl Portable for Char where U32: Portable {}
+unsafe impl Portable for Char
+where
+    U32: Portable,
+{
+    const SCHEMA: &'static Schema = &Schema {
+        name: "Char",
+        size: Some(size_of::<Self>()),
+        align: align_of::<Self>(),
+        kind: SchemaKind::Primitive(Primitive::Char),
+    };
+}
 
 impl Char {
     /// Returns the `Char` corresponding to the given `char`.
@@ -0,0 +1,168 @@
+//! Mutable relative references and related types.
+
+use ::core::{fmt, marker::PhantomData};
+use ::mischief::{In, Region, Slot};
+use ::munge::munge;
+use ::ptr_meta::Pointee;
+use ::situ::{
+    fmt::{DebugRaw, DisplayRaw},
+    DropRaw,
+    Mut,
+    Ref,
+};
+
+use crate::{
+    Basis,
+    BasisPointee,
+    DefaultBasis,
+    Emplace,
+    EmplaceExt,
+    Move,
+    Portable,
+    RelPtr,
+};
+
+/// A mutable reference stored using a relative pointer.
+#[repr(C)]
+#[derive(DropRaw, Move, Portable)]
+#[rel_core = "crate"]
+#[zero_padding]
+pub struct RelMut<'a, T, R, B = DefaultBasis>
+where
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+{
+    inner: RelPtr<T, R, B>,
+    _phantom: PhantomData<&'a mut T>,
+}
+
+impl<'a, T: BasisPointee<B> + ?Sized, R: Region, B: Basis> RelMut<'a, T, R, B> {
+    /// Returns a `Ref` to the underlying value.
+    pub fn deref(this: Ref<'_, Self>) -> Ref<'_, T> {
+        munge!(let RelMut { inner, .. } = this);
+        // SAFETY: The `RelPtr` in a `RelMut` is always non-null, properly
+        // aligned, and valid for reads. It is guaranteed to point to an
+        // initialized value, and because `this` is borrowed immutably the
+        // pointee cannot be mutably aliased for the returned lifetime.
+        unsafe { RelPtr::as_ref(inner) }
+    }
+
+    /// Returns a `Mut` to the underlying value.
+    pub fn deref_mut(this: Mut<'_, Self>) -> Mut<'_, T> {
+        munge!(let RelMut { inner, .. } = this);
+        // SAFETY:
+        // - The `RelPtr` in a `RelMut` is always non-null, properly aligned,
+        //   and valid for reads and writes.
+        // - A `RelMut` is the unique reference to its pointee, and `this` is
+        //   borrowed mutably for the returned lifetime. So the pointee does not
+        //   alias any other accessible references for that lifetime.
+        // - The pointee was emplaced from a `Mut`, so it is initialized and
+        //   immovable.
+        unsafe { RelPtr::as_mut(inner) }
+    }
+}
+
+// SAFETY:
+// - `RelMut` is `Sized` and always has metadata `()`, so `emplaced_meta` always
+//   returns valid metadata for it.
+// - `emplace_unsized_unchecked` initializes its `out` parameter.
+unsafe impl<'a, T, R, B> Emplace<RelMut<'a, T, R, B>, R> for In<Mut<'a, T>, R>
+where
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+{
+    #[inline]
+    fn emplaced_meta(&self) -> <RelMut<'a, T, R, B> as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelMut<'a, T, R, B>>, R>,
+    ) {
+        munge!(let RelMut { inner: out_inner, .. } = out);
+
+        self.as_raw().emplace(out_inner);
+    }
+}
+
+impl<'a, T, R, B> DebugRaw for RelMut<'a, T, R, B>
+where
+    T: BasisPointee<B> + DebugRaw + ?Sized,
+    R: Region,
+    B: Basis,
+{
+    fn fmt_raw(
+        this: Ref<'_, Self>,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        DebugRaw::fmt_raw(RelMut::deref(this), f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a, T, R, B> crate::ser::SerializeRaw for RelMut<'a, T, R, B>
+where
+    T: BasisPointee<B> + crate::ser::SerializeRaw + ?Sized,
+    R: Region,
+    B: Basis,
+{
+    fn serialize_raw<S: ::serde::Serializer>(
+        this: Ref<'_, Self>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        crate::ser::SerializeRaw::serialize_raw(RelMut::deref(this), serializer)
+    }
+}
+
+impl<'a, T, R, B> DisplayRaw for RelMut<'a, T, R, B>
+where
+    T: BasisPointee<B> + DisplayRaw + ?Sized,
+    R: Region,
+    B: Basis,
+{
+    fn fmt_raw(
+        this: Ref<'_, Self>,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        DisplayRaw::fmt_raw(RelMut::deref(this), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::core::mem::MaybeUninit;
+    use ::mischief::{In, Region, Slot};
+    use ::situ::Mut;
+    use ::std::format;
+
+    use super::RelMut;
+    use crate::{EmplaceExt, I32};
+
+    struct TestRegion;
+
+    // SAFETY: `TestRegion` is only used to emplace into local slots.
+    unsafe impl Region for TestRegion {}
+
+    #[test]
+    fn emplace_deref_mutate() {
+        let mut value = I32::from_ne(10);
+        // SAFETY: `value` is initialized, is not accessed again until the
+        // `RelMut` is dropped, and is not moved while it is borrowed.
+        let target = unsafe { Mut::new_unchecked(&mut value) };
+        // SAFETY: All locals are considered to be in `TestRegion`.
+        let target = unsafe { In::<_, TestRegion>::new_unchecked(target) };
+
+        let mut out = MaybeUninit::<RelMut<'_, I32, TestRegion>>::uninit();
+        let slot = Slot::new(&mut out);
+        // SAFETY: All local slots are considered to be in `TestRegion`.
+        let slot = unsafe { In::<_, TestRegion>::new_unchecked(slot) };
+        let mut rel = In::into_inner(target.emplace_mut(slot));
+
+        assert_eq!(RelMut::deref(rel.as_ref()).to_ne(), 10);
+        *RelMut::deref_mut(rel.as_mut()) = I32::from_ne(-3);
+        assert_eq!(RelMut::deref(rel.as_ref()).to_ne(), -3);
+        assert_eq!(format!("{:?}", rel.as_ref()), "-3");
+        assert_eq!(value.to_ne(), -3);
+    }
+}
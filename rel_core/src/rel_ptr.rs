@@ -11,26 +11,55 @@ use ::situ::{DropRaw, Mut, Pinned, Ref, Val};
 
 use crate::{
     rel_mem,
+    schema::PointsTo,
     Basis,
     BasisPointee,
+    CloneRaw,
     Emplace,
+    EmplaceError,
     EmplaceExt,
     Move,
+    Offset,
     Portable,
+    TryEmplace,
 };
 
 /// A pointer that stores the difference between itself and its pointee.
+///
+/// The offset is stored as an `O`, which defaults to the `Isize` of the basis
+/// `B`. A smaller offset type makes the pointer more compact, but limits how
+/// far away from its pointee it can be. Emplacing a `RelPtr` whose pointee is
+/// too far away panics, while [`TryEmplace`] and [`RelPtr::try_set`] return
+/// [`EmplaceError::OffsetOverflow`] instead.
+///
+/// Because [`Move`] and [`CloneRaw`] are infallible, moving or cloning a
+/// `RelPtr` to a location that is too far away from its pointee also panics.
+/// Containers of `RelPtr`s with small offsets should be placed so that they
+/// stay within range of their pointees.
 #[repr(C)]
 #[derive(DropRaw, Portable)]
 #[rel_core = "crate"]
-pub struct RelPtr<T: BasisPointee<B> + ?Sized, R: Region, B: Basis> {
-    offset: B::Isize,
+#[zero_padding]
+pub struct RelPtr<
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+    O: Offset = <B as Basis>::Isize,
+> {
+    offset: O,
     metadata: MaybeUninit<<T as BasisPointee<B>>::BasisMetadata>,
     _phantom: PhantomData<(*mut T, R)>,
+    _pointee: PointsTo<T>,
     _pinned: PhantomPinned,
 }
 
-impl<T: BasisPointee<B> + ?Sized, R: Region, B: Basis> RelPtr<T, R, B> {
+impl<T, R, B, O> RelPtr<T, R, B, O>
+where
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+    O: Offset,
+{
     /// Returns the base pointer for the relative pointer.
     ///
     /// The base of the relative pointer is always its location in memory.
@@ -50,7 +79,7 @@ impl<T: BasisPointee<B> + ?Sized, R: Region, B: Basis> RelPtr<T, R, B> {
     /// Returns the offset of the relative pointer's target from its base.
     #[inline]
     pub fn offset(&self) -> isize {
-        B::to_native_isize(self.offset).unwrap()
+        self.offset.to_native_isize().unwrap()
     }
 
     /// Returns whether the offset of the relative pointer is `0`.
@@ -188,11 +217,38 @@ impl<T: BasisPointee<B> + ?Sized, R: Region, B: Basis> RelPtr<T, R, B> {
         rel_mem::replace(In::new(this), ptr);
     }
 
+    /// Attempts to set the pointee of this `RelPtr`.
+    ///
+    /// If the offset or metadata of `ptr` cannot be represented in `B`, this
+    /// returns an error and leaves the `RelPtr` unchanged.
+    pub fn try_set(
+        this: Mut<'_, Self>,
+        ptr: In<*mut T, R>,
+    ) -> Result<(), EmplaceError> {
+        // SAFETY:
+        // - `this.as_ptr()` is non-null, properly aligned, and valid for reads
+        //   and writes because it comes from a `Mut`.
+        // - `this` is mutably borrowed for the lifetime of the slot, so the
+        //   slot cannot alias any other accessible references.
+        let out = unsafe { Slot::new_unchecked(this.as_ptr()) };
+        // SAFETY:
+        // - `RelPtr` is `Pinned<R>`, so `this` is located in `R` along with
+        //   `ptr`. Both must therefore be located in the same contiguous memory
+        //   segment.
+        // - `RelPtr` has no drop glue, so overwriting it without dropping it
+        //   first is sound. `try_emplace_new` does not write to `out` unless it
+        //   succeeds.
+        unsafe { Self::try_emplace_new(In::into_inner(ptr), out) }
+    }
+
     /// # Safety
     ///
     /// The memory pointed to by `ptr` and `slot` must be located in the same
     /// contiguous memory segment.
-    unsafe fn emplace_new(ptr: *mut T, mut out: Slot<'_, Self>) {
+    unsafe fn try_emplace_new(
+        ptr: *mut T,
+        mut out: Slot<'_, Self>,
+    ) -> Result<(), EmplaceError> {
         let base = out.as_ptr().cast();
 
         // SAFETY:
@@ -202,9 +258,11 @@ impl<T: BasisPointee<B> + ?Sized, R: Region, B: Basis> RelPtr<T, R, B> {
         // - The size of a `u8` is 1, so the distance between the base and
         //   target is always a multiple of it.
         let offset = unsafe { ptr.cast::<u8>().offset_from(base) };
-        let offset = B::from_native_isize(offset).unwrap();
+        let offset =
+            O::from_native_isize(offset).ok_or(EmplaceError::OffsetOverflow)?;
 
-        let metadata = T::from_native_metadata(metadata(ptr)).unwrap();
+        let metadata = T::from_native_metadata(metadata(ptr))
+            .map_err(|_| EmplaceError::MetadataOverflow)?;
 
         munge!(
             let RelPtr {
@@ -215,6 +273,8 @@ impl<T: BasisPointee<B> + ?Sized, R: Region, B: Basis> RelPtr<T, R, B> {
         );
         out_offset.write(offset);
         out_metadata.write(MaybeUninit::new(metadata));
+
+        Ok(())
     }
 
     fn emplace_null(mut slot: Slot<'_, Self>) {
@@ -225,19 +285,20 @@ impl<T: BasisPointee<B> + ?Sized, R: Region, B: Basis> RelPtr<T, R, B> {
                 ..
             } = slot.as_mut();
         );
-        out_offset.write(B::from_native_isize(0).unwrap());
+        out_offset.write(O::from_native_isize(0).unwrap());
         out_metadata.write(MaybeUninit::zeroed());
     }
 }
 
-// SAFETY: Values of type `RelPtr<T, R, B>` can only be created in `R` and may
-// only be moved within `R`. Therefore, all values of the type must be located
-// in `R`.
-unsafe impl<T, R, B> Pinned<R> for RelPtr<T, R, B>
+// SAFETY: Values of type `RelPtr<T, R, B, O>` can only be created in `R` and
+// may only be moved within `R`. Therefore, all values of the type must be
+// located in `R`.
+unsafe impl<T, R, B, O> Pinned<R> for RelPtr<T, R, B, O>
 where
     T: BasisPointee<B> + Pointee + ?Sized,
     R: Region,
     B: Basis,
+    O: Offset,
 {
 }
 
@@ -245,25 +306,50 @@ where
 // - `RelPtr` is `Sized` and always has metadata `()`, so `emplaced_meta` always
 //   returns valid metadata for it.
 // - `emplace_unsized_unchecked` initializes its `out` parameter.
-unsafe impl<T, R, B> Emplace<RelPtr<T, R, B>, R> for In<*mut T, R>
+unsafe impl<T, R, B, O> Emplace<RelPtr<T, R, B, O>, R> for In<*mut T, R>
 where
     T: BasisPointee<B> + Pointee + ?Sized,
     R: Region,
     B: Basis,
+    O: Offset,
 {
-    fn emplaced_meta(&self) -> <RelPtr<T, R, B> as Pointee>::Metadata {}
+    fn emplaced_meta(&self) -> <RelPtr<T, R, B, O> as Pointee>::Metadata {}
 
+    /// # Panics
+    ///
+    /// Panics if the offset from `out` to the pointee cannot be represented in
+    /// `O`, or if the pointee metadata cannot be represented in `B`.
     unsafe fn emplace_unsized_unchecked(
         self,
-        out: In<Slot<'_, RelPtr<T, R, B>>, R>,
+        out: In<Slot<'_, RelPtr<T, R, B, O>>, R>,
     ) {
+        // SAFETY: The caller has guaranteed that `out` has the metadata
+        // returned by `emplaced_meta`.
+        let result = unsafe { self.try_emplace_unsized_unchecked(out) };
+        if let Err(e) = result {
+            panic!("failed to emplace `RelPtr`: {e}");
+        }
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter if
+// it returns `Ok`.
+unsafe impl<T, R, B, O> TryEmplace<RelPtr<T, R, B, O>, R> for In<*mut T, R>
+where
+    T: BasisPointee<B> + Pointee + ?Sized,
+    R: Region,
+    B: Basis,
+    O: Offset,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelPtr<T, R, B, O>>, R>,
+    ) -> Result<(), EmplaceError> {
         let ptr = In::into_inner(self);
 
-        // SAFETY: `emplace_new` returns the same slot, but initialized.
-        // Therefore it must be located in the same region.
-        unsafe {
-            RelPtr::emplace_new(ptr, In::into_inner(out));
-        }
+        // SAFETY: `ptr` and `out` are both located in `R`, so they must be
+        // located in the same contiguous memory segment.
+        unsafe { RelPtr::try_emplace_new(ptr, In::into_inner(out)) }
     }
 }
 
@@ -274,29 +360,53 @@ pub struct Null;
 // - `RelPtr` is `Sized` and always has metadata `()`, so `emplaced_meta` always
 //   returns valid metadata for it.
 // - `emplace_unsized_unchecked` initializes its `out` parameter.
-unsafe impl<T, R, B> Emplace<RelPtr<T, R, B>, R> for Null
+unsafe impl<T, R, B, O> Emplace<RelPtr<T, R, B, O>, R> for Null
 where
     T: BasisPointee<B> + ?Sized,
     R: Region,
     B: Basis,
+    O: Offset,
 {
-    fn emplaced_meta(&self) -> <RelPtr<T, R, B> as Pointee>::Metadata {}
+    fn emplaced_meta(&self) -> <RelPtr<T, R, B, O> as Pointee>::Metadata {}
 
     unsafe fn emplace_unsized_unchecked(
         self,
-        out: In<Slot<'_, RelPtr<T, R, B>>, R>,
+        out: In<Slot<'_, RelPtr<T, R, B, O>>, R>,
     ) {
         RelPtr::emplace_null(In::into_inner(out));
     }
 }
 
+// SAFETY: `try_emplace_unsized_unchecked` always initializes its `out`
+// parameter.
+unsafe impl<T, R, B, O> TryEmplace<RelPtr<T, R, B, O>, R> for Null
+where
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+    O: Offset,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelPtr<T, R, B, O>>, R>,
+    ) -> Result<(), EmplaceError> {
+        RelPtr::emplace_null(In::into_inner(out));
+        Ok(())
+    }
+}
+
 // SAFETY: `move_unsized_unchecked` initializes `out` by emplacing into it.
-unsafe impl<T, R, B> Move<R> for RelPtr<T, R, B>
+unsafe impl<T, R, B, O> Move<R> for RelPtr<T, R, B, O>
 where
     T: BasisPointee<B> + ?Sized,
     R: Region,
     B: Basis,
+    O: Offset,
 {
+    /// # Panics
+    ///
+    /// Panics if the offset from `out` to the pointee cannot be represented in
+    /// `O`.
     unsafe fn move_unsized_unchecked(
         this: In<Val<'_, Self>, R>,
         out: In<Slot<'_, Self>, R>,
@@ -313,3 +423,114 @@ where
         }
     }
 }
+
+// SAFETY: `clone_raw_unchecked` initializes `out` by emplacing into it.
+unsafe impl<T, R, B, O> CloneRaw<R> for RelPtr<T, R, B, O>
+where
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+    O: Offset,
+{
+    /// # Panics
+    ///
+    /// Panics if the offset from `out` to the pointee cannot be represented in
+    /// `O`.
+    unsafe fn clone_raw_unchecked(
+        this: Ref<'_, Self>,
+        out: In<Slot<'_, Self>, R>,
+    ) {
+        if let Some(ptr) = RelPtr::as_ptr(this) {
+            // SAFETY: `ptr` is the pointee of a `RelPtr` in `R`, so it must be
+            // located in `R`.
+            let ptr = unsafe { In::new_unchecked(ptr.cast_mut()) };
+            ptr.emplace(out);
+        } else {
+            Null.emplace(out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::core::mem::MaybeUninit;
+    use ::mischief::{In, Region, Slot};
+    use ::situ::Mut;
+
+    use super::RelPtr;
+    use crate::{Basis32, EmplaceError, EmplaceExt, TryEmplaceExt, I16};
+
+    struct TestRegion;
+
+    // SAFETY: `TestRegion` is only used to emplace into local slots.
+    unsafe impl Region for TestRegion {}
+
+    type ShortPtr = RelPtr<u8, TestRegion, Basis32, I16>;
+
+    /// A relative pointer followed by more bytes than a 16-bit offset can
+    /// reach.
+    #[repr(C)]
+    struct Buffer {
+        ptr: MaybeUninit<ShortPtr>,
+        bytes: [u8; 40_000],
+    }
+
+    impl Buffer {
+        fn new() -> Self {
+            Self {
+                ptr: MaybeUninit::uninit(),
+                bytes: [0; 40_000],
+            }
+        }
+
+        fn target(&mut self, i: usize) -> In<*mut u8, TestRegion> {
+            // SAFETY: All locals are considered to be in `TestRegion`.
+            unsafe { In::new_unchecked(&mut self.bytes[i]) }
+        }
+
+        fn slot(&mut self) -> In<Slot<'_, ShortPtr>, TestRegion> {
+            // SAFETY: All local slots are considered to be in `TestRegion`.
+            unsafe { In::new_unchecked(Slot::new(&mut self.ptr)) }
+        }
+    }
+
+    #[test]
+    fn small_offset() {
+        let mut buffer = Buffer::new();
+
+        let near = buffer.target(100);
+        let near_ptr = *near.ptr();
+        near.try_emplace(buffer.slot()).unwrap();
+        // SAFETY: `ptr` was just initialized.
+        let ptr = unsafe { buffer.ptr.assume_init_ref() };
+        let expected = ::core::mem::size_of::<ShortPtr>() + 100;
+        assert_eq!(ptr.offset(), isize::try_from(expected).unwrap());
+
+        let far = buffer.target(39_999);
+        assert_eq!(
+            far.try_emplace(buffer.slot()),
+            Err(EmplaceError::OffsetOverflow),
+        );
+
+        // A failed `try_set` leaves the pointer unchanged.
+        let far = buffer.target(39_999);
+        // SAFETY: `ptr` is initialized, is not otherwise accessed while the
+        // `Mut` exists, and is not moved.
+        let ptr = unsafe { Mut::new_unchecked(buffer.ptr.as_mut_ptr()) };
+        assert_eq!(
+            RelPtr::try_set(ptr, far),
+            Err(EmplaceError::OffsetOverflow),
+        );
+        // SAFETY: `ptr` is still initialized.
+        let ptr = unsafe { Mut::new_unchecked(buffer.ptr.as_mut_ptr()) };
+        assert_eq!(RelPtr::as_mut_ptr(ptr), Some(near_ptr));
+    }
+
+    #[test]
+    #[should_panic = "pointer offset overflowed its offset type"]
+    fn small_offset_emplace_panics() {
+        let mut buffer = Buffer::new();
+        let far = buffer.target(39_999);
+        far.emplace(buffer.slot());
+    }
+}
@@ -13,6 +13,7 @@ use ::situ::{
 use crate::{
     Basis,
     BasisPointee,
+    CloneRaw,
     DefaultBasis,
     Emplace,
     EmplaceExt,
@@ -23,8 +24,9 @@ use crate::{
 
 /// A reference stored using a relative pointer.
 #[repr(C)]
-#[derive(DropRaw, Move, Portable)]
+#[derive(CloneRaw, DropRaw, Move, Portable)]
 #[rel_core = "crate"]
+#[zero_padding]
 pub struct RelRef<'a, T, R, B = DefaultBasis>
 where
     T: BasisPointee<B> + ?Sized,
@@ -83,6 +85,21 @@ where
     }
 }
 
+#[cfg(feature = "serde")]
+impl<'a, T, R, B> crate::ser::SerializeRaw for RelRef<'a, T, R, B>
+where
+    T: BasisPointee<B> + crate::ser::SerializeRaw + ?Sized,
+    R: Region,
+    B: Basis,
+{
+    fn serialize_raw<S: ::serde::Serializer>(
+        this: Ref<'_, Self>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        crate::ser::SerializeRaw::serialize_raw(RelRef::deref(this), serializer)
+    }
+}
+
 impl<'a, T, R, B> DisplayRaw for RelRef<'a, T, R, B>
 where
     T: BasisPointee<B> + DisplayRaw + ?Sized,
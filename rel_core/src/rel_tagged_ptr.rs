@@ -0,0 +1,632 @@
+//! Relative pointers with tag bits packed into their offsets.
+
+use ::core::{
+    marker::{PhantomData, PhantomPinned},
+    mem::{align_of, MaybeUninit},
+};
+use ::mischief::{In, Region, Slot};
+use ::munge::munge;
+use ::ptr_meta::{metadata, Pointee};
+use ::situ::{DropRaw, Mut, Pinned, Ref, Val};
+
+use crate::{
+    rel_ptr::Null,
+    schema::PointsTo,
+    Basis,
+    BasisPointee,
+    CloneRaw,
+    Emplace,
+    EmplaceError,
+    Move,
+    Offset,
+    Portable,
+    TryEmplace,
+};
+
+/// A relative pointer that stores a `BITS`-bit tag in the low bits of its
+/// offset.
+///
+/// Those bits of the offset are always zero because both the tagged pointer and
+/// its pointee must be aligned to at least `2^BITS` bytes. This is checked when
+/// a tagged pointer is emplaced, moved, or cloned, and fails to compile if
+/// either alignment is too small. For example, a tagged pointer with a 32-bit
+/// offset is aligned to 4 bytes and so can store at most 2 tag bits.
+///
+/// Tagged pointers are emplaced from a pointer and a tag, like
+/// `(In<*mut T, R>, usize)`. Null tagged pointers are emplaced from
+/// `(Null, usize)`. Emplacing a tag that does not fit in `BITS` bits panics.
+#[repr(C)]
+#[derive(DropRaw, Portable)]
+#[rel_core = "crate"]
+#[zero_padding]
+pub struct RelTaggedPtr<
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+    const BITS: u32,
+> {
+    offset: B::Isize,
+    metadata: MaybeUninit<<T as BasisPointee<B>>::BasisMetadata>,
+    _phantom: PhantomData<(*mut T, R)>,
+    _pointee: PointsTo<T>,
+    _pinned: PhantomPinned,
+}
+
+impl<T, R, B, const BITS: u32> RelTaggedPtr<T, R, B, BITS>
+where
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+{
+    const TAG_MASK: usize = (1 << BITS) - 1;
+
+    /// Fails to compile unless the tagged pointer and its pointee are both
+    /// aligned to at least `2^BITS` bytes, which keeps the low `BITS` bits of
+    /// every offset free for the tag.
+    const ALIGNED: () = assert!(
+        align_of::<Self>() >= 1 << BITS && T::ALIGN >= 1 << BITS,
+        "tagged pointers and their pointees must be aligned to 2^BITS bytes",
+    );
+
+    /// Returns the base pointer for the tagged pointer.
+    ///
+    /// The base of the tagged pointer is always its location in memory.
+    #[inline]
+    pub fn base(this: Ref<'_, Self>) -> *const u8 {
+        this.as_ptr().cast()
+    }
+
+    /// Returns the mutable base pointer for the tagged pointer.
+    ///
+    /// The base of the tagged pointer is always its location in memory.
+    #[inline]
+    pub fn base_mut(this: Mut<'_, Self>) -> *mut u8 {
+        this.as_ptr().cast()
+    }
+
+    #[inline]
+    fn packed(&self) -> isize {
+        self.offset.to_native_isize().unwrap()
+    }
+
+    #[inline]
+    fn tag_mask() -> isize {
+        isize::try_from(Self::TAG_MASK).unwrap()
+    }
+
+    /// Returns the offset of the tagged pointer's target from its base.
+    #[inline]
+    pub fn offset(&self) -> isize {
+        self.packed() & !Self::tag_mask()
+    }
+
+    /// Returns whether the offset of the tagged pointer is `0`.
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        self.offset() == 0
+    }
+
+    /// Returns the tag of the tagged pointer.
+    #[inline]
+    pub fn tag(&self) -> usize {
+        usize::try_from(self.packed() & Self::tag_mask()).unwrap()
+    }
+
+    /// Sets the tag of the tagged pointer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tag` does not fit in `BITS` bits.
+    pub fn set_tag(this: Mut<'_, Self>, tag: usize) {
+        let packed = this.offset() | Self::packable_tag(tag);
+        munge!(let RelTaggedPtr { offset: mut out_offset, .. } = this);
+        // The offset already held `packed` with a different tag, so it must be
+        // representable.
+        *out_offset = B::Isize::from_native_isize(packed).unwrap();
+    }
+
+    /// Returns the metadata of the tagged pointer's pointee if it is not null.
+    #[inline]
+    pub fn metadata(&self) -> Option<T::Metadata> {
+        if self.is_null() {
+            None
+        } else {
+            // SAFETY: This tagged pointer is not null.
+            unsafe { Some(self.metadata_unchecked()) }
+        }
+    }
+
+    /// Returns the metadata of the tagged pointer's pointee.
+    ///
+    /// # Safety
+    ///
+    /// The tagged pointer must not be null.
+    #[inline]
+    pub unsafe fn metadata_unchecked(&self) -> T::Metadata {
+        // SAFETY: Non-null tagged pointers always have valid, initialized
+        // metadata.
+        let metadata = unsafe { self.metadata.assume_init() };
+        T::to_native_metadata(metadata).unwrap()
+    }
+
+    /// Returns the target of the tagged pointer if it is not null.
+    #[inline]
+    pub fn as_ptr(this: Ref<'_, Self>) -> Option<*const T> {
+        if this.is_null() {
+            None
+        } else {
+            // SAFETY: We checked that the tagged pointer is not null.
+            unsafe { Some(Self::as_ptr_unchecked(this)) }
+        }
+    }
+
+    /// Returns the target of the tagged pointer.
+    ///
+    /// # Safety
+    ///
+    /// The tagged pointer must not be null.
+    #[inline]
+    pub unsafe fn as_ptr_unchecked(this: Ref<'_, Self>) -> *const T {
+        // SAFETY:
+        // - Tagged pointers always point to in-bounds targets.
+        // - `self.offset()` always fits in an `isize`.
+        // - The offset of the tagged pointer never leaves the contiguous memory
+        //   segment in which it is located.
+        let data_address = unsafe { Self::base(this).offset(this.offset()) };
+        // SAFETY: The caller has guaranteed that this tagged pointer is not
+        // null.
+        let metadata = unsafe { this.metadata_unchecked() };
+
+        ::ptr_meta::from_raw_parts(data_address.cast(), metadata)
+    }
+
+    /// Returns a `Ref` to the target of the tagged pointer.
+    ///
+    /// # Safety
+    ///
+    /// - `this` must be non-null, properly aligned, and valid for reads.
+    /// - `this` must not alias any other mutable references for `'a`.
+    /// - The value pointed to by `this` must be initialized.
+    pub unsafe fn as_ref<'a>(this: Ref<'_, Self>) -> Ref<'a, T> {
+        // SAFETY: The caller has guaranteed that `this` is non-null, properly
+        // aligned, and valid for reads. They have also guaranteed that `this`
+        // does not alias any other mutable references for `'a` and that the
+        // value pointed to by `this` must be initialized.
+        unsafe { Ref::new_unchecked(Self::as_ptr_unchecked(this)) }
+    }
+
+    /// Returns a mutable pointer to the target of the tagged pointer if it is
+    /// not null.
+    #[inline]
+    pub fn as_mut_ptr(this: Mut<'_, Self>) -> Option<*mut T> {
+        if this.is_null() {
+            None
+        } else {
+            // SAFETY: We checked that the tagged pointer is not null.
+            unsafe { Some(Self::as_mut_ptr_unchecked(this)) }
+        }
+    }
+
+    /// Returns a mutable pointer to the target of the tagged pointer.
+    ///
+    /// # Safety
+    ///
+    /// `this` must not be null.
+    #[inline]
+    pub unsafe fn as_mut_ptr_unchecked(mut this: Mut<'_, Self>) -> *mut T {
+        let data_address =
+            // SAFETY:
+            // - Tagged pointers always point to in-bounds targets.
+            // - `self.offset()` always fits in an `isize`.
+            // - The offset of the tagged pointer never leaves the contiguous
+            //   memory segment in which it is located.
+            unsafe { Self::base_mut(this.as_mut()).offset(this.offset()) };
+        // SAFETY: The caller has guaranteed that this tagged pointer is not
+        // null.
+        let metadata = unsafe { this.metadata_unchecked() };
+
+        ::ptr_meta::from_raw_parts_mut(data_address.cast(), metadata)
+    }
+
+    /// Returns a `Mut` to the target of the tagged pointer.
+    ///
+    /// # Safety
+    ///
+    /// - `this` must be non-null, properly aligned, and valid for reads and
+    ///   writes.
+    /// - `this` must not alias any other accessible references for `'a`.
+    /// - The pointee of `this` must be initialized and immovable.
+    pub unsafe fn as_mut<'a>(this: Mut<'_, Self>) -> Mut<'a, T> {
+        // SAFETY:
+        // - The caller has guaranteed that `this` is non-null, properly
+        //   aligned, and valid for reads and writes.
+        // - The caller has guaranteed that the pointee of `this` does not alias
+        //   any other accessible references for `'a` and that the pointee of
+        //   `this` is initialized.
+        // - The caller has guaranteed that the pointee of `this` is initialized
+        //   and immovable.
+        unsafe { Mut::new_unchecked(Self::as_mut_ptr_unchecked(this)) }
+    }
+
+    /// Returns `tag` as an `isize` that can be packed into the offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tag` does not fit in `BITS` bits.
+    fn packable_tag(tag: usize) -> isize {
+        assert!(
+            tag & !Self::TAG_MASK == 0,
+            "tag does not fit in {BITS} bits"
+        );
+        isize::try_from(tag).unwrap()
+    }
+
+    /// # Safety
+    ///
+    /// The memory pointed to by `ptr` and `slot` must be located in the same
+    /// contiguous memory segment.
+    unsafe fn try_emplace_new(
+        ptr: *mut T,
+        tag: usize,
+        out: Slot<'_, Self>,
+    ) -> Result<(), EmplaceError> {
+        let tag = Self::packable_tag(tag);
+        let metadata = T::from_native_metadata(metadata(ptr))
+            .map_err(|_| EmplaceError::MetadataOverflow)?;
+
+        // SAFETY: The caller has guaranteed that `ptr` and `out` are located in
+        // the same contiguous memory segment.
+        unsafe {
+            Self::try_write(
+                Some(ptr.cast()),
+                tag,
+                MaybeUninit::new(metadata),
+                out,
+            )
+        }
+    }
+
+    fn try_emplace_null(
+        tag: usize,
+        out: Slot<'_, Self>,
+    ) -> Result<(), EmplaceError> {
+        let tag = Self::packable_tag(tag);
+        // SAFETY: There is no target, so there is nothing that needs to be
+        // located in the same memory segment as `out`.
+        unsafe { Self::try_write(None, tag, MaybeUninit::zeroed(), out) }
+    }
+
+    /// Writes a tagged pointer to `target` into `out`, or a null tagged pointer
+    /// if `target` is `None`.
+    ///
+    /// The only error this can return is [`EmplaceError::OffsetOverflow`].
+    ///
+    /// # Safety
+    ///
+    /// - If `target` is `Some`, the memory it points to and `out` must be
+    ///   located in the same contiguous memory segment.
+    /// - `tag` must fit in `BITS` bits.
+    /// - If `target` is `Some`, `metadata` must be initialized with valid
+    ///   metadata for it.
+    unsafe fn try_write(
+        target: Option<*const u8>,
+        tag: isize,
+        metadata: MaybeUninit<<T as BasisPointee<B>>::BasisMetadata>,
+        mut out: Slot<'_, Self>,
+    ) -> Result<(), EmplaceError> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::ALIGNED;
+
+        let offset = match target {
+            // SAFETY:
+            // - The caller has guaranteed that `out` and `target` are in bounds
+            //   of the same allocated object and derived from a pointer to the
+            //   same object.
+            // - The size of a `u8` is 1, so the distance between the base and
+            //   target is always a multiple of it.
+            Some(target) => unsafe {
+                target.offset_from(out.as_ptr().cast::<u8>())
+            },
+            None => 0,
+        };
+        // `out` and the target are both aligned to at least `2^BITS` bytes, so
+        // the low `BITS` bits of the offset are always free for the tag.
+        debug_assert_eq!(offset & Self::tag_mask(), 0);
+        let packed = B::Isize::from_native_isize(offset | tag)
+            .ok_or(EmplaceError::OffsetOverflow)?;
+
+        munge!(
+            let RelTaggedPtr {
+                offset: mut out_offset,
+                metadata: mut out_metadata,
+                ..
+            } = out.as_mut();
+        );
+        out_offset.write(packed);
+        out_metadata.write(metadata);
+
+        Ok(())
+    }
+
+    /// Writes a copy of `this` with the same target, tag, and metadata into
+    /// `out`.
+    ///
+    /// # Safety
+    ///
+    /// `this` and `out` must be located in the same region.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset from `out` to the target cannot be represented in
+    /// `B`.
+    unsafe fn write_copy(this: Ref<'_, Self>, out: In<Slot<'_, Self>, R>) {
+        let tag = this.packed() & Self::tag_mask();
+        let target = Self::as_ptr(this).map(|ptr| ptr.cast::<u8>());
+        // SAFETY:
+        // - `this` is located in `R`, so its target is also located in `R`.
+        //   The caller has guaranteed that `out` is located in `R` as well.
+        // - `tag` was unpacked from a tagged pointer, so it fits in `BITS`
+        //   bits.
+        // - If `this` is not null, its metadata is initialized and valid.
+        let result = unsafe {
+            Self::try_write(target, tag, this.metadata, In::into_inner(out))
+        };
+        if let Err(e) = result {
+            panic!("failed to relocate `RelTaggedPtr`: {e}");
+        }
+    }
+}
+
+// SAFETY: Values of type `RelTaggedPtr<T, R, B, BITS>` can only be created in
+// `R` and may only be moved within `R`. Therefore, all values of the type must
+// be located in `R`.
+unsafe impl<T, R, B, const BITS: u32> Pinned<R> for RelTaggedPtr<T, R, B, BITS>
+where
+    T: BasisPointee<B> + Pointee + ?Sized,
+    R: Region,
+    B: Basis,
+{
+}
+
+// SAFETY:
+// - `RelTaggedPtr` is `Sized` and always has metadata `()`, so `emplaced_meta`
+//   always returns valid metadata for it.
+// - `emplace_unsized_unchecked` initializes its `out` parameter.
+unsafe impl<T, R, B, const BITS: u32> Emplace<RelTaggedPtr<T, R, B, BITS>, R>
+    for (In<*mut T, R>, usize)
+where
+    T: BasisPointee<B> + Pointee + ?Sized,
+    R: Region,
+    B: Basis,
+{
+    fn emplaced_meta(
+        &self,
+    ) -> <RelTaggedPtr<T, R, B, BITS> as Pointee>::Metadata {
+    }
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelTaggedPtr<T, R, B, BITS>>, R>,
+    ) {
+        // SAFETY: The caller has guaranteed that `out` has the metadata
+        // returned by `emplaced_meta`.
+        let result = unsafe { self.try_emplace_unsized_unchecked(out) };
+        if let Err(e) = result {
+            panic!("failed to emplace `RelTaggedPtr`: {e}");
+        }
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter if
+// it returns `Ok`.
+unsafe impl<T, R, B, const BITS: u32> TryEmplace<RelTaggedPtr<T, R, B, BITS>, R>
+    for (In<*mut T, R>, usize)
+where
+    T: BasisPointee<B> + Pointee + ?Sized,
+    R: Region,
+    B: Basis,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelTaggedPtr<T, R, B, BITS>>, R>,
+    ) -> Result<(), EmplaceError> {
+        let (ptr, tag) = self;
+
+        // SAFETY: `ptr` and `out` are both located in `R`, so they must be
+        // located in the same contiguous memory segment.
+        unsafe {
+            RelTaggedPtr::try_emplace_new(
+                In::into_inner(ptr),
+                tag,
+                In::into_inner(out),
+            )
+        }
+    }
+}
+
+// SAFETY:
+// - `RelTaggedPtr` is `Sized` and always has metadata `()`, so `emplaced_meta`
+//   always returns valid metadata for it.
+// - `emplace_unsized_unchecked` initializes its `out` parameter.
+unsafe impl<T, R, B, const BITS: u32> Emplace<RelTaggedPtr<T, R, B, BITS>, R>
+    for (Null, usize)
+where
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+{
+    fn emplaced_meta(
+        &self,
+    ) -> <RelTaggedPtr<T, R, B, BITS> as Pointee>::Metadata {
+    }
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelTaggedPtr<T, R, B, BITS>>, R>,
+    ) {
+        // SAFETY: The caller has guaranteed that `out` has the metadata
+        // returned by `emplaced_meta`.
+        let result = unsafe { self.try_emplace_unsized_unchecked(out) };
+        if let Err(e) = result {
+            panic!("failed to emplace `RelTaggedPtr`: {e}");
+        }
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter if
+// it returns `Ok`.
+unsafe impl<T, R, B, const BITS: u32> TryEmplace<RelTaggedPtr<T, R, B, BITS>, R>
+    for (Null, usize)
+where
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelTaggedPtr<T, R, B, BITS>>, R>,
+    ) -> Result<(), EmplaceError> {
+        RelTaggedPtr::try_emplace_null(self.1, In::into_inner(out))
+    }
+}
+
+// SAFETY: `move_unsized_unchecked` initializes `out` by writing a copy of
+// `this` into it.
+unsafe impl<T, R, B, const BITS: u32> Move<R> for RelTaggedPtr<T, R, B, BITS>
+where
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+{
+    /// # Panics
+    ///
+    /// Panics if the offset from `out` to the target cannot be represented in
+    /// `B`.
+    unsafe fn move_unsized_unchecked(
+        this: In<Val<'_, Self>, R>,
+        out: In<Slot<'_, Self>, R>,
+    ) {
+        let this = In::into_inner(this);
+        // SAFETY: `this` was located in `R`, and `out` is located in `R`.
+        unsafe {
+            RelTaggedPtr::write_copy(this.as_ref(), out);
+        }
+    }
+}
+
+// SAFETY: `clone_raw_unchecked` initializes `out` by writing a copy of `this`
+// into it.
+unsafe impl<T, R, B, const BITS: u32> CloneRaw<R>
+    for RelTaggedPtr<T, R, B, BITS>
+where
+    T: BasisPointee<B> + ?Sized,
+    R: Region,
+    B: Basis,
+{
+    /// # Panics
+    ///
+    /// Panics if the offset from `out` to the target cannot be represented in
+    /// `B`.
+    unsafe fn clone_raw_unchecked(
+        this: Ref<'_, Self>,
+        out: In<Slot<'_, Self>, R>,
+    ) {
+        // SAFETY: `RelTaggedPtr` is `Pinned<R>`, so `this` is located in `R`
+        // along with `out`.
+        unsafe {
+            RelTaggedPtr::write_copy(this, out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::core::mem::MaybeUninit;
+    use ::mischief::{In, Region, Slot};
+    use ::situ::Ref;
+
+    use super::RelTaggedPtr;
+    use crate::{Basis32, EmplaceExt, MoveExt, I32};
+
+    struct TestRegion;
+
+    // SAFETY: `TestRegion` is only used to emplace into local slots.
+    unsafe impl Region for TestRegion {}
+
+    type TaggedPtr = RelTaggedPtr<I32, TestRegion, Basis32, 2>;
+
+    /// Two tagged pointer slots followed by their possible targets.
+    #[repr(C)]
+    struct Buffer {
+        a: MaybeUninit<TaggedPtr>,
+        b: MaybeUninit<TaggedPtr>,
+        targets: [I32; 4],
+    }
+
+    impl Buffer {
+        fn new() -> Self {
+            Self {
+                a: MaybeUninit::uninit(),
+                b: MaybeUninit::uninit(),
+                targets: [I32::from_ne(0); 4],
+            }
+        }
+
+        fn target(&mut self, i: usize) -> In<*mut I32, TestRegion> {
+            // SAFETY: All locals are considered to be in `TestRegion`.
+            unsafe { In::new_unchecked(&mut self.targets[i]) }
+        }
+    }
+
+    fn slot(
+        ptr: &mut MaybeUninit<TaggedPtr>,
+    ) -> In<Slot<'_, TaggedPtr>, TestRegion> {
+        // SAFETY: All local slots are considered to be in `TestRegion`.
+        unsafe { In::new_unchecked(Slot::new(ptr)) }
+    }
+
+    fn new_ref(ptr: &MaybeUninit<TaggedPtr>) -> Ref<'_, TaggedPtr> {
+        // SAFETY: The caller has initialized `ptr`, and it is not mutated for
+        // the lifetime of the `Ref`.
+        unsafe { Ref::new_unchecked(ptr.as_ptr()) }
+    }
+
+    #[test]
+    fn emplace_with_tag() {
+        let mut buffer = Buffer::new();
+
+        let target = buffer.target(1);
+        let target_ptr = *target.ptr();
+        (target, 3).emplace(slot(&mut buffer.a));
+        let ptr = new_ref(&buffer.a);
+        assert_eq!(ptr.tag(), 3);
+        assert_eq!(RelTaggedPtr::as_ptr(ptr), Some(target_ptr.cast_const()));
+
+        (buffer.target(3), 1).emplace(slot(&mut buffer.b));
+        let ptr = new_ref(&buffer.b);
+        assert_eq!(ptr.tag(), 1);
+        assert_eq!(ptr.offset() & 0b11, 0);
+    }
+
+    #[test]
+    #[should_panic = "tag does not fit in 2 bits"]
+    fn emplace_tag_too_large() {
+        let mut buffer = Buffer::new();
+        (buffer.target(0), 4).emplace(slot(&mut buffer.a));
+    }
+
+    #[test]
+    fn move_with_tag() {
+        let mut buffer = Buffer::new();
+
+        let target = buffer.target(2);
+        let target_ptr = *target.ptr();
+        let Buffer { a, b, .. } = &mut buffer;
+        let val = (target, 2).emplace_val(slot(a));
+        TaggedPtr::r#move(val, slot(b));
+
+        let ptr = new_ref(&buffer.b);
+        assert_eq!(ptr.tag(), 2);
+        assert_eq!(RelTaggedPtr::as_ptr(ptr), Some(target_ptr.cast_const()));
+    }
+}
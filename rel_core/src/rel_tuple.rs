@@ -3,9 +3,21 @@
 use ::mischief::{In, Region, Slot};
 use ::munge::munge;
 use ::ptr_meta::Pointee;
-use ::situ::DropRaw;
+use ::situ::{
+    cmp::{EqRaw, OrdRaw, PartialEqRaw, PartialOrdRaw},
+    hash::HashRaw,
+    DropGuard,
+    DropRaw,
+};
 
-use crate::{Emplace, EmplaceExt, Move, Portable};
+use crate::{
+    transcode::Transcode,
+    CloneRaw,
+    Emplace,
+    EmplaceExt,
+    Move,
+    Portable,
+};
 
 macro_rules! define_tuple {
     (
@@ -15,10 +27,22 @@ macro_rules! define_tuple {
         $($indices:tt,)*
     ) => {
         #[doc = concat!("A relative ", stringify!($n), "-tuple")]
-        #[derive(DropRaw, Move, Portable)]
+        #[derive(
+            CloneRaw,
+            DropRaw,
+            EqRaw,
+            HashRaw,
+            Move,
+            OrdRaw,
+            PartialEqRaw,
+            PartialOrdRaw,
+            Portable,
+            Transcode,
+        )]
         #[rel_core = "crate"]
         #[repr(C)]
-        pub struct $ident<$($types),*>($($types),*);
+        #[zero_padding]
+        pub struct $ident<$($types),*>($(pub $types),*);
 
         // SAFETY:
         // - `emplaced_meta` returns `()`, the only valid metadata for `Sized`
@@ -43,9 +67,44 @@ macro_rules! define_tuple {
                 out: In<Slot<'_, $ident<$($types),*>>, R>,
             ) {
                 munge!(let $ident($($types,)*) = out);
+                // If an emplacer panics, each guard drops the field that it
+                // guards.
                 $(
-                    self.$indices.emplace($types);
+                    let $types = {
+                        let ptr = $types.ptr().as_ptr();
+                        self.$indices.emplace($types);
+                        // SAFETY: `ptr` points to a field of `out` that we just
+                        // initialized by emplacing to it. It is not accessed
+                        // again until the guard is forgotten.
+                        unsafe { DropGuard::new(ptr) }
+                    };
                 )*
+                $(
+                    DropGuard::forget($types);
+                )*
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<$($types),*> crate::ser::SerializeRaw for $ident<$($types),*>
+        where
+            $($types: crate::ser::SerializeRaw,)*
+        {
+            #[allow(non_snake_case)]
+            fn serialize_raw<S: ::serde::Serializer>(
+                this: ::situ::Ref<'_, Self>,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                use ::serde::ser::SerializeTuple;
+
+                munge!(let $ident($($types,)*) = this);
+                let mut tuple = serializer.serialize_tuple($n)?;
+                $(
+                    tuple.serialize_element(
+                        &crate::ser::SerializeRef($types),
+                    )?;
+                )*
+                tuple.end()
             }
         }
     }
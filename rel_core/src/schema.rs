@@ -0,0 +1,672 @@
+//! Layout descriptors for portable types.
+//!
+//! Every [`Portable`](crate::Portable) type provides a [`Schema`] describing
+//! its layout. Schemas are built entirely at compile time, so they can be
+//! embedded in buffer headers and compared when a buffer is loaded.
+
+use ::core::{marker::PhantomData, ptr};
+use ::situ::{DropRaw, Mut};
+
+/// A description of the layout of a `Portable` type.
+#[derive(Debug)]
+pub struct Schema {
+    /// The name of the type, without any generic arguments.
+    pub name: &'static str,
+    /// The size of the type in bytes, or `None` if the type is unsized.
+    pub size: Option<usize>,
+    /// The alignment of the type in bytes.
+    pub align: usize,
+    /// The kind of the type, along with its fields or elements.
+    pub kind: SchemaKind,
+}
+
+/// The kind of a type described by a [`Schema`].
+#[derive(Debug)]
+pub enum SchemaKind {
+    /// A primitive type.
+    Primitive(Primitive),
+    /// A zero-sized marker type with no bit patterns.
+    Marker,
+    /// A possibly-uninitialized value.
+    MaybeUninit(&'static Schema),
+    /// A zero-sized marker for the type pointed to by a relative pointer.
+    ///
+    /// The schema of the pointee is returned by a function so that relative
+    /// pointers can form recursive types.
+    Pointee(fn() -> &'static Schema),
+    /// An array with a fixed number of elements.
+    Array {
+        /// The schema of the elements of the array.
+        element: &'static Schema,
+        /// The number of elements in the array.
+        len: usize,
+    },
+    /// A dynamically-sized slice of elements.
+    Slice {
+        /// The schema of the elements of the slice.
+        element: &'static Schema,
+    },
+    /// A dynamically-sized string slice.
+    Str,
+    /// A struct.
+    Struct {
+        /// The `repr` of the struct.
+        repr: Repr,
+        /// The fields of the struct, in declaration order.
+        fields: &'static [Field],
+    },
+    /// A union.
+    Union {
+        /// The `repr` of the union.
+        repr: Repr,
+        /// The fields of the union, in declaration order.
+        fields: &'static [Field],
+    },
+    /// An enum with a raw discriminant.
+    Enum {
+        /// The `repr` of the enum.
+        repr: Repr,
+        /// The type of the discriminant of the enum.
+        discriminant: Primitive,
+        /// The variants of the enum, in declaration order.
+        variants: &'static [Variant],
+    },
+}
+
+/// A primitive type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Primitive {
+    /// `()`
+    Unit,
+    /// `bool`
+    Bool,
+    /// `i8`
+    I8,
+    /// `u8`
+    U8,
+    /// A portable `i16`.
+    I16,
+    /// A portable `i32`.
+    I32,
+    /// A portable `i64`.
+    I64,
+    /// A portable `i128`.
+    I128,
+    /// A portable `u16`.
+    U16,
+    /// A portable `u32`.
+    U32,
+    /// A portable `u64`.
+    U64,
+    /// A portable `u128`.
+    U128,
+    /// A portable `f32`.
+    F32,
+    /// A portable `f64`.
+    F64,
+    /// A portable `char`.
+    Char,
+}
+
+/// The base `repr` of a struct, union, or enum.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Repr {
+    /// `repr(C)`, including `repr(C, u8)` and `repr(C, i8)` for enums.
+    C,
+    /// `repr(transparent)`
+    Transparent,
+    /// `repr(u8)` or `repr(i8)`.
+    Primitive,
+}
+
+/// A field of a struct, union, or enum variant.
+#[derive(Debug)]
+pub struct Field {
+    /// The name of the field, or its index for tuple fields.
+    pub name: &'static str,
+    /// The offset of the field in bytes from the start of its containing type.
+    pub offset: usize,
+    /// The schema of the field's type.
+    pub schema: &'static Schema,
+}
+
+/// A variant of an enum.
+#[derive(Debug)]
+pub struct Variant {
+    /// The name of the variant.
+    pub name: &'static str,
+    /// The bit pattern of the variant's discriminant.
+    pub discriminant: u8,
+    /// The fields of the variant, in declaration order.
+    pub fields: &'static [Field],
+}
+
+/// A zero-sized marker for the type pointed to by a relative pointer.
+///
+/// Relative pointers contain a `PointsTo` so that the schema of their pointee
+/// is part of their own schema.
+#[repr(transparent)]
+pub struct PointsTo<T: ?Sized>(PhantomData<*const T>);
+
+impl<T: ?Sized> PointsTo<T> {
+    /// Returns a new `PointsTo`.
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: ?Sized> Clone for PointsTo<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for PointsTo<T> {}
+
+impl<T: ?Sized> Default for PointsTo<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized> DropRaw for PointsTo<T> {
+    #[inline]
+    unsafe fn drop_raw(_: Mut<'_, Self>) {}
+}
+
+/// Returns the schema of `T`.
+///
+/// This is used to build [`SchemaKind::Pointee`] without evaluating the schema
+/// of the pointee, which may contain the pointer itself.
+pub fn schema_of<T: crate::Portable + ?Sized>() -> &'static Schema {
+    T::SCHEMA
+}
+
+// FNV-1a
+const HASH_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const HASH_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+const fn hash_bytes(mut hash: u64, bytes: &[u8]) -> u64 {
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= u64::from_le_bytes([bytes[i], 0, 0, 0, 0, 0, 0, 0]);
+        hash = hash.wrapping_mul(HASH_PRIME);
+        i += 1;
+    }
+    hash
+}
+
+const fn hash_u8(hash: u64, value: u8) -> u64 {
+    hash_bytes(hash, &[value])
+}
+
+const fn hash_usize(hash: u64, value: usize) -> u64 {
+    // Always hash sizes as eight bytes so that the hash is the same on all
+    // targets.
+    let bytes = value.to_le_bytes();
+    let mut padded = [0; 8];
+    let mut i = 0;
+    while i < bytes.len() {
+        padded[i] = bytes[i];
+        i += 1;
+    }
+    hash_bytes(hash, &padded)
+}
+
+const fn hash_str(hash: u64, value: &str) -> u64 {
+    hash_bytes(hash_usize(hash, value.len()), value.as_bytes())
+}
+
+const fn hash_primitive(hash: u64, primitive: Primitive) -> u64 {
+    hash_u8(
+        hash,
+        match primitive {
+            Primitive::Unit => 0,
+            Primitive::Bool => 1,
+            Primitive::I8 => 2,
+            Primitive::U8 => 3,
+            Primitive::I16 => 4,
+            Primitive::I32 => 5,
+            Primitive::I64 => 6,
+            Primitive::I128 => 7,
+            Primitive::U16 => 8,
+            Primitive::U32 => 9,
+            Primitive::U64 => 10,
+            Primitive::U128 => 11,
+            Primitive::F32 => 12,
+            Primitive::F64 => 13,
+            Primitive::Char => 14,
+        },
+    )
+}
+
+const fn hash_repr(hash: u64, repr: Repr) -> u64 {
+    hash_u8(
+        hash,
+        match repr {
+            Repr::C => 0,
+            Repr::Transparent => 1,
+            Repr::Primitive => 2,
+        },
+    )
+}
+
+/// The pointee schemas which are currently being hashed, innermost first.
+struct Visiting<'a> {
+    schema: &'static Schema,
+    parent: Option<&'a Visiting<'a>>,
+}
+
+/// A pair of schemas which are assumed to be equivalent while they are being
+/// compared.
+struct Assumed<'a> {
+    a: &'static Schema,
+    b: &'static Schema,
+    parent: Option<&'a Assumed<'a>>,
+}
+
+fn hash_fields(
+    mut hash: u64,
+    fields: &'static [Field],
+    visiting: &Visiting<'_>,
+) -> u64 {
+    hash = hash_usize(hash, fields.len());
+    for field in fields {
+        hash = hash_str(hash, field.name);
+        hash = hash_usize(hash, field.offset);
+        hash = field.schema.hash_into(hash, visiting);
+    }
+    hash
+}
+
+fn equivalent_fields(
+    a: &'static [Field],
+    b: &'static [Field],
+    assumed: Option<&Assumed<'_>>,
+) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).all(|(a, b)| {
+            a.name == b.name
+                && a.offset == b.offset
+                && equivalent(a.schema, b.schema, assumed)
+        })
+}
+
+/// Returns whether two schemas describe the same type.
+///
+/// Pointees are compared coinductively: a pair of pointees which is already
+/// being compared is assumed to be equivalent. Schemas are compared by
+/// structure rather than by address because the same schema may be placed at
+/// different addresses.
+fn equivalent(
+    a: &'static Schema,
+    b: &'static Schema,
+    assumed: Option<&Assumed<'_>>,
+) -> bool {
+    if ptr::eq(a, b) {
+        return true;
+    }
+    let markers =
+        matches!((&a.kind, &b.kind), (SchemaKind::Marker, SchemaKind::Marker));
+    if (a.name != b.name && !markers) || a.size != b.size || a.align != b.align
+    {
+        return false;
+    }
+
+    match (&a.kind, &b.kind) {
+        (SchemaKind::Primitive(a), SchemaKind::Primitive(b)) => a == b,
+        (SchemaKind::Marker, SchemaKind::Marker)
+        | (SchemaKind::Str, SchemaKind::Str) => true,
+        (SchemaKind::MaybeUninit(a), SchemaKind::MaybeUninit(b)) => {
+            equivalent(a, b, assumed)
+        }
+        (SchemaKind::Pointee(a), SchemaKind::Pointee(b)) => {
+            let (a, b) = (a(), b());
+            let mut current = assumed;
+            while let Some(pair) = current {
+                if ptr::eq(pair.a, a) && ptr::eq(pair.b, b) {
+                    return true;
+                }
+                current = pair.parent;
+            }
+            equivalent(
+                a,
+                b,
+                Some(&Assumed {
+                    a,
+                    b,
+                    parent: assumed,
+                }),
+            )
+        }
+        (
+            SchemaKind::Array {
+                element: a,
+                len: a_len,
+            },
+            SchemaKind::Array {
+                element: b,
+                len: b_len,
+            },
+        ) => a_len == b_len && equivalent(a, b, assumed),
+        (
+            SchemaKind::Slice { element: a },
+            SchemaKind::Slice { element: b },
+        ) => equivalent(a, b, assumed),
+        (
+            SchemaKind::Struct {
+                repr: a_repr,
+                fields: a,
+            },
+            SchemaKind::Struct {
+                repr: b_repr,
+                fields: b,
+            },
+        )
+        | (
+            SchemaKind::Union {
+                repr: a_repr,
+                fields: a,
+            },
+            SchemaKind::Union {
+                repr: b_repr,
+                fields: b,
+            },
+        ) => a_repr == b_repr && equivalent_fields(a, b, assumed),
+        (
+            SchemaKind::Enum {
+                repr: a_repr,
+                discriminant: a_discriminant,
+                variants: a,
+            },
+            SchemaKind::Enum {
+                repr: b_repr,
+                discriminant: b_discriminant,
+                variants: b,
+            },
+        ) => {
+            a_repr == b_repr
+                && a_discriminant == b_discriminant
+                && a.len() == b.len()
+                && a.iter().zip(b.iter()).all(|(a, b)| {
+                    a.name == b.name
+                        && a.discriminant == b.discriminant
+                        && equivalent_fields(a.fields, b.fields, assumed)
+                })
+        }
+        _ => false,
+    }
+}
+
+impl Schema {
+    /// Returns a stable hash of the schema.
+    ///
+    /// The hash covers the names, sizes, alignments, and offsets of the type
+    /// and all of the types it contains, and is the same on all targets. Two
+    /// types with the same schema hash can be expected to have the same
+    /// layout.
+    ///
+    /// The names of marker types are not part of the hash, since they have no
+    /// bit patterns. The types pointed to by relative pointers are part of the
+    /// schema of the pointer. When a pointee is the same as a type which is
+    /// already being hashed, only its depth is hashed so that recursive types
+    /// have a finite hash.
+    pub fn hash(&'static self) -> u64 {
+        self.hash_into(
+            HASH_OFFSET_BASIS,
+            &Visiting {
+                schema: self,
+                parent: None,
+            },
+        )
+    }
+
+    fn hash_into(&'static self, mut hash: u64, visiting: &Visiting<'_>) -> u64 {
+        // Markers have no bit patterns, so their names are left out. This
+        // keeps the hash of a type the same no matter which token or region
+        // marker it was created with.
+        if !matches!(self.kind, SchemaKind::Marker) {
+            hash = hash_str(hash, self.name);
+        }
+        hash = match self.size {
+            None => hash_u8(hash, 0),
+            Some(size) => hash_usize(hash_u8(hash, 1), size),
+        };
+        hash = hash_usize(hash, self.align);
+        match &self.kind {
+            SchemaKind::Primitive(primitive) => {
+                hash_primitive(hash_u8(hash, 0), *primitive)
+            }
+            SchemaKind::Marker => hash_u8(hash, 1),
+            SchemaKind::MaybeUninit(inner) => {
+                inner.hash_into(hash_u8(hash, 2), visiting)
+            }
+            SchemaKind::Array { element, len } => {
+                element.hash_into(hash_usize(hash_u8(hash, 3), *len), visiting)
+            }
+            SchemaKind::Slice { element } => {
+                element.hash_into(hash_u8(hash, 4), visiting)
+            }
+            SchemaKind::Str => hash_u8(hash, 5),
+            SchemaKind::Struct { repr, fields } => hash_fields(
+                hash_repr(hash_u8(hash, 6), *repr),
+                fields,
+                visiting,
+            ),
+            SchemaKind::Union { repr, fields } => hash_fields(
+                hash_repr(hash_u8(hash, 7), *repr),
+                fields,
+                visiting,
+            ),
+            SchemaKind::Enum {
+                repr,
+                discriminant,
+                variants,
+            } => {
+                hash = hash_repr(hash_u8(hash, 8), *repr);
+                hash = hash_primitive(hash, *discriminant);
+                hash = hash_usize(hash, variants.len());
+                for variant in variants.iter() {
+                    hash = hash_str(hash, variant.name);
+                    hash = hash_u8(hash, variant.discriminant);
+                    hash = hash_fields(hash, variant.fields, visiting);
+                }
+                hash
+            }
+            SchemaKind::Pointee(pointee) => {
+                let pointee = pointee();
+                let mut depth = 0;
+                let mut current = Some(visiting);
+                while let Some(outer) = current {
+                    if equivalent(pointee, outer.schema, None) {
+                        return hash_usize(hash_u8(hash, 10), depth);
+                    }
+                    depth += 1;
+                    current = outer.parent;
+                }
+                pointee.hash_into(
+                    hash_u8(hash, 9),
+                    &Visiting {
+                        schema: pointee,
+                        parent: Some(visiting),
+                    },
+                )
+            }
+        }
+    }
+}
+
+const fn size_of_field(schema: &Schema) -> usize {
+    match schema.size {
+        Some(size) => size,
+        None => panic!("enum fields must be sized"),
+    }
+}
+
+const fn align_up(offset: usize, align: usize) -> usize {
+    (offset + align - 1) & !(align - 1)
+}
+
+/// Returns the offset of the unsized last field of a `Portable` struct.
+///
+/// `end` is the offset of the end of the field before it, or zero if it is the
+/// only field.
+#[doc(hidden)]
+pub const fn unsized_field_offset(end: usize, schema: &Schema) -> usize {
+    align_up(end, schema.align)
+}
+
+/// Returns the alignment of a `Portable` struct with an unsized last field.
+///
+/// `repr_align` is the alignment from the struct's `repr(align(N))`, or one if
+/// it has none.
+#[doc(hidden)]
+pub const fn unsized_struct_align(
+    repr_align: usize,
+    fields: &[&Schema],
+) -> usize {
+    let mut align = repr_align;
+    let mut i = 0;
+    while i < fields.len() {
+        if fields[i].align > align {
+            align = fields[i].align;
+        }
+        i += 1;
+    }
+    align
+}
+
+/// Returns the offset of a field of a `Portable` enum variant.
+///
+/// `variants` contains the schemas of the fields of every variant of the enum.
+/// The offset of the field at index `field` of the variant at index `variant`
+/// is computed from the layout rules for `repr(C, u8)` enums if `c_repr` is
+/// `true`, and `repr(u8)` enums otherwise.
+#[doc(hidden)]
+pub const fn enum_field_offset(
+    c_repr: bool,
+    variants: &[&[&Schema]],
+    variant: usize,
+    field: usize,
+) -> usize {
+    // `Portable` enums always have a one-byte discriminant.
+    let mut offset = 1;
+    if c_repr {
+        // `repr(C, u8)` enums are laid out as a `repr(C)` struct of the
+        // discriminant followed by a `repr(C)` union of each variant's fields.
+        let mut align = 1;
+        let mut i = 0;
+        while i < variants.len() {
+            let mut j = 0;
+            while j < variants[i].len() {
+                if variants[i][j].align > align {
+                    align = variants[i][j].align;
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+        offset = align_up(offset, align);
+    }
+
+    let fields = variants[variant];
+    let mut i = 0;
+    loop {
+        offset = align_up(offset, fields[i].align);
+        if i == field {
+            return offset;
+        }
+        offset += size_of_field(fields[i]);
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::mischief::Region;
+
+    use super::SchemaKind;
+    use crate::{
+        option::RelOption,
+        rel_ptr::RelPtr,
+        rel_tuple::RelTuple2,
+        DefaultBasis,
+        Portable,
+        I32,
+        U16,
+        U8,
+    };
+
+    struct TestRegion;
+
+    // SAFETY: `TestRegion` is only used to name the types of pointers, which
+    // are never created.
+    unsafe impl Region for TestRegion {}
+
+    #[derive(Portable)]
+    #[rel_core = "crate"]
+    #[repr(C)]
+    struct Node<T> {
+        value: T,
+        next: RelPtr<Node<T>, TestRegion, DefaultBasis>,
+    }
+
+    #[derive(Portable)]
+    #[rel_core = "crate"]
+    #[repr(C)]
+    struct Tail<T: ?Sized> {
+        len: U16,
+        tail: T,
+    }
+
+    #[test]
+    fn enum_offsets() {
+        let SchemaKind::Enum { variants, .. } =
+            &<RelOption<I32> as Portable>::SCHEMA.kind
+        else {
+            panic!("`RelOption` should be an enum");
+        };
+        assert_eq!(variants.len(), 2);
+        assert_eq!(variants[1].name, "Some");
+        assert_eq!(variants[1].discriminant, 1);
+        assert_eq!(variants[1].fields[0].offset, 4);
+    }
+
+    #[test]
+    fn schema_hash() {
+        let a = <RelTuple2<I32, U8> as Portable>::SCHEMA.hash();
+        let b = <RelTuple2<U8, I32> as Portable>::SCHEMA.hash();
+        assert_ne!(a, b);
+        assert_eq!(a, <RelTuple2<I32, U8> as Portable>::SCHEMA.hash());
+    }
+
+    #[test]
+    fn recursive_schema_hash() {
+        let a = <Node<I32> as Portable>::SCHEMA.hash();
+        let b = <Node<U16> as Portable>::SCHEMA.hash();
+        assert_ne!(a, b);
+        assert_eq!(a, <Node<I32> as Portable>::SCHEMA.hash());
+        assert_ne!(
+            <RelPtr<I32, TestRegion, DefaultBasis> as Portable>::SCHEMA.hash(),
+            <RelPtr<U16, TestRegion, DefaultBasis> as Portable>::SCHEMA.hash(),
+        );
+    }
+
+    #[test]
+    fn unsized_last_field() {
+        let schema = <Tail<[I32]> as Portable>::SCHEMA;
+        assert_eq!(schema.size, None);
+        assert_eq!(schema.align, 4);
+        let SchemaKind::Struct { fields, .. } = &schema.kind else {
+            panic!("`Tail` should be a struct");
+        };
+        assert_eq!(fields[1].offset, 4);
+
+        let schema = <Tail<str> as Portable>::SCHEMA;
+        assert_eq!(schema.align, 2);
+        let SchemaKind::Struct { fields, .. } = &schema.kind else {
+            panic!("`Tail` should be a struct");
+        };
+        assert_eq!(fields[1].offset, 2);
+    }
+}
@@ -0,0 +1,88 @@
+//! Serialization of relative types with `serde`.
+
+use ::core::marker::PhantomData;
+pub use ::rel_core_derive::SerializeRaw;
+use ::serde::{
+    ser::{SerializeSeq, SerializeTuple},
+    Serialize,
+    Serializer,
+};
+use ::situ::{ops::IndexRaw, slice, Ref};
+
+/// A data structure that can be serialized through a raw reference.
+pub trait SerializeRaw {
+    /// Serializes the value with the given serializer.
+    fn serialize_raw<S: Serializer>(
+        this: Ref<'_, Self>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>;
+}
+
+/// A `Ref` that implements `Serialize` with its target's `SerializeRaw`
+/// implementation.
+pub struct SerializeRef<'a, T: ?Sized>(pub Ref<'a, T>);
+
+impl<T: SerializeRaw + ?Sized> Serialize for SerializeRef<'_, T> {
+    #[inline]
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        SerializeRaw::serialize_raw(self.0, serializer)
+    }
+}
+
+macro_rules! impl_builtin {
+    ($($ty:ty),*) => {
+        $(
+            impl SerializeRaw for $ty {
+                #[inline]
+                fn serialize_raw<S: Serializer>(
+                    this: Ref<'_, Self>,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    Serialize::serialize(&*this, serializer)
+                }
+            }
+        )*
+    };
+}
+
+impl_builtin!(i8, u8, bool, (), str);
+
+impl<T: ?Sized> SerializeRaw for PhantomData<T> {
+    #[inline]
+    fn serialize_raw<S: Serializer>(
+        _: Ref<'_, Self>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_unit_struct("PhantomData")
+    }
+}
+
+impl<T: SerializeRaw, const N: usize> SerializeRaw for [T; N] {
+    fn serialize_raw<S: Serializer>(
+        this: Ref<'_, Self>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(N)?;
+        for i in 0..N {
+            let element = IndexRaw::index_raw(this, i);
+            tuple.serialize_element(&SerializeRef(element))?;
+        }
+        tuple.end()
+    }
+}
+
+impl<T: SerializeRaw> SerializeRaw for [T] {
+    fn serialize_raw<S: Serializer>(
+        this: Ref<'_, Self>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(this.len()))?;
+        for element in slice::iter(this) {
+            seq.serialize_element(&SerializeRef(element))?;
+        }
+        seq.end()
+    }
+}
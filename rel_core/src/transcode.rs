@@ -0,0 +1,466 @@
+//! Copying values between bases and endiannesses.
+//!
+//! A [`Transcoder`] copies an object graph into a new region while converting
+//! it to a target [`Basis`] and [`Endianness`]. Every `Isize`, `Usize`, and
+//! relative pointer is re-encoded in the target basis, and an error is returned
+//! if a value does not fit. If the target endianness differs from the current
+//! one, every multibyte primitive is byte-swapped as well.
+//!
+//! Transcoding happens in two steps. [`Transcode::transcode`] copies a value
+//! into its target basis, leaving it in the current endianness so that it can
+//! still be used to finish emplacing its owner. After that, the owner of the
+//! value calls [`Transcoder::encode`] to convert it to the target endianness.
+//! The byte swapping is driven by the [`Schema`] of the target type, so only
+//! the types which own allocations need to take part in it.
+//!
+//! `Isize` and `Usize` fields are detected by the `Transcode` derive when a
+//! struct or enum is annotated with `#[basis = "B"]`, where `B` is its basis
+//! type parameter. Similarly, `#[allocator = "A"]` marks the type parameter to
+//! replace with the target allocator.
+
+use ::core::{
+    marker::{PhantomData, PhantomPinned},
+    mem::MaybeUninit,
+};
+use ::mischief::{In, RegionalAllocator, Slot};
+use ::ptr_meta::Pointee;
+pub use ::rel_core_derive::Transcode;
+use ::situ::{DropRaw, Ref};
+
+use crate::{
+    schema::{Schema, SchemaKind},
+    Basis,
+    Char,
+    Emplace,
+    EmplaceError,
+    Portable,
+    TryEmplace,
+    F32,
+    F64,
+    I128,
+    I16,
+    I32,
+    I64,
+    U128,
+    U16,
+    U32,
+    U64,
+};
+
+/// The byte order of multibyte primitives.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    /// The least significant byte is stored first.
+    Little,
+    /// The most significant byte is stored first.
+    Big,
+}
+
+impl Endianness {
+    /// The endianness that portable primitives are stored in, as selected by
+    /// the `little_endian` and `big_endian` features.
+    #[cfg(feature = "little_endian")]
+    pub const CURRENT: Self = Endianness::Little;
+    /// The endianness that portable primitives are stored in, as selected by
+    /// the `little_endian` and `big_endian` features.
+    #[cfg(feature = "big_endian")]
+    pub const CURRENT: Self = Endianness::Big;
+}
+
+/// The target of a transcoding operation.
+///
+/// Values are transcoded into the basis `B`, and any allocations they own are
+/// allocated in `A` and store an allocator of type `E`.
+pub struct Transcoder<B, A, E> {
+    alloc: A,
+    endianness: Endianness,
+    _phantom: PhantomData<fn() -> (B, E)>,
+}
+
+impl<B: Basis, A: RegionalAllocator, E> Transcoder<B, A, E> {
+    /// Returns a new `Transcoder` that allocates in `alloc` and keeps the
+    /// current endianness.
+    pub fn new(alloc: A) -> Self {
+        Self {
+            alloc,
+            endianness: Endianness::CURRENT,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns a new `Transcoder` that allocates in `alloc` and converts
+    /// values to the given endianness.
+    ///
+    /// # Safety
+    ///
+    /// If `endianness` is not [`Endianness::CURRENT`], the values transcoded
+    /// with the returned `Transcoder` must not be accessed or dropped. Their
+    /// bytes may only be copied out, for example to send them to a target
+    /// with that endianness.
+    pub unsafe fn with_endianness(alloc: A, endianness: Endianness) -> Self {
+        Self {
+            alloc,
+            endianness,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the allocator that transcoded values are allocated in.
+    pub fn alloc(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Returns the endianness that values are transcoded to.
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    /// Converts a transcoded value to the target endianness.
+    ///
+    /// Only the bytes of the value itself are converted. The values that it
+    /// owns must already have been converted by [`Transcode::transcode`].
+    ///
+    /// # Safety
+    ///
+    /// - `value` must be non-null, properly aligned, and valid for reads and
+    ///   writes.
+    /// - `value` must point to a value that was just initialized by
+    ///   `Transcode::transcode` with this transcoder, and has not been
+    ///   converted yet.
+    pub unsafe fn encode<T: Portable>(&self, value: *mut T) {
+        if self.endianness != Endianness::CURRENT {
+            // SAFETY: The caller has guaranteed that `value` points to an
+            // initialized `T`, which `T::SCHEMA` describes the layout of.
+            unsafe {
+                swap_endianness(T::SCHEMA, value.cast());
+            }
+        }
+    }
+}
+
+/// Reverses the bytes of every multibyte primitive described by `schema`.
+///
+/// # Safety
+///
+/// `ptr` must point to an initialized value with the layout of `schema` which
+/// is valid for reads and writes.
+unsafe fn swap_endianness(schema: &Schema, ptr: *mut u8) {
+    match &schema.kind {
+        SchemaKind::Primitive(_) => {
+            let len = schema.size.unwrap_or(0);
+            // The bytes may be uninitialized padding of a `MaybeUninit`, so
+            // they are only moved around as `MaybeUninit<u8>`.
+            let bytes = ptr.cast::<MaybeUninit<u8>>();
+            for i in 0..len / 2 {
+                // SAFETY: `i` and `len - 1 - i` are both less than the size of
+                // the primitive, so both are in bounds of the value.
+                unsafe {
+                    ::core::ptr::swap(bytes.add(i), bytes.add(len - 1 - i));
+                }
+            }
+        }
+        SchemaKind::MaybeUninit(inner) => {
+            // SAFETY: `MaybeUninit<T>` has the same layout as `T`.
+            unsafe { swap_endianness(inner, ptr) }
+        }
+        SchemaKind::Array { element, len } => {
+            let size = element.size.unwrap_or(0);
+            for i in 0..*len {
+                // SAFETY: The `i`th element of the array is located `i *
+                // size` bytes from its start.
+                unsafe { swap_endianness(element, ptr.add(i * size)) }
+            }
+        }
+        SchemaKind::Struct { fields, .. } => {
+            for field in fields.iter() {
+                // SAFETY: `field.offset` is the offset of the field from the
+                // start of the struct.
+                unsafe { swap_endianness(field.schema, ptr.add(field.offset)) }
+            }
+        }
+        SchemaKind::Enum { variants, .. } => {
+            // SAFETY: `Portable` enums always have a one-byte discriminant at
+            // the start of the value, so it is unaffected by endianness.
+            let discriminant = unsafe { ptr.read() };
+            let variant =
+                variants.iter().find(|v| v.discriminant == discriminant);
+            for field in variant.map_or(&[][..], |v| v.fields).iter() {
+                // SAFETY: `field.offset` is the offset of the field from the
+                // start of the enum when it is the active variant.
+                unsafe { swap_endianness(field.schema, ptr.add(field.offset)) }
+            }
+        }
+        // The active field of a union is unknown, so its bytes are left as-is.
+        // Unsized values are handled by their owners one element at a time.
+        SchemaKind::Marker
+        | SchemaKind::Pointee(_)
+        | SchemaKind::Union { .. }
+        | SchemaKind::Slice { .. }
+        | SchemaKind::Str => (),
+    }
+}
+
+/// A value that can be copied into another basis, endianness, and region.
+///
+/// Transcoding a value does not modify it. If transcoding fails, any values
+/// that were already emplaced in the target region are leaked.
+///
+/// # Safety
+///
+/// If `transcode` returns `Ok`, it must have initialized its `out` parameter.
+/// All of the allocations owned by the initialized value must have been
+/// converted to the endianness of the transcoder, while the value itself must
+/// be left in the current endianness.
+pub unsafe trait Transcode<B: Basis, A: RegionalAllocator, E>:
+    DropRaw + Sized
+{
+    /// The type of the transcoded value.
+    type Target: DropRaw + Portable;
+
+    /// Copies `this` into `out`, converting it to the basis of `transcoder`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a value does not fit in the target basis, or if
+    /// allocating in the target region fails.
+    fn transcode(
+        this: Ref<'_, Self>,
+        transcoder: &Transcoder<B, A, E>,
+        out: In<Slot<'_, Self::Target>, A::Region>,
+    ) -> Result<(), EmplaceError>;
+}
+
+/// Converts an `Isize` from one basis to another.
+///
+/// # Errors
+///
+/// Returns [`EmplaceError::IntOverflow`] if the value cannot be represented in
+/// the target basis.
+pub fn transcode_isize<B1: Basis, B2: Basis>(
+    value: B1::Isize,
+) -> Result<B2::Isize, EmplaceError> {
+    let value =
+        B1::to_native_isize(value).map_err(|_| EmplaceError::IntOverflow)?;
+    B2::from_native_isize(value).map_err(|_| EmplaceError::IntOverflow)
+}
+
+/// Converts a `Usize` from one basis to another.
+///
+/// # Errors
+///
+/// Returns [`EmplaceError::IntOverflow`] if the value cannot be represented in
+/// the target basis.
+pub fn transcode_usize<B1: Basis, B2: Basis>(
+    value: B1::Usize,
+) -> Result<B2::Usize, EmplaceError> {
+    let value =
+        B1::to_native_usize(value).map_err(|_| EmplaceError::IntOverflow)?;
+    B2::from_native_usize(value).map_err(|_| EmplaceError::IntOverflow)
+}
+
+macro_rules! impl_copy {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            // SAFETY: `transcode` initializes `out` by copying `this` into it.
+            // The value does not own any allocations.
+            unsafe impl<B, A, E> Transcode<B, A, E> for $ty
+            where
+                B: Basis,
+                A: RegionalAllocator,
+            {
+                type Target = Self;
+
+                #[inline]
+                fn transcode(
+                    this: Ref<'_, Self>,
+                    _: &Transcoder<B, A, E>,
+                    out: In<Slot<'_, Self::Target>, A::Region>,
+                ) -> Result<(), EmplaceError> {
+                    In::into_inner(out).write(*this);
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_copy!(
+    (),
+    bool,
+    i8,
+    u8,
+    I16,
+    I32,
+    I64,
+    I128,
+    U16,
+    U32,
+    U64,
+    U128,
+    F32,
+    F64,
+    Char,
+);
+
+// SAFETY: `PhantomData` and `PhantomPinned` are zero-sized, so `transcode`
+// initializes `out` by doing nothing. They do not own any allocations.
+unsafe impl<T, B, A, E> Transcode<B, A, E> for PhantomData<T>
+where
+    T: Transcode<B, A, E>,
+    B: Basis,
+    A: RegionalAllocator,
+{
+    type Target = PhantomData<T::Target>;
+
+    #[inline]
+    fn transcode(
+        _: Ref<'_, Self>,
+        _: &Transcoder<B, A, E>,
+        _: In<Slot<'_, Self::Target>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        Ok(())
+    }
+}
+
+// SAFETY: `PhantomPinned` is zero-sized, so `transcode` initializes `out` by
+// doing nothing. It does not own any allocations.
+unsafe impl<B, A, E> Transcode<B, A, E> for PhantomPinned
+where
+    B: Basis,
+    A: RegionalAllocator,
+{
+    type Target = Self;
+
+    #[inline]
+    fn transcode(
+        _: Ref<'_, Self>,
+        _: &Transcoder<B, A, E>,
+        _: In<Slot<'_, Self::Target>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        Ok(())
+    }
+}
+
+// SAFETY: `transcode` initializes `out` by transcoding each element into it.
+// The elements are transcoded by `T`, which converts the allocations they own.
+unsafe impl<T, B, A, E, const N: usize> Transcode<B, A, E> for [T; N]
+where
+    T: Transcode<B, A, E>,
+    B: Basis,
+    A: RegionalAllocator,
+{
+    type Target = [T::Target; N];
+
+    fn transcode(
+        this: Ref<'_, Self>,
+        transcoder: &Transcoder<B, A, E>,
+        out: In<Slot<'_, Self::Target>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        let this_ptr = this.as_ptr().cast::<T>();
+        let out_ptr = out.ptr().as_ptr().cast::<T::Target>();
+        for i in 0..N {
+            // SAFETY: `i` is less than `N`, so the element is in bounds of
+            // `this`. It is borrowed for the duration of the transcode, so it
+            // is initialized and cannot alias any mutable references.
+            let this_elem = unsafe { Ref::new_unchecked(this_ptr.add(i)) };
+            // SAFETY: `i` is less than `N`, so the element is in bounds of
+            // `out`, which is not aliased by any other references.
+            let out_elem = unsafe { Slot::new_unchecked(out_ptr.add(i)) };
+            // SAFETY: `out_elem` is an element of `out`, so it must be located
+            // in the same region as it.
+            let out_elem = unsafe { In::new_unchecked(out_elem) };
+            T::transcode(this_elem, transcoder, out_elem)?;
+        }
+        Ok(())
+    }
+}
+
+/// An emplacer that transcodes the value behind a `Ref`.
+///
+/// The emplaced value is converted to the endianness of the transcoder.
+pub struct Transcoded<'a, T, B, A, E>(
+    pub Ref<'a, T>,
+    pub &'a Transcoder<B, A, E>,
+);
+
+// SAFETY:
+// - `emplaced_meta` returns `()`, the only valid metadata for `Sized` types.
+// - `emplace_unsized_unchecked` initializes its `out` parameter by transcoding
+//   into it.
+unsafe impl<T, B, A, E> Emplace<T::Target, A::Region>
+    for Transcoded<'_, T, B, A, E>
+where
+    T: Transcode<B, A, E>,
+    B: Basis,
+    A: RegionalAllocator,
+{
+    fn emplaced_meta(&self) -> <T::Target as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, T::Target>, A::Region>,
+    ) {
+        // SAFETY: `T::Target` is `Sized`, so `out` must have the metadata
+        // returned by `emplaced_meta`.
+        unsafe { self.try_emplace_unsized_unchecked(out).unwrap() }
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter by
+// transcoding into it if it returns `Ok`.
+unsafe impl<T, B, A, E> TryEmplace<T::Target, A::Region>
+    for Transcoded<'_, T, B, A, E>
+where
+    T: Transcode<B, A, E>,
+    B: Basis,
+    A: RegionalAllocator,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, T::Target>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        let Self(value, transcoder) = self;
+        let out_ptr = out.ptr().as_ptr();
+        T::transcode(value, transcoder, out)?;
+        // SAFETY: `out_ptr` points to the value that was just transcoded.
+        unsafe {
+            transcoder.encode(out_ptr);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::core::mem::MaybeUninit;
+
+    use super::swap_endianness;
+    use crate::{option::RelOption, Portable, I32, U16};
+
+    #[derive(Portable)]
+    #[rel_core = "crate"]
+    #[repr(C)]
+    struct Pair {
+        a: I32,
+        b: RelOption<U16>,
+    }
+
+    #[test]
+    fn swap_schema() {
+        let mut value = MaybeUninit::new(Pair {
+            a: I32::from_ne(0x0102_0304),
+            b: RelOption::Some(U16::from_ne(0x0506)),
+        });
+        // SAFETY: `value` is an initialized `Pair`.
+        unsafe {
+            swap_endianness(Pair::SCHEMA, value.as_mut_ptr().cast());
+        }
+        // SAFETY: All of the fields of `value` are still initialized.
+        let Pair { a, b } = unsafe { value.assume_init() };
+        assert_eq!(a.to_ne(), 0x0403_0201);
+        let RelOption::Some(b) = b else {
+            panic!("the discriminant should not be swapped");
+        };
+        assert_eq!(b.to_ne(), 0x0605);
+    }
+}
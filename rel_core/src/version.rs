@@ -0,0 +1,601 @@
+//! Versioned portable structs and migration between their versions.
+//!
+//! A struct becomes versioned by deriving `Portable` with a `#[version = "N"]`
+//! attribute. Every field added after the first version must be placed after
+//! all of the fields from earlier versions, and be annotated with the version
+//! it was added in and an emplacer for its default value:
+//!
+//! ```ignore
+//! #[derive(DropRaw, Portable, Transcode)]
+//! #[repr(C)]
+//! #[version = "2"]
+//! struct Config {
+//!     width: I32,
+//!     #[since = "2"]
+//!     #[default_value = "1i32"]
+//!     scale: I32,
+//! }
+//! ```
+//!
+//! Because fields are only ever appended, a value written by an older version
+//! is a prefix of the current layout. Adding a field must not change the
+//! alignment of the struct, which the derive checks when `VERSION` is
+//! evaluated. A [`RelVersioned`] stores a value along with the version it was
+//! written in, and a [`VersionedRef`] points to a value of any version. The
+//! derive generates a `versioned_<field>` reader for each field, which returns
+//! `None` if the field is missing from the version of the value.
+//!
+//! Versioned structs which also derive `Transcode` implement [`Migrate`].
+//! [`Upgrade`] migrates a value into the current version in the region of a
+//! [`Transcoder`], transcoding each field and emplacing the default values of
+//! any missing fields. Nested `RelVersioned` values are migrated as well.
+//! [`UpgradeVersioned`] migrates a whole `RelVersioned`, which may have been
+//! written by an older version and so must only be read through a raw pointer.
+
+use ::core::{
+    alloc::Layout,
+    marker::PhantomData,
+    mem::{align_of, offset_of},
+    ptr::{self, NonNull},
+};
+use ::mischief::{In, Region, RegionalAllocator, Slot};
+use ::munge::munge;
+use ::ptr_meta::Pointee;
+use ::situ::{DropRaw, Mut, Ref};
+
+use crate::{
+    transcode::{Transcode, Transcoder},
+    Basis,
+    Emplace,
+    EmplaceError,
+    EmplaceExt,
+    Portable,
+    TryEmplace,
+    U16,
+};
+
+/// A `Portable` struct that has a version and may have trailing fields which
+/// were added in later versions.
+///
+/// # Safety
+///
+/// - `VERSION` must be at least 1, and `SIZES` must contain exactly `VERSION`
+///   elements.
+/// - The fields present in each version must be a prefix of the fields of the
+///   struct, and `SIZES[v - 1]` must be the number of bytes from the start of
+///   the struct to the end of the last field present in version `v`.
+/// - The alignment of the struct must be the alignment of the fields present
+///   in version 1.
+/// - `drop_version` must drop exactly the fields present in `version`.
+pub unsafe trait Versioned: DropRaw + Portable + Sized {
+    /// The current version of the struct.
+    const VERSION: u16;
+    /// The size in bytes of the fields present in each version, starting from
+    /// version 1.
+    const SIZES: &'static [usize];
+
+    /// Drops the fields of the value at `this` which are present in `version`.
+    ///
+    /// # Safety
+    ///
+    /// - `version` must be between 1 and `VERSION`, inclusive.
+    /// - `this` must be non-null, properly aligned, and valid for reads and
+    ///   writes of `SIZES[version - 1]` bytes.
+    /// - The fields present in `version` must be initialized, and must not be
+    ///   accessed after they are dropped.
+    unsafe fn drop_version(this: *mut Self, version: u16);
+}
+
+/// A versioned struct that can be migrated from any of its versions into the
+/// current version in another region.
+///
+/// # Safety
+///
+/// - `Target` must be the same struct as `Self`, transcoded into the basis and
+///   allocator of the transcoder. It must have the same current version.
+/// - If `migrate` returns `Ok`, it must have initialized its `out` parameter.
+///   All of the allocations owned by the initialized value must have been
+///   converted to the endianness of the transcoder, while the value itself
+///   must be left in the current endianness.
+pub unsafe trait Migrate<B: Basis, A: RegionalAllocator, E>:
+    Versioned
+{
+    /// The type of the migrated value.
+    type Target: Versioned;
+
+    /// Migrates the value behind `from` into `out`, transcoding the fields
+    /// present in its version and using default values for any fields that
+    /// are missing from it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a field cannot be transcoded.
+    fn migrate(
+        from: VersionedRef<'_, Self>,
+        transcoder: &Transcoder<B, A, E>,
+        out: In<Slot<'_, Self::Target>, A::Region>,
+    ) -> Result<(), EmplaceError>;
+}
+
+/// A reference to a versioned struct, which may be of an older version.
+///
+/// Only the fields present in the version of the struct may be accessed.
+pub struct VersionedRef<'a, T> {
+    ptr: NonNull<T>,
+    version: u16,
+    _phantom: PhantomData<&'a T>,
+}
+
+impl<T> Clone for VersionedRef<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for VersionedRef<'_, T> {}
+
+impl<'a, T: Versioned> VersionedRef<'a, T> {
+    /// Creates a new `VersionedRef` from a pointer to a value of the given
+    /// version.
+    ///
+    /// # Safety
+    ///
+    /// - `version` must be between 1 and `T::VERSION`, inclusive.
+    /// - `ptr` must be non-null, properly aligned for `T`, and valid for reads
+    ///   of `T::SIZES[version - 1]` bytes.
+    /// - The fields present in `version` must be initialized and immovable,
+    ///   and must not be mutably aliased for `'a`.
+    pub unsafe fn new_unchecked(ptr: *const T, version: u16) -> Self {
+        Self {
+            // SAFETY: The caller has guaranteed that `ptr` is non-null.
+            ptr: unsafe { NonNull::new_unchecked(ptr.cast_mut()) },
+            version,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns a `VersionedRef` to a value of the current version.
+    pub fn current(value: Ref<'a, T>) -> Self {
+        // SAFETY: `value` is a reference to an initialized value of the
+        // current version, so all of its fields are present.
+        unsafe { Self::new_unchecked(value.as_ptr(), T::VERSION) }
+    }
+
+    /// Returns the version of the referenced value.
+    pub fn version(this: Self) -> u16 {
+        this.version
+    }
+
+    /// Returns a pointer to the referenced value.
+    ///
+    /// Only the fields present in the version of the value may be read through
+    /// the returned pointer.
+    pub fn as_ptr(this: Self) -> *const T {
+        this.ptr.as_ptr()
+    }
+
+    /// Returns a `Ref` to the value if it is of the current version.
+    pub fn get(this: Self) -> Option<Ref<'a, T>> {
+        if this.version == T::VERSION {
+            // SAFETY: The value is of the current version, so all of its
+            // fields are present and it is valid to create a `Ref` to it.
+            Some(unsafe { Ref::new_unchecked(this.ptr.as_ptr()) })
+        } else {
+            None
+        }
+    }
+}
+
+/// A versioned struct stored along with the version it was written in.
+///
+/// A `RelVersioned` written by an older version of `T` only contains the
+/// fields present in that version, so it may be smaller than the current
+/// layout and must not be accessed through a `Ref` or `Mut`. Values which may
+/// be of an older version are read through a raw pointer with
+/// [`RelVersioned::get_raw`], and must be allocated and deallocated with the
+/// layout returned by [`RelVersioned::layout_of_version`] for their version.
+#[derive(Portable)]
+#[rel_core = "crate"]
+#[repr(C)]
+pub struct RelVersioned<T> {
+    version: U16,
+    value: T,
+}
+
+impl<T: Versioned> RelVersioned<T> {
+    /// Returns the layout of a `RelVersioned` written by the given version.
+    ///
+    /// The layout of the current version is the layout of `RelVersioned<T>`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `version` is not between 1 and `T::VERSION`, inclusive.
+    pub fn layout_of_version(version: u16) -> Layout {
+        let size =
+            offset_of!(Self, value) + T::SIZES[check_version::<T>(version)];
+        Layout::from_size_align(size, align_of::<Self>())
+            .unwrap()
+            .pad_to_align()
+    }
+
+    /// Returns the version that the value was written in.
+    pub fn version(this: Ref<'_, Self>) -> u16 {
+        // SAFETY: `this` is a `Ref`, so it is valid for reads of its version.
+        unsafe { Self::version_raw(this.as_ptr()) }
+    }
+
+    /// Returns the version that the value at `this` was written in.
+    ///
+    /// # Safety
+    ///
+    /// `this` must be non-null, properly aligned, and valid for reads of the
+    /// version of a `RelVersioned`.
+    pub unsafe fn version_raw(this: *const Self) -> u16 {
+        // SAFETY: The caller has guaranteed that `this` is valid for reads of
+        // its version.
+        unsafe { (*ptr::addr_of!((*this).version)).to_ne() }
+    }
+
+    /// Returns a `VersionedRef` to the value.
+    ///
+    /// `this` covers the whole layout of `RelVersioned<T>`, so it must have
+    /// been allocated for the current version. Use
+    /// [`get_raw`](RelVersioned::get_raw) to read values which may have been
+    /// written by an older version.
+    pub fn get(this: Ref<'_, Self>) -> VersionedRef<'_, T> {
+        // SAFETY: `this` is a `Ref`, so it is valid for reads of the layout of
+        // every version, initialized, and not mutably aliased for `'_`.
+        unsafe { Self::get_raw(this.as_ptr()) }
+    }
+
+    /// Returns a `VersionedRef` to the value at `this`, which may have been
+    /// written by an older version.
+    ///
+    /// # Safety
+    ///
+    /// - `this` must be non-null, properly aligned, and valid for reads of the
+    ///   layout of the version it was written in.
+    /// - The version and the fields present in that version must be
+    ///   initialized and immovable, and must not be mutably aliased for `'a`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stored version is not between 1 and `T::VERSION`,
+    /// inclusive.
+    pub unsafe fn get_raw<'a>(this: *const Self) -> VersionedRef<'a, T> {
+        // SAFETY: The caller has guaranteed that `this` is valid for reads of
+        // its version.
+        let version = unsafe { Self::version_raw(this) };
+        check_version::<T>(version);
+        // SAFETY: The version of a `RelVersioned` is always written with the
+        // value, which contains all of the fields present in that version. It
+        // was checked to be between 1 and `T::VERSION`, and the caller has
+        // guaranteed that the fields are initialized, immovable, and not
+        // mutably aliased for `'a`.
+        unsafe {
+            VersionedRef::new_unchecked(ptr::addr_of!((*this).value), version)
+        }
+    }
+
+    /// Drops the value at `this`, which may have been written by an older
+    /// version.
+    ///
+    /// # Safety
+    ///
+    /// - `this` must be non-null, properly aligned, and valid for reads and
+    ///   writes of the layout of the version it was written in.
+    /// - The version and the fields present in that version must be
+    ///   initialized, and must not be accessed after they are dropped.
+    pub unsafe fn drop_version_raw(this: *mut Self) {
+        // SAFETY: The caller has guaranteed that `this` is valid for reads of
+        // its version.
+        let version = unsafe { Self::version_raw(this) };
+        check_version::<T>(version);
+        // SAFETY: The caller has guaranteed that the value is initialized and
+        // not accessed after it is dropped. It contains all of the fields
+        // present in its version, which is between 1 and `T::VERSION`.
+        unsafe {
+            T::drop_version(ptr::addr_of_mut!((*this).value), version);
+        }
+    }
+}
+
+/// Returns the index of `version` in the sizes of `T`.
+///
+/// # Panics
+///
+/// Panics if `version` is not between 1 and `T::VERSION`, inclusive.
+fn check_version<T: Versioned>(version: u16) -> usize {
+    assert!(
+        version >= 1 && version <= T::VERSION,
+        "invalid version {version} of a versioned struct with version {}",
+        T::VERSION,
+    );
+    usize::from(version - 1)
+}
+
+impl<T: Versioned> DropRaw for RelVersioned<T> {
+    #[inline]
+    unsafe fn drop_raw(this: Mut<'_, Self>) {
+        // SAFETY: The caller has guaranteed that the value is initialized and
+        // not accessed after it is dropped. `this` is a `Mut`, so it is valid
+        // for reads and writes of the layout of every version.
+        unsafe {
+            Self::drop_version_raw(this.as_ptr());
+        }
+    }
+}
+
+/// An emplacer for a [`RelVersioned`] that emplaces a value of the current
+/// version.
+pub struct WithVersion<E>(pub E);
+
+// SAFETY:
+// - `emplaced_meta` returns `()`, the only valid metadata for `Sized` types.
+// - `emplace_unsized_unchecked` initializes its `out` parameter by emplacing
+//   the current version and the value.
+unsafe impl<T, E, R> Emplace<RelVersioned<T>, R> for WithVersion<E>
+where
+    T: Versioned,
+    E: Emplace<T, R>,
+    R: Region,
+{
+    fn emplaced_meta(&self) -> <RelVersioned<T> as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelVersioned<T>>, R>,
+    ) {
+        munge!(let RelVersioned { version, value } = out);
+        In::into_inner(version).write(U16::from_ne(T::VERSION));
+        self.0.emplace(value);
+    }
+}
+
+// SAFETY: `transcode` initializes its `out` parameter by migrating the value
+// and writing its new version. The value converts the allocations it owns to
+// the target endianness.
+unsafe impl<T, B, A, E> Transcode<B, A, E> for RelVersioned<T>
+where
+    T: Migrate<B, A, E>,
+    B: Basis,
+    A: RegionalAllocator,
+{
+    type Target = RelVersioned<T::Target>;
+
+    fn transcode(
+        this: Ref<'_, Self>,
+        transcoder: &Transcoder<B, A, E>,
+        out: In<Slot<'_, Self::Target>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        munge!(let RelVersioned { version, value } = out);
+        T::migrate(Self::get(this), transcoder, value)?;
+        In::into_inner(version).write(U16::from_ne(T::Target::VERSION));
+        Ok(())
+    }
+}
+
+/// An emplacer that migrates a versioned struct to its current version in the
+/// region of a transcoder.
+///
+/// The emplaced value is converted to the endianness of the transcoder.
+pub struct Upgrade<'a, T, B, A, E>(
+    pub VersionedRef<'a, T>,
+    pub &'a Transcoder<B, A, E>,
+);
+
+// SAFETY:
+// - `emplaced_meta` returns `()`, the only valid metadata for `Sized` types.
+// - `emplace_unsized_unchecked` initializes its `out` parameter by migrating
+//   into it.
+unsafe impl<T, B, A, E> Emplace<T::Target, A::Region>
+    for Upgrade<'_, T, B, A, E>
+where
+    T: Migrate<B, A, E>,
+    B: Basis,
+    A: RegionalAllocator,
+{
+    fn emplaced_meta(&self) -> <T::Target as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, T::Target>, A::Region>,
+    ) {
+        // SAFETY: `T::Target` is `Sized`, so `out` must have the metadata
+        // returned by `emplaced_meta`.
+        unsafe { self.try_emplace_unsized_unchecked(out).unwrap() }
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter by
+// migrating into it if it returns `Ok`.
+unsafe impl<T, B, A, E> TryEmplace<T::Target, A::Region>
+    for Upgrade<'_, T, B, A, E>
+where
+    T: Migrate<B, A, E>,
+    B: Basis,
+    A: RegionalAllocator,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, T::Target>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        let Self(from, transcoder) = self;
+        let out_ptr = out.ptr().as_ptr();
+        T::migrate(from, transcoder, out)?;
+        // SAFETY: `out_ptr` points to the value that was just migrated.
+        unsafe {
+            transcoder.encode(out_ptr);
+        }
+        Ok(())
+    }
+}
+
+/// An emplacer that migrates a versioned struct into a [`RelVersioned`] of
+/// its current version in the region of a transcoder.
+///
+/// The emplaced value is converted to the endianness of the transcoder.
+pub struct UpgradeVersioned<'a, T, B, A, E>(
+    pub VersionedRef<'a, T>,
+    pub &'a Transcoder<B, A, E>,
+);
+
+// SAFETY:
+// - `emplaced_meta` returns `()`, the only valid metadata for `Sized` types.
+// - `emplace_unsized_unchecked` initializes its `out` parameter by migrating
+//   into it.
+unsafe impl<T, B, A, E> Emplace<RelVersioned<T::Target>, A::Region>
+    for UpgradeVersioned<'_, T, B, A, E>
+where
+    T: Migrate<B, A, E>,
+    B: Basis,
+    A: RegionalAllocator,
+{
+    fn emplaced_meta(&self) -> <RelVersioned<T::Target> as Pointee>::Metadata {}
+
+    unsafe fn emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelVersioned<T::Target>>, A::Region>,
+    ) {
+        // SAFETY: `RelVersioned<T::Target>` is `Sized`, so `out` must have the
+        // metadata returned by `emplaced_meta`.
+        unsafe { self.try_emplace_unsized_unchecked(out).unwrap() }
+    }
+}
+
+// SAFETY: `try_emplace_unsized_unchecked` initializes its `out` parameter by
+// migrating the value and writing its new version if it returns `Ok`.
+unsafe impl<T, B, A, E> TryEmplace<RelVersioned<T::Target>, A::Region>
+    for UpgradeVersioned<'_, T, B, A, E>
+where
+    T: Migrate<B, A, E>,
+    B: Basis,
+    A: RegionalAllocator,
+{
+    unsafe fn try_emplace_unsized_unchecked(
+        self,
+        out: In<Slot<'_, RelVersioned<T::Target>>, A::Region>,
+    ) -> Result<(), EmplaceError> {
+        let Self(from, transcoder) = self;
+        let out_ptr = out.ptr().as_ptr();
+        munge!(let RelVersioned { version, value } = out);
+        T::migrate(from, transcoder, value)?;
+        In::into_inner(version).write(U16::from_ne(T::Target::VERSION));
+        // SAFETY: `out_ptr` points to the value that was just migrated and its
+        // version.
+        unsafe {
+            transcoder.encode(out_ptr);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::core::{
+        alloc::Layout,
+        mem::{size_of, MaybeUninit},
+        ptr,
+    };
+    use ::mischief::{In, Region, Slot};
+    use ::munge::munge;
+    use ::situ::DropRaw;
+
+    use super::{RelVersioned, Versioned, VersionedRef, WithVersion};
+    use crate::{Emplace, EmplaceExt, Portable, I32, U16};
+
+    struct TestRegion;
+
+    // SAFETY: `TestRegion` is only used to emplace into local slots.
+    unsafe impl Region for TestRegion {}
+
+    #[derive(DropRaw, Portable)]
+    #[rel_core = "crate"]
+    #[repr(C)]
+    #[version = "1"]
+    struct ConfigV1 {
+        width: I32,
+    }
+
+    struct NewConfigV1(i32);
+
+    // SAFETY:
+    // - `emplaced_meta` returns `()`, the only valid metadata for `Sized`
+    //   types.
+    // - `emplace_unsized_unchecked` initializes its `out` parameter by
+    //   emplacing its only field.
+    unsafe impl<R: Region> Emplace<ConfigV1, R> for NewConfigV1 {
+        fn emplaced_meta(&self) {}
+
+        unsafe fn emplace_unsized_unchecked(
+            self,
+            out: In<Slot<'_, ConfigV1>, R>,
+        ) {
+            munge!(let ConfigV1 { width } = out);
+            self.0.emplace(width);
+        }
+    }
+
+    #[derive(DropRaw, Portable)]
+    #[rel_core = "crate"]
+    #[repr(C)]
+    #[version = "3"]
+    struct Config {
+        width: I32,
+        #[since = "2"]
+        #[default_value = "1u16"]
+        scale: U16,
+        #[since = "3"]
+        #[default_value = "-1i32"]
+        depth: I32,
+    }
+
+    #[test]
+    fn readers() {
+        assert_eq!(Config::VERSION, 3);
+        assert_eq!(Config::SIZES, &[4, 6, 12]);
+
+        let old = ConfigV1 {
+            width: I32::from_ne(640),
+        };
+        let old_ptr = ptr::from_ref(&old).cast::<Config>();
+        assert!(size_of::<ConfigV1>() >= Config::SIZES[0]);
+        // SAFETY: `ConfigV1` has the layout of version 1 of `Config`.
+        let from = unsafe { VersionedRef::new_unchecked(old_ptr, 1) };
+        assert!(VersionedRef::get(from).is_none());
+        assert_eq!(Config::versioned_width(from).to_ne(), 640);
+        assert!(Config::versioned_scale(from).is_none());
+        assert!(Config::versioned_depth(from).is_none());
+    }
+
+    #[test]
+    fn stored_version() {
+        assert_eq!(
+            RelVersioned::<Config>::layout_of_version(1),
+            Layout::new::<RelVersioned<ConfigV1>>(),
+        );
+        assert_eq!(
+            RelVersioned::<Config>::layout_of_version(3),
+            Layout::new::<RelVersioned<Config>>(),
+        );
+
+        // Old values are only as large as the version they were written in.
+        let mut out = MaybeUninit::<RelVersioned<ConfigV1>>::uninit();
+        let old_ptr = out.as_mut_ptr();
+        // SAFETY: `out` is not accessed while `slot` is alive.
+        let slot = unsafe { Slot::new_unchecked(old_ptr) };
+        // SAFETY: All local slots are considered to be in `TestRegion`.
+        let slot = unsafe { In::<_, TestRegion>::new_unchecked(slot) };
+        let old = WithVersion(NewConfigV1(640)).emplace_mut(slot);
+        let old = In::into_inner(old);
+        assert_eq!(RelVersioned::version(old.as_ref()), 1);
+
+        let old_ptr = old_ptr.cast::<RelVersioned<Config>>();
+        // SAFETY: `old_ptr` points to a `RelVersioned<Config>` written by
+        // version 1, which is valid for reads of the layout of version 1.
+        let from = unsafe { RelVersioned::get_raw(old_ptr) };
+        assert_eq!(VersionedRef::version(from), 1);
+        assert_eq!(Config::versioned_width(from).to_ne(), 640);
+        assert!(Config::versioned_scale(from).is_none());
+    }
+}
@@ -0,0 +1,230 @@
+use ::macroix::{visit_fields, AttrValue};
+use ::proc_macro2::{Ident, TokenStream};
+use ::quote::{format_ident, quote};
+use ::raw_enum::RawEnum;
+use ::syn::{
+    parse2,
+    parse_quote,
+    Data,
+    DeriveInput,
+    Error,
+    Fields,
+    Index,
+    Path,
+};
+
+pub fn derive(input: DeriveInput) -> Result<TokenStream, Error> {
+    let mut rel_core = None;
+    for attr in input.attrs.iter() {
+        if attr.path.is_ident("rel_core") {
+            rel_core =
+                Some(parse2::<AttrValue<Path>>(attr.tokens.clone())?.value);
+        }
+    }
+    let rel_core = rel_core.unwrap_or_else(|| parse_quote! { ::rel_core });
+
+    let mut clone_generics = input.generics.clone();
+    clone_generics.params.push(parse_quote! {
+        __R: #rel_core::export::mischief::Region
+    });
+    let where_clause = clone_generics.make_where_clause();
+    where_clause.predicates.push(parse_quote! {
+        Self: #rel_core::export::situ::DropRaw
+    });
+    visit_fields(&input.data, |f| {
+        let ty = &f.ty;
+        where_clause
+            .predicates
+            .push(parse_quote! { #ty: #rel_core::CloneRaw<__R> });
+    });
+
+    let (clone, util) = match &input.data {
+        Data::Enum(data_enum) => {
+            let raw_enum = RawEnum::for_derive(&input)?;
+
+            let raw_variants = &raw_enum.idents.variants;
+            let raw_enum_fn = &raw_enum.idents.raw_enum_fn;
+            let raw_discriminant_fn = &raw_enum.idents.discriminant_fn;
+            let raw_variant_fn = &raw_enum.idents.variant_fn;
+
+            let match_arms = data_enum.variants.iter().map(|v| {
+                let ident = &v.ident;
+                let clone_variant = clone_fields(&v.fields, &rel_core, true);
+                quote! {
+                    #raw_variants::#ident(this_ptr) => {
+                        match #raw_variant_fn(out_raw) {
+                            #raw_variants::#ident(out_ptr) => {
+                                #clone_variant
+                            },
+                            // SAFETY: `this` and `out` must be the same variant
+                            // because we copied the discriminant from `this` to
+                            // out.
+                            _ => unsafe {
+                                ::core::hint::unreachable_unchecked();
+                            },
+                        }
+                    }
+                }
+            });
+
+            (
+                Some(quote! {
+                    let this_raw = #raw_enum_fn(this_ptr);
+                    let out_raw = #raw_enum_fn(out_ptr);
+                    let this_discriminant = #raw_discriminant_fn(this_raw);
+                    let out_discriminant = #raw_discriminant_fn(out_raw);
+                    *out_discriminant = *this_discriminant;
+
+                    match #raw_variant_fn(this_raw) {
+                        #(#match_arms)*
+                    }
+                }),
+                Some(raw_enum.tokens),
+            )
+        }
+        Data::Struct(data_struct) => {
+            (clone_fields(&data_struct.fields, &rel_core, false), None)
+        }
+        Data::Union(data_union) => {
+            return Err(Error::new_spanned(
+                data_union.union_token,
+                "`CloneRaw` cannot be derived for unions",
+            ))
+        }
+    };
+
+    let (impl_generics, _, where_clause) = clone_generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let ty_name = &input.ident;
+    Ok(quote! {
+        const _: () = {
+            #util
+
+            // SAFETY: `clone_raw_unchecked` initializes its `out` parameter by
+            // destructuring it and cloning all of the fields.
+            #[allow(non_snake_case, unused_variables)]
+            unsafe impl #impl_generics #rel_core::CloneRaw<__R>
+                for #ty_name #ty_generics
+            #where_clause
+            {
+                unsafe fn clone_raw_unchecked(
+                    this: #rel_core::export::situ::Ref<'_, Self>,
+                    out: #rel_core::export::mischief::In<
+                        #rel_core::export::mischief::Slot<'_, Self>,
+                        __R,
+                    >,
+                ) {
+                    let this_ptr =
+                        #rel_core::export::situ::Ref::as_ptr(this).cast_mut();
+                    let out_ptr = #rel_core::export::mischief::Pointer::target(
+                        out.ptr(),
+                    );
+
+                    #clone
+                }
+            }
+        };
+    })
+}
+
+fn clone_field(rel_core: &Path, guard: &Ident) -> TokenStream {
+    quote! {
+        let guard_ptr = out_field;
+        // SAFETY:
+        // - `this_field` is a subfield of the value being cloned, and so is
+        //   guaranteed to be non-null, properly aligned, and valid for reads.
+        // - `this_field` is derived from a shared reference that is borrowed
+        //   for the duration of the clone, so it cannot alias any mutable
+        //   references.
+        // - Because `this_field` is a subfield of the value being cloned, and
+        //   that value is initialized and immovable, the value pointed to by
+        //   `this_field` is also initialized and immovable.
+        let this_field = unsafe {
+            #rel_core::export::situ::Ref::new_unchecked(this_field)
+        };
+        // SAFETY:
+        // - `out_field` is a pointer to a subfield of the slot being cloned
+        //   into, and so is guaranteed to be non-null, properly aligned, and
+        //   valid for reads and writes.
+        // - `out_field` is the only pointer to the subfield we created, so it
+        //   cannot alias any other accessible references for its lifetime.
+        let out_field = unsafe {
+            #rel_core::export::mischief::Slot::new_unchecked(out_field)
+        };
+        // SAFETY: `out_field` is a subfield of the slot being cloned into, so
+        // it must be contained in the same region as it.
+        let out_field = unsafe {
+            #rel_core::export::mischief::In::new_unchecked(out_field)
+        };
+        #rel_core::EmplaceExt::emplace(
+            #rel_core::clone::Clone(this_field),
+            out_field,
+        );
+        // SAFETY: `guard_ptr` points to the subfield that we just initialized.
+        // It is not accessed again until the guard is forgotten.
+        let #guard = unsafe {
+            #rel_core::export::situ::DropGuard::new(guard_ptr)
+        };
+    }
+}
+
+fn clone_fields(
+    fields: &Fields,
+    rel_core: &Path,
+    skip_discriminant: bool,
+) -> Option<TokenStream> {
+    let guards = (0..fields.len())
+        .map(|i| format_ident!("__guard_{}", i))
+        .collect::<Vec<_>>();
+
+    let clone_fields = match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .zip(guards.iter())
+            .map(|(f, guard)| {
+                let ident = &f.ident;
+                let clone_field = clone_field(rel_core, guard);
+                quote! {
+                    let this_field = ::core::ptr::addr_of!(
+                        (*this_ptr).#ident
+                    );
+                    let out_field = ::core::ptr::addr_of_mut!(
+                        (*out_ptr).#ident
+                    );
+                    #clone_field
+                }
+            })
+            .collect::<Vec<_>>(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .zip(guards.iter())
+            .map(|((i, _), guard)| {
+                // In enum tuple structs, the tag is the first element so we
+                // have to skip over it.
+                let offset = if skip_discriminant { 1 } else { 0 };
+                let i = Index::from(i + offset);
+                let clone_field = clone_field(rel_core, guard);
+                quote! {
+                    let this_field = ::core::ptr::addr_of!(
+                        (*this_ptr).#i
+                    );
+                    let out_field = ::core::ptr::addr_of_mut!(
+                        (*out_ptr).#i
+                    );
+                    #clone_field
+                }
+            })
+            .collect::<Vec<_>>(),
+        Fields::Unit => return None,
+    };
+
+    // If a field panics, the guards for the fields that were already
+    // initialized drop them as they go out of scope.
+    Some(quote! {
+        #(#clone_fields)*
+        #(#rel_core::export::situ::DropGuard::forget(#guards);)*
+    })
+}
@@ -10,12 +10,26 @@
     rustdoc::missing_crate_level_docs
 )]
 
+mod clone;
 mod r#move;
 mod portable;
+mod serialize;
+mod transcode;
+mod version;
+mod zero_padding;
 
 use ::proc_macro::TokenStream;
 use ::syn::{parse_macro_input, DeriveInput};
 
+/// Derives `CloneRaw` on the annotated type.
+#[proc_macro_derive(CloneRaw, attributes(rel_core))]
+pub fn derive_clone_raw(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    clone::derive(derive_input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
 /// Derives `Move` on the annotated type.
 #[proc_macro_derive(Move, attributes(rel_core))]
 pub fn derive_move(input: TokenStream) -> TokenStream {
@@ -26,10 +40,57 @@ pub fn derive_move(input: TokenStream) -> TokenStream {
 }
 
 /// Derives `Portable` on the annotated type.
-#[proc_macro_derive(Portable, attributes(rel_core))]
+///
+/// The derived implementation also provides a schema describing the layout of
+/// the type, which is built from the schemas of its fields. Fields whose types
+/// name the type itself, like relative pointers to it, are not required to be
+/// `Portable` in the `where` clause of the implementation so that recursive
+/// types can be `Portable`.
+///
+/// Structs annotated with `#[version = "N"]` also implement `Versioned`, and
+/// get a `versioned_<field>` reader for each field. Fields added after the
+/// first version must be annotated with `#[since = "M"]` and
+/// `#[default_value = "..."]`.
+///
+/// Types annotated with `#[zero_padding]` also implement `ZeroPadding`, which
+/// writes zeroes to the padding bytes between and after their fields.
+#[proc_macro_derive(
+    Portable,
+    attributes(rel_core, version, since, default_value, zero_padding)
+)]
 pub fn derive_portable(input: TokenStream) -> TokenStream {
     let derive_input = parse_macro_input!(input as DeriveInput);
     portable::derive(derive_input)
         .unwrap_or_else(|e| e.to_compile_error())
         .into()
 }
+
+/// Derives `SerializeRaw` on the annotated type.
+#[proc_macro_derive(SerializeRaw, attributes(rel_core))]
+pub fn derive_serialize_raw(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    serialize::derive(derive_input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Derives `Transcode` on the annotated type.
+///
+/// The type parameters of the type which are its basis and allocator should be
+/// marked with `#[basis = "B"]` and `#[allocator = "A"]`. The fields with the
+/// `Isize` and `Usize` types of the basis are converted to the target basis,
+/// and all other fields are transcoded.
+///
+/// Structs annotated with `#[version = "N"]` also implement `Migrate`, which
+/// transcodes the fields present in the version of a value and emplaces the
+/// default values of the fields that are missing from it.
+#[proc_macro_derive(
+    Transcode,
+    attributes(rel_core, basis, allocator, version, since, default_value)
+)]
+pub fn derive_transcode(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    transcode::derive(derive_input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
@@ -1,6 +1,6 @@
 use ::macroix::{repr::Repr, visit_fields, AttrValue};
-use ::proc_macro2::TokenStream;
-use ::quote::quote;
+use ::proc_macro2::{Ident, TokenStream};
+use ::quote::{format_ident, quote};
 use ::raw_enum::RawEnum;
 use ::syn::{
     parse2,
@@ -136,8 +136,9 @@ pub fn derive(input: DeriveInput) -> Result<TokenStream, Error> {
     })
 }
 
-fn move_field(rel_core: &Path) -> TokenStream {
+fn move_field(rel_core: &Path, guard: &Ident) -> TokenStream {
     quote! {
+        let guard_ptr = out_field;
         // SAFETY:
         // - `this_field` is a subfield of the value being moved out of, and so
         //   is guaranteed to be non-null, properly aligned, and valid for
@@ -185,6 +186,11 @@ fn move_field(rel_core: &Path) -> TokenStream {
         unsafe {
             #rel_core::MoveExt::r#move(this_field, out_field);
         }
+        // SAFETY: `guard_ptr` points to the subfield that we just initialized.
+        // It is not accessed again until the guard is forgotten.
+        let #guard = unsafe {
+            #rel_core::export::situ::DropGuard::new(guard_ptr)
+        };
     }
 }
 
@@ -193,11 +199,18 @@ fn move_fields(
     rel_core: &Path,
     skip_discriminant: bool,
 ) -> Option<TokenStream> {
-    match fields {
-        Fields::Named(fields) => {
-            let move_fields = fields.named.iter().map(|f| {
+    let guards = (0..fields.len())
+        .map(|i| format_ident!("__guard_{}", i))
+        .collect::<Vec<_>>();
+
+    let move_fields = match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .zip(guards.iter())
+            .map(|(f, guard)| {
                 let ident = &f.ident;
-                let move_field = move_field(rel_core);
+                let move_field = move_field(rel_core, guard);
                 quote! {
                     let this_field = ::core::ptr::addr_of_mut!(
                         (*this_ptr).#ident
@@ -207,33 +220,37 @@ fn move_fields(
                     );
                     #move_field
                 }
-            });
-            Some(quote! {
-                #(#move_fields)*
             })
-        }
-        Fields::Unnamed(fields) => {
-            let move_fields =
-                fields.unnamed.iter().enumerate().map(|(i, _)| {
-                    // In enum tuple structs, the tag is the first element so we
-                    // have to skip over it.
-                    let offset = if skip_discriminant { 1 } else { 0 };
-                    let i = Index::from(i + offset);
-                    let move_field = move_field(rel_core);
-                    quote! {
-                        let this_field = ::core::ptr::addr_of_mut!(
-                            (*this_ptr).#i
-                        );
-                        let out_field = ::core::ptr::addr_of_mut!(
-                            (*out_ptr).#i
-                        );
-                        #move_field
-                    }
-                });
-            Some(quote! {
-                #(#move_fields)*
+            .collect::<Vec<_>>(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .zip(guards.iter())
+            .map(|((i, _), guard)| {
+                // In enum tuple structs, the tag is the first element so we
+                // have to skip over it.
+                let offset = if skip_discriminant { 1 } else { 0 };
+                let i = Index::from(i + offset);
+                let move_field = move_field(rel_core, guard);
+                quote! {
+                    let this_field = ::core::ptr::addr_of_mut!(
+                        (*this_ptr).#i
+                    );
+                    let out_field = ::core::ptr::addr_of_mut!(
+                        (*out_ptr).#i
+                    );
+                    #move_field
+                }
             })
-        }
-        Fields::Unit => None,
-    }
+            .collect::<Vec<_>>(),
+        Fields::Unit => return None,
+    };
+
+    // If a field panics, the guards for the fields that were already
+    // initialized drop them as they go out of scope.
+    Some(quote! {
+        #(#move_fields)*
+        #(#rel_core::export::situ::DropGuard::forget(#guards);)*
+    })
 }
@@ -1,21 +1,55 @@
 use ::macroix::{
-    repr::{BaseKind, Int, PrimitiveType, Repr},
+    repr::{
+        BaseKind,
+        Int,
+        Modifier,
+        ModifierArg,
+        ModifierKind,
+        PrimitiveType,
+        Repr,
+    },
     visit_fields,
     AttrValue,
 };
-use ::proc_macro2::{Span, TokenStream};
+use ::proc_macro2::{Literal, Span, TokenStream, TokenTree};
 use ::quote::quote;
-use ::syn::{parse2, parse_quote, Data, DeriveInput, Error, Path};
+use ::syn::{
+    ext::IdentExt,
+    parse2,
+    parse_quote,
+    Data,
+    DataEnum,
+    DeriveInput,
+    Error,
+    Fields,
+    Ident,
+    Index,
+    LitInt,
+    Path,
+    TraitBoundModifier,
+    Type,
+    TypeParamBound,
+    WherePredicate,
+};
+
+use crate::{version, zero_padding};
 
 pub fn derive(mut input: DeriveInput) -> Result<TokenStream, Error> {
     let mut repr = None;
     let mut rel_core = None;
+    let mut version = None;
+    let mut zero_padding = false;
     for attr in input.attrs.iter() {
         if attr.path.is_ident("repr") {
             Repr::merge_attr(&mut repr, attr.tokens.clone())?;
         } else if attr.path.is_ident("rel_core") {
             rel_core =
                 Some(parse2::<AttrValue<Path>>(attr.tokens.clone())?.value);
+        } else if attr.path.is_ident("version") {
+            version =
+                Some(parse2::<AttrValue<LitInt>>(attr.tokens.clone())?.value);
+        } else if attr.path.is_ident("zero_padding") {
+            zero_padding = true;
         }
     }
     let rel_core = rel_core.unwrap_or_else(|| parse_quote! { ::rel_core });
@@ -26,7 +60,7 @@ pub fn derive(mut input: DeriveInput) -> Result<TokenStream, Error> {
             "`Portable` types require an explicit `repr` attribute",
         )
     })?;
-    let repr_base = repr.base.ok_or_else(|| {
+    let repr_base = repr.base.as_ref().ok_or_else(|| {
         Error::new(
             Span::call_site(),
             "`Portable` types require a base `repr` kind",
@@ -37,7 +71,7 @@ pub fn derive(mut input: DeriveInput) -> Result<TokenStream, Error> {
         Data::Struct(_) => {
             if !matches!(repr_base.kind, BaseKind::C | BaseKind::Transparent) {
                 return Err(Error::new_spanned(
-                    repr_base.kind_token,
+                    &repr_base.kind_token,
                     "`Portable` structs must be `repr(C)` or \
                     `repr(transparent)`",
                 ));
@@ -59,7 +93,7 @@ pub fn derive(mut input: DeriveInput) -> Result<TokenStream, Error> {
                 }
                 None => {
                     return Err(Error::new_spanned(
-                        repr_base.kind_token,
+                        &repr_base.kind_token,
                         "`Portable` enums that are `repr(C)` must specify \
                             a primitive type with `repr(C, i8)` or \
                             `repr(C, u8)`",
@@ -68,7 +102,7 @@ pub fn derive(mut input: DeriveInput) -> Result<TokenStream, Error> {
             },
             _ => {
                 return Err(Error::new_spanned(
-                    repr_base.kind_token,
+                    &repr_base.kind_token,
                     "`Portable` enums must be `repr(i8)`, `repr(u8)`, \
                         `repr(C, i8)`, or `repr(C, u8)`",
                 ));
@@ -77,7 +111,7 @@ pub fn derive(mut input: DeriveInput) -> Result<TokenStream, Error> {
         Data::Union(_) => {
             if !matches!(repr_base.kind, BaseKind::C | BaseKind::Transparent) {
                 return Err(Error::new_spanned(
-                    repr_base.kind_token,
+                    &repr_base.kind_token,
                     "`Portable` unions must be `repr(C)` or \
                     `repr(transparent)`",
                 ));
@@ -85,21 +119,357 @@ pub fn derive(mut input: DeriveInput) -> Result<TokenStream, Error> {
         }
     }
 
+    // Fields which contain the type itself, like relative pointers to it, are
+    // not bounded. Their bounds would require the type to be `Portable` in
+    // order for it to be `Portable`, which can never be satisfied.
+    let ident = input.ident.clone();
     let where_clause = input.generics.make_where_clause();
     visit_fields(&input.data, |f| {
         let ty = &f.ty;
-        where_clause
-            .predicates
-            .push(parse_quote! { #ty: #rel_core::Portable });
+        if !mentions(quote! { #ty }, &ident) {
+            where_clause
+                .predicates
+                .push(parse_quote! { #ty: #rel_core::Portable });
+        }
     });
 
+    let schema = schema(&input, &repr, &rel_core);
+    let versioned = version
+        .map(|version| version::derive(&input, &version, &rel_core))
+        .transpose()?;
+    let zero_padding = zero_padding
+        .then(|| zero_padding::derive(&input, &repr, &rel_core))
+        .transpose()?;
+
     let (impl_generics, ty_generics, where_clause) =
         input.generics.split_for_impl();
     let ty_name = &input.ident;
     Ok(quote! {
         // SAFETY: This type has a valid `repr` and contains only `Portable`
-        // fields.
+        // fields. Its schema is built from the layouts of its fields and their
+        // offsets within it.
         unsafe impl #impl_generics #rel_core::Portable
-            for #ty_name #ty_generics #where_clause {}
+            for #ty_name #ty_generics #where_clause
+        {
+            const SCHEMA: &'static #rel_core::schema::Schema = #schema;
+        }
+
+        #versioned
+
+        #zero_padding
     })
 }
+
+fn schema(input: &DeriveInput, repr: &Repr, rel_core: &Path) -> TokenStream {
+    let name = input.ident.unraw().to_string();
+    let schema_repr = match repr.base.as_ref().map(|base| &base.kind) {
+        Some(BaseKind::Transparent) => quote! { Transparent },
+        Some(BaseKind::Primitive(_)) => quote! { Primitive },
+        _ => quote! { C },
+    };
+
+    let kind = match &input.data {
+        Data::Struct(data) => {
+            let fields = struct_fields(
+                &data.fields,
+                has_unsized_last_field(input, &data.fields),
+                rel_core,
+            );
+            quote! {
+                Struct {
+                    repr: #rel_core::schema::Repr::#schema_repr,
+                    fields: &[#(#fields,)*],
+                }
+            }
+        }
+        Data::Union(data) => {
+            let fields = data.fields.named.iter().map(|f| {
+                let ident = f.ident.as_ref().unwrap();
+                field(
+                    &ident.unraw().to_string(),
+                    quote! { #ident },
+                    &f.ty,
+                    rel_core,
+                )
+            });
+            quote! {
+                Union {
+                    repr: #rel_core::schema::Repr::#schema_repr,
+                    fields: &[#(#fields,)*],
+                }
+            }
+        }
+        Data::Enum(data) => {
+            let (int, int_token) = enum_int(repr);
+            let discriminant = match int {
+                Int::I8 => quote! { I8 },
+                _ => quote! { U8 },
+            };
+            let c_repr = repr.primitive_type.is_some();
+
+            let variant_fields = variant_fields(data, rel_core);
+            let values = discriminants(data);
+            let variants = data.variants.iter().enumerate().map(|(i, v)| {
+                let value = &values[i];
+                let variant_name = v.ident.unraw().to_string();
+                let fields = v.fields.iter().enumerate().map(|(j, f)| {
+                    let name = match &f.ident {
+                        Some(ident) => ident.unraw().to_string(),
+                        None => j.to_string(),
+                    };
+                    let ty = &f.ty;
+                    quote! {
+                        #rel_core::schema::Field {
+                            name: #name,
+                            offset: #rel_core::schema::enum_field_offset(
+                                #c_repr,
+                                #variant_fields,
+                                #i,
+                                #j,
+                            ),
+                            schema: <#ty as #rel_core::Portable>::SCHEMA,
+                        }
+                    }
+                });
+                quote! {
+                    #rel_core::schema::Variant {
+                        name: #variant_name,
+                        discriminant: #int_token::to_ne_bytes(#value)[0],
+                        fields: &[#(#fields,)*],
+                    }
+                }
+            });
+            quote! {
+                Enum {
+                    repr: #rel_core::schema::Repr::#schema_repr,
+                    discriminant: #rel_core::schema::Primitive::#discriminant,
+                    variants: &[#(#variants,)*],
+                }
+            }
+        }
+    };
+
+    let (size, align) = match &input.data {
+        Data::Struct(data) if has_unsized_last_field(input, &data.fields) => {
+            let tys = data.fields.iter().map(|f| &f.ty);
+            let repr_align = match &repr.modifier {
+                Some(Modifier {
+                    kind: ModifierKind::Align(ModifierArg { value, .. }),
+                    ..
+                }) => quote! { #value },
+                _ => quote! { 1 },
+            };
+            (
+                quote! { ::core::option::Option::None },
+                quote! {
+                    #rel_core::schema::unsized_struct_align(
+                        #repr_align,
+                        &[#(<#tys as #rel_core::Portable>::SCHEMA,)*],
+                    )
+                },
+            )
+        }
+        _ => (
+            quote! {
+                ::core::option::Option::Some(::core::mem::size_of::<Self>())
+            },
+            quote! { ::core::mem::align_of::<Self>() },
+        ),
+    };
+
+    quote! {
+        &#rel_core::schema::Schema {
+            name: #name,
+            size: #size,
+            align: #align,
+            kind: #rel_core::schema::SchemaKind::#kind,
+        }
+    }
+}
+
+/// Returns whether the given tokens contain `Self` or the identifier `ident`.
+fn mentions(tokens: TokenStream, ident: &Ident) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(i) => i == *ident || i == "Self",
+        TokenTree::Group(group) => mentions(group.stream(), ident),
+        _ => false,
+    })
+}
+
+/// Returns whether the last field of a struct is unsized.
+///
+/// A field is unsized if its type is a slice, `str`, or a type parameter with
+/// a `?Sized` bound.
+fn has_unsized_last_field(input: &DeriveInput, fields: &Fields) -> bool {
+    let Some(last) = fields.iter().next_back() else {
+        return false;
+    };
+    match &last.ty {
+        Type::Slice(_) => true,
+        Type::Path(path) if path.qself.is_none() => {
+            let Some(ident) = path.path.get_ident() else {
+                return false;
+            };
+            if ident == "str" {
+                return true;
+            }
+            input
+                .generics
+                .type_params()
+                .filter(|param| &param.ident == ident)
+                .flat_map(|param| param.bounds.iter())
+                .chain(
+                    input
+                        .generics
+                        .where_clause
+                        .iter()
+                        .flat_map(|clause| clause.predicates.iter())
+                        .filter_map(|predicate| match predicate {
+                            WherePredicate::Type(predicate) => Some(predicate),
+                            _ => None,
+                        })
+                        .filter(|predicate| {
+                            matches!(
+                                &predicate.bounded_ty,
+                                Type::Path(path) if path.path.is_ident(ident)
+                            )
+                        })
+                        .flat_map(|predicate| predicate.bounds.iter()),
+                )
+                .any(|bound| {
+                    matches!(
+                        bound,
+                        TypeParamBound::Trait(bound)
+                            if matches!(
+                                bound.modifier,
+                                TraitBoundModifier::Maybe(_),
+                            )
+                    )
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Returns the integer type of the discriminant of a `Portable` enum, along
+/// with its token.
+pub fn enum_int(repr: &Repr) -> (&Int, &Ident) {
+    match (&repr.base, &repr.primitive_type) {
+        (_, Some(primitive_type)) => {
+            (&primitive_type.int, &primitive_type.int_token)
+        }
+        (Some(base), None) => match &base.kind {
+            BaseKind::Primitive(int) => (int, &base.kind_token),
+            _ => unreachable!(),
+        },
+        (None, None) => unreachable!(),
+    }
+}
+
+/// Returns the schemas of the fields of each variant of an enum, as a nested
+/// slice suitable for `enum_field_offset`.
+pub fn variant_fields(data: &DataEnum, rel_core: &Path) -> TokenStream {
+    let variant_fields = data.variants.iter().map(|v| {
+        let tys = v.fields.iter().map(|f| &f.ty);
+        quote! {
+            &[#(<#tys as #rel_core::Portable>::SCHEMA,)*]
+        }
+    });
+    quote! { &[#(#variant_fields,)*] }
+}
+
+/// Returns an expression for the value of the discriminant of each variant of
+/// an enum.
+pub fn discriminants(data: &DataEnum) -> Vec<TokenStream> {
+    let mut prev_discriminant = None;
+    let mut count = 0usize;
+    data.variants
+        .iter()
+        .map(|v| {
+            if let Some((_, expr)) = &v.discriminant {
+                prev_discriminant = Some(expr.clone());
+                count = 0;
+            }
+            let count_lit = Literal::usize_unsuffixed(count);
+            let value = match &prev_discriminant {
+                Some(expr) => quote! { (#expr) + #count_lit },
+                None => quote! { #count_lit },
+            };
+            count += 1;
+            value
+        })
+        .collect()
+}
+
+fn struct_fields(
+    fields: &Fields,
+    unsized_last: bool,
+    rel_core: &Path,
+) -> Vec<TokenStream> {
+    let members = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| match &f.ident {
+            Some(ident) => (ident.unraw().to_string(), quote! { #ident }),
+            None => {
+                let index = Index::from(i);
+                (i.to_string(), quote! { #index })
+            }
+        })
+        .collect::<Vec<_>>();
+
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            let (name, member) = &members[i];
+            let ty = &f.ty;
+            let offset = if unsized_last && i == members.len() - 1 {
+                // `offset_of!` requires its field to be sized, so the offset
+                // of an unsized last field is computed from the end of the
+                // field before it.
+                let end = match i.checked_sub(1) {
+                    None => quote! { 0 },
+                    Some(prev) => {
+                        let prev_member = &members[prev].1;
+                        let prev_ty = &fields.iter().nth(prev).unwrap().ty;
+                        quote! {
+                            ::core::mem::offset_of!(Self, #prev_member)
+                                + ::core::mem::size_of::<#prev_ty>()
+                        }
+                    }
+                };
+                quote! {
+                    #rel_core::schema::unsized_field_offset(
+                        #end,
+                        <#ty as #rel_core::Portable>::SCHEMA,
+                    )
+                }
+            } else {
+                quote! { ::core::mem::offset_of!(Self, #member) }
+            };
+            quote! {
+                #rel_core::schema::Field {
+                    name: #name,
+                    offset: #offset,
+                    schema: <#ty as #rel_core::Portable>::SCHEMA,
+                }
+            }
+        })
+        .collect()
+}
+
+fn field(
+    name: &str,
+    member: TokenStream,
+    ty: &Type,
+    rel_core: &Path,
+) -> TokenStream {
+    quote! {
+        #rel_core::schema::Field {
+            name: #name,
+            offset: ::core::mem::offset_of!(Self, #member),
+            schema: <#ty as #rel_core::Portable>::SCHEMA,
+        }
+    }
+}
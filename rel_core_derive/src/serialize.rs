@@ -0,0 +1,305 @@
+use ::macroix::{visit_fields, AttrValue};
+use ::proc_macro2::{Ident, TokenStream};
+use ::quote::quote;
+use ::raw_enum::RawEnum;
+use ::syn::{
+    ext::IdentExt,
+    parse2,
+    parse_quote,
+    Data,
+    DeriveInput,
+    Error,
+    Fields,
+    Index,
+    Path,
+};
+
+pub fn derive(mut input: DeriveInput) -> Result<TokenStream, Error> {
+    let mut rel_core = None;
+    for attr in input.attrs.iter() {
+        if attr.path.is_ident("rel_core") {
+            rel_core =
+                Some(parse2::<AttrValue<Path>>(attr.tokens.clone())?.value);
+        }
+    }
+    let rel_core = rel_core.unwrap_or_else(|| parse_quote! { ::rel_core });
+
+    let where_clause = input.generics.make_where_clause();
+    visit_fields(&input.data, |f| {
+        let ty = &f.ty;
+        where_clause
+            .predicates
+            .push(parse_quote! { #ty: #rel_core::ser::SerializeRaw });
+    });
+
+    let name = &input.ident;
+    let name_str = name.unraw().to_string();
+
+    let (serialize, util) = match &input.data {
+        Data::Struct(data_struct) => {
+            let fields = &data_struct.fields;
+            let refs = field_refs(fields, &rel_core, false);
+            let serialize =
+                serialize_struct(&name_str, fields, &refs, &rel_core);
+            (
+                quote! {
+                    let this_ptr = #rel_core::export::situ::Ref::as_ptr(this);
+                    #serialize
+                },
+                None,
+            )
+        }
+        Data::Enum(data_enum) => {
+            let raw_enum = RawEnum::for_derive(&input)?;
+
+            let raw_variants = &raw_enum.idents.variants;
+            let raw_enum_fn = &raw_enum.idents.raw_enum_fn;
+            let raw_variant_fn = &raw_enum.idents.variant_fn;
+
+            let match_arms =
+                data_enum.variants.iter().enumerate().map(|(i, v)| {
+                    let ident = &v.ident;
+                    let index = u32::try_from(i).unwrap();
+                    let refs = field_refs(&v.fields, &rel_core, true);
+                    let serialize = serialize_variant(
+                        &name_str, index, ident, &v.fields, &refs, &rel_core,
+                    );
+                    quote! {
+                        #raw_variants::#ident(this_ptr) => {
+                            let this_ptr = this_ptr.cast_const();
+                            #serialize
+                        }
+                    }
+                });
+
+            (
+                quote! {
+                    let this_raw = #raw_enum_fn(
+                        #rel_core::export::situ::Ref::as_ptr(this).cast_mut(),
+                    );
+                    match #raw_variant_fn(this_raw) {
+                        #(#match_arms)*
+                    }
+                },
+                Some(raw_enum.tokens),
+            )
+        }
+        Data::Union(data_union) => {
+            return Err(Error::new_spanned(
+                data_union.union_token,
+                "`SerializeRaw` cannot be derived for unions",
+            ))
+        }
+    };
+
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+
+    Ok(quote! {
+        const _: () = {
+            #util
+
+            #[allow(unused_variables)]
+            impl #impl_generics #rel_core::ser::SerializeRaw
+                for #name #ty_generics
+            #where_clause
+            {
+                fn serialize_raw<__S>(
+                    this: #rel_core::export::situ::Ref<'_, Self>,
+                    serializer: __S,
+                ) -> ::core::result::Result<__S::Ok, __S::Error>
+                where
+                    __S: #rel_core::export::serde::Serializer,
+                {
+                    #serialize
+                }
+            }
+        };
+    })
+}
+
+/// Returns expressions which wrap a `Ref` to each of the fields pointed to by
+/// `this_ptr` in a `SerializeRef`.
+fn field_refs(
+    fields: &Fields,
+    rel_core: &Path,
+    skip_discriminant: bool,
+) -> Vec<TokenStream> {
+    let place = |field: TokenStream| {
+        quote! {
+            #rel_core::ser::SerializeRef(
+                // SAFETY: The field is borrowed from `this`, so it is
+                // non-null, properly aligned, valid for reads, not aliased by
+                // any mutable references, and initialized.
+                unsafe {
+                    #rel_core::export::situ::Ref::new_unchecked(
+                        ::core::ptr::addr_of!((*this_ptr).#field),
+                    )
+                }
+            )
+        }
+    };
+    match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|f| {
+                let ident = &f.ident;
+                place(quote! { #ident })
+            })
+            .collect(),
+        Fields::Unnamed(fields) => (0..fields.unnamed.len())
+            .map(|i| {
+                // In enum tuple structs, the tag is the first element so we
+                // have to skip over it.
+                let offset = if skip_discriminant { 1 } else { 0 };
+                let i = Index::from(i + offset);
+                place(quote! { #i })
+            })
+            .collect(),
+        Fields::Unit => Vec::new(),
+    }
+}
+
+fn field_names(fields: &Fields) -> Vec<String> {
+    match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|f| f.ident.as_ref().unwrap().unraw().to_string())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns an expression which serializes a struct the same way that
+/// `#[derive(Serialize)]` would.
+fn serialize_struct(
+    name: &str,
+    fields: &Fields,
+    refs: &[TokenStream],
+    rel_core: &Path,
+) -> TokenStream {
+    let serde = quote! { #rel_core::export::serde };
+    let len = refs.len();
+    match fields {
+        Fields::Named(_) => {
+            let names = field_names(fields);
+            quote! {
+                let mut state = #serde::Serializer::serialize_struct(
+                    serializer,
+                    #name,
+                    #len,
+                )?;
+                #(
+                    #serde::ser::SerializeStruct::serialize_field(
+                        &mut state,
+                        #names,
+                        &#refs,
+                    )?;
+                )*
+                #serde::ser::SerializeStruct::end(state)
+            }
+        }
+        Fields::Unnamed(_) if len == 1 => {
+            let field = &refs[0];
+            quote! {
+                #serde::Serializer::serialize_newtype_struct(
+                    serializer,
+                    #name,
+                    &#field,
+                )
+            }
+        }
+        Fields::Unnamed(_) => quote! {
+            let mut state = #serde::Serializer::serialize_tuple_struct(
+                serializer,
+                #name,
+                #len,
+            )?;
+            #(
+                #serde::ser::SerializeTupleStruct::serialize_field(
+                    &mut state,
+                    &#refs,
+                )?;
+            )*
+            #serde::ser::SerializeTupleStruct::end(state)
+        },
+        Fields::Unit => quote! {
+            #serde::Serializer::serialize_unit_struct(serializer, #name)
+        },
+    }
+}
+
+/// Returns an expression which serializes an enum variant the same way that
+/// `#[derive(Serialize)]` would.
+fn serialize_variant(
+    name: &str,
+    index: u32,
+    variant: &Ident,
+    fields: &Fields,
+    refs: &[TokenStream],
+    rel_core: &Path,
+) -> TokenStream {
+    let serde = quote! { #rel_core::export::serde };
+    let variant = variant.unraw().to_string();
+    let len = refs.len();
+    match fields {
+        Fields::Named(_) => {
+            let names = field_names(fields);
+            quote! {
+                let mut state = #serde::Serializer::serialize_struct_variant(
+                    serializer,
+                    #name,
+                    #index,
+                    #variant,
+                    #len,
+                )?;
+                #(
+                    #serde::ser::SerializeStructVariant::serialize_field(
+                        &mut state,
+                        #names,
+                        &#refs,
+                    )?;
+                )*
+                #serde::ser::SerializeStructVariant::end(state)
+            }
+        }
+        Fields::Unnamed(_) if len == 1 => {
+            let field = &refs[0];
+            quote! {
+                #serde::Serializer::serialize_newtype_variant(
+                    serializer,
+                    #name,
+                    #index,
+                    #variant,
+                    &#field,
+                )
+            }
+        }
+        Fields::Unnamed(_) => quote! {
+            let mut state = #serde::Serializer::serialize_tuple_variant(
+                serializer,
+                #name,
+                #index,
+                #variant,
+                #len,
+            )?;
+            #(
+                #serde::ser::SerializeTupleVariant::serialize_field(
+                    &mut state,
+                    &#refs,
+                )?;
+            )*
+            #serde::ser::SerializeTupleVariant::end(state)
+        },
+        Fields::Unit => quote! {
+            #serde::Serializer::serialize_unit_variant(
+                serializer,
+                #name,
+                #index,
+                #variant,
+            )
+        },
+    }
+}
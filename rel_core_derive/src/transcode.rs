@@ -0,0 +1,557 @@
+use ::macroix::{visit_fields, AttrValue};
+use ::proc_macro2::{Group, Ident, TokenStream, TokenTree};
+use ::quote::quote;
+use ::raw_enum::RawEnum;
+use ::syn::{
+    parse2,
+    parse_quote,
+    Data,
+    DeriveInput,
+    Error,
+    Fields,
+    GenericParam,
+    Generics,
+    Index,
+    LitInt,
+    Member,
+    Path,
+    TraitBoundModifier,
+    Type,
+    TypeParamBound,
+};
+
+use crate::version::{self, VersionedField};
+
+pub fn derive(input: DeriveInput) -> Result<TokenStream, Error> {
+    let mut rel_core = None;
+    let mut basis = None;
+    let mut allocator = None;
+    let mut version = None;
+    for attr in input.attrs.iter() {
+        if attr.path.is_ident("rel_core") {
+            rel_core =
+                Some(parse2::<AttrValue<Path>>(attr.tokens.clone())?.value);
+        } else if attr.path.is_ident("basis") {
+            basis =
+                Some(parse2::<AttrValue<Ident>>(attr.tokens.clone())?.value);
+        } else if attr.path.is_ident("allocator") {
+            allocator =
+                Some(parse2::<AttrValue<Ident>>(attr.tokens.clone())?.value);
+        } else if attr.path.is_ident("version") {
+            version =
+                Some(parse2::<AttrValue<LitInt>>(attr.tokens.clone())?.value);
+        }
+    }
+    let rel_core = rel_core.unwrap_or_else(|| parse_quote! { ::rel_core });
+
+    let transcode = quote! {
+        #rel_core::transcode::Transcode<__B, __A, __E>
+    };
+
+    // Every type parameter is replaced with its transcoded counterpart: the
+    // basis and allocator parameters become the target basis and allocator,
+    // and all others become their `Transcode::Target`.
+    let mut substitutions = Vec::new();
+    for param in input.generics.type_params() {
+        let ident = &param.ident;
+        let target = if Some(ident) == basis.as_ref() {
+            quote! { __B }
+        } else if Some(ident) == allocator.as_ref() {
+            quote! { __E }
+        } else {
+            quote! { <#ident as #transcode>::Target }
+        };
+        substitutions.push((ident.clone(), target));
+    }
+    for (ident, name) in [(&basis, "basis"), (&allocator, "allocator")] {
+        if let Some(ident) = ident {
+            if !substitutions.iter().any(|(from, _)| from == ident) {
+                return Err(Error::new_spanned(
+                    ident,
+                    format!("the {name} must be a type parameter"),
+                ));
+            }
+        }
+    }
+
+    let mut transcode_generics = input.generics.clone();
+    transcode_generics
+        .params
+        .push(parse_quote! { __B: #rel_core::Basis });
+    transcode_generics.params.push(parse_quote! {
+        __A: #rel_core::export::mischief::RegionalAllocator
+    });
+    transcode_generics.params.push(parse_quote! { __E });
+
+    let ty_name = &input.ident;
+    let target_args = input.generics.params.iter().map(|param| match param {
+        GenericParam::Type(param) => substitute(
+            {
+                let ident = &param.ident;
+                quote! { #ident }
+            },
+            &substitutions,
+        ),
+        GenericParam::Lifetime(param) => {
+            let lifetime = &param.lifetime;
+            quote! { #lifetime }
+        }
+        GenericParam::Const(param) => {
+            let ident = &param.ident;
+            quote! { #ident }
+        }
+    });
+    let target = quote! { #ty_name<#(#target_args,)*> };
+
+    let where_clause = transcode_generics.make_where_clause();
+    where_clause.predicates.push(parse_quote! {
+        Self: #rel_core::export::situ::DropRaw
+    });
+    where_clause.predicates.push(parse_quote! {
+        #target: #rel_core::export::situ::DropRaw + #rel_core::Portable
+    });
+    for param in input.generics.type_params() {
+        let ident = &param.ident;
+        if Some(ident) != basis.as_ref() && Some(ident) != allocator.as_ref() {
+            where_clause
+                .predicates
+                .push(parse_quote! { #ident: #transcode });
+        }
+
+        // The transcoded parameters must satisfy the same bounds as the
+        // original parameters for the target type to be well-formed.
+        let bounds = param
+            .bounds
+            .iter()
+            .filter(|bound| {
+                !matches!(
+                    bound,
+                    TypeParamBound::Trait(bound)
+                        if matches!(bound.modifier, TraitBoundModifier::Maybe(_))
+                )
+            })
+            .map(|bound| substitute(quote! { #bound }, &substitutions))
+            .collect::<Vec<_>>();
+        if !bounds.is_empty() {
+            let target = substitute(quote! { #ident }, &substitutions);
+            where_clause
+                .predicates
+                .push(parse_quote! { #target: #(#bounds)+* });
+        }
+    }
+    if let Some(input_where_clause) = &input.generics.where_clause {
+        for predicate in input_where_clause.predicates.iter() {
+            where_clause.predicates.push(parse2(substitute(
+                quote! { #predicate },
+                &substitutions,
+            ))?);
+        }
+    }
+    visit_fields(&input.data, |f| {
+        let ty = &f.ty;
+        // Fields which are type parameters are already bounded above, and
+        // bounding their `Target` by itself would overflow.
+        let is_param = input
+            .generics
+            .type_params()
+            .any(|param| matches!(ty, Type::Path(ty) if ty.path.is_ident(&param.ident)));
+        let is_basis_int = basis
+            .as_ref()
+            .and_then(|basis| basis_int(ty, basis))
+            .is_some();
+        if !is_param && !is_basis_int {
+            let target = substitute(quote! { #ty }, &substitutions);
+            where_clause.predicates.push(parse_quote! {
+                #ty: #rel_core::transcode::Transcode<
+                    __B,
+                    __A,
+                    __E,
+                    Target = #target,
+                >
+            });
+        }
+    });
+
+    let (body, util) = match &input.data {
+        Data::Enum(data_enum) => {
+            let raw_enum = RawEnum::for_derive(&input)?;
+
+            let raw_variants = &raw_enum.idents.variants;
+            let raw_enum_fn = &raw_enum.idents.raw_enum_fn;
+            let raw_discriminant_fn = &raw_enum.idents.discriminant_fn;
+            let raw_variant_fn = &raw_enum.idents.variant_fn;
+
+            let match_arms = data_enum.variants.iter().map(|v| {
+                let ident = &v.ident;
+                let transcode_variant = transcode_fields(
+                    &v.fields,
+                    &rel_core,
+                    basis.as_ref(),
+                    true,
+                );
+                quote! {
+                    #raw_variants::#ident(this_ptr) => {
+                        match #raw_variant_fn(out_raw) {
+                            #raw_variants::#ident(out_ptr) => {
+                                #transcode_variant
+                            },
+                            // SAFETY: `this` and `out` must be the same variant
+                            // because we copied the discriminant from `this` to
+                            // out.
+                            _ => unsafe {
+                                ::core::hint::unreachable_unchecked();
+                            },
+                        }
+                    }
+                }
+            });
+
+            (
+                quote! {
+                    let this_raw = #raw_enum_fn(this_ptr.cast_mut());
+                    let out_raw = #raw_enum_fn(out_ptr);
+                    let this_discriminant = #raw_discriminant_fn(this_raw);
+                    let out_discriminant = #raw_discriminant_fn(out_raw);
+                    // SAFETY: The discriminant of `this` is initialized, and
+                    // the discriminant of `out` is valid for writes.
+                    unsafe {
+                        *out_discriminant = *this_discriminant;
+                    }
+
+                    match #raw_variant_fn(this_raw) {
+                        #(#match_arms)*
+                    }
+                },
+                Some(raw_enum.tokens),
+            )
+        }
+        Data::Struct(data_struct) => (
+            transcode_fields(
+                &data_struct.fields,
+                &rel_core,
+                basis.as_ref(),
+                false,
+            ),
+            None,
+        ),
+        Data::Union(data_union) => {
+            return Err(Error::new_spanned(
+                data_union.union_token,
+                "`Transcode` cannot be derived for unions",
+            ))
+        }
+    };
+
+    let migrate = version
+        .map(|version| {
+            migrate(&input, &version, &transcode_generics, &target, &rel_core)
+        })
+        .transpose()?;
+
+    let (impl_generics, _, where_clause) = transcode_generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    Ok(quote! {
+        const _: () = {
+            #util
+
+            // SAFETY: `transcode` initializes its `out` parameter by
+            // destructuring it and transcoding all of the fields. The fields
+            // convert the allocations they own to the target endianness.
+            #[allow(non_snake_case, unused_variables)]
+            unsafe impl #impl_generics #transcode for #ty_name #ty_generics
+            #where_clause
+            {
+                type Target = #target;
+
+                fn transcode(
+                    this: #rel_core::export::situ::Ref<'_, Self>,
+                    transcoder: &#rel_core::transcode::Transcoder<
+                        __B,
+                        __A,
+                        __E,
+                    >,
+                    out: #rel_core::export::mischief::In<
+                        #rel_core::export::mischief::Slot<'_, Self::Target>,
+                        <__A as #rel_core::export::mischief::RegionalAllocator>
+                            ::Region,
+                    >,
+                ) -> ::core::result::Result<(), #rel_core::EmplaceError> {
+                    let this_ptr = #rel_core::export::situ::Ref::as_ptr(this);
+                    let out_ptr = #rel_core::export::mischief::Pointer::target(
+                        out.ptr(),
+                    );
+
+                    #body
+
+                    ::core::result::Result::Ok(())
+                }
+            }
+
+            #migrate
+        };
+    })
+}
+
+/// Returns an implementation of `Migrate` for a versioned struct, which
+/// transcodes the fields present in the version of a value and emplaces the
+/// default values of the rest.
+fn migrate(
+    input: &DeriveInput,
+    version: &LitInt,
+    transcode_generics: &Generics,
+    target: &TokenStream,
+    rel_core: &Path,
+) -> Result<TokenStream, Error> {
+    let versioned_fields = version::versioned_fields(input, version)?;
+    let basis = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path.is_ident("basis"))
+        .map(|attr| parse2::<AttrValue<Ident>>(attr.tokens.clone()))
+        .transpose()?
+        .map(|attr| attr.value);
+
+    let mut migrate_generics = transcode_generics.clone();
+    let where_clause = migrate_generics.make_where_clause();
+    where_clause
+        .predicates
+        .push(parse_quote! { Self: #rel_core::version::Versioned });
+    where_clause
+        .predicates
+        .push(parse_quote! { #target: #rel_core::version::Versioned });
+
+    let migrate_fields = versioned_fields.iter().map(|field| {
+        let VersionedField {
+            member,
+            ty,
+            since,
+            default_value,
+            ..
+        } = field;
+        let transcode_field = transcode_field(ty, rel_core, basis.as_ref());
+        let migrate_field = match default_value {
+            Some(default_value) if *since > 1 => quote! {
+                if version >= #since {
+                    #transcode_field
+                } else {
+                    // SAFETY: `out_field` is a pointer to a subfield of the
+                    // slot being migrated into, so it is non-null, properly
+                    // aligned, valid for reads and writes, and not aliased.
+                    let out_field = unsafe {
+                        #rel_core::export::mischief::Slot::new_unchecked(
+                            out_field,
+                        )
+                    };
+                    // SAFETY: `out_field` is a subfield of the slot being
+                    // migrated into, so it must be contained in the same region
+                    // as it.
+                    let out_field = unsafe {
+                        #rel_core::export::mischief::In::<
+                            _,
+                            <__A as #rel_core::export::mischief::RegionalAllocator>
+                                ::Region,
+                        >::new_unchecked(out_field)
+                    };
+                    #rel_core::EmplaceExt::emplace(#default_value, out_field);
+                }
+            },
+            _ => transcode_field,
+        };
+        quote! {
+            // SAFETY: `this_ptr` points to a value which has all of the fields
+            // present in `version`, and `out_ptr` points to the slot being
+            // migrated into, so they are valid for projecting to their fields.
+            // The field of `this_ptr` is only read if it is present.
+            let (this_field, out_field) = unsafe {
+                (
+                    ::core::ptr::addr_of!((*this_ptr).#member),
+                    ::core::ptr::addr_of_mut!((*out_ptr).#member),
+                )
+            };
+            #migrate_field
+        }
+    });
+
+    let (impl_generics, _, where_clause) = migrate_generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let ty_name = &input.ident;
+    Ok(quote! {
+        // SAFETY: `migrate` initializes its `out` parameter by transcoding
+        // each field present in the version of `from` and emplacing the
+        // default value of each field that is missing. The fields convert the
+        // allocations they own to the target endianness. The target is the
+        // same struct as the target of `Transcode`.
+        #[allow(non_snake_case, unused_variables)]
+        unsafe impl #impl_generics #rel_core::version::Migrate<__B, __A, __E>
+            for #ty_name #ty_generics
+        #where_clause
+        {
+            type Target = #target;
+
+            fn migrate(
+                from: #rel_core::version::VersionedRef<'_, Self>,
+                transcoder: &#rel_core::transcode::Transcoder<__B, __A, __E>,
+                out: #rel_core::export::mischief::In<
+                    #rel_core::export::mischief::Slot<'_, Self::Target>,
+                    <__A as #rel_core::export::mischief::RegionalAllocator>
+                        ::Region,
+                >,
+            ) -> ::core::result::Result<(), #rel_core::EmplaceError> {
+                let version = #rel_core::version::VersionedRef::version(from);
+                let this_ptr = #rel_core::version::VersionedRef::as_ptr(from);
+                let out_ptr = #rel_core::export::mischief::Pointer::target(
+                    out.ptr(),
+                );
+
+                #(#migrate_fields)*
+
+                ::core::result::Result::Ok(())
+            }
+        }
+    })
+}
+
+/// Replaces each of the identifiers in `substitutions` with its replacement.
+fn substitute(
+    tokens: TokenStream,
+    substitutions: &[(Ident, TokenStream)],
+) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|tt| match tt {
+            TokenTree::Ident(ident) => substitutions
+                .iter()
+                .find(|(from, _)| *from == ident)
+                .map_or_else(|| quote! { #ident }, |(_, to)| to.clone()),
+            TokenTree::Group(group) => {
+                let mut substituted = Group::new(
+                    group.delimiter(),
+                    substitute(group.stream(), substitutions),
+                );
+                substituted.set_span(group.span());
+                quote! { #substituted }
+            }
+            tt => quote! { #tt },
+        })
+        .collect()
+}
+
+/// Returns `Isize` or `Usize` if `ty` is that associated type of `basis`.
+fn basis_int<'a>(ty: &'a Type, basis: &Ident) -> Option<&'a Ident> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let int = &path.path.segments.last()?.ident;
+    if int != "Isize" && int != "Usize" {
+        return None;
+    }
+    let is_basis = match &path.qself {
+        // `<B as Basis>::Usize`
+        Some(qself) => {
+            matches!(&*qself.ty, Type::Path(ty) if ty.path.is_ident(basis))
+        }
+        // `B::Usize`
+        None => {
+            path.path.segments.len() == 2
+                && path.path.segments[0].ident == *basis
+        }
+    };
+    is_basis.then_some(int)
+}
+
+fn transcode_field(
+    ty: &Type,
+    rel_core: &Path,
+    basis: Option<&Ident>,
+) -> TokenStream {
+    if let Some((basis, int)) =
+        basis.and_then(|basis| Some((basis, basis_int(ty, basis)?)))
+    {
+        let transcode_int = if int == "Isize" {
+            quote! { transcode_isize }
+        } else {
+            quote! { transcode_usize }
+        };
+        return quote! {
+            // SAFETY: `this_field` is a subfield of the value being transcoded,
+            // so it is properly aligned and valid for reads.
+            let value = unsafe { this_field.read() };
+            let value =
+                #rel_core::transcode::#transcode_int::<#basis, __B>(value)?;
+            // SAFETY: `out_field` is a subfield of the slot being transcoded
+            // into, so it is properly aligned and valid for writes.
+            unsafe {
+                out_field.write(value);
+            }
+        };
+    }
+
+    quote! {
+        // SAFETY:
+        // - `this_field` is a subfield of the value being transcoded, and so is
+        //   guaranteed to be non-null, properly aligned, and valid for reads.
+        // - `this_field` is derived from a shared reference that is borrowed
+        //   for the duration of the transcode, so it cannot alias any mutable
+        //   references.
+        // - Because `this_field` is a subfield of the value being transcoded,
+        //   and that value is initialized and immovable, the value pointed to
+        //   by `this_field` is also initialized and immovable.
+        let this_field = unsafe {
+            #rel_core::export::situ::Ref::new_unchecked(this_field)
+        };
+        // SAFETY:
+        // - `out_field` is a pointer to a subfield of the slot being transcoded
+        //   into, and so is guaranteed to be non-null, properly aligned, and
+        //   valid for reads and writes.
+        // - `out_field` is the only pointer to the subfield we created, so it
+        //   cannot alias any other accessible references for its lifetime.
+        let out_field = unsafe {
+            #rel_core::export::mischief::Slot::new_unchecked(out_field)
+        };
+        // SAFETY: `out_field` is a subfield of the slot being transcoded into,
+        // so it must be contained in the same region as it.
+        let out_field = unsafe {
+            #rel_core::export::mischief::In::new_unchecked(out_field)
+        };
+        <#ty as #rel_core::transcode::Transcode<__B, __A, __E>>::transcode(
+            this_field,
+            transcoder,
+            out_field,
+        )?;
+    }
+}
+
+fn transcode_fields(
+    fields: &Fields,
+    rel_core: &Path,
+    basis: Option<&Ident>,
+    skip_discriminant: bool,
+) -> TokenStream {
+    let transcode_fields = fields.iter().enumerate().map(|(i, f)| {
+        let member = match &f.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            // In enum tuple structs, the tag is the first element so we have to
+            // skip over it.
+            None => Member::Unnamed(Index::from(
+                i + if skip_discriminant { 1 } else { 0 },
+            )),
+        };
+        let transcode_field = transcode_field(&f.ty, rel_core, basis);
+        quote! {
+            // SAFETY: `this_ptr` and `out_ptr` point to the value being
+            // transcoded and the slot being transcoded into, so they are valid
+            // for projecting to their fields.
+            let (this_field, out_field) = unsafe {
+                (
+                    ::core::ptr::addr_of!((*this_ptr).#member),
+                    ::core::ptr::addr_of_mut!((*out_ptr).#member),
+                )
+            };
+            #transcode_field
+        }
+    });
+
+    quote! {
+        #(#transcode_fields)*
+    }
+}
@@ -0,0 +1,275 @@
+use ::macroix::AttrValue;
+use ::proc_macro2::{Span, TokenStream};
+use ::quote::{format_ident, quote};
+use ::syn::{
+    ext::IdentExt,
+    parse2,
+    parse_quote,
+    Data,
+    DeriveInput,
+    Error,
+    Expr,
+    Index,
+    LitInt,
+    Member,
+    Path,
+    Visibility,
+};
+
+/// A field of a versioned struct.
+pub struct VersionedField<'a> {
+    pub member: Member,
+    pub vis: &'a Visibility,
+    pub ty: &'a ::syn::Type,
+    pub since: u16,
+    pub default_value: Option<Expr>,
+}
+
+/// Parses the fields of a versioned struct, checking that fields added in
+/// later versions come after all of the fields from earlier versions.
+pub fn versioned_fields<'a>(
+    input: &'a DeriveInput,
+    version: &LitInt,
+) -> Result<Vec<VersionedField<'a>>, Error> {
+    let version_value = version.base10_parse::<u16>()?;
+    if version_value == 0 {
+        return Err(Error::new_spanned(version, "versions must start at 1"));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "only structs can be versioned",
+            ))
+        }
+    };
+
+    let mut versioned_fields = Vec::new();
+    let mut prev_since = 1;
+    for (i, field) in fields.iter().enumerate() {
+        let mut since = None;
+        let mut default_value = None;
+        for attr in field.attrs.iter() {
+            if attr.path.is_ident("since") {
+                since = Some(
+                    parse2::<AttrValue<LitInt>>(attr.tokens.clone())?.value,
+                );
+            } else if attr.path.is_ident("default_value") {
+                default_value =
+                    Some(parse2::<AttrValue<Expr>>(attr.tokens.clone())?.value);
+            }
+        }
+
+        let since_value = match &since {
+            Some(since) => since.base10_parse::<u16>()?,
+            None => 1,
+        };
+        if since_value == 0 || since_value > version_value {
+            return Err(Error::new_spanned(
+                since,
+                "fields must be added in a version between 1 and the current \
+                    version",
+            ));
+        }
+        if since_value < prev_since {
+            return Err(Error::new_spanned(
+                since.map_or_else(|| quote! { #field }, |s| quote! { #s }),
+                "fields added in later versions must come after all fields \
+                    from earlier versions",
+            ));
+        }
+        if since_value > 1 && default_value.is_none() {
+            return Err(Error::new_spanned(
+                field,
+                "fields added after the first version must have a \
+                    `#[default_value = \"...\"]`",
+            ));
+        }
+        prev_since = since_value;
+
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(i)),
+        };
+        versioned_fields.push(VersionedField {
+            member,
+            vis: &field.vis,
+            ty: &field.ty,
+            since: since_value,
+            default_value,
+        });
+    }
+
+    Ok(versioned_fields)
+}
+
+pub fn derive(
+    input: &DeriveInput,
+    version: &LitInt,
+    rel_core: &Path,
+) -> Result<TokenStream, Error> {
+    let version_value = version.base10_parse::<u16>()?;
+    let versioned_fields = versioned_fields(input, version)?;
+
+    let sizes = (1..=version_value).map(|v| {
+        match versioned_fields.iter().rev().find(|f| f.since <= v) {
+            Some(VersionedField { member, ty, .. }) => quote! {
+                ::core::mem::offset_of!(Self, #member)
+                    + ::core::mem::size_of::<#ty>()
+            },
+            None => quote! { 0 },
+        }
+    });
+
+    // Values written by version 1 are only aligned for the fields present in
+    // it, so later fields must not raise the alignment of the struct.
+    let v1_aligns = versioned_fields.iter().filter(|f| f.since == 1).map(
+        |VersionedField { ty, .. }| {
+            quote! {
+                if ::core::mem::align_of::<#ty>() > align {
+                    align = ::core::mem::align_of::<#ty>();
+                }
+            }
+        },
+    );
+    let check_align = quote! {
+        let mut align = 1;
+        #(#v1_aligns)*
+        ::core::assert!(
+            ::core::mem::align_of::<Self>() == align,
+            "fields added after the first version must not change the \
+                alignment of a versioned struct",
+        );
+    };
+
+    let mut versioned_generics = input.generics.clone();
+    versioned_generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote! { Self: #rel_core::export::situ::DropRaw });
+
+    let where_clause = versioned_generics.make_where_clause();
+    for field in versioned_fields.iter() {
+        let ty = field.ty;
+        where_clause
+            .predicates
+            .push(parse_quote! { #ty: #rel_core::export::situ::DropRaw });
+    }
+
+    let drop_fields = versioned_fields.iter().map(|field| {
+        let VersionedField {
+            member, ty, since, ..
+        } = field;
+        quote! {
+            if version >= #since {
+                // SAFETY: The field is present in `version`, so the caller has
+                // guaranteed that it is initialized, valid for reads and
+                // writes, and not accessed after it is dropped.
+                unsafe {
+                    <#ty as #rel_core::export::situ::DropRaw>::drop_raw(
+                        #rel_core::export::situ::Mut::new_unchecked(
+                            ::core::ptr::addr_of_mut!((*this).#member),
+                        ),
+                    );
+                }
+            }
+        }
+    });
+
+    let readers = versioned_fields.iter().map(|field| reader(field, rel_core));
+
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let (versioned_impl_generics, _, versioned_where_clause) =
+        versioned_generics.split_for_impl();
+    let ty_name = &input.ident;
+    Ok(quote! {
+        // SAFETY: Fields added in later versions must come after all of the
+        // fields from earlier versions, and the size of each version is the
+        // end of the last field present in it. Evaluating `VERSION` checks that
+        // the alignment of the struct is the alignment of the fields present
+        // in version 1. `drop_version` drops each field that was added in or
+        // before `version`.
+        unsafe impl #versioned_impl_generics #rel_core::version::Versioned
+            for #ty_name #ty_generics
+        #versioned_where_clause
+        {
+            const VERSION: u16 = {
+                #check_align
+                #version
+            };
+            const SIZES: &'static [usize] = &[#(#sizes,)*];
+
+            unsafe fn drop_version(this: *mut Self, version: u16) {
+                #(#drop_fields)*
+            }
+        }
+
+        impl #versioned_impl_generics #ty_name #ty_generics
+        #versioned_where_clause
+        {
+            #(#readers)*
+        }
+    })
+}
+
+fn reader(field: &VersionedField<'_>, rel_core: &Path) -> TokenStream {
+    let VersionedField {
+        member,
+        vis,
+        ty,
+        since,
+        ..
+    } = field;
+    let name = match member {
+        Member::Named(ident) => ident.unraw().to_string(),
+        Member::Unnamed(index) => index.index.to_string(),
+    };
+    let reader = format_ident!("versioned_{}", name);
+
+    let field_ref = quote! {
+        let this_ptr = #rel_core::version::VersionedRef::as_ptr(this);
+        // SAFETY: The field is present in the version of `this`, so it is
+        // non-null, properly aligned, valid for reads, and initialized. `this`
+        // is borrowed for `'__a`, so it cannot alias any mutable references.
+        unsafe {
+            #rel_core::export::situ::Ref::new_unchecked(
+                ::core::ptr::addr_of!((*this_ptr).#member),
+            )
+        }
+    };
+
+    if *since == 1 {
+        let doc =
+            format!("Returns the `{name}` field of a value of any version.");
+        quote! {
+            #[doc = #doc]
+            #[allow(dead_code)]
+            #vis fn #reader<'__a>(
+                this: #rel_core::version::VersionedRef<'__a, Self>,
+            ) -> #rel_core::export::situ::Ref<'__a, #ty> {
+                #field_ref
+            }
+        }
+    } else {
+        let doc = format!(
+            "Returns the `{name}` field of a value, or `None` if the value \
+            was written by a version before {since}."
+        );
+        quote! {
+            #[doc = #doc]
+            #[allow(dead_code)]
+            #vis fn #reader<'__a>(
+                this: #rel_core::version::VersionedRef<'__a, Self>,
+            ) -> ::core::option::Option<
+                #rel_core::export::situ::Ref<'__a, #ty>,
+            > {
+                if #rel_core::version::VersionedRef::version(this) < #since {
+                    return ::core::option::Option::None;
+                }
+                ::core::option::Option::Some({ #field_ref })
+            }
+        }
+    }
+}
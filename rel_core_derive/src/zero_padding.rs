@@ -0,0 +1,145 @@
+use ::macroix::{repr::Repr, visit_fields};
+use ::proc_macro2::{Span, TokenStream};
+use ::quote::quote;
+use ::syn::{parse_quote, Data, DeriveInput, Error, Index, Path};
+
+use crate::portable::{discriminants, enum_int, variant_fields};
+
+pub fn derive(
+    input: &DeriveInput,
+    repr: &Repr,
+    rel_core: &Path,
+) -> Result<TokenStream, Error> {
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let members = data
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(i, f)| match &f.ident {
+                    Some(ident) => quote! { #ident },
+                    None => {
+                        let index = Index::from(i);
+                        quote! { #index }
+                    }
+                })
+                .collect::<Vec<_>>();
+            let tys = data.fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
+
+            quote! {
+                let ptr = #rel_core::export::situ::Mut::as_ptr(&this);
+                // SAFETY: `ptr` points to a `Self`, and the bytes which are
+                // not covered by any of its fields are padding.
+                unsafe {
+                    #rel_core::padding::zero_gaps(
+                        ptr.cast::<u8>(),
+                        0,
+                        ::core::mem::size_of::<Self>(),
+                        &[#((
+                            ::core::mem::offset_of!(Self, #members),
+                            ::core::mem::size_of::<#tys>(),
+                        ),)*],
+                    );
+                }
+                #(
+                    // SAFETY: The field is initialized, and `this` is not
+                    // accessed while the returned `Mut` is alive.
+                    <#tys as #rel_core::padding::ZeroPadding>::zero_padding(
+                        unsafe {
+                            #rel_core::export::situ::Mut::new_unchecked(
+                                ::core::ptr::addr_of_mut!((*ptr).#members),
+                            )
+                        },
+                    );
+                )*
+            }
+        }
+        Data::Enum(data) => {
+            let (_, int_token) = enum_int(repr);
+            let c_repr = repr.primitive_type.is_some();
+            let variant_fields = variant_fields(data, rel_core);
+            let values = discriminants(data);
+
+            let variants = data.variants.iter().enumerate().map(|(i, v)| {
+                let value = &values[i];
+                let indices = (0..v.fields.len()).collect::<Vec<_>>();
+                let tys = v.fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
+                quote! {
+                    if discriminant == #int_token::to_ne_bytes(#value)[0] {
+                        let fields: &[(usize, usize)] = &[#((
+                            #rel_core::schema::enum_field_offset(
+                                #c_repr,
+                                #variant_fields,
+                                #i,
+                                #indices,
+                            ),
+                            ::core::mem::size_of::<#tys>(),
+                        ),)*];
+                        // SAFETY: `ptr` points to a `Self` with this variant,
+                        // and the bytes which are not covered by its
+                        // discriminant or fields are padding.
+                        unsafe {
+                            #rel_core::padding::zero_gaps(
+                                ptr,
+                                1,
+                                ::core::mem::size_of::<Self>(),
+                                fields,
+                            );
+                        }
+                        #(
+                            // SAFETY: The field is initialized at its offset,
+                            // and `this` is not accessed while the returned
+                            // `Mut` is alive.
+                            <#tys as #rel_core::padding::ZeroPadding>
+                                ::zero_padding(unsafe {
+                                    #rel_core::export::situ::Mut::new_unchecked(
+                                        ptr.add(fields[#indices].0)
+                                            .cast::<#tys>(),
+                                    )
+                                });
+                        )*
+                    } else
+                }
+            });
+
+            quote! {
+                let ptr =
+                    #rel_core::export::situ::Mut::as_ptr(&this).cast::<u8>();
+                // SAFETY: `Portable` enums always start with an initialized
+                // one-byte discriminant.
+                let discriminant = unsafe { *ptr };
+                #(#variants)* {}
+            }
+        }
+        Data::Union(_) => {
+            return Err(Error::new(
+                Span::call_site(),
+                "`zero_padding` is not supported for unions",
+            ))
+        }
+    };
+
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    visit_fields(&input.data, |f| {
+        let ty = &f.ty;
+        where_clause
+            .predicates
+            .push(parse_quote! { #ty: #rel_core::padding::ZeroPadding });
+    });
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ty_name = &input.ident;
+    Ok(quote! {
+        // SAFETY: `zero_padding` zeroes the bytes of this type which are not
+        // covered by its fields, then zeroes the padding of each field.
+        unsafe impl #impl_generics #rel_core::padding::ZeroPadding
+            for #ty_name #ty_generics #where_clause
+        {
+            #[inline]
+            fn zero_padding(this: #rel_core::export::situ::Mut<'_, Self>) {
+                #body
+            }
+        }
+    })
+}
@@ -0,0 +1,153 @@
+//! The command-line interface of `rel-inspect`.
+//!
+//! The schemas of the root types that can be inspected must be known at
+//! compile time. A crate can build its own inspector for its types by calling
+//! [`run`] from its own binary:
+//!
+//! ```ignore
+//! fn main() -> std::process::ExitCode {
+//!     rel_inspect::cli::run(&[Root {
+//!         name: "MyRoot",
+//!         bits: Some(32),
+//!         schema: <MyRoot<MyAlloc<Basis32>, Basis32> as Portable>::SCHEMA,
+//!     }])
+//! }
+//! ```
+
+use ::core::mem::size_of;
+use ::rel_core::{schema::Schema, transcode::Endianness, Basis, DefaultBasis};
+use ::std::{env, fs, process::ExitCode};
+
+use crate::{inspect, slab_header_schema, Options};
+
+/// A type which can be selected as the root of a buffer with `--root`.
+#[derive(Clone, Copy, Debug)]
+pub struct Root {
+    /// The name which selects the type.
+    pub name: &'static str,
+    /// The number of bits of the basis that the type is laid out with, or
+    /// `None` if its layout does not depend on the basis.
+    pub bits: Option<u32>,
+    /// The schema of the type.
+    pub schema: &'static Schema,
+}
+
+const USAGE: &str = "\
+usage: rel-inspect [options] <file>
+
+options:
+    --json                  print the report as JSON
+    --basis <16|32|64>      the basis the buffer was written in
+    --endian <little|big>   the endianness the buffer was written in
+    --root <type>           the name of the type of the root
+    --help                  print this message";
+
+struct Args {
+    path: String,
+    json: bool,
+    bits: u32,
+    endianness: Endianness,
+    root: Option<String>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let default_bits = size_of::<<DefaultBasis as Basis>::Usize>() * 8;
+    let mut result = Args {
+        path: String::new(),
+        json: false,
+        bits: default_bits.try_into().unwrap(),
+        endianness: Endianness::CURRENT,
+        root: None,
+    };
+    let mut path = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = |name| {
+            args.next()
+                .ok_or_else(|| format!("missing value for `{name}`"))
+        };
+        match arg.as_str() {
+            "--json" => result.json = true,
+            "--basis" => {
+                let bits = value("--basis")?;
+                result.bits = bits
+                    .parse()
+                    .map_err(|_| format!("invalid basis `{bits}`"))?;
+            }
+            "--endian" => {
+                result.endianness = match value("--endian")?.as_str() {
+                    "little" => Endianness::Little,
+                    "big" => Endianness::Big,
+                    other => {
+                        return Err(format!("invalid endianness `{other}`"))
+                    }
+                };
+            }
+            "--root" => result.root = Some(value("--root")?),
+            "--help" => return Err(USAGE.to_string()),
+            _ if arg.starts_with("--") => {
+                return Err(format!("unknown option `{arg}`"))
+            }
+            _ if path.is_none() => path = Some(arg),
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+
+    result.path = path.ok_or_else(|| USAGE.to_string())?;
+    Ok(result)
+}
+
+fn try_run(roots: &[Root]) -> Result<(), String> {
+    let args = parse_args(env::args().skip(1))?;
+
+    let header = slab_header_schema(args.bits)
+        .ok_or_else(|| format!("unsupported basis `{}`", args.bits))?;
+    let root = match &args.root {
+        Some(name) => Some(
+            roots
+                .iter()
+                .find(|root| {
+                    root.name == name
+                        && root.bits.unwrap_or(args.bits) == args.bits
+                })
+                .ok_or_else(|| format!("unknown root type `{name}`"))?
+                .schema,
+        ),
+        None => None,
+    };
+    let options = Options {
+        header,
+        root,
+        endianness: args.endianness,
+    };
+
+    let bytes = fs::read(&args.path)
+        .map_err(|e| format!("failed to read `{}`: {e}", args.path))?;
+    let report = inspect(&bytes, &options).map_err(|e| e.to_string())?;
+
+    if args.json {
+        println!("{}", report.json());
+    } else {
+        print!("{report}");
+    }
+
+    if report.out_of_bounds().next().is_some() {
+        Err("found out-of-bounds pointers".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// Runs `rel-inspect` with the command-line arguments of the process.
+///
+/// `roots` are the types which can be selected as the root of the buffer with
+/// `--root`.
+pub fn run(roots: &[Root]) -> ExitCode {
+    match try_run(roots) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
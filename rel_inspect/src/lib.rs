@@ -0,0 +1,293 @@
+//! Inspection of buffers written through a `Prefix` allocator.
+//!
+//! A buffer written through a [`Prefix`](rel_allocators::prefix::Prefix)
+//! allocator starts with a header that holds the capacity of the buffer and
+//! the state of its control. The memory managed by the allocator immediately
+//! follows the header, and the first allocation made in it is the root of the
+//! buffer.
+//!
+//! [`inspect`] reads the header of a buffer using its [`Schema`], and if the
+//! schema of the root is known, walks the object graph starting from the root.
+//! Every relative and base pointer found along the way is checked to point to a
+//! value that lies within the header or the allocated memory of the buffer, and
+//! is then followed using the schema of its pointee. Each value is walked at
+//! most once, so cyclic and shared pointees are only printed the first time
+//! they are reached.
+
+#![deny(
+    missing_docs,
+    unsafe_op_in_unsafe_fn,
+    clippy::as_conversions,
+    clippy::missing_safety_doc,
+    clippy::undocumented_unsafe_blocks,
+    rustdoc::broken_intra_doc_links,
+    rustdoc::missing_crate_level_docs
+)]
+
+pub mod cli;
+mod node;
+mod output;
+
+use ::core::fmt;
+use ::rel_allocators::{prefix::header_schema, slab::Slab};
+use ::rel_core::{
+    schema::Schema,
+    transcode::Endianness,
+    Basis16,
+    Basis32,
+    Basis64,
+};
+
+use self::node::Walker;
+pub use self::node::{Node, Value};
+
+/// Returns the schema of the header of a buffer written through a `Prefix`
+/// allocator with a `Slab` control, or `None` if no basis has the given number
+/// of bits.
+pub fn slab_header_schema(bits: u32) -> Option<&'static Schema> {
+    match bits {
+        16 => Some(header_schema::<Slab<Basis16>, Basis16>()),
+        32 => Some(header_schema::<Slab<Basis32>, Basis32>()),
+        64 => Some(header_schema::<Slab<Basis64>, Basis64>()),
+        _ => None,
+    }
+}
+
+/// Options for inspecting a buffer.
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    /// The schema of the header at the start of the buffer.
+    pub header: &'static Schema,
+    /// The schema of the root of the buffer, if it is known.
+    pub root: Option<&'static Schema>,
+    /// The endianness that the buffer was written in.
+    pub endianness: Endianness,
+}
+
+/// An error that occurred while inspecting a buffer.
+#[derive(Debug)]
+pub enum InspectError {
+    /// The buffer is too short to contain its header.
+    TooShort {
+        /// The length of the buffer.
+        len: usize,
+        /// The size of the header.
+        header_size: usize,
+    },
+    /// The header does not have a `cap` field, or a `control` field with a
+    /// `len`.
+    UnknownHeader,
+}
+
+impl fmt::Display for InspectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort { len, header_size } => write!(
+                f,
+                "the buffer is {len} bytes long, but its header is \
+                 {header_size} bytes"
+            ),
+            Self::UnknownHeader => {
+                write!(f, "the header does not have a capacity and length")
+            }
+        }
+    }
+}
+
+impl ::std::error::Error for InspectError {}
+
+/// A relative pointer found while walking a buffer.
+#[derive(Clone, Copy, Debug)]
+pub struct PointerCheck {
+    /// The offset of the pointer from the start of the buffer.
+    pub location: usize,
+    /// The offset of the target of the pointer from the start of the buffer.
+    pub target: i128,
+    /// The size of the pointee in bytes, if it could be determined from its
+    /// schema and the metadata of the pointer.
+    pub size: Option<usize>,
+    /// Whether the pointer is null or its pointee lies within the header and
+    /// allocated memory of the buffer. If the size of the pointee is unknown,
+    /// only the start of the pointee is checked.
+    pub in_bounds: bool,
+}
+
+/// The result of inspecting a buffer.
+#[derive(Debug)]
+pub struct Report {
+    /// The length of the buffer in bytes.
+    pub len: usize,
+    /// The header of the buffer.
+    pub header: Node,
+    /// The capacity recorded in the header.
+    pub capacity: usize,
+    /// The number of bytes of the buffer's memory that are in use.
+    pub used: usize,
+    /// The offset of the root from the start of the buffer.
+    pub root_offset: usize,
+    /// The root of the buffer, if its schema is known.
+    pub root: Option<Node>,
+    /// The relative pointers found while walking the root.
+    pub pointers: Vec<PointerCheck>,
+}
+
+impl Report {
+    /// Returns the number of unused bytes remaining in the buffer's memory.
+    pub fn free(&self) -> usize {
+        self.capacity.saturating_sub(self.used)
+    }
+
+    /// Returns an iterator over the relative pointers which point outside of
+    /// the header and allocated memory of the buffer.
+    pub fn out_of_bounds(&self) -> impl Iterator<Item = &PointerCheck> {
+        self.pointers.iter().filter(|p| !p.in_bounds)
+    }
+
+    /// Returns a value which displays the report as JSON.
+    pub fn json(&self) -> impl fmt::Display + '_ {
+        output::Json(self)
+    }
+}
+
+/// Inspects the given buffer.
+pub fn inspect(
+    bytes: &[u8],
+    options: &Options,
+) -> Result<Report, InspectError> {
+    let header_size = options.header.size.unwrap();
+    if bytes.len() < header_size {
+        return Err(InspectError::TooShort {
+            len: bytes.len(),
+            header_size,
+        });
+    }
+
+    let mut walker = Walker::new(bytes, options.endianness);
+    let header = walker.walk(options.header, 0);
+    let capacity = header
+        .field("cap")
+        .and_then(Node::as_usize)
+        .ok_or(InspectError::UnknownHeader)?;
+    let used = header
+        .field("control")
+        .and_then(|control| control.field("len"))
+        .and_then(Node::as_usize)
+        .ok_or(InspectError::UnknownHeader)?;
+
+    // The buffer does not record the location of its root. A `Slab` control
+    // never pads the start of its memory, and fails allocations whose
+    // alignment the start of its memory does not satisfy. So the root, which
+    // is the first allocation, is always located right after the header.
+    let root_offset = header_size;
+    walker.set_end(root_offset + used);
+    walker.visit(options.header, 0);
+    let root = options.root.map(|root| {
+        walker.visit(root, root_offset);
+        walker.walk(root, root_offset)
+    });
+
+    Ok(Report {
+        len: bytes.len(),
+        header,
+        capacity,
+        used,
+        root_offset,
+        root,
+        pointers: walker.into_pointers(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ::core::mem::{forget, size_of, MaybeUninit};
+    use ::mischief::{Slot, StaticToken};
+    use ::rel_alloc::{vec, EmplaceIn, RelVec};
+    use ::rel_allocators::{
+        prefix::{Prefix, RelPrefix},
+        slab::Slab,
+    };
+    use ::rel_core::{
+        schema::Schema,
+        transcode::Endianness,
+        Basis,
+        DefaultBasis,
+        Portable,
+        I32,
+    };
+    use ::rel_util::Align16;
+
+    use super::{inspect, slab_header_schema, Options, Value};
+
+    fn schema_of<T: Portable>(_: &T) -> &'static Schema {
+        T::SCHEMA
+    }
+
+    #[test]
+    fn inspect_vec() {
+        let mut backing = Align16(MaybeUninit::<[u8; 128]>::zeroed());
+        let root = StaticToken::acquire(|mut token| {
+            let bytes = Slot::new(&mut backing.0).unsize();
+            let alloc = Prefix::<Slab, _>::try_new_in_region(bytes, &mut token)
+                .unwrap();
+            let values = vec::FromIter(alloc, [1, 2, 3].into_iter())
+                .emplace_in::<RelVec<I32, RelPrefix<Slab, _>>>(alloc);
+            let root = schema_of(&*values);
+            forget(values);
+            root
+        });
+        // SAFETY: The backing memory was zeroed before it was written to, so
+        // all of its bytes are initialized.
+        let mut bytes = unsafe { backing.0.assume_init() };
+
+        let bits = size_of::<<DefaultBasis as Basis>::Usize>() * 8;
+        let options = Options {
+            header: slab_header_schema(bits.try_into().unwrap()).unwrap(),
+            root: Some(root),
+            endianness: Endianness::CURRENT,
+        };
+        let report = inspect(&bytes, &options).unwrap();
+        assert_eq!(report.capacity, bytes.len());
+        assert_eq!(report.root_offset, 16);
+        let root_node = report.root.as_ref().unwrap();
+        let len = root_node.field("len").unwrap();
+        assert!(matches!(len.value, Value::Uint(3)));
+        // The vector points to its elements, and its allocator points to the
+        // header.
+        assert_eq!(report.pointers.len(), 2);
+        assert_eq!(report.out_of_bounds().count(), 0);
+
+        // The pointer of the vector is followed to all of its elements.
+        let ptr = root_node.field("ptr").unwrap();
+        let Value::Pointer {
+            pointee: Some(elements),
+            ..
+        } = &ptr.value
+        else {
+            panic!("the elements of the vector were not walked");
+        };
+        let Value::Array(elements) = &elements.value else {
+            panic!("the elements of the vector are not an array");
+        };
+        let elements = elements
+            .iter()
+            .map(|element| match element.value {
+                Value::Int(value) => value,
+                _ => panic!("an element of the vector is not an integer"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(elements, [1, 2, 3]);
+        assert_eq!(report.pointers[0].size, Some(3 * size_of::<i32>()));
+
+        // The elements of the vector start in bounds, but no longer fit once
+        // its length is increased.
+        let mut long = bytes;
+        long[len.offset] = 100;
+        let report = inspect(&long, &options).unwrap();
+        assert_eq!(report.out_of_bounds().count(), 1);
+
+        let offset_size = size_of::<<DefaultBasis as Basis>::Isize>();
+        bytes[16..16 + offset_size].fill(0x7f);
+        let report = inspect(&bytes, &options).unwrap();
+        assert_eq!(report.out_of_bounds().count(), 1);
+    }
+}
@@ -0,0 +1,81 @@
+use ::mischief::Region;
+use ::rel_alloc::{RelBox, RelString, RelVec};
+use ::rel_allocators::{prefix::RelPrefix, slab::Slab};
+use ::rel_core::{
+    Basis16,
+    Basis32,
+    Basis64,
+    Portable,
+    F32,
+    F64,
+    I128,
+    I16,
+    I32,
+    I64,
+    U128,
+    U16,
+    U32,
+    U64,
+};
+use ::rel_inspect::cli::Root;
+use ::std::process::ExitCode;
+
+/// The region of the allocators in the root types.
+///
+/// Regions have no representation in a buffer, so any region can be used to
+/// name the root types.
+struct InspectRegion;
+
+// SAFETY: No pointers are ever created in `InspectRegion`.
+unsafe impl Region for InspectRegion {}
+
+/// The allocator of the root types in the basis `B`.
+type Alloc<B> = RelPrefix<'static, Slab<B>, InspectRegion, B, B>;
+
+macro_rules! roots {
+    ($($prim:ident),*) => {
+        [
+            &roots!(@primitives $($prim),*)[..],
+            &roots!(@basis Basis16, 16; $($prim),*),
+            &roots!(@basis Basis32, 32; $($prim),*),
+            &roots!(@basis Basis64, 64; $($prim),*),
+        ]
+        .concat()
+    };
+    (@primitives $($prim:ident),*) => {
+        [$(
+            Root {
+                name: stringify!($prim),
+                bits: None,
+                schema: $prim::SCHEMA,
+            },
+        )*]
+    };
+    (@basis $basis:ident, $bits:literal; $($prim:ident),*) => {
+        [
+            Root {
+                name: "RelString",
+                bits: Some($bits),
+                schema: RelString::<Alloc<$basis>, $basis>::SCHEMA,
+            },
+            $(
+                Root {
+                    name: concat!("RelBox<", stringify!($prim), ">"),
+                    bits: Some($bits),
+                    schema: RelBox::<$prim, Alloc<$basis>, $basis>::SCHEMA,
+                },
+                Root {
+                    name: concat!("RelVec<", stringify!($prim), ">"),
+                    bits: Some($bits),
+                    schema: RelVec::<$prim, Alloc<$basis>, $basis>::SCHEMA,
+                },
+            )*
+        ]
+    };
+}
+
+fn main() -> ExitCode {
+    ::rel_inspect::cli::run(&roots!(
+        I16, I32, I64, I128, U16, U32, U64, U128, F32, F64
+    ))
+}
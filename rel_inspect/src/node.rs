@@ -0,0 +1,444 @@
+use ::core::str;
+use ::rel_core::{
+    schema::{Field, Primitive, Schema, SchemaKind},
+    transcode::Endianness,
+};
+use ::std::collections::HashSet;
+
+use crate::PointerCheck;
+
+/// A value read from a buffer using its schema.
+#[derive(Debug)]
+pub struct Node {
+    /// The offset of the value from the start of the buffer.
+    pub offset: usize,
+    /// The name of the type of the value.
+    pub name: &'static str,
+    /// The contents of the value.
+    pub value: Value,
+}
+
+/// The contents of a [`Node`].
+#[derive(Debug)]
+pub enum Value {
+    /// A unit value.
+    Unit,
+    /// A boolean.
+    Bool(bool),
+    /// A signed integer.
+    Int(i128),
+    /// An unsigned integer.
+    Uint(u128),
+    /// A floating-point number.
+    Float(f64),
+    /// A character.
+    Char(char),
+    /// A string slice.
+    Str(String),
+    /// A zero-sized marker.
+    Marker,
+    /// The elements of an array.
+    Array(Vec<Node>),
+    /// The fields of a struct.
+    Struct(Vec<(&'static str, Node)>),
+    /// A variant of an enum and its fields.
+    Enum {
+        /// The name of the variant.
+        variant: &'static str,
+        /// The fields of the variant.
+        fields: Vec<(&'static str, Node)>,
+    },
+    /// A relative or base pointer.
+    Pointer {
+        /// The offset of the target of the pointer from the start of the
+        /// buffer, or `None` if the pointer is null.
+        target: Option<i128>,
+        /// Whether the pointer is null or its pointee lies within the header
+        /// and allocated memory of the buffer.
+        in_bounds: bool,
+        /// The metadata of the pointer.
+        metadata: Box<Node>,
+        /// The value pointed to, or `None` if the pointer is null, out of
+        /// bounds, or points to a value that was already walked.
+        pointee: Option<Box<Node>>,
+    },
+    /// A value whose contents can't be determined from its schema, like a
+    /// union or a slice without a length.
+    Opaque,
+    /// A value which is not valid for its type.
+    Invalid(&'static str),
+}
+
+impl Node {
+    /// Returns the field of this struct with the given name, if any.
+    pub fn field(&self, name: &str) -> Option<&Node> {
+        match &self.value {
+            Value::Struct(fields) => fields
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, node)| node),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of this node as a `usize`, if it is an integer.
+    ///
+    /// Structs with a single field, like `Cell`, are treated as the value of
+    /// their field.
+    pub fn as_usize(&self) -> Option<usize> {
+        match &self.value {
+            Value::Int(value) => usize::try_from(*value).ok(),
+            Value::Uint(value) => usize::try_from(*value).ok(),
+            Value::Struct(fields) if fields.len() == 1 => {
+                fields[0].1.as_usize()
+            }
+            _ => None,
+        }
+    }
+
+    fn as_i128(&self) -> Option<i128> {
+        match &self.value {
+            Value::Int(value) => Some(*value),
+            Value::Uint(value) => i128::try_from(*value).ok(),
+            _ => None,
+        }
+    }
+}
+
+pub struct Walker<'a> {
+    bytes: &'a [u8],
+    endianness: Endianness,
+    end: usize,
+    pointers: Vec<PointerCheck>,
+    visited: HashSet<(usize, *const Schema)>,
+}
+
+impl<'a> Walker<'a> {
+    pub fn new(bytes: &'a [u8], endianness: Endianness) -> Self {
+        Self {
+            bytes,
+            endianness,
+            end: bytes.len(),
+            pointers: Vec::new(),
+            visited: HashSet::new(),
+        }
+    }
+
+    /// Sets the end of the allocated memory that pointers may point into.
+    pub fn set_end(&mut self, end: usize) {
+        self.end = end;
+    }
+
+    /// Marks the value at `offset` as walked, so that pointers to it are not
+    /// followed.
+    pub fn visit(&mut self, schema: &'static Schema, offset: usize) {
+        self.visited.insert((offset, schema));
+    }
+
+    pub fn into_pointers(self) -> Vec<PointerCheck> {
+        self.pointers
+    }
+
+    pub fn walk(&mut self, schema: &'static Schema, offset: usize) -> Node {
+        Node {
+            offset,
+            name: schema.name,
+            value: self.walk_value(schema, offset),
+        }
+    }
+
+    fn walk_value(&mut self, schema: &'static Schema, offset: usize) -> Value {
+        if let Some(size) = schema.size {
+            if !matches!(
+                offset.checked_add(size),
+                Some(end) if end <= self.bytes.len(),
+            ) {
+                return Value::Invalid("out of bounds");
+            }
+        }
+
+        match &schema.kind {
+            SchemaKind::Primitive(primitive) => {
+                self.primitive(*primitive, offset)
+            }
+            SchemaKind::Marker | SchemaKind::Pointee(_) => Value::Marker,
+            SchemaKind::MaybeUninit(inner) => self.walk_value(inner, offset),
+            SchemaKind::Array { element, len } => {
+                let size = element.size.unwrap();
+                Value::Array(
+                    (0..*len)
+                        .map(|i| self.walk(element, offset + i * size))
+                        .collect(),
+                )
+            }
+            SchemaKind::Slice { .. } | SchemaKind::Str => Value::Opaque,
+            SchemaKind::Struct { fields, .. } => match schema.name {
+                "RelPtr" => self.pointer(fields, offset, true, None),
+                "BasePtr" => self.pointer(fields, offset, false, None),
+                "RelVec" => self.vec(fields, offset),
+                _ => Value::Struct(self.fields(fields, offset)),
+            },
+            SchemaKind::Union { .. } => Value::Opaque,
+            SchemaKind::Enum { variants, .. } => {
+                let discriminant = self.bytes[offset];
+                match variants.iter().find(|v| v.discriminant == discriminant) {
+                    Some(variant) => Value::Enum {
+                        variant: variant.name,
+                        fields: self.fields(variant.fields, offset),
+                    },
+                    None => Value::Invalid("invalid discriminant"),
+                }
+            }
+        }
+    }
+
+    fn fields(
+        &mut self,
+        fields: &'static [Field],
+        offset: usize,
+    ) -> Vec<(&'static str, Node)> {
+        fields
+            .iter()
+            .map(|field| {
+                (field.name, self.walk(field.schema, offset + field.offset))
+            })
+            .collect()
+    }
+
+    /// Walks a vector. Its pointer is followed to all `len` of its elements
+    /// instead of only the first.
+    fn vec(&mut self, fields: &'static [Field], offset: usize) -> Value {
+        let len = fields
+            .iter()
+            .find(|f| f.name == "len")
+            .and_then(|f| self.walk(f.schema, offset + f.offset).as_usize());
+        let Some(len) = len else {
+            return Value::Struct(self.fields(fields, offset));
+        };
+
+        let fields = fields
+            .iter()
+            .map(|field| {
+                let offset = offset + field.offset;
+                let node = match &field.schema.kind {
+                    SchemaKind::Struct { fields, .. }
+                        if field.name == "ptr"
+                            && field.schema.name == "RelPtr" =>
+                    {
+                        Node {
+                            offset,
+                            name: field.schema.name,
+                            value: self.pointer(
+                                fields,
+                                offset,
+                                true,
+                                Some(len),
+                            ),
+                        }
+                    }
+                    _ => self.walk(field.schema, offset),
+                };
+                (field.name, node)
+            })
+            .collect();
+        Value::Struct(fields)
+    }
+
+    /// Reads a pointer, checks that its pointee lies within the buffer, and
+    /// walks its pointee. Relative pointers are offset from their own
+    /// location, and base pointers are offset from the start of the buffer.
+    ///
+    /// If `count` is `Some`, the pointer points to that many consecutive
+    /// values of its pointee type.
+    fn pointer(
+        &mut self,
+        fields: &'static [Field],
+        offset: usize,
+        relative: bool,
+        count: Option<usize>,
+    ) -> Value {
+        let find = |name| fields.iter().find(|f| f.name == name);
+        let (Some(offset_field), Some(metadata_field)) =
+            (find("offset"), find("metadata"))
+        else {
+            return Value::Struct(self.fields(fields, offset));
+        };
+        let pointee = find("_pointee").and_then(|f| match f.schema.kind {
+            SchemaKind::Pointee(pointee) => Some(pointee()),
+            _ => None,
+        });
+
+        let raw = self.walk(offset_field.schema, offset + offset_field.offset);
+        let Some(raw) = raw.as_i128() else {
+            return Value::Invalid("invalid pointer offset");
+        };
+        let metadata =
+            self.walk(metadata_field.schema, offset + metadata_field.offset);
+
+        let location = i128::try_from(offset).unwrap();
+        let target = match (relative, raw) {
+            (true, 0) => None,
+            (true, raw) => Some(location + raw),
+            (false, raw) => Some(raw),
+        };
+        let size =
+            pointee.and_then(|pointee| pointee_size(pointee, &metadata, count));
+        let end = i128::try_from(self.end).unwrap();
+        let in_bounds = match (target, size) {
+            (None, _) => true,
+            // The size of the pointee is not known, so only its start can be
+            // checked.
+            (Some(target), None) => (0..=end).contains(&target),
+            (Some(target), Some(size)) => {
+                target >= 0 && target + i128::try_from(size).unwrap() <= end
+            }
+        };
+        self.pointers.push(PointerCheck {
+            location: offset,
+            target: target.unwrap_or(location),
+            size,
+            in_bounds,
+        });
+
+        let pointee = match (target, pointee) {
+            (Some(target), Some(pointee)) if in_bounds => {
+                let target = usize::try_from(target).unwrap();
+                self.visited.insert((target, pointee)).then(|| {
+                    Box::new(self.pointee(pointee, target, &metadata, count))
+                })
+            }
+            _ => None,
+        };
+
+        Value::Pointer {
+            target,
+            in_bounds,
+            metadata: Box::new(metadata),
+            pointee,
+        }
+    }
+
+    /// Walks the pointee of a pointer with the given metadata.
+    fn pointee(
+        &mut self,
+        schema: &'static Schema,
+        offset: usize,
+        metadata: &Node,
+        count: Option<usize>,
+    ) -> Node {
+        let value = match (&schema.kind, schema.size, count) {
+            (_, Some(size), Some(count)) => Value::Array(
+                (0..count)
+                    .map(|i| self.walk(schema, offset + i * size))
+                    .collect(),
+            ),
+            (SchemaKind::Slice { element }, None, _) => {
+                match metadata.as_usize() {
+                    Some(len) => {
+                        let size = element.size.unwrap();
+                        Value::Array(
+                            (0..len)
+                                .map(|i| self.walk(element, offset + i * size))
+                                .collect(),
+                        )
+                    }
+                    None => Value::Opaque,
+                }
+            }
+            (SchemaKind::Str, None, _) => match metadata.as_usize() {
+                Some(len) => match self.bytes.get(offset..offset + len) {
+                    Some(bytes) => match str::from_utf8(bytes) {
+                        Ok(value) => Value::Str(value.to_string()),
+                        Err(_) => Value::Invalid("invalid str"),
+                    },
+                    None => Value::Invalid("out of bounds"),
+                },
+                None => Value::Opaque,
+            },
+            _ => self.walk_value(schema, offset),
+        };
+        Node {
+            offset,
+            name: schema.name,
+            value,
+        }
+    }
+
+    fn read<const N: usize>(&self, offset: usize) -> [u8; N] {
+        let mut bytes = [0; N];
+        bytes.copy_from_slice(&self.bytes[offset..offset + N]);
+        // Primitives are always decoded from little-endian bytes.
+        if self.endianness == Endianness::Big {
+            bytes.reverse();
+        }
+        bytes
+    }
+
+    fn primitive(&self, primitive: Primitive, offset: usize) -> Value {
+        match primitive {
+            Primitive::Unit => Value::Unit,
+            Primitive::Bool => match self.bytes[offset] {
+                0 => Value::Bool(false),
+                1 => Value::Bool(true),
+                _ => Value::Invalid("invalid bool"),
+            },
+            Primitive::I8 => {
+                Value::Int(i8::from_le_bytes(self.read(offset)).into())
+            }
+            Primitive::U8 => Value::Uint(self.bytes[offset].into()),
+            Primitive::I16 => {
+                Value::Int(i16::from_le_bytes(self.read(offset)).into())
+            }
+            Primitive::I32 => {
+                Value::Int(i32::from_le_bytes(self.read(offset)).into())
+            }
+            Primitive::I64 => {
+                Value::Int(i64::from_le_bytes(self.read(offset)).into())
+            }
+            Primitive::I128 => {
+                Value::Int(i128::from_le_bytes(self.read(offset)))
+            }
+            Primitive::U16 => {
+                Value::Uint(u16::from_le_bytes(self.read(offset)).into())
+            }
+            Primitive::U32 => {
+                Value::Uint(u32::from_le_bytes(self.read(offset)).into())
+            }
+            Primitive::U64 => {
+                Value::Uint(u64::from_le_bytes(self.read(offset)).into())
+            }
+            Primitive::U128 => {
+                Value::Uint(u128::from_le_bytes(self.read(offset)))
+            }
+            Primitive::F32 => {
+                Value::Float(f32::from_le_bytes(self.read(offset)).into())
+            }
+            Primitive::F64 => {
+                Value::Float(f64::from_le_bytes(self.read(offset)))
+            }
+            Primitive::Char => {
+                match char::from_u32(u32::from_le_bytes(self.read(offset))) {
+                    Some(c) => Value::Char(c),
+                    None => Value::Invalid("invalid char"),
+                }
+            }
+        }
+    }
+}
+
+/// Returns the size of the pointee of a pointer with the given metadata, or
+/// `None` if it can't be determined from its schema.
+fn pointee_size(
+    schema: &'static Schema,
+    metadata: &Node,
+    count: Option<usize>,
+) -> Option<usize> {
+    match (&schema.kind, schema.size) {
+        (_, Some(size)) => size.checked_mul(count.unwrap_or(1)),
+        (SchemaKind::Slice { element }, None) => {
+            element.size?.checked_mul(metadata.as_usize()?)
+        }
+        (SchemaKind::Str, None) => metadata.as_usize(),
+        _ => None,
+    }
+}
@@ -0,0 +1,261 @@
+use ::core::fmt::{self, Display, Formatter, Write};
+
+use crate::{Node, PointerCheck, Report, Value};
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "length:      {} bytes", self.len)?;
+        writeln!(f, "capacity:    {} bytes", self.capacity)?;
+        writeln!(f, "used:        {} bytes", self.used)?;
+        writeln!(f, "free:        {} bytes", self.free())?;
+        writeln!(f, "root offset: {}", self.root_offset)?;
+
+        write!(f, "header: ")?;
+        write_node(f, &self.header, 0)?;
+        writeln!(f)?;
+        if let Some(root) = &self.root {
+            write!(f, "root: ")?;
+            write_node(f, root, 0)?;
+            writeln!(f)?;
+
+            let out_of_bounds = self.out_of_bounds().count();
+            writeln!(
+                f,
+                "pointers: {} checked, {} out of bounds",
+                self.pointers.len(),
+                out_of_bounds,
+            )?;
+            for pointer in self.out_of_bounds() {
+                writeln!(
+                    f,
+                    "  pointer at {} targets {}",
+                    pointer.location, pointer.target,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn indent(f: &mut Formatter<'_>, depth: usize) -> fmt::Result {
+    for _ in 0..depth {
+        f.write_str("    ")?;
+    }
+    Ok(())
+}
+
+fn write_fields(
+    f: &mut Formatter<'_>,
+    fields: &[(&'static str, Node)],
+    depth: usize,
+) -> fmt::Result {
+    if fields.is_empty() {
+        return Ok(());
+    }
+    writeln!(f, " {{")?;
+    for (name, node) in fields {
+        indent(f, depth + 1)?;
+        write!(f, "{name}: ")?;
+        write_node(f, node, depth + 1)?;
+        writeln!(f, ",")?;
+    }
+    indent(f, depth)?;
+    f.write_char('}')
+}
+
+fn write_node(f: &mut Formatter<'_>, node: &Node, depth: usize) -> fmt::Result {
+    match &node.value {
+        Value::Unit => f.write_str("()"),
+        Value::Bool(value) => write!(f, "{value}"),
+        Value::Int(value) => write!(f, "{value}"),
+        Value::Uint(value) => write!(f, "{value}"),
+        Value::Float(value) => write!(f, "{value}"),
+        Value::Char(value) => write!(f, "{value:?}"),
+        Value::Str(value) => write!(f, "{value:?}"),
+        Value::Marker => f.write_str(node.name),
+        Value::Array(elements) => {
+            if elements.is_empty() {
+                return f.write_str("[]");
+            }
+            writeln!(f, "[")?;
+            for element in elements {
+                indent(f, depth + 1)?;
+                write_node(f, element, depth + 1)?;
+                writeln!(f, ",")?;
+            }
+            indent(f, depth)?;
+            f.write_char(']')
+        }
+        Value::Struct(fields) => {
+            f.write_str(node.name)?;
+            write_fields(f, fields, depth)
+        }
+        Value::Enum { variant, fields } => {
+            write!(f, "{}::{variant}", node.name)?;
+            write_fields(f, fields, depth)
+        }
+        Value::Pointer {
+            target,
+            in_bounds,
+            metadata,
+            pointee,
+        } => {
+            write!(f, "{} -> ", node.name)?;
+            match target {
+                Some(target) => write!(f, "{target}")?,
+                None => f.write_str("null")?,
+            }
+            if !matches!(metadata.value, Value::Unit) {
+                f.write_str(" (metadata: ")?;
+                write_node(f, metadata, depth)?;
+                f.write_char(')')?;
+            }
+            if !in_bounds {
+                f.write_str(" (out of bounds)")?;
+            }
+            if let Some(pointee) = pointee {
+                f.write_str(" = ")?;
+                write_node(f, pointee, depth)?;
+            }
+            Ok(())
+        }
+        Value::Opaque => write!(f, "{} <opaque>", node.name),
+        Value::Invalid(reason) => write!(f, "{} <{reason}>", node.name),
+    }
+}
+
+/// Displays a [`Report`] as JSON.
+pub struct Json<'a>(pub &'a Report);
+
+impl Display for Json<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let report = self.0;
+        write!(
+            f,
+            "{{\"len\":{},\"capacity\":{},\"used\":{},\"free\":{},\
+             \"root_offset\":{},\"header\":",
+            report.len,
+            report.capacity,
+            report.used,
+            report.free(),
+            report.root_offset,
+        )?;
+        json_node(f, &report.header)?;
+        f.write_str(",\"root\":")?;
+        match &report.root {
+            Some(root) => json_node(f, root)?,
+            None => f.write_str("null")?,
+        }
+        f.write_str(",\"pointers\":[")?;
+        for (i, pointer) in report.pointers.iter().enumerate() {
+            if i != 0 {
+                f.write_char(',')?;
+            }
+            json_pointer(f, pointer)?;
+        }
+        f.write_str("]}")
+    }
+}
+
+fn json_str(f: &mut Formatter<'_>, value: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", u32::from(c))?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+fn json_pointer(f: &mut Formatter<'_>, pointer: &PointerCheck) -> fmt::Result {
+    write!(
+        f,
+        "{{\"location\":{},\"target\":{},\"size\":",
+        pointer.location, pointer.target,
+    )?;
+    match pointer.size {
+        Some(size) => write!(f, "{size}")?,
+        None => f.write_str("null")?,
+    }
+    write!(f, ",\"in_bounds\":{}}}", pointer.in_bounds)
+}
+
+fn json_fields(
+    f: &mut Formatter<'_>,
+    fields: &[(&'static str, Node)],
+) -> fmt::Result {
+    f.write_char('{')?;
+    for (i, (name, node)) in fields.iter().enumerate() {
+        if i != 0 {
+            f.write_char(',')?;
+        }
+        json_str(f, name)?;
+        f.write_char(':')?;
+        json_node(f, node)?;
+    }
+    f.write_char('}')
+}
+
+fn json_node(f: &mut Formatter<'_>, node: &Node) -> fmt::Result {
+    f.write_str("{\"type\":")?;
+    json_str(f, node.name)?;
+    write!(f, ",\"offset\":{},\"value\":", node.offset)?;
+    match &node.value {
+        Value::Unit | Value::Marker | Value::Opaque => f.write_str("null")?,
+        Value::Bool(value) => write!(f, "{value}")?,
+        Value::Int(value) => write!(f, "{value}")?,
+        Value::Uint(value) => write!(f, "{value}")?,
+        // JSON can't represent infinities or NaN.
+        Value::Float(value) if !value.is_finite() => f.write_str("null")?,
+        Value::Float(value) => write!(f, "{value}")?,
+        Value::Char(value) => json_str(f, value.encode_utf8(&mut [0; 4]))?,
+        Value::Str(value) => json_str(f, value)?,
+        Value::Array(elements) => {
+            f.write_char('[')?;
+            for (i, element) in elements.iter().enumerate() {
+                if i != 0 {
+                    f.write_char(',')?;
+                }
+                json_node(f, element)?;
+            }
+            f.write_char(']')?;
+        }
+        Value::Struct(fields) => json_fields(f, fields)?,
+        Value::Enum { variant, fields } => {
+            f.write_str("{\"variant\":")?;
+            json_str(f, variant)?;
+            f.write_str(",\"fields\":")?;
+            json_fields(f, fields)?;
+            f.write_char('}')?;
+        }
+        Value::Pointer {
+            target,
+            in_bounds,
+            metadata,
+            pointee,
+        } => {
+            f.write_str("{\"target\":")?;
+            match target {
+                Some(target) => write!(f, "{target}")?,
+                None => f.write_str("null")?,
+            }
+            write!(f, ",\"in_bounds\":{in_bounds},\"metadata\":")?;
+            json_node(f, metadata)?;
+            f.write_str(",\"pointee\":")?;
+            match pointee {
+                Some(pointee) => json_node(f, pointee)?,
+                None => f.write_str("null")?,
+            }
+            f.write_char('}')?;
+        }
+        Value::Invalid(reason) => {
+            f.write_str("{\"invalid\":")?;
+            json_str(f, reason)?;
+            f.write_char('}')?;
+        }
+    }
+    f.write_char('}')
+}
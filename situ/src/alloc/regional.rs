@@ -1,6 +1,8 @@
-use ::mischief::{Region, RegionalAllocator};
+use ::core::{alloc::Layout, ptr::NonNull};
+use ::heresy::alloc::AllocError;
+use ::mischief::{In, Region, RegionalAllocator};
 
-use crate::alloc::RawAllocator;
+use crate::{alloc::RawAllocator, Ref};
 
 /// A `RawAllocator` that allocates inside a single contiguous memory region.
 ///
@@ -19,3 +21,36 @@ pub unsafe trait RawRegionalAllocator: RawAllocator {
 unsafe impl<T: RegionalAllocator> RawRegionalAllocator for T {
     type Region = T::Region;
 }
+
+/// Extension methods for [`RawRegionalAllocator`].
+pub trait RawRegionalAllocatorExt: RawRegionalAllocator {
+    /// Attempts to allocate a block of memory, returning it in the allocator's
+    /// region.
+    ///
+    /// See [`RawAllocator::raw_allocate`] for more details.
+    fn raw_allocate_in(
+        this: Ref<'_, Self>,
+        layout: Layout,
+    ) -> Result<In<NonNull<[u8]>, Self::Region>, AllocError> {
+        let ptr = Self::raw_allocate(this, layout)?;
+        // SAFETY: `ptr` was allocated by a `RawRegionalAllocator`, so it is
+        // contained in its region.
+        Ok(unsafe { In::new_unchecked(ptr) })
+    }
+
+    /// Behaves like `raw_allocate_in`, but also ensures that the returned
+    /// memory is zero-initialized.
+    ///
+    /// See [`RawAllocator::raw_allocate_zeroed`] for more details.
+    fn raw_allocate_zeroed_in(
+        this: Ref<'_, Self>,
+        layout: Layout,
+    ) -> Result<In<NonNull<[u8]>, Self::Region>, AllocError> {
+        let ptr = Self::raw_allocate_zeroed(this, layout)?;
+        // SAFETY: `ptr` was allocated by a `RawRegionalAllocator`, so it is
+        // contained in its region.
+        Ok(unsafe { In::new_unchecked(ptr) })
+    }
+}
+
+impl<A: RawRegionalAllocator + ?Sized> RawRegionalAllocatorExt for A {}
@@ -0,0 +1,291 @@
+//! Utilities for comparing and ordering raw values.
+
+use ::core::cmp::Ordering;
+pub use ::situ_derive::{EqRaw, OrdRaw, PartialEqRaw, PartialOrdRaw};
+
+use crate::Ref;
+
+/// A variant of `PartialEq` that works with raw references.
+pub trait PartialEqRaw<Rhs: ?Sized = Self> {
+    /// Tests for `self` and `other` values to be equal, and is used by `==`.
+    fn eq_raw(this: Ref<'_, Self>, other: Ref<'_, Rhs>) -> bool;
+
+    /// Tests for `!=`. The default implementation is almost always sufficient,
+    /// and should not be overridden without very good reason.
+    fn ne_raw(this: Ref<'_, Self>, other: Ref<'_, Rhs>) -> bool {
+        !Self::eq_raw(this, other)
+    }
+}
+
+impl<T, U> PartialEqRaw<U> for T
+where
+    T: PartialEq<U> + ?Sized,
+    U: ?Sized,
+{
+    fn eq_raw(this: Ref<'_, Self>, other: Ref<'_, U>) -> bool {
+        PartialEq::eq(&*this, &*other)
+    }
+
+    fn ne_raw(this: Ref<'_, Self>, other: Ref<'_, U>) -> bool {
+        PartialEq::ne(&*this, &*other)
+    }
+}
+
+/// A variant of `Eq` that works with raw references.
+pub trait EqRaw: PartialEqRaw {}
+
+impl<T: Eq + ?Sized> EqRaw for T {}
+
+/// A variant of `PartialOrd` that works with raw references.
+pub trait PartialOrdRaw<Rhs: ?Sized = Self>: PartialEqRaw<Rhs> {
+    /// Returns an ordering between `this` and `other` values if one exists.
+    fn partial_cmp_raw(
+        this: Ref<'_, Self>,
+        other: Ref<'_, Rhs>,
+    ) -> Option<Ordering>;
+
+    /// Tests less than (for `this` and `other`) and is used by the `<`
+    /// operator.
+    fn lt_raw(this: Ref<'_, Self>, other: Ref<'_, Rhs>) -> bool {
+        matches!(Self::partial_cmp_raw(this, other), Some(Ordering::Less))
+    }
+
+    /// Tests less than or equal to (for `this` and `other`) and is used by the
+    /// `<=` operator.
+    fn le_raw(this: Ref<'_, Self>, other: Ref<'_, Rhs>) -> bool {
+        matches!(
+            Self::partial_cmp_raw(this, other),
+            Some(Ordering::Less | Ordering::Equal),
+        )
+    }
+
+    /// Tests greater than (for `this` and `other`) and is used by the `>`
+    /// operator.
+    fn gt_raw(this: Ref<'_, Self>, other: Ref<'_, Rhs>) -> bool {
+        matches!(Self::partial_cmp_raw(this, other), Some(Ordering::Greater))
+    }
+
+    /// Tests greater than or equal to (for `this` and `other`) and is used by
+    /// the `>=` operator.
+    fn ge_raw(this: Ref<'_, Self>, other: Ref<'_, Rhs>) -> bool {
+        matches!(
+            Self::partial_cmp_raw(this, other),
+            Some(Ordering::Greater | Ordering::Equal),
+        )
+    }
+}
+
+impl<T, U> PartialOrdRaw<U> for T
+where
+    T: PartialOrd<U> + ?Sized,
+    U: ?Sized,
+{
+    fn partial_cmp_raw(
+        this: Ref<'_, Self>,
+        other: Ref<'_, U>,
+    ) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&*this, &*other)
+    }
+}
+
+/// A variant of `Ord` that works with raw references.
+pub trait OrdRaw: EqRaw + PartialOrdRaw {
+    /// Returns an ordering between `this` and `other`.
+    fn cmp_raw(this: Ref<'_, Self>, other: Ref<'_, Self>) -> Ordering;
+}
+
+impl<T: Ord + ?Sized> OrdRaw for T {
+    fn cmp_raw(this: Ref<'_, Self>, other: Ref<'_, Self>) -> Ordering {
+        Ord::cmp(&*this, &*other)
+    }
+}
+
+/// Compares two iterators of raw references for equality element-wise.
+pub fn iter_eq_raw<'a, 'b, T, U, I, J>(this: I, other: J) -> bool
+where
+    T: PartialEqRaw<U> + ?Sized + 'a,
+    U: ?Sized + 'b,
+    I: IntoIterator<Item = Ref<'a, T>>,
+    J: IntoIterator<Item = Ref<'b, U>>,
+{
+    let mut this = this.into_iter();
+    let mut other = other.into_iter();
+    loop {
+        match (this.next(), other.next()) {
+            (None, None) => return true,
+            (Some(x), Some(y)) => {
+                if !T::eq_raw(x, y) {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+    }
+}
+
+/// Lexicographically compares two iterators of raw references.
+pub fn iter_partial_cmp_raw<'a, 'b, T, U, I, J>(
+    this: I,
+    other: J,
+) -> Option<Ordering>
+where
+    T: PartialOrdRaw<U> + ?Sized + 'a,
+    U: ?Sized + 'b,
+    I: IntoIterator<Item = Ref<'a, T>>,
+    J: IntoIterator<Item = Ref<'b, U>>,
+{
+    let mut this = this.into_iter();
+    let mut other = other.into_iter();
+    loop {
+        match (this.next(), other.next()) {
+            (None, None) => return Some(Ordering::Equal),
+            (None, Some(_)) => return Some(Ordering::Less),
+            (Some(_), None) => return Some(Ordering::Greater),
+            (Some(x), Some(y)) => match T::partial_cmp_raw(x, y) {
+                Some(Ordering::Equal) => (),
+                non_eq => return non_eq,
+            },
+        }
+    }
+}
+
+/// Lexicographically compares two iterators of raw references.
+pub fn iter_cmp_raw<'a, 'b, T, I, J>(this: I, other: J) -> Ordering
+where
+    T: OrdRaw + ?Sized + 'a + 'b,
+    I: IntoIterator<Item = Ref<'a, T>>,
+    J: IntoIterator<Item = Ref<'b, T>>,
+{
+    let mut this = this.into_iter();
+    let mut other = other.into_iter();
+    loop {
+        match (this.next(), other.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => match T::cmp_raw(x, y) {
+                Ordering::Equal => (),
+                non_eq => return non_eq,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::core::cmp::Ordering;
+
+    use super::{EqRaw, OrdRaw, PartialEqRaw, PartialOrdRaw};
+    use crate::Ref;
+
+    fn new_ref<T: ?Sized>(value: &T) -> Ref<'_, T> {
+        // SAFETY: `value` is a shared reference, so it is non-null, properly
+        // aligned, initialized, and not mutated for its lifetime.
+        unsafe { Ref::new_unchecked(value) }
+    }
+
+    fn eq_raw<T: PartialEqRaw + ?Sized>(a: &T, b: &T) -> bool {
+        PartialEqRaw::eq_raw(new_ref(a), new_ref(b))
+    }
+
+    fn partial_cmp_raw<T: PartialOrdRaw + ?Sized>(
+        a: &T,
+        b: &T,
+    ) -> Option<Ordering> {
+        PartialOrdRaw::partial_cmp_raw(new_ref(a), new_ref(b))
+    }
+
+    fn cmp_raw<T: OrdRaw + ?Sized>(a: &T, b: &T) -> Ordering {
+        OrdRaw::cmp_raw(new_ref(a), new_ref(b))
+    }
+
+    #[derive(PartialEqRaw, EqRaw, PartialOrdRaw, OrdRaw)]
+    #[situ = "crate"]
+    struct Point {
+        x: u32,
+        y: u16,
+    }
+
+    #[derive(PartialEqRaw, PartialOrdRaw)]
+    #[situ = "crate"]
+    struct Measure(u8, f32);
+
+    #[derive(PartialEqRaw, EqRaw, PartialOrdRaw, OrdRaw)]
+    #[situ = "crate"]
+    struct Unit;
+
+    // The variant fields are only read through raw pointers.
+    #[allow(dead_code)]
+    #[derive(PartialEqRaw, EqRaw, PartialOrdRaw, OrdRaw)]
+    #[situ = "crate"]
+    #[repr(u8)]
+    enum Shape {
+        Empty,
+        Circle(u32),
+        Rect { w: u32, h: u32 },
+    }
+
+    #[test]
+    fn blanket() {
+        assert!(eq_raw(&1u32, &1));
+        assert!(!eq_raw("a", "b"));
+        assert_eq!(partial_cmp_raw(&1.0f32, &f32::NAN), None);
+        assert_eq!(cmp_raw(&[1u8, 2][..], &[1, 3][..]), Ordering::Less);
+        assert!(PartialOrdRaw::lt_raw(new_ref(&1u8), new_ref(&2u8)));
+        assert!(PartialOrdRaw::ge_raw(new_ref(&2u8), new_ref(&2u8)));
+        assert!(PartialEqRaw::ne_raw(new_ref(&1u8), new_ref(&2u8)));
+    }
+
+    #[test]
+    fn derive_struct() {
+        let a = Point { x: 1, y: 2 };
+        assert!(eq_raw(&a, &Point { x: 1, y: 2 }));
+        assert!(!eq_raw(&a, &Point { x: 1, y: 3 }));
+
+        // Structs compare their fields lexicographically in order, just like
+        // `#[derive(PartialOrd, Ord)]`.
+        assert_eq!(cmp_raw(&a, &Point { x: 1, y: 2 }), Ordering::Equal);
+        assert_eq!(cmp_raw(&a, &Point { x: 0, y: 9 }), Ordering::Greater);
+        assert_eq!(cmp_raw(&a, &Point { x: 1, y: 3 }), Ordering::Less);
+        assert_eq!(
+            partial_cmp_raw(&a, &Point { x: 2, y: 0 }),
+            Some(Ordering::Less),
+        );
+
+        assert_eq!(
+            partial_cmp_raw(&Measure(1, 2.0), &Measure(1, 3.0)),
+            Some(Ordering::Less),
+        );
+        assert_eq!(
+            partial_cmp_raw(&Measure(0, f32::NAN), &Measure(1, 0.0)),
+            Some(Ordering::Less),
+        );
+        assert_eq!(
+            partial_cmp_raw(&Measure(1, f32::NAN), &Measure(1, 0.0)),
+            None,
+        );
+        assert!(!eq_raw(&Measure(1, f32::NAN), &Measure(1, f32::NAN)));
+
+        assert!(eq_raw(&Unit, &Unit));
+        assert_eq!(cmp_raw(&Unit, &Unit), Ordering::Equal);
+    }
+
+    #[test]
+    fn derive_enum() {
+        // Variants are ordered by their discriminants, then by their fields.
+        let shapes = [
+            Shape::Empty,
+            Shape::Circle(1),
+            Shape::Circle(2),
+            Shape::Rect { w: 1, h: 2 },
+            Shape::Rect { w: 2, h: 1 },
+        ];
+        for (i, a) in shapes.iter().enumerate() {
+            for (j, b) in shapes.iter().enumerate() {
+                assert_eq!(eq_raw(a, b), i == j);
+                assert_eq!(cmp_raw(a, b), i.cmp(&j));
+                assert_eq!(partial_cmp_raw(a, b), Some(i.cmp(&j)));
+            }
+        }
+    }
+}
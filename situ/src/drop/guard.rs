@@ -0,0 +1,127 @@
+use ::core::{mem::forget, ptr::slice_from_raw_parts_mut};
+
+use crate::{DropRaw, Mut};
+
+/// A guard that drops a value with `DropRaw` when it goes out of scope.
+///
+/// This is used to clean up values that have already been initialized if a
+/// later step of initialization panics. Once initialization has succeeded, the
+/// guard should be disarmed with [`forget`](DropGuard::forget).
+pub struct DropGuard<T: DropRaw + ?Sized> {
+    ptr: *mut T,
+}
+
+impl<T: DropRaw + ?Sized> DropGuard<T> {
+    /// Creates a new `DropGuard` for the value pointed to by `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// Until the guard is dropped or forgotten:
+    ///
+    /// - `ptr` must be non-null, properly aligned, and valid for reads and
+    ///   writes.
+    /// - `ptr` must not alias any other accessible references.
+    /// - The value pointed to by `ptr` must be initialized, immovable, and
+    ///   valid for dropping.
+    #[inline]
+    pub unsafe fn new(ptr: *mut T) -> Self {
+        Self { ptr }
+    }
+
+    /// Disarms the guard so that it does not drop its value.
+    #[inline]
+    pub fn forget(this: Self) {
+        forget(this);
+    }
+}
+
+impl<T: DropRaw + ?Sized> Drop for DropGuard<T> {
+    fn drop(&mut self) {
+        // SAFETY: The caller of `new` guaranteed that `ptr` is non-null,
+        // properly aligned, valid for reads and writes, does not alias any
+        // other accessible references, and points to an initialized and
+        // immovable value.
+        let value = unsafe { Mut::new_unchecked(self.ptr) };
+        // SAFETY: The caller of `new` guaranteed that the value is valid for
+        // dropping. The guard is being dropped, so the value will not be
+        // accessed through it again.
+        unsafe {
+            DropRaw::drop_raw(value);
+        }
+    }
+}
+
+/// A guard that drops the initialized prefix of a slice with `DropRaw` when it
+/// goes out of scope.
+///
+/// This is used to clean up the elements of a slice that have already been
+/// initialized if initializing a later element panics. Once initialization has
+/// succeeded, the guard should be disarmed with
+/// [`forget`](PrefixDropGuard::forget).
+pub struct PrefixDropGuard<T: DropRaw> {
+    ptr: *mut T,
+    len: usize,
+}
+
+impl<T: DropRaw> PrefixDropGuard<T> {
+    /// Creates a new `PrefixDropGuard` for the elements starting at `ptr`. The
+    /// initialized prefix is empty.
+    ///
+    /// # Safety
+    ///
+    /// Until the guard is dropped or forgotten, `ptr` must be non-null,
+    /// properly aligned, and valid for reads and writes of every element in
+    /// the initialized prefix. Those elements must not alias any other
+    /// accessible references.
+    #[inline]
+    pub unsafe fn new(ptr: *mut T) -> Self {
+        Self { ptr, len: 0 }
+    }
+
+    /// Returns the length of the initialized prefix.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the initialized prefix is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Sets the length of the initialized prefix.
+    ///
+    /// # Safety
+    ///
+    /// The first `len` elements starting at the guard's pointer must be
+    /// initialized, immovable, and valid for dropping.
+    #[inline]
+    pub unsafe fn set_len(&mut self, len: usize) {
+        self.len = len;
+    }
+
+    /// Disarms the guard so that it does not drop the initialized prefix.
+    #[inline]
+    pub fn forget(this: Self) {
+        forget(this);
+    }
+}
+
+impl<T: DropRaw> Drop for PrefixDropGuard<T> {
+    fn drop(&mut self) {
+        let prefix = slice_from_raw_parts_mut(self.ptr, self.len);
+        // SAFETY: The caller of `new` guaranteed that `ptr` is non-null,
+        // properly aligned, and valid for reads and writes of every element in
+        // the initialized prefix, and that those elements do not alias any
+        // other accessible references. The caller of `set_len` guaranteed that
+        // the elements are initialized and immovable.
+        let prefix = unsafe { Mut::new_unchecked(prefix) };
+        // SAFETY: The caller of `set_len` guaranteed that the elements are
+        // valid for dropping. The guard is being dropped, so they will not be
+        // accessed through it again.
+        unsafe {
+            DropRaw::drop_raw(prefix);
+        }
+    }
+}
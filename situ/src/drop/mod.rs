@@ -1,7 +1,9 @@
+mod guard;
 mod impls;
 
 pub use ::situ_derive::DropRaw;
 
+pub use self::guard::*;
 use crate::Mut;
 
 /// A type that can be dropped through a raw pointer, without creating an
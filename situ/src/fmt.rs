@@ -1,6 +1,7 @@
 //! Utilities for formatting and printing raw values.
 
 use ::core::fmt::{Debug, Display, Error, Formatter};
+pub use ::situ_derive::{DebugRaw, DisplayRaw};
 
 use crate::Ref;
 
@@ -35,3 +36,99 @@ impl<T: Display + ?Sized> DisplayRaw for T {
         Display::fmt(&*this, f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ::std::format;
+
+    use super::{DebugRaw, DisplayRaw};
+    use crate::Ref;
+
+    fn new_ref<T: ?Sized>(value: &T) -> Ref<'_, T> {
+        // SAFETY: `value` is a shared reference, so it is non-null, properly
+        // aligned, initialized, and not mutated for its lifetime.
+        unsafe { Ref::new_unchecked(value) }
+    }
+
+    #[derive(DebugRaw)]
+    #[situ = "crate"]
+    struct Point {
+        x: u32,
+        r#type: char,
+    }
+
+    #[derive(DebugRaw)]
+    #[situ = "crate"]
+    struct Pair(u8, Option<u16>);
+
+    #[derive(DebugRaw)]
+    #[situ = "crate"]
+    struct Unit;
+
+    // The variant fields are only read through raw pointers.
+    #[allow(dead_code)]
+    #[derive(DebugRaw)]
+    #[situ = "crate"]
+    #[repr(u8)]
+    enum Shape {
+        Empty,
+        Circle(u32),
+        Rect { w: u32, h: u32 },
+    }
+
+    /// The same types with `#[derive(Debug)]`, to compare output against.
+    mod std_debug {
+        // The fields are only read by the derived `Debug` impls.
+        #![allow(dead_code)]
+
+        #[derive(Debug)]
+        pub struct Point {
+            pub x: u32,
+            pub r#type: char,
+        }
+
+        #[derive(Debug)]
+        pub struct Pair(pub u8, pub Option<u16>);
+
+        #[derive(Debug)]
+        pub struct Unit;
+
+        #[derive(Debug)]
+        pub enum Shape {
+            Empty,
+            Circle(u32),
+            Rect { w: u32, h: u32 },
+        }
+    }
+
+    #[derive(DisplayRaw)]
+    #[situ = "crate"]
+    struct Name(u32);
+
+    fn assert_same<T: DebugRaw, U: ::core::fmt::Debug>(raw: &T, std: &U) {
+        assert_eq!(format!("{:?}", new_ref(raw)), format!("{:?}", std));
+        assert_eq!(format!("{:#?}", new_ref(raw)), format!("{:#?}", std));
+    }
+
+    #[test]
+    fn debug_matches_derive() {
+        assert_same(
+            &Point { x: 1, r#type: 'a' },
+            &std_debug::Point { x: 1, r#type: 'a' },
+        );
+        assert_same(&Pair(1, Some(2)), &std_debug::Pair(1, Some(2)));
+        assert_same(&Unit, &std_debug::Unit);
+        assert_same(&Shape::Empty, &std_debug::Shape::Empty);
+        assert_same(&Shape::Circle(3), &std_debug::Shape::Circle(3));
+        assert_same(
+            &Shape::Rect { w: 4, h: 5 },
+            &std_debug::Shape::Rect { w: 4, h: 5 },
+        );
+    }
+
+    #[test]
+    fn display_forwards() {
+        assert_eq!(format!("{}", new_ref(&Name(42))), "42");
+        assert_eq!(format!("{:>4}", new_ref(&Name(42))), "  42");
+    }
+}
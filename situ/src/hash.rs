@@ -0,0 +1,112 @@
+//! Utilities for hashing raw values.
+
+use ::core::hash::{Hash, Hasher};
+pub use ::situ_derive::HashRaw;
+
+use crate::Ref;
+
+/// A variant of `Hash` that works with raw references.
+pub trait HashRaw {
+    /// Feeds this value into the given `Hasher`.
+    fn hash_raw<H: Hasher>(this: Ref<'_, Self>, state: &mut H);
+}
+
+impl<T: Hash + ?Sized> HashRaw for T {
+    fn hash_raw<H: Hasher>(this: Ref<'_, Self>, state: &mut H) {
+        Hash::hash(&*this, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::core::hash::{Hash, Hasher};
+    use ::std::collections::hash_map::DefaultHasher;
+
+    use super::HashRaw;
+    use crate::Ref;
+
+    fn new_ref<T: ?Sized>(value: &T) -> Ref<'_, T> {
+        // SAFETY: `value` is a shared reference, so it is non-null, properly
+        // aligned, initialized, and not mutated for its lifetime.
+        unsafe { Ref::new_unchecked(value) }
+    }
+
+    fn hash_raw<T: HashRaw + ?Sized>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        HashRaw::hash_raw(new_ref(value), &mut hasher);
+        hasher.finish()
+    }
+
+    fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[derive(HashRaw)]
+    #[situ = "crate"]
+    struct Point {
+        x: u32,
+        y: u16,
+    }
+
+    #[derive(HashRaw)]
+    #[situ = "crate"]
+    struct Pair(u8, [u8; 3]);
+
+    #[derive(HashRaw)]
+    #[situ = "crate"]
+    struct Unit;
+
+    // The variant fields are only read through raw pointers.
+    #[allow(dead_code)]
+    #[derive(HashRaw)]
+    #[situ = "crate"]
+    #[repr(u8)]
+    enum Shape {
+        Empty,
+        Circle(u32),
+        Rect { w: u32, h: u32 },
+    }
+
+    #[test]
+    fn blanket() {
+        assert_eq!(hash_raw(&42u32), hash(&42u32));
+        assert_eq!(hash_raw("hello"), hash("hello"));
+        assert_eq!(hash_raw(&[1u8, 2, 3][..]), hash(&[1u8, 2, 3][..]));
+    }
+
+    #[test]
+    fn derive_struct() {
+        // Structs hash their fields in order, just like `#[derive(Hash)]`.
+        assert_eq!(hash_raw(&Point { x: 1, y: 2 }), hash(&(1u32, 2u16)));
+        assert_ne!(
+            hash_raw(&Point { x: 1, y: 2 }),
+            hash_raw(&Point { x: 2, y: 1 }),
+        );
+        assert_eq!(hash_raw(&Pair(1, [2, 3, 4])), hash(&(1u8, [2u8, 3, 4])));
+        assert_eq!(hash_raw(&Unit), hash(&()));
+    }
+
+    #[test]
+    fn derive_enum() {
+        let hashes = [
+            hash_raw(&Shape::Empty),
+            hash_raw(&Shape::Circle(1)),
+            hash_raw(&Shape::Circle(2)),
+            hash_raw(&Shape::Rect { w: 1, h: 2 }),
+            hash_raw(&Shape::Rect { w: 2, h: 1 }),
+        ];
+        for (i, a) in hashes.iter().enumerate() {
+            for b in &hashes[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+
+        assert_eq!(hash_raw(&Shape::Circle(1)), hash_raw(&Shape::Circle(1)));
+        assert_eq!(
+            hash_raw(&Shape::Rect { w: 3, h: 4 }),
+            hash_raw(&Shape::Rect { w: 3, h: 4 }),
+        );
+    }
+}
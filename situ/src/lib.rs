@@ -11,14 +11,21 @@
 )]
 #![no_std]
 
+#[cfg(test)]
+extern crate std;
+
 pub mod alloc;
+pub mod cmp;
 mod drop;
+pub mod export;
 pub mod fmt;
+pub mod hash;
 mod r#mut;
 pub mod ops;
 mod owned_val;
 mod pinned;
 mod r#ref;
+pub mod slice;
 pub mod str;
 mod val;
 
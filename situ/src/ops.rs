@@ -1,8 +1,21 @@
 //! Variants of `ops` traits that work with raw references.
 
-use core::ops::{Deref, DerefMut};
-
-use ::ptr_meta::metadata;
+use ::core::{
+    ops::{
+        Bound,
+        Deref,
+        DerefMut,
+        Range,
+        RangeBounds,
+        RangeFrom,
+        RangeFull,
+        RangeInclusive,
+        RangeTo,
+        RangeToInclusive,
+    },
+    ptr::{slice_from_raw_parts, slice_from_raw_parts_mut},
+};
+use ::ptr_meta::{from_raw_parts_mut, metadata};
 
 use crate::{Mut, Ref};
 
@@ -85,122 +98,545 @@ pub trait IndexMutRaw<Idx>: IndexRaw<Idx> {
     ) -> Mut<'_, Self::Output>;
 }
 
-impl<T, const N: usize> IndexRaw<usize> for [T; N] {
+/// A variant of `SliceIndex` that works with raw references.
+///
+/// This is implemented for `usize` and all of the range types so that slices
+/// and string slices can be indexed and subsliced through `IndexRaw` and
+/// `IndexMutRaw`.
+pub trait SliceIndexRaw<T: ?Sized> {
+    /// The output type returned by methods.
+    type Output: ?Sized;
+
+    /// Returns a shared reference to the output at this location, if in
+    /// bounds.
+    fn get_raw(self, slice: Ref<'_, T>) -> Option<Ref<'_, Self::Output>>;
+
+    /// Returns a mutable reference to the output at this location, if in
+    /// bounds.
+    fn get_mut_raw(self, slice: Mut<'_, T>) -> Option<Mut<'_, Self::Output>>;
+
+    /// Returns a shared reference to the output at this location, without
+    /// performing any bounds checking.
+    ///
+    /// # Safety
+    ///
+    /// `self` must be in bounds for `slice`.
+    unsafe fn get_raw_unchecked(
+        self,
+        slice: Ref<'_, T>,
+    ) -> Ref<'_, Self::Output>;
+
+    /// Returns a mutable reference to the output at this location, without
+    /// performing any bounds checking.
+    ///
+    /// # Safety
+    ///
+    /// `self` must be in bounds for `slice`.
+    unsafe fn get_mut_raw_unchecked(
+        self,
+        slice: Mut<'_, T>,
+    ) -> Mut<'_, Self::Output>;
+
+    /// Returns a shared reference to the output at this location.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is out of bounds.
+    fn index_raw(self, slice: Ref<'_, T>) -> Ref<'_, Self::Output>;
+
+    /// Returns a mutable reference to the output at this location.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is out of bounds.
+    fn index_mut_raw(self, slice: Mut<'_, T>) -> Mut<'_, Self::Output>;
+}
+
+impl<T> SliceIndexRaw<[T]> for usize {
     type Output = T;
 
-    fn index_raw(this: Ref<'_, Self>, index: usize) -> Ref<'_, Self::Output> {
-        assert!(index < N);
-        // SAFETY: We asserted that `index` is less than `N` and so it in
-        // bounds.
-        unsafe { Self::index_raw_unchecked(this, index) }
+    fn get_raw(self, slice: Ref<'_, [T]>) -> Option<Ref<'_, T>> {
+        if self < metadata(slice.as_ptr()) {
+            // SAFETY: We checked that `self` is less than the length of
+            // `slice`, so it is in bounds.
+            Some(unsafe { self.get_raw_unchecked(slice) })
+        } else {
+            None
+        }
     }
 
-    unsafe fn index_raw_unchecked(
-        this: Ref<'_, Self>,
-        index: usize,
-    ) -> Ref<'_, Self::Output> {
+    fn get_mut_raw(self, slice: Mut<'_, [T]>) -> Option<Mut<'_, T>> {
+        if self < metadata(slice.as_ptr()) {
+            // SAFETY: We checked that `self` is less than the length of
+            // `slice`, so it is in bounds.
+            Some(unsafe { self.get_mut_raw_unchecked(slice) })
+        } else {
+            None
+        }
+    }
+
+    unsafe fn get_raw_unchecked(self, slice: Ref<'_, [T]>) -> Ref<'_, T> {
         // SAFETY: This pointer add is safe because the pointer is guaranteed to
-        // be to the first `T` in an array of `N` consecutive `T`, and the
+        // be to the first `T` in a slice of `len` consecutive `T`, and the
         // resulting pointer must be in-bounds because the caller has guaranteed
-        // that `index` is less than `N`.
-        let ptr = unsafe { this.as_ptr().cast::<T>().add(index) };
-        // SAFETY: The offset pointer is to an element of the original array,
+        // that `self` is less than `len`.
+        let ptr = unsafe { slice.as_ptr().cast::<T>().add(self) };
+        // SAFETY: The offset pointer is to an element of the original slice,
         // and so must be non-null, properly aligned, valid for reads, and
         // initialized. It has the same shared aliasing as the `Ref` it is
         // derived from, and so must not alias any other mutable references for
         // `'_`.
         unsafe { Ref::new_unchecked(ptr) }
     }
-}
 
-impl<T, const N: usize> IndexMutRaw<usize> for [T; N] {
-    fn index_mut_raw(
-        this: Mut<'_, Self>,
-        index: usize,
-    ) -> Mut<'_, Self::Output> {
-        assert!(index < N);
-        // SAFETY: We asserted that `index` is less than `N` and so is in
-        // bounds.
-        unsafe { Self::index_mut_raw_unchecked(this, index) }
-    }
-
-    unsafe fn index_mut_raw_unchecked(
-        this: Mut<'_, Self>,
-        index: usize,
-    ) -> Mut<'_, Self::Output> {
+    unsafe fn get_mut_raw_unchecked(self, slice: Mut<'_, [T]>) -> Mut<'_, T> {
         // SAFETY: This pointer add is safe because the pointer is guaranteed to
-        // be to the first `T` in an array of `N` consecutive `T`, and the
+        // be to the first `T` in a slice of `len` consecutive `T`, and the
         // resulting pointer must be in-bounds because the caller has guaranteed
-        // that `index` is less than `N`.
-        let ptr = unsafe { this.as_ptr().cast::<T>().add(index) };
-        // SAFETY: The offset pointer is to an element of the original array,
+        // that `self` is less than `len`.
+        let ptr = unsafe { slice.as_ptr().cast::<T>().add(self) };
+        // SAFETY: The offset pointer is to an element of the original slice,
         // and so must be non-null, properly aligned, valid for reads, and
         // initialized. It has the same mutable aliasing as the `Mut` it is
         // derived from, and so must not alias any other accessible references
         // for `'_`.
         unsafe { Mut::new_unchecked(ptr) }
     }
+
+    fn index_raw(self, slice: Ref<'_, [T]>) -> Ref<'_, T> {
+        self.get_raw(slice).expect("index out of bounds")
+    }
+
+    fn index_mut_raw(self, slice: Mut<'_, [T]>) -> Mut<'_, T> {
+        self.get_mut_raw(slice).expect("index out of bounds")
+    }
 }
 
-impl<T> IndexRaw<usize> for [T] {
-    type Output = T;
+/// Converts `range` into a half-open range of indices, returning `None` if it
+/// is not a valid range for a slice of length `len`.
+fn slice_range<R: RangeBounds<usize>>(
+    range: &R,
+    len: usize,
+) -> Option<Range<usize>> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.checked_add(1)?,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1)?,
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
 
-    fn index_raw(this: Ref<'_, Self>, index: usize) -> Ref<'_, Self::Output> {
-        let slice_ptr = this.as_ptr();
-        let len = metadata(slice_ptr);
-        assert!(index < len, "index out of bounds");
-        // SAFETY: We asserted that `index` is less than `len` and so it in
-        // bounds.
-        unsafe { Self::index_raw_unchecked(this, index) }
+    if start <= end && end <= len {
+        Some(start..end)
+    } else {
+        None
+    }
+}
+
+/// Returns a subslice of `slice` covering `range`.
+///
+/// # Safety
+///
+/// `range` must be in bounds for `slice`.
+unsafe fn subslice<T>(slice: *mut [T], range: Range<usize>) -> *mut [T] {
+    // SAFETY: The caller has guaranteed that `range` is in bounds for `slice`,
+    // so `range.start` is at most the length of `slice` and the offset pointer
+    // is in bounds.
+    let ptr = unsafe { slice.cast::<T>().add(range.start) };
+    slice_from_raw_parts_mut(ptr, range.end - range.start)
+}
+
+/// Returns a string subslice of `slice` covering `range`.
+///
+/// # Safety
+///
+/// `range` must be in bounds for `slice` and lie on UTF-8 sequence boundaries.
+unsafe fn substr(slice: *mut str, range: Range<usize>) -> *mut str {
+    let bytes = from_raw_parts_mut::<[u8]>(slice.cast(), metadata(slice));
+    // SAFETY: The caller has guaranteed that `range` is in bounds for `slice`.
+    let bytes = unsafe { subslice(bytes, range) };
+    from_raw_parts_mut(bytes.cast(), metadata(bytes))
+}
+
+/// Returns whether `range` lies on UTF-8 sequence boundaries of `s`.
+fn is_char_range(s: &str, range: &Range<usize>) -> bool {
+    s.is_char_boundary(range.start) && s.is_char_boundary(range.end)
+}
+
+macro_rules! impl_slice_index_range {
+    ($($range:ty),* $(,)?) => {
+        $(
+            impl<T> SliceIndexRaw<[T]> for $range {
+                type Output = [T];
+
+                fn get_raw(
+                    self,
+                    slice: Ref<'_, [T]>,
+                ) -> Option<Ref<'_, [T]>> {
+                    slice_range(&self, metadata(slice.as_ptr()))?;
+                    // SAFETY: We checked that `self` is in bounds for `slice`.
+                    Some(unsafe { self.get_raw_unchecked(slice) })
+                }
+
+                fn get_mut_raw(
+                    self,
+                    slice: Mut<'_, [T]>,
+                ) -> Option<Mut<'_, [T]>> {
+                    slice_range(&self, metadata(slice.as_ptr()))?;
+                    // SAFETY: We checked that `self` is in bounds for `slice`.
+                    Some(unsafe { self.get_mut_raw_unchecked(slice) })
+                }
+
+                unsafe fn get_raw_unchecked(
+                    self,
+                    slice: Ref<'_, [T]>,
+                ) -> Ref<'_, [T]> {
+                    let ptr = slice.as_ptr().cast_mut();
+                    // SAFETY: The caller has guaranteed that `self` is in
+                    // bounds for `slice`.
+                    let range = unsafe {
+                        slice_range(&self, metadata(ptr)).unwrap_unchecked()
+                    };
+                    // SAFETY: The caller has guaranteed that `self` is in
+                    // bounds for `slice`.
+                    let ptr = unsafe { subslice(ptr, range) };
+                    // SAFETY: The subslice pointer is to elements of the
+                    // original slice, and so must be non-null, properly
+                    // aligned, valid for reads, and initialized. It has the
+                    // same shared aliasing as the `Ref` it is derived from, and
+                    // so must not alias any other mutable references for `'_`.
+                    unsafe { Ref::new_unchecked(ptr) }
+                }
+
+                unsafe fn get_mut_raw_unchecked(
+                    self,
+                    slice: Mut<'_, [T]>,
+                ) -> Mut<'_, [T]> {
+                    let ptr = slice.as_ptr();
+                    // SAFETY: The caller has guaranteed that `self` is in
+                    // bounds for `slice`.
+                    let range = unsafe {
+                        slice_range(&self, metadata(ptr)).unwrap_unchecked()
+                    };
+                    // SAFETY: The caller has guaranteed that `self` is in
+                    // bounds for `slice`.
+                    let ptr = unsafe { subslice(ptr, range) };
+                    // SAFETY: The subslice pointer is to elements of the
+                    // original slice, and so must be non-null, properly
+                    // aligned, valid for reads, and initialized. It has the
+                    // same mutable aliasing as the `Mut` it is derived from,
+                    // and so must not alias any other accessible references
+                    // for `'_`.
+                    unsafe { Mut::new_unchecked(ptr) }
+                }
+
+                fn index_raw(self, slice: Ref<'_, [T]>) -> Ref<'_, [T]> {
+                    self.get_raw(slice).expect("range out of bounds")
+                }
+
+                fn index_mut_raw(self, slice: Mut<'_, [T]>) -> Mut<'_, [T]> {
+                    self.get_mut_raw(slice).expect("range out of bounds")
+                }
+            }
+
+            impl SliceIndexRaw<str> for $range {
+                type Output = str;
+
+                fn get_raw(self, slice: Ref<'_, str>) -> Option<Ref<'_, str>> {
+                    let range = slice_range(&self, slice.len())?;
+                    if is_char_range(&slice, &range) {
+                        // SAFETY: We checked that `self` is in bounds for
+                        // `slice` and lies on UTF-8 sequence boundaries.
+                        Some(unsafe { self.get_raw_unchecked(slice) })
+                    } else {
+                        None
+                    }
+                }
+
+                fn get_mut_raw(
+                    self,
+                    slice: Mut<'_, str>,
+                ) -> Option<Mut<'_, str>> {
+                    let range = slice_range(&self, slice.as_ref().len())?;
+                    if is_char_range(&slice.as_ref(), &range) {
+                        // SAFETY: We checked that `self` is in bounds for
+                        // `slice` and lies on UTF-8 sequence boundaries.
+                        Some(unsafe { self.get_mut_raw_unchecked(slice) })
+                    } else {
+                        None
+                    }
+                }
+
+                unsafe fn get_raw_unchecked(
+                    self,
+                    slice: Ref<'_, str>,
+                ) -> Ref<'_, str> {
+                    let ptr = slice.as_ptr().cast_mut();
+                    // SAFETY: The caller has guaranteed that `self` is in
+                    // bounds for `slice`.
+                    let range = unsafe {
+                        slice_range(&self, metadata(ptr)).unwrap_unchecked()
+                    };
+                    // SAFETY: The caller has guaranteed that `self` is in
+                    // bounds for `slice` and lies on UTF-8 sequence boundaries.
+                    let ptr = unsafe { substr(ptr, range) };
+                    // SAFETY: The substring pointer is to bytes of the
+                    // original string slice, and so must be non-null, properly
+                    // aligned, valid for reads, and initialized. It has the
+                    // same shared aliasing as the `Ref` it is derived from, and
+                    // so must not alias any other mutable references for `'_`.
+                    unsafe { Ref::new_unchecked(ptr) }
+                }
+
+                unsafe fn get_mut_raw_unchecked(
+                    self,
+                    slice: Mut<'_, str>,
+                ) -> Mut<'_, str> {
+                    let ptr = slice.as_ptr();
+                    // SAFETY: The caller has guaranteed that `self` is in
+                    // bounds for `slice`.
+                    let range = unsafe {
+                        slice_range(&self, metadata(ptr)).unwrap_unchecked()
+                    };
+                    // SAFETY: The caller has guaranteed that `self` is in
+                    // bounds for `slice` and lies on UTF-8 sequence boundaries.
+                    let ptr = unsafe { substr(ptr, range) };
+                    // SAFETY: The substring pointer is to bytes of the
+                    // original string slice, and so must be non-null, properly
+                    // aligned, valid for reads, and initialized. It has the
+                    // same mutable aliasing as the `Mut` it is derived from,
+                    // and so must not alias any other accessible references
+                    // for `'_`.
+                    unsafe { Mut::new_unchecked(ptr) }
+                }
+
+                fn index_raw(self, slice: Ref<'_, str>) -> Ref<'_, str> {
+                    self.get_raw(slice)
+                        .expect("range out of bounds or not on char boundary")
+                }
+
+                fn index_mut_raw(self, slice: Mut<'_, str>) -> Mut<'_, str> {
+                    self.get_mut_raw(slice)
+                        .expect("range out of bounds or not on char boundary")
+                }
+            }
+        )*
+    };
+}
+
+impl_slice_index_range!(
+    Range<usize>,
+    RangeFrom<usize>,
+    RangeFull,
+    RangeInclusive<usize>,
+    RangeTo<usize>,
+    RangeToInclusive<usize>,
+);
+
+/// Unsizes a reference to an array into a reference to a slice.
+fn array_as_slice<T, const N: usize>(this: Ref<'_, [T; N]>) -> Ref<'_, [T]> {
+    let ptr = slice_from_raw_parts(this.as_ptr().cast::<T>(), N);
+    // SAFETY: `ptr` points to the same `N` elements as `this`, and so upholds
+    // all of the invariants required by `new_unchecked`.
+    unsafe { Ref::new_unchecked(ptr) }
+}
+
+/// Unsizes a mutable reference to an array into a mutable reference to a slice.
+fn array_as_mut_slice<T, const N: usize>(
+    this: Mut<'_, [T; N]>,
+) -> Mut<'_, [T]> {
+    let ptr = slice_from_raw_parts_mut(this.as_ptr().cast::<T>(), N);
+    // SAFETY: `ptr` points to the same `N` elements as `this`, and so upholds
+    // all of the invariants required by `new_unchecked`.
+    unsafe { Mut::new_unchecked(ptr) }
+}
+
+impl<T, I: SliceIndexRaw<[T]>, const N: usize> IndexRaw<I> for [T; N] {
+    type Output = I::Output;
+
+    fn index_raw(this: Ref<'_, Self>, index: I) -> Ref<'_, Self::Output> {
+        index.index_raw(array_as_slice(this))
     }
 
     unsafe fn index_raw_unchecked(
         this: Ref<'_, Self>,
-        index: usize,
+        index: I,
     ) -> Ref<'_, Self::Output> {
-        let slice_ptr = this.as_ptr();
-        // SAFETY: This pointer add is safe because the pointer is guaranteed to
-        // be to the first `T` in a slice of `len` consecutive `T`, and the
-        // resulting pointer must be in-bounds because the caller has guaranteed
-        // that `index` is less than `len`.
-        let ptr = unsafe { slice_ptr.cast::<T>().add(index) };
-        // SAFETY: The offset pointer is to an element of the original slice,
-        // and so must be non-null, properly aligned, valid for reads, and
-        // initialized. It has the same shared aliasing as the `Ref` it is
-        // derived from, and so must not alias any other mutable references for
-        // `'_`.
-        unsafe { Ref::new_unchecked(ptr) }
+        // SAFETY: The caller has guaranteed that `index` is in bounds for
+        // `this`.
+        unsafe { index.get_raw_unchecked(array_as_slice(this)) }
     }
 }
 
-impl<T> IndexMutRaw<usize> for [T] {
-    fn index_mut_raw(
+impl<T, I: SliceIndexRaw<[T]>, const N: usize> IndexMutRaw<I> for [T; N] {
+    fn index_mut_raw(this: Mut<'_, Self>, index: I) -> Mut<'_, Self::Output> {
+        index.index_mut_raw(array_as_mut_slice(this))
+    }
+
+    unsafe fn index_mut_raw_unchecked(
         this: Mut<'_, Self>,
-        index: usize,
+        index: I,
     ) -> Mut<'_, Self::Output> {
-        let slice_ptr = this.as_ptr();
-        let len = metadata(slice_ptr);
-        assert!(index < len, "index out of bounds");
-        // SAFETY: We asserted that `index` is less than `len` and so is in
-        // bounds.
-        unsafe { Self::index_mut_raw_unchecked(this, index) }
+        // SAFETY: The caller has guaranteed that `index` is in bounds for
+        // `this`.
+        unsafe { index.get_mut_raw_unchecked(array_as_mut_slice(this)) }
+    }
+}
+
+impl<T, I: SliceIndexRaw<[T]>> IndexRaw<I> for [T] {
+    type Output = I::Output;
+
+    fn index_raw(this: Ref<'_, Self>, index: I) -> Ref<'_, Self::Output> {
+        index.index_raw(this)
+    }
+
+    unsafe fn index_raw_unchecked(
+        this: Ref<'_, Self>,
+        index: I,
+    ) -> Ref<'_, Self::Output> {
+        // SAFETY: The caller has guaranteed that `index` is in bounds for
+        // `this`.
+        unsafe { index.get_raw_unchecked(this) }
+    }
+}
+
+impl<T, I: SliceIndexRaw<[T]>> IndexMutRaw<I> for [T] {
+    fn index_mut_raw(this: Mut<'_, Self>, index: I) -> Mut<'_, Self::Output> {
+        index.index_mut_raw(this)
     }
 
     unsafe fn index_mut_raw_unchecked(
         this: Mut<'_, Self>,
-        index: usize,
+        index: I,
     ) -> Mut<'_, Self::Output> {
-        let slice_ptr = this.as_ptr();
-        // SAFETY: This pointer add is safe because the pointer is guaranteed to
-        // be to the first `T` in a slice of `len` consecutive `T`, and the
-        // resulting pointer must be in-bounds because the caller has guaranteed
-        // that `index` is less than `len`.
-        let ptr = unsafe { slice_ptr.cast::<T>().add(index) };
-        // SAFETY: The offset pointer is to an element of the original slice,
-        // and so must be non-null, properly aligned, valid for reads, and
-        // initialized. It has the same mutable aliasing as the `Mut` it is
-        // derived from, and so must not alias any other accessible references
-        // for `'_`.
-        unsafe { Mut::new_unchecked(ptr) }
+        // SAFETY: The caller has guaranteed that `index` is in bounds for
+        // `this`.
+        unsafe { index.get_mut_raw_unchecked(this) }
+    }
+}
+
+impl<I: SliceIndexRaw<str>> IndexRaw<I> for str {
+    type Output = I::Output;
+
+    fn index_raw(this: Ref<'_, Self>, index: I) -> Ref<'_, Self::Output> {
+        index.index_raw(this)
+    }
+
+    unsafe fn index_raw_unchecked(
+        this: Ref<'_, Self>,
+        index: I,
+    ) -> Ref<'_, Self::Output> {
+        // SAFETY: The caller has guaranteed that `index` is in bounds for
+        // `this`.
+        unsafe { index.get_raw_unchecked(this) }
+    }
+}
+
+impl<I: SliceIndexRaw<str>> IndexMutRaw<I> for str {
+    fn index_mut_raw(this: Mut<'_, Self>, index: I) -> Mut<'_, Self::Output> {
+        index.index_mut_raw(this)
+    }
+
+    unsafe fn index_mut_raw_unchecked(
+        this: Mut<'_, Self>,
+        index: I,
+    ) -> Mut<'_, Self::Output> {
+        // SAFETY: The caller has guaranteed that `index` is in bounds for
+        // `this`.
+        unsafe { index.get_mut_raw_unchecked(this) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IndexMutRaw, IndexRaw, SliceIndexRaw};
+    use crate::{Mut, Ref};
+
+    fn new_ref<T: ?Sized>(value: &T) -> Ref<'_, T> {
+        // SAFETY: `value` is a shared reference, so it is non-null, properly
+        // aligned, initialized, and not mutated for its lifetime.
+        unsafe { Ref::new_unchecked(value) }
+    }
+
+    fn new_mut<T: ?Sized>(value: &mut T) -> Mut<'_, T> {
+        // SAFETY: `value` is a mutable reference, so it is non-null, properly
+        // aligned, initialized, and unaliased for its lifetime. It is not moved
+        // while it is borrowed.
+        unsafe { Mut::new_unchecked(value) }
+    }
+
+    #[test]
+    fn slice_ranges() {
+        let array = [0, 1, 2, 3, 4];
+        let slice = new_ref(&array[..]);
+
+        assert_eq!(*IndexRaw::index_raw(slice, 1..3), [1, 2]);
+        assert_eq!(*IndexRaw::index_raw(slice, 1..=3), [1, 2, 3]);
+        assert_eq!(*IndexRaw::index_raw(slice, ..2), [0, 1]);
+        assert_eq!(*IndexRaw::index_raw(slice, ..=2), [0, 1, 2]);
+        assert_eq!(*IndexRaw::index_raw(slice, 3..), [3, 4]);
+        assert_eq!(*IndexRaw::index_raw(slice, ..), array);
+        assert_eq!(*IndexRaw::index_raw(slice, 5..), []);
+        assert_eq!(*IndexRaw::index_raw(new_ref(&array), 2..4), [2, 3]);
+
+        assert!((2..6).get_raw(slice).is_none());
+        let (start, end) = (3, 2);
+        assert!((start..end).get_raw(slice).is_none());
+        assert!((..=5).get_raw(slice).is_none());
+        assert!((6..).get_raw(slice).is_none());
+    }
+
+    #[test]
+    fn slice_ranges_mut() {
+        let mut array = [0, 1, 2, 3, 4];
+
+        for value in
+            IndexMutRaw::index_mut_raw(new_mut(&mut array[..]), 1..4).iter_mut()
+        {
+            *value *= 10;
+        }
+        let tail = IndexMutRaw::index_mut_raw(new_mut(&mut array), 3..);
+        assert_eq!(*tail, [30, 4]);
+        assert!((4..6).get_mut_raw(new_mut(&mut array[..])).is_none());
+
+        assert_eq!(array, [0, 10, 20, 30, 4]);
+    }
+
+    #[test]
+    #[should_panic = "range out of bounds"]
+    fn slice_range_out_of_bounds() {
+        let array = [0, 1, 2];
+        IndexRaw::index_raw(new_ref(&array[..]), 1..4);
+    }
+
+    #[test]
+    fn str_ranges() {
+        let s = new_ref("h\u{e9}llo");
+
+        assert_eq!(&*IndexRaw::index_raw(s, ..1), "h");
+        assert_eq!(&*IndexRaw::index_raw(s, 1..3), "\u{e9}");
+        assert_eq!(&*IndexRaw::index_raw(s, 3..), "llo");
+        assert_eq!(&*IndexRaw::index_raw(s, ..), "h\u{e9}llo");
+
+        // Ranges that split the two-byte `é` are rejected.
+        assert!((1..2).get_raw(s).is_none());
+        assert!((2..).get_raw(s).is_none());
+        assert!((..=1).get_raw(s).is_none());
+        assert!((..7).get_raw(s).is_none());
+
+        let mut bytes = *b"abcdef";
+        let s = ::core::str::from_utf8_mut(&mut bytes).unwrap();
+        let mut upper = IndexMutRaw::index_mut_raw(new_mut(s), 2..4);
+        upper.make_ascii_uppercase();
+        assert_eq!(&bytes, b"abCDef");
+    }
+
+    #[test]
+    #[should_panic = "range out of bounds or not on char boundary"]
+    fn str_range_not_char_boundary() {
+        IndexRaw::index_raw(new_ref("\u{e9}"), ..1);
     }
 }
@@ -10,7 +10,15 @@ use ::core::{
 use ::heresy::alloc::Allocator;
 #[cfg(feature = "alloc")]
 use ::heresy::alloc::Global;
-use ::mischief::{Frame, Metadata, Pointer, RegionalAllocator, Within};
+use ::mischief::{
+    Frame,
+    In,
+    Metadata,
+    Pointer,
+    Region,
+    RegionalAllocator,
+    Within,
+};
 use ::ptr_meta::Pointee;
 
 use crate::{
@@ -183,6 +191,43 @@ where
 {
 }
 
+/// Extension methods for [`OwnedVal`]s allocated in a [`RegionalAllocator`].
+pub trait OwnedValExt {
+    /// The type of the owned value.
+    type Target: ?Sized;
+    /// The region that the owned value is located in.
+    type Region: Region;
+
+    /// Returns a `Ref` of the owned value in its region.
+    fn deref_in(&self) -> In<Ref<'_, Self::Target>, Self::Region>;
+
+    /// Returns a `Mut` of the owned value in its region.
+    fn deref_mut_in(&mut self) -> In<Mut<'_, Self::Target>, Self::Region>;
+}
+
+impl<T, A> OwnedValExt for OwnedVal<T, A>
+where
+    T: DropRaw + Pointee + ?Sized,
+    A: RegionalAllocator,
+{
+    type Target = T;
+    type Region = A::Region;
+
+    #[inline]
+    fn deref_in(&self) -> In<Ref<'_, T>, A::Region> {
+        // SAFETY: The owned value is allocated in `A`, so it is located in
+        // `A::Region`.
+        unsafe { In::new_unchecked(self.as_ref()) }
+    }
+
+    #[inline]
+    fn deref_mut_in(&mut self) -> In<Mut<'_, T>, A::Region> {
+        // SAFETY: The owned value is allocated in `A`, so it is located in
+        // `A::Region`.
+        unsafe { In::new_unchecked(self.as_mut()) }
+    }
+}
+
 impl<T: DropRaw + ?Sized, A: Allocator> Deref for OwnedVal<T, A> {
     type Target = T;
 
@@ -0,0 +1,592 @@
+//! Utilities for the slice primitive type.
+
+use ::core::{
+    iter::FusedIterator,
+    marker::PhantomData,
+    ptr::{slice_from_raw_parts, slice_from_raw_parts_mut},
+};
+use ::ptr_meta::metadata;
+
+use crate::{Mut, Ref};
+
+/// Returns the first element of the slice, or `None` if it is empty.
+pub fn first<T>(slice: Ref<'_, [T]>) -> Option<Ref<'_, T>> {
+    iter(slice).next()
+}
+
+/// Returns a mutable reference to the first element of the slice, or `None` if
+/// it is empty.
+pub fn first_mut<T>(slice: Mut<'_, [T]>) -> Option<Mut<'_, T>> {
+    iter_mut(slice).next()
+}
+
+/// Returns the last element of the slice, or `None` if it is empty.
+pub fn last<T>(slice: Ref<'_, [T]>) -> Option<Ref<'_, T>> {
+    iter(slice).next_back()
+}
+
+/// Returns a mutable reference to the last element of the slice, or `None` if
+/// it is empty.
+pub fn last_mut<T>(slice: Mut<'_, [T]>) -> Option<Mut<'_, T>> {
+    iter_mut(slice).next_back()
+}
+
+/// Divides one slice into two at an index.
+///
+/// The first will contain all indices from `[0, mid)` and the second will
+/// contain all indices from `[mid, len)`.
+///
+/// # Panics
+///
+/// Panics if `mid > len`.
+pub fn split_at<T>(
+    slice: Ref<'_, [T]>,
+    mid: usize,
+) -> (Ref<'_, [T]>, Ref<'_, [T]>) {
+    let len = metadata(slice.as_ptr());
+    assert!(mid <= len, "mid > len");
+
+    let ptr = slice.as_ptr().cast::<T>();
+    // SAFETY: We asserted that `mid` is at most `len`, so the offset pointer is
+    // in bounds of the slice.
+    let mid_ptr = unsafe { ptr.add(mid) };
+    // SAFETY: Both halves are subslices of the original slice, and so must be
+    // non-null, properly aligned, valid for reads, and initialized. They have
+    // the same shared aliasing as the `Ref` they are derived from, and so must
+    // not alias any other mutable references for `'_`.
+    unsafe {
+        (
+            Ref::new_unchecked(slice_from_raw_parts(ptr, mid)),
+            Ref::new_unchecked(slice_from_raw_parts(mid_ptr, len - mid)),
+        )
+    }
+}
+
+/// Divides one mutable slice into two at an index.
+///
+/// The first will contain all indices from `[0, mid)` and the second will
+/// contain all indices from `[mid, len)`.
+///
+/// # Panics
+///
+/// Panics if `mid > len`.
+pub fn split_at_mut<T>(
+    slice: Mut<'_, [T]>,
+    mid: usize,
+) -> (Mut<'_, [T]>, Mut<'_, [T]>) {
+    let len = metadata(slice.as_ptr());
+    assert!(mid <= len, "mid > len");
+
+    let ptr = slice.as_ptr().cast::<T>();
+    // SAFETY: We asserted that `mid` is at most `len`, so the offset pointer is
+    // in bounds of the slice.
+    let mid_ptr = unsafe { ptr.add(mid) };
+    // SAFETY: Both halves are disjoint subslices of the original slice, and so
+    // must be non-null, properly aligned, valid for reads and writes, and
+    // initialized. Because they are disjoint and derived from a `Mut`, they do
+    // not alias each other or any other accessible references for `'_`.
+    unsafe {
+        (
+            Mut::new_unchecked(slice_from_raw_parts_mut(ptr, mid)),
+            Mut::new_unchecked(slice_from_raw_parts_mut(mid_ptr, len - mid)),
+        )
+    }
+}
+
+/// Returns an iterator over the slice.
+pub fn iter<T>(slice: Ref<'_, [T]>) -> IterRaw<'_, T> {
+    IterRaw {
+        ptr: slice.as_ptr().cast(),
+        len: metadata(slice.as_ptr()),
+        _phantom: PhantomData,
+    }
+}
+
+/// Returns an iterator that allows modifying each value.
+pub fn iter_mut<T>(slice: Mut<'_, [T]>) -> IterMutRaw<'_, T> {
+    IterMutRaw {
+        ptr: slice.as_ptr().cast(),
+        len: metadata(slice.as_ptr()),
+        _phantom: PhantomData,
+    }
+}
+
+/// Returns an iterator over `chunk_size` elements of the slice at a time,
+/// starting at the beginning of the slice.
+///
+/// The chunks are slices and do not overlap. If `chunk_size` does not divide
+/// the length of the slice, then the last chunk will not have length
+/// `chunk_size`.
+///
+/// # Panics
+///
+/// Panics if `chunk_size` is 0.
+pub fn chunks<T>(slice: Ref<'_, [T]>, chunk_size: usize) -> ChunksRaw<'_, T> {
+    assert!(chunk_size != 0, "chunk size must be non-zero");
+    ChunksRaw { slice, chunk_size }
+}
+
+/// Returns an iterator over `chunk_size` elements of the slice at a time,
+/// starting at the beginning of the slice.
+///
+/// The chunks are mutable slices and do not overlap. If `chunk_size` does not
+/// divide the length of the slice, then the last chunk will not have length
+/// `chunk_size`.
+///
+/// # Panics
+///
+/// Panics if `chunk_size` is 0.
+pub fn chunks_mut<T>(
+    slice: Mut<'_, [T]>,
+    chunk_size: usize,
+) -> ChunksMutRaw<'_, T> {
+    assert!(chunk_size != 0, "chunk size must be non-zero");
+    ChunksMutRaw {
+        slice: Some(slice),
+        chunk_size,
+    }
+}
+
+/// Returns an iterator over all contiguous windows of length `size`.
+///
+/// The windows overlap. If the slice is shorter than `size`, the iterator
+/// returns no values.
+///
+/// # Panics
+///
+/// Panics if `size` is 0.
+pub fn windows<T>(slice: Ref<'_, [T]>, size: usize) -> WindowsRaw<'_, T> {
+    assert!(size != 0, "window size must be non-zero");
+    WindowsRaw { slice, size }
+}
+
+/// An iterator over the elements of a raw slice.
+///
+/// This struct is created by [`iter`].
+pub struct IterRaw<'a, T> {
+    ptr: *const T,
+    len: usize,
+    _phantom: PhantomData<&'a T>,
+}
+
+impl<T> Clone for IterRaw<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            ptr: self.ptr,
+            len: self.len,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> IterRaw<'a, T> {
+    /// Returns the remaining elements of the iterator as a slice.
+    pub fn as_slice(&self) -> Ref<'a, [T]> {
+        // SAFETY: `self.ptr` and `self.len` always describe the remaining
+        // elements of the original slice, so they uphold all of the invariants
+        // required by `new_unchecked`.
+        unsafe { Ref::new_unchecked(slice_from_raw_parts(self.ptr, self.len)) }
+    }
+}
+
+impl<'a, T> Iterator for IterRaw<'a, T> {
+    type Item = Ref<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            None
+        } else {
+            // SAFETY: `self.ptr` points to the first remaining element of the
+            // original slice, and so upholds all of the invariants required by
+            // `new_unchecked`.
+            let result = unsafe { Ref::new_unchecked(self.ptr) };
+            // SAFETY: There is at least one remaining element, so the offset
+            // pointer is at most one past the end of the original slice.
+            self.ptr = unsafe { self.ptr.add(1) };
+            self.len -= 1;
+            Some(result)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterRaw<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            // SAFETY: `self.len` is less than the number of remaining elements,
+            // so the offset pointer is in bounds of the original slice.
+            let ptr = unsafe { self.ptr.add(self.len) };
+            // SAFETY: `ptr` points to the last remaining element of the
+            // original slice, and so upholds all of the invariants required by
+            // `new_unchecked`.
+            Some(unsafe { Ref::new_unchecked(ptr) })
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for IterRaw<'_, T> {}
+
+impl<T> FusedIterator for IterRaw<'_, T> {}
+
+impl<'a, T> IntoIterator for Ref<'a, [T]> {
+    type Item = Ref<'a, T>;
+    type IntoIter = IterRaw<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        iter(self)
+    }
+}
+
+/// An iterator over the elements of a raw mutable slice.
+///
+/// This struct is created by [`iter_mut`].
+pub struct IterMutRaw<'a, T> {
+    ptr: *mut T,
+    len: usize,
+    _phantom: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> IterMutRaw<'a, T> {
+    /// Returns the remaining elements of the iterator as a mutable slice.
+    pub fn into_slice(self) -> Mut<'a, [T]> {
+        let ptr = slice_from_raw_parts_mut(self.ptr, self.len);
+        // SAFETY: `self.ptr` and `self.len` always describe the remaining
+        // elements of the original slice, none of which have been yielded yet.
+        // So they uphold all of the invariants required by `new_unchecked`.
+        unsafe { Mut::new_unchecked(ptr) }
+    }
+}
+
+impl<'a, T> Iterator for IterMutRaw<'a, T> {
+    type Item = Mut<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            None
+        } else {
+            // SAFETY: `self.ptr` points to the first remaining element of the
+            // original slice. Each element is only yielded once, so the
+            // returned `Mut` does not alias any other accessible references.
+            let result = unsafe { Mut::new_unchecked(self.ptr) };
+            // SAFETY: There is at least one remaining element, so the offset
+            // pointer is at most one past the end of the original slice.
+            self.ptr = unsafe { self.ptr.add(1) };
+            self.len -= 1;
+            Some(result)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMutRaw<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            // SAFETY: `self.len` is less than the number of remaining elements,
+            // so the offset pointer is in bounds of the original slice.
+            let ptr = unsafe { self.ptr.add(self.len) };
+            // SAFETY: `ptr` points to the last remaining element of the
+            // original slice. Each element is only yielded once, so the
+            // returned `Mut` does not alias any other accessible references.
+            Some(unsafe { Mut::new_unchecked(ptr) })
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for IterMutRaw<'_, T> {}
+
+impl<T> FusedIterator for IterMutRaw<'_, T> {}
+
+impl<'a, T> IntoIterator for Mut<'a, [T]> {
+    type Item = Mut<'a, T>;
+    type IntoIter = IterMutRaw<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        iter_mut(self)
+    }
+}
+
+/// An iterator over a raw slice in non-overlapping chunks.
+///
+/// This struct is created by [`chunks`].
+pub struct ChunksRaw<'a, T> {
+    slice: Ref<'a, [T]>,
+    chunk_size: usize,
+}
+
+impl<T> Clone for ChunksRaw<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            slice: self.slice,
+            chunk_size: self.chunk_size,
+        }
+    }
+}
+
+impl<'a, T> Iterator for ChunksRaw<'a, T> {
+    type Item = Ref<'a, [T]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = metadata(self.slice.as_ptr());
+        if len == 0 {
+            None
+        } else {
+            let (chunk, rest) =
+                split_at(self.slice, usize::min(self.chunk_size, len));
+            self.slice = rest;
+            Some(chunk)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = metadata(self.slice.as_ptr());
+        let n = if len == 0 {
+            0
+        } else {
+            (len - 1) / self.chunk_size + 1
+        };
+        (n, Some(n))
+    }
+}
+
+impl<T> ExactSizeIterator for ChunksRaw<'_, T> {}
+
+impl<T> FusedIterator for ChunksRaw<'_, T> {}
+
+/// An iterator over a raw mutable slice in non-overlapping chunks.
+///
+/// This struct is created by [`chunks_mut`].
+pub struct ChunksMutRaw<'a, T> {
+    slice: Option<Mut<'a, [T]>>,
+    chunk_size: usize,
+}
+
+impl<'a, T> Iterator for ChunksMutRaw<'a, T> {
+    type Item = Mut<'a, [T]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let slice = self.slice.take()?;
+        let len = metadata(slice.as_ptr());
+        if len == 0 {
+            None
+        } else {
+            let (chunk, rest) =
+                split_at_mut(slice, usize::min(self.chunk_size, len));
+            self.slice = Some(rest);
+            Some(chunk)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.slice.as_ref().map_or(0, |s| metadata(s.as_ptr()));
+        let n = if len == 0 {
+            0
+        } else {
+            (len - 1) / self.chunk_size + 1
+        };
+        (n, Some(n))
+    }
+}
+
+impl<T> ExactSizeIterator for ChunksMutRaw<'_, T> {}
+
+impl<T> FusedIterator for ChunksMutRaw<'_, T> {}
+
+/// An iterator over overlapping windows of a raw slice.
+///
+/// This struct is created by [`windows`].
+pub struct WindowsRaw<'a, T> {
+    slice: Ref<'a, [T]>,
+    size: usize,
+}
+
+impl<T> Clone for WindowsRaw<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            slice: self.slice,
+            size: self.size,
+        }
+    }
+}
+
+impl<'a, T> Iterator for WindowsRaw<'a, T> {
+    type Item = Ref<'a, [T]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = metadata(self.slice.as_ptr());
+        if len < self.size {
+            None
+        } else {
+            let ptr = self.slice.as_ptr().cast::<T>();
+            // SAFETY: The window is a subslice of the remaining slice because
+            // `self.size` is at most `len`. So it is non-null, properly
+            // aligned, valid for reads, initialized, and has the same shared
+            // aliasing as the original slice.
+            let window = unsafe {
+                Ref::new_unchecked(slice_from_raw_parts(ptr, self.size))
+            };
+            self.slice = split_at(self.slice, 1).1;
+            Some(window)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = metadata(self.slice.as_ptr());
+        let n = len.checked_sub(self.size).map_or(0, |n| n + 1);
+        (n, Some(n))
+    }
+}
+
+impl<T> ExactSizeIterator for WindowsRaw<'_, T> {}
+
+impl<T> FusedIterator for WindowsRaw<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        chunks,
+        chunks_mut,
+        first,
+        first_mut,
+        iter,
+        iter_mut,
+        last,
+        last_mut,
+        split_at,
+        split_at_mut,
+        windows,
+    };
+    use crate::{Mut, Ref};
+
+    fn new_ref<T: ?Sized>(value: &T) -> Ref<'_, T> {
+        // SAFETY: `value` is a shared reference, so it is non-null, properly
+        // aligned, initialized, and not mutated for its lifetime.
+        unsafe { Ref::new_unchecked(value) }
+    }
+
+    fn new_mut<T: ?Sized>(value: &mut T) -> Mut<'_, T> {
+        // SAFETY: `value` is a mutable reference, so it is non-null, properly
+        // aligned, initialized, and unaliased for its lifetime. It is not moved
+        // while it is borrowed.
+        unsafe { Mut::new_unchecked(value) }
+    }
+
+    #[test]
+    fn iter_both_ends() {
+        let array = [1, 2, 3, 4];
+        let slice = new_ref(&array[..]);
+
+        assert!(iter(slice).map(|x| *x).eq([1, 2, 3, 4]));
+        assert!(iter(slice).rev().map(|x| *x).eq([4, 3, 2, 1]));
+        assert!(slice.into_iter().map(|x| *x).eq([1, 2, 3, 4]));
+
+        let mut it = iter(slice);
+        assert_eq!(it.len(), 4);
+        assert_eq!(*it.next().unwrap(), 1);
+        assert_eq!(*it.next_back().unwrap(), 4);
+        assert_eq!(it.len(), 2);
+        assert_eq!(*it.as_slice(), [2, 3]);
+        assert_eq!(*it.next_back().unwrap(), 3);
+        assert_eq!(*it.next().unwrap(), 2);
+        assert!(it.next().is_none());
+        assert!(it.next_back().is_none());
+        assert_eq!(*it.as_slice(), []);
+
+        assert_eq!(iter(new_ref(&[(); 3][..])).count(), 3);
+        assert_eq!(*first(slice).unwrap(), 1);
+        assert_eq!(*last(slice).unwrap(), 4);
+        assert!(first(new_ref(&[0u8; 0][..])).is_none());
+        assert!(last(new_ref(&[0u8; 0][..])).is_none());
+    }
+
+    #[test]
+    fn iter_mut_both_ends() {
+        let mut array = [1, 2, 3, 4];
+
+        for (i, mut value) in iter_mut(new_mut(&mut array[..])).enumerate() {
+            *value += i * 10;
+        }
+        assert_eq!(array, [1, 12, 23, 34]);
+
+        let mut it = new_mut(&mut array[..]).into_iter();
+        *it.next_back().unwrap() = 0;
+        assert_eq!(it.len(), 3);
+        *it.next().unwrap() = 0;
+        assert_eq!(*it.into_slice(), [12, 23]);
+        assert_eq!(array, [0, 12, 23, 0]);
+
+        *first_mut(new_mut(&mut array[..])).unwrap() = 5;
+        *last_mut(new_mut(&mut array[..])).unwrap() = 6;
+        assert_eq!(array, [5, 12, 23, 6]);
+    }
+
+    #[test]
+    fn split() {
+        let array = [1, 2, 3];
+        let (left, right) = split_at(new_ref(&array[..]), 1);
+        assert_eq!((&*left, &*right), (&[1][..], &[2, 3][..]));
+        let (left, right) = split_at(new_ref(&array[..]), 3);
+        assert_eq!((&*left, &*right), (&[1, 2, 3][..], &[][..]));
+
+        let mut array = [1, 2, 3];
+        let (mut left, mut right) = split_at_mut(new_mut(&mut array[..]), 2);
+        left.swap(0, 1);
+        right[0] = 0;
+        assert_eq!(array, [2, 1, 0]);
+    }
+
+    #[test]
+    #[should_panic = "mid > len"]
+    fn split_out_of_bounds() {
+        split_at(new_ref(&[1, 2, 3][..]), 4);
+    }
+
+    #[test]
+    fn chunks_and_windows() {
+        let array = [1, 2, 3, 4, 5];
+        let slice = new_ref(&array[..]);
+
+        let mut it = chunks(slice, 2);
+        assert_eq!(it.len(), 3);
+        assert_eq!(*it.next().unwrap(), [1, 2]);
+        assert_eq!(*it.next().unwrap(), [3, 4]);
+        assert_eq!(*it.next().unwrap(), [5]);
+        assert!(it.next().is_none());
+        assert_eq!(chunks(slice, 5).len(), 1);
+        assert_eq!(chunks(new_ref(&[0u8; 0][..]), 3).len(), 0);
+
+        let mut it = windows(slice, 3);
+        assert_eq!(it.len(), 3);
+        assert_eq!(*it.next().unwrap(), [1, 2, 3]);
+        assert_eq!(*it.next().unwrap(), [2, 3, 4]);
+        assert_eq!(*it.next().unwrap(), [3, 4, 5]);
+        assert!(it.next().is_none());
+        assert_eq!(windows(slice, 6).len(), 0);
+
+        let mut array = [1, 2, 3, 4, 5];
+        let mut it = chunks_mut(new_mut(&mut array[..]), 2);
+        assert_eq!(it.len(), 3);
+        for mut chunk in &mut it {
+            chunk.reverse();
+        }
+        assert_eq!(it.len(), 0);
+        assert_eq!(array, [2, 1, 4, 3, 5]);
+    }
+
+    #[test]
+    #[should_panic = "chunk size must be non-zero"]
+    fn zero_chunk_size() {
+        chunks(new_ref(&[1, 2, 3][..]), 0);
+    }
+}
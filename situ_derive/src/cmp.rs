@@ -0,0 +1,268 @@
+use ::proc_macro2::{Ident, Span, TokenStream};
+use ::quote::quote;
+use ::raw_enum::RawEnum;
+use ::syn::{parse_quote, Data, DeriveInput, Error, Path};
+
+use crate::util::{add_bounds, field_refs, situ_path};
+
+pub fn derive_partial_eq_raw(input: DeriveInput) -> Result<TokenStream, Error> {
+    let situ = situ_path(&input)?;
+    let trait_path = parse_quote! { #situ::cmp::PartialEqRaw };
+
+    derive_binary(
+        input,
+        &situ,
+        &trait_path,
+        quote! {
+            if this_discriminant != other_discriminant {
+                return false;
+            }
+        },
+        |this_fields, other_fields| {
+            quote! {
+                true #(&& #trait_path::eq_raw(#this_fields, #other_fields))*
+            }
+        },
+        |name, ty_generics, body| {
+            quote! {
+                fn eq_raw(
+                    this: #situ::Ref<'_, Self>,
+                    other: #situ::Ref<'_, #name #ty_generics>,
+                ) -> bool {
+                    #body
+                }
+            }
+        },
+    )
+}
+
+pub fn derive_eq_raw(mut input: DeriveInput) -> Result<TokenStream, Error> {
+    let situ = situ_path(&input)?;
+
+    add_bounds(&mut input, &parse_quote! { #situ::cmp::EqRaw });
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #situ::cmp::EqRaw for #name #ty_generics
+        #where_clause
+        {}
+    })
+}
+
+pub fn derive_partial_ord_raw(
+    input: DeriveInput,
+) -> Result<TokenStream, Error> {
+    let situ = situ_path(&input)?;
+    let trait_path = parse_quote! { #situ::cmp::PartialOrdRaw };
+
+    derive_binary(
+        input,
+        &situ,
+        &trait_path,
+        quote! {
+            match ::core::cmp::PartialOrd::partial_cmp(
+                &this_discriminant,
+                &other_discriminant,
+            ) {
+                Some(::core::cmp::Ordering::Equal) => (),
+                cmp => return cmp,
+            }
+        },
+        |this_fields, other_fields| {
+            quote! {
+                #(
+                    match #trait_path::partial_cmp_raw(
+                        #this_fields,
+                        #other_fields,
+                    ) {
+                        Some(::core::cmp::Ordering::Equal) => (),
+                        cmp => return cmp,
+                    }
+                )*
+                Some(::core::cmp::Ordering::Equal)
+            }
+        },
+        |name, ty_generics, body| {
+            quote! {
+                fn partial_cmp_raw(
+                    this: #situ::Ref<'_, Self>,
+                    other: #situ::Ref<'_, #name #ty_generics>,
+                ) -> Option<::core::cmp::Ordering> {
+                    #body
+                }
+            }
+        },
+    )
+}
+
+pub fn derive_ord_raw(input: DeriveInput) -> Result<TokenStream, Error> {
+    let situ = situ_path(&input)?;
+    let trait_path = parse_quote! { #situ::cmp::OrdRaw };
+
+    derive_binary(
+        input,
+        &situ,
+        &trait_path,
+        quote! {
+            match ::core::cmp::Ord::cmp(
+                &this_discriminant,
+                &other_discriminant,
+            ) {
+                ::core::cmp::Ordering::Equal => (),
+                cmp => return cmp,
+            }
+        },
+        |this_fields, other_fields| {
+            quote! {
+                #(
+                    match #trait_path::cmp_raw(#this_fields, #other_fields) {
+                        ::core::cmp::Ordering::Equal => (),
+                        cmp => return cmp,
+                    }
+                )*
+                ::core::cmp::Ordering::Equal
+            }
+        },
+        |name, ty_generics, body| {
+            quote! {
+                fn cmp_raw(
+                    this: #situ::Ref<'_, Self>,
+                    other: #situ::Ref<'_, #name #ty_generics>,
+                ) -> ::core::cmp::Ordering {
+                    #body
+                }
+            }
+        },
+    )
+}
+
+/// Derives a trait with a single method that compares two values field by
+/// field.
+///
+/// - `compare_discriminants` is a statement that compares `this_discriminant`
+///   and `other_discriminant` and returns early if they are not equal.
+/// - `compare_fields` generates an expression that compares the given fields
+///   of `this` and `other`.
+/// - `method` generates the trait method from the type name, type generics,
+///   and method body.
+fn derive_binary<F, M>(
+    mut input: DeriveInput,
+    situ: &Path,
+    trait_path: &Path,
+    compare_discriminants: TokenStream,
+    compare_fields: F,
+    method: M,
+) -> Result<TokenStream, Error>
+where
+    F: Fn(Vec<TokenStream>, Vec<TokenStream>) -> TokenStream,
+    M: FnOnce(&Ident, TokenStream, TokenStream) -> TokenStream,
+{
+    add_bounds(&mut input, trait_path);
+
+    let this_ptr = Ident::new("this_ptr", Span::call_site());
+    let other_ptr = Ident::new("other_ptr", Span::call_site());
+
+    let (body, util) = match &input.data {
+        Data::Struct(data_struct) => {
+            let this_fields =
+                field_refs(&data_struct.fields, &this_ptr, situ, false);
+            let other_fields =
+                field_refs(&data_struct.fields, &other_ptr, situ, false);
+            let is_unit = this_fields.is_empty();
+            let compare = compare_fields(this_fields, other_fields);
+            let body = if is_unit {
+                quote! {
+                    let _ = (this, other);
+                    #compare
+                }
+            } else {
+                quote! {
+                    let this_ptr = #situ::Ref::as_ptr(this);
+                    let other_ptr = #situ::Ref::as_ptr(other);
+                    unsafe { #compare }
+                }
+            };
+            (body, None)
+        }
+        Data::Enum(data_enum) => {
+            let raw_enum = RawEnum::for_derive(&input)?;
+
+            let raw_variants = &raw_enum.idents.variants;
+            let raw_enum_fn = &raw_enum.idents.raw_enum_fn;
+            let raw_discriminant_fn = &raw_enum.idents.discriminant_fn;
+            let raw_variant_fn = &raw_enum.idents.variant_fn;
+
+            let match_arms = data_enum.variants.iter().map(|v| {
+                let ident = &v.ident;
+                let this_fields = field_refs(&v.fields, &this_ptr, situ, true);
+                let other_fields =
+                    field_refs(&v.fields, &other_ptr, situ, true);
+                let is_unit = this_fields.is_empty();
+                let compare = compare_fields(this_fields, other_fields);
+                if is_unit {
+                    quote! {
+                        (#raw_variants::#ident(_), #raw_variants::#ident(_)) => {
+                            #compare
+                        }
+                    }
+                } else {
+                    quote! {
+                        (
+                            #raw_variants::#ident(this_ptr),
+                            #raw_variants::#ident(other_ptr),
+                        ) => unsafe { #compare },
+                    }
+                }
+            });
+
+            (
+                quote! {
+                    let this_raw =
+                        #raw_enum_fn(#situ::Ref::as_ptr(this).cast_mut());
+                    let other_raw =
+                        #raw_enum_fn(#situ::Ref::as_ptr(other).cast_mut());
+                    let this_discriminant =
+                        unsafe { *#raw_discriminant_fn(this_raw) };
+                    let other_discriminant =
+                        unsafe { *#raw_discriminant_fn(other_raw) };
+                    #compare_discriminants
+                    match (
+                        #raw_variant_fn(this_raw),
+                        #raw_variant_fn(other_raw),
+                    ) {
+                        #(#match_arms)*
+                        #[allow(unreachable_patterns)]
+                        _ => unsafe { ::core::hint::unreachable_unchecked() },
+                    }
+                },
+                Some(raw_enum.tokens),
+            )
+        }
+        Data::Union(data_union) => {
+            return Err(Error::new_spanned(
+                data_union.union_token,
+                "comparison traits cannot be derived for unions",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+    let method = method(name, quote! { #ty_generics }, body);
+
+    Ok(quote! {
+        const _: () = {
+            #util
+
+            impl #impl_generics #trait_path for #name #ty_generics
+            #where_clause
+            {
+                #method
+            }
+        };
+    })
+}
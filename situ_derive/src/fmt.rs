@@ -0,0 +1,217 @@
+use ::proc_macro2::{Ident, TokenStream};
+use ::quote::{format_ident, quote};
+use ::raw_enum::RawEnum;
+use ::syn::{
+    ext::IdentExt,
+    parse_quote,
+    Data,
+    DeriveInput,
+    Error,
+    Fields,
+    Path,
+};
+
+use crate::util::{add_bounds, situ_path};
+
+pub fn derive_debug_raw(mut input: DeriveInput) -> Result<TokenStream, Error> {
+    let situ = situ_path(&input)?;
+
+    add_bounds(&mut input, &parse_quote! { #situ::fmt::DebugRaw });
+
+    let name = &input.ident;
+
+    let (fmt, util) = match &input.data {
+        Data::Struct(data_struct) => {
+            let (pattern, bindings) =
+                munge_pattern(&parse_quote! { #name }, &data_struct.fields, 0);
+            let debug = debug_fields(name, &data_struct.fields, &bindings);
+            let body = if bindings.is_empty() {
+                quote! {
+                    let _ = this;
+                    #debug
+                }
+            } else {
+                quote! {
+                    #situ::export::munge::munge!(let #pattern = this);
+                    #debug
+                }
+            };
+            (body, None)
+        }
+        Data::Enum(data_enum) => {
+            let raw_enum = RawEnum::for_derive(&input)?;
+
+            let raw_variants = &raw_enum.idents.variants;
+            let raw_enum_fn = &raw_enum.idents.raw_enum_fn;
+            let raw_variant_fn = &raw_enum.idents.variant_fn;
+
+            let match_arms = data_enum.variants.iter().map(|v| {
+                let ident = &v.ident;
+                let variant_struct = raw_enum.idents.variant(ident);
+                // In enum variant structs, the tag is the first element so we
+                // have to skip over it.
+                let (pattern, bindings) = munge_pattern(
+                    &parse_quote! { #variant_struct },
+                    &v.fields,
+                    1,
+                );
+                let debug = debug_fields(ident, &v.fields, &bindings);
+                if bindings.is_empty() {
+                    quote! {
+                        #raw_variants::#ident(_) => { #debug }
+                    }
+                } else {
+                    quote! {
+                        #raw_variants::#ident(variant_ptr) => {
+                            // SAFETY: `variant_ptr` points to the active
+                            // variant of `this`, and so upholds all of the
+                            // invariants required by `new_unchecked`.
+                            let variant = unsafe {
+                                #situ::Ref::new_unchecked(variant_ptr)
+                            };
+                            #situ::export::munge::munge!(let #pattern = variant);
+                            #debug
+                        }
+                    }
+                }
+            });
+
+            (
+                quote! {
+                    let this_raw =
+                        #raw_enum_fn(#situ::Ref::as_ptr(this).cast_mut());
+                    match #raw_variant_fn(this_raw) {
+                        #(#match_arms)*
+                    }
+                },
+                Some(raw_enum.tokens),
+            )
+        }
+        Data::Union(data_union) => {
+            return Err(Error::new_spanned(
+                data_union.union_token,
+                "`DebugRaw` cannot be derived for unions",
+            ))
+        }
+    };
+
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+
+    Ok(quote! {
+        const _: () = {
+            #util
+
+            impl #impl_generics #situ::fmt::DebugRaw for #name #ty_generics
+            #where_clause
+            {
+                fn fmt_raw(
+                    this: #situ::Ref<'_, Self>,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::result::Result<(), ::core::fmt::Error> {
+                    #fmt
+                }
+            }
+        };
+    })
+}
+
+pub fn derive_display_raw(
+    mut input: DeriveInput,
+) -> Result<TokenStream, Error> {
+    let situ = situ_path(&input)?;
+
+    let name = input.ident.clone();
+
+    let (pattern, inner) = match &input.data {
+        Data::Struct(data_struct) if data_struct.fields.len() == 1 => {
+            let (pattern, mut bindings) =
+                munge_pattern(&parse_quote! { #name }, &data_struct.fields, 0);
+            (pattern, bindings.remove(0))
+        }
+        _ => {
+            return Err(Error::new_spanned(
+                name,
+                "`DisplayRaw` can only be derived for newtype structs",
+            ))
+        }
+    };
+
+    add_bounds(&mut input, &parse_quote! { #situ::fmt::DisplayRaw });
+
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #situ::fmt::DisplayRaw for #name #ty_generics
+        #where_clause
+        {
+            fn fmt_raw(
+                this: #situ::Ref<'_, Self>,
+                f: &mut ::core::fmt::Formatter<'_>,
+            ) -> ::core::result::Result<(), ::core::fmt::Error> {
+                #situ::export::munge::munge!(let #pattern = this);
+                #situ::fmt::DisplayRaw::fmt_raw(#inner, f)
+            }
+        }
+    })
+}
+
+/// Returns a `munge` destructuring pattern for `fields` and the identifiers
+/// that the fields are bound to.
+///
+/// `skip` is the number of leading unnamed fields to ignore.
+fn munge_pattern(
+    path: &Path,
+    fields: &Fields,
+    skip: usize,
+) -> (TokenStream, Vec<Ident>) {
+    let bindings = (0..fields.len())
+        .map(|i| format_ident!("field_{}", i))
+        .collect::<Vec<_>>();
+
+    let pattern = match fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|f| &f.ident);
+            quote! { #path { #(#names: #bindings,)* .. } }
+        }
+        Fields::Unnamed(_) => {
+            let skipped = (0..skip).map(|_| quote! { _ });
+            quote! { #path(#(#skipped,)* #(#bindings,)* ..) }
+        }
+        Fields::Unit => quote! { #path },
+    };
+
+    (pattern, bindings)
+}
+
+/// Returns an expression which formats the bound fields the same way that
+/// `#[derive(Debug)]` would.
+fn debug_fields(
+    name: &Ident,
+    fields: &Fields,
+    bindings: &[Ident],
+) -> TokenStream {
+    let name_str = name.unraw().to_string();
+    match fields {
+        Fields::Named(fields) => {
+            let names = fields
+                .named
+                .iter()
+                .map(|f| f.ident.as_ref().unwrap().unraw().to_string());
+            quote! {
+                f.debug_struct(#name_str)
+                    #(.field(#names, &#bindings))*
+                    .finish()
+            }
+        }
+        Fields::Unnamed(_) => quote! {
+            f.debug_tuple(#name_str)
+                #(.field(&#bindings))*
+                .finish()
+        },
+        Fields::Unit => quote! {
+            f.write_str(#name_str)
+        },
+    }
+}
@@ -0,0 +1,99 @@
+use ::proc_macro2::{Ident, Span, TokenStream};
+use ::quote::quote;
+use ::raw_enum::RawEnum;
+use ::syn::{parse_quote, Data, DeriveInput, Error};
+
+use crate::util::{add_bounds, field_refs, situ_path};
+
+pub fn derive(mut input: DeriveInput) -> Result<TokenStream, Error> {
+    let situ = situ_path(&input)?;
+
+    add_bounds(&mut input, &parse_quote! { #situ::hash::HashRaw });
+
+    let this_ptr = Ident::new("this_ptr", Span::call_site());
+
+    let (body, util) = match &input.data {
+        Data::Struct(data_struct) => {
+            let fields =
+                field_refs(&data_struct.fields, &this_ptr, &situ, false);
+            let body = if fields.is_empty() {
+                quote! {
+                    let _ = (this, state);
+                }
+            } else {
+                quote! {
+                    let this_ptr = #situ::Ref::as_ptr(this);
+                    unsafe {
+                        #(#situ::hash::HashRaw::hash_raw(#fields, state);)*
+                    }
+                }
+            };
+            (body, None)
+        }
+        Data::Enum(data_enum) => {
+            let raw_enum = RawEnum::for_derive(&input)?;
+
+            let raw_variants = &raw_enum.idents.variants;
+            let raw_enum_fn = &raw_enum.idents.raw_enum_fn;
+            let raw_discriminant_fn = &raw_enum.idents.discriminant_fn;
+            let raw_variant_fn = &raw_enum.idents.variant_fn;
+
+            let match_arms = data_enum.variants.iter().map(|v| {
+                let ident = &v.ident;
+                let fields = field_refs(&v.fields, &this_ptr, &situ, true);
+                if fields.is_empty() {
+                    quote! {
+                        #raw_variants::#ident(_) => (),
+                    }
+                } else {
+                    quote! {
+                        #raw_variants::#ident(this_ptr) => unsafe {
+                            #(#situ::hash::HashRaw::hash_raw(#fields, state);)*
+                        },
+                    }
+                }
+            });
+
+            (
+                quote! {
+                    let this_raw =
+                        #raw_enum_fn(#situ::Ref::as_ptr(this).cast_mut());
+                    let this_discriminant =
+                        unsafe { *#raw_discriminant_fn(this_raw) };
+                    ::core::hash::Hash::hash(&this_discriminant, state);
+                    match #raw_variant_fn(this_raw) {
+                        #(#match_arms)*
+                    }
+                },
+                Some(raw_enum.tokens),
+            )
+        }
+        Data::Union(data_union) => {
+            return Err(Error::new_spanned(
+                data_union.union_token,
+                "`HashRaw` cannot be derived for unions",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+
+    Ok(quote! {
+        const _: () = {
+            #util
+
+            impl #impl_generics #situ::hash::HashRaw for #name #ty_generics
+            #where_clause
+            {
+                fn hash_raw<H: ::core::hash::Hasher>(
+                    this: #situ::Ref<'_, Self>,
+                    state: &mut H,
+                ) {
+                    #body
+                }
+            }
+        };
+    })
+}
@@ -10,7 +10,11 @@
     rustdoc::missing_crate_level_docs
 )]
 
+mod cmp;
 mod drop_raw;
+mod fmt;
+mod hash;
+mod util;
 
 use ::proc_macro::TokenStream;
 use ::syn::{parse_macro_input, DeriveInput};
@@ -23,3 +27,69 @@ pub fn derive_drop_raw(input: TokenStream) -> TokenStream {
         .unwrap_or_else(|e| e.to_compile_error())
         .into()
 }
+
+/// Derives `PartialEqRaw` on the annotated type.
+#[proc_macro_derive(PartialEqRaw, attributes(situ))]
+pub fn derive_partial_eq_raw(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    cmp::derive_partial_eq_raw(derive_input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Derives `EqRaw` on the annotated type.
+#[proc_macro_derive(EqRaw, attributes(situ))]
+pub fn derive_eq_raw(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    cmp::derive_eq_raw(derive_input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Derives `PartialOrdRaw` on the annotated type.
+#[proc_macro_derive(PartialOrdRaw, attributes(situ))]
+pub fn derive_partial_ord_raw(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    cmp::derive_partial_ord_raw(derive_input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Derives `OrdRaw` on the annotated type.
+#[proc_macro_derive(OrdRaw, attributes(situ))]
+pub fn derive_ord_raw(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    cmp::derive_ord_raw(derive_input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Derives `HashRaw` on the annotated type.
+#[proc_macro_derive(HashRaw, attributes(situ))]
+pub fn derive_hash_raw(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    hash::derive(derive_input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Derives `DebugRaw` on the annotated type.
+///
+/// The output matches that of `#[derive(Debug)]`.
+#[proc_macro_derive(DebugRaw, attributes(situ))]
+pub fn derive_debug_raw(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    fmt::derive_debug_raw(derive_input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Derives `DisplayRaw` on the annotated newtype by forwarding to its only
+/// field.
+#[proc_macro_derive(DisplayRaw, attributes(situ))]
+pub fn derive_display_raw(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    fmt::derive_display_raw(derive_input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
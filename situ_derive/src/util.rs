@@ -0,0 +1,71 @@
+use ::macroix::{visit_fields, AttrValue};
+use ::proc_macro2::{Ident, TokenStream};
+use ::quote::quote;
+use ::syn::{parse2, parse_quote, DeriveInput, Error, Fields, Index, Path};
+
+/// Returns the path to `situ` specified by the `#[situ = ...]` attribute, or
+/// `::situ` if none was specified.
+pub fn situ_path(input: &DeriveInput) -> Result<Path, Error> {
+    let mut situ = None;
+    for attr in input.attrs.iter() {
+        if attr.path.is_ident("situ") {
+            situ = Some(parse2::<AttrValue<Path>>(attr.tokens.clone())?.value);
+        }
+    }
+    Ok(situ.unwrap_or_else(|| parse_quote! { ::situ }))
+}
+
+/// Adds a `where` bound requiring every field type of `input` to implement the
+/// trait at `trait_path`.
+pub fn add_bounds(input: &mut DeriveInput, trait_path: &Path) {
+    let where_clause = input.generics.make_where_clause();
+    visit_fields(&input.data, |f| {
+        let ty = &f.ty;
+        where_clause
+            .predicates
+            .push(parse_quote! { #ty: #trait_path });
+    });
+}
+
+/// Returns expressions which create a `Ref` to each of the given fields through
+/// the raw pointer `ptr`.
+///
+/// The returned expressions must be used in an unsafe context.
+pub fn field_refs(
+    fields: &Fields,
+    ptr: &Ident,
+    situ: &Path,
+    skip_discriminant: bool,
+) -> Vec<TokenStream> {
+    match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|f| {
+                let ident = &f.ident;
+                quote! {
+                    #situ::Ref::new_unchecked(
+                        ::core::ptr::addr_of!((*#ptr).#ident)
+                    )
+                }
+            })
+            .collect(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(i, _)| {
+                // In enum tuple structs, the tag is the first element so we
+                // have to skip over it.
+                let offset = if skip_discriminant { 1 } else { 0 };
+                let i = Index::from(i + offset);
+                quote! {
+                    #situ::Ref::new_unchecked(
+                        ::core::ptr::addr_of!((*#ptr).#i)
+                    )
+                }
+            })
+            .collect(),
+        Fields::Unit => Vec::new(),
+    }
+}